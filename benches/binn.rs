@@ -0,0 +1,80 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Benchmarks
+//!
+//! Run with:
+//!
+//! ```shell
+//! cargo bench --features=bench-utils --bench binn [-- criterion options...]
+//! ```
+
+extern crate binn_ir;
+
+use {
+    criterion::{BatchSize, Criterion, Throughput, criterion_group, criterion_main},
+
+    binn_ir::{
+        Value,
+        bench_utils,
+    },
+};
+
+/// # Encodes a value into new buffer
+fn encode(value: &Value) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(value.size().unwrap() as usize);
+    value.encode(&mut buf).unwrap();
+    buf
+}
+
+/// # Runs size/encode/decode benchmarks on a value
+fn bench_value(c: &mut Criterion, name: &str, value: &Value) {
+    let buf = encode(value);
+
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Bytes(buf.len() as u64));
+    group.bench_function("size", |b| b.iter(|| value.size().unwrap()));
+    group.bench_function("encode", |b| b.iter_batched_ref(
+        || Vec::with_capacity(buf.len()), |out| value.encode(out).unwrap(), BatchSize::LargeInput,
+    ));
+    group.bench_function("decode", |b| b.iter(|| binn_ir::decode(&mut &buf[..]).unwrap()));
+    group.finish();
+}
+
+fn small_scalars(c: &mut Criterion) {
+    let scalars = bench_utils::small_scalars(1_000);
+    let buf = scalars.iter().flat_map(encode).collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("small-scalars");
+    group.throughput(Throughput::Bytes(buf.len() as u64));
+    group.bench_function("encode", |b| b.iter_batched_ref(
+        || Vec::with_capacity(buf.len()),
+        |out| for value in scalars.iter() {
+            value.encode(out).unwrap();
+        },
+        BatchSize::SmallInput,
+    ));
+    group.bench_function("decode", |b| b.iter(|| {
+        let mut source = &buf[..];
+        while binn_ir::decode(&mut source).unwrap().is_some() {}
+    }));
+    group.finish();
+}
+
+fn large_list(c: &mut Criterion) {
+    bench_value(c, "large-list", &bench_utils::large_list(100_000));
+}
+
+fn deep_nesting(c: &mut Criterion) {
+    bench_value(c, "deep-nesting", &bench_utils::deep_nesting(100));
+}
+
+fn big_blob(c: &mut Criterion) {
+    bench_value(c, "big-blob", &bench_utils::big_blob(16 * 1024 * 1024));
+}
+
+fn object_heavy(c: &mut Criterion) {
+    bench_value(c, "object-heavy", &bench_utils::object_heavy(1_000, 16));
+}
+
+criterion_group!(benches, small_scalars, large_list, deep_nesting, big_blob, object_heavy);
+criterion_main!(benches);
@@ -0,0 +1,105 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Benchmarks of serde support, against the `Value` path
+//!
+//! Run with:
+//!
+//! ```shell
+//! cargo bench --features=serde --bench serde [-- criterion options...]
+//! ```
+
+extern crate binn_ir;
+
+use {
+    core::convert::TryFrom,
+
+    criterion::{BatchSize, Criterion, Throughput, criterion_group, criterion_main},
+    serde::{Deserialize, Serialize},
+
+    binn_ir::{List, Object, Value},
+};
+
+#[derive(Serialize, Deserialize)]
+struct Record {
+    id: u64,
+    name: String,
+    score: f64,
+    active: bool,
+    tags: Vec<String>,
+}
+
+impl Record {
+
+    /// # Converts this record into a value
+    fn to_value(&self) -> Value {
+        let mut object = Object::new();
+        binn_ir::object_insert(&mut object, "id", self.id);
+        binn_ir::object_insert(&mut object, "name", self.name.as_str());
+        binn_ir::object_insert(&mut object, "score", self.score);
+        binn_ir::object_insert(&mut object, "active", self.active);
+        binn_ir::object_insert(&mut object, "tags", self.tags.iter().map(|tag| Value::from(tag.as_str())).collect::<List>());
+        object.into()
+    }
+
+    /// # Converts a value into a record
+    fn from_value(value: Value) -> Option<Self> {
+        let mut object = Object::try_from(value).ok()?;
+        let mut take = |key: &str| object.remove(key);
+        Some(Self {
+            id: take("id")?.as_u64_lossless().ok()?,
+            name: String::try_from(take("name")?).ok()?,
+            score: take("score")?.as_f64_lossy().ok()?,
+            active: bool::try_from(take("active")?).ok()?,
+            tags: List::try_from(take("tags")?).ok()?.into_iter().map(|tag| String::try_from(tag).ok()).collect::<Option<_>>()?,
+        })
+    }
+
+}
+
+/// # Only ID of a record
+#[derive(Deserialize)]
+struct RecordId {
+    #[allow(dead_code)]
+    id: u64,
+}
+
+/// # Makes sample records
+fn records(count: u64) -> Vec<Record> {
+    (0..count).map(|id| Record {
+        id,
+        name: format!("record-{}", id),
+        score: id as f64 / 3.0,
+        active: id % 2 == 0,
+        tags: (0..id % 4).map(|i| format!("tag-{}", i)).collect(),
+    }).collect()
+}
+
+fn records_to_value(records: &[Record]) -> Value {
+    records.iter().map(Record::to_value).collect::<List>().into()
+}
+
+fn records_from_value(value: Value) -> Vec<Record> {
+    List::try_from(value).unwrap().into_iter().map(|value| Record::from_value(value).unwrap()).collect()
+}
+
+fn records_benches(c: &mut Criterion) {
+    let records = records(10_000);
+    let mut buf = vec![];
+    binn_ir::to_writer(&mut buf, &records).unwrap();
+
+    let mut group = c.benchmark_group("records");
+    group.throughput(Throughput::Bytes(buf.len() as u64));
+    group.bench_function("encode/value", |b| b.iter_batched_ref(
+        || Vec::with_capacity(buf.len()), |out| records_to_value(&records).encode(out).unwrap(), BatchSize::LargeInput,
+    ));
+    group.bench_function("encode/serde", |b| b.iter_batched_ref(
+        || Vec::with_capacity(buf.len()), |out| binn_ir::to_writer(out, &records).unwrap(), BatchSize::LargeInput,
+    ));
+    group.bench_function("decode/value", |b| b.iter(|| records_from_value(binn_ir::decode(&mut &buf[..]).unwrap().unwrap())));
+    group.bench_function("decode/serde", |b| b.iter(|| binn_ir::from_reader::<Vec<Record>>(&mut &buf[..]).unwrap()));
+    group.bench_function("decode/serde-ids", |b| b.iter(|| binn_ir::from_reader::<Vec<RecordId>>(&mut &buf[..]).unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, records_benches);
+criterion_main!(benches);
@@ -0,0 +1,24 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Decodes arbitrary bytes
+//!
+//! Decoding must never panic. Every value decoded must be able to calculate its size, which must not exceed the bytes it was decoded from
+//! (sizes are allowed to be encoded in 4 bytes, even if they fit in 1 byte -- so the source can be larger).
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let mut source = data;
+    loop {
+        let remaining = source.len();
+        match binn_ir::decode(&mut source) {
+            Ok(Some(value)) => {
+                let size = value.size().expect("failed to calculate size of a decoded value");
+                assert!(size as usize <= remaining - source.len());
+            },
+            Ok(None) | Err(_) => break,
+        };
+    }
+});
@@ -0,0 +1,24 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Encodes structured values, then decodes them back
+//!
+//! Values are compared via their encoded bytes, since floating point values can be `NaN`.
+
+#![no_main]
+
+use {
+    binn_ir::Value,
+    libfuzzer_sys::fuzz_target,
+};
+
+fuzz_target!(|value: Value| {
+    let mut buf = vec![];
+    let size = value.encode(&mut buf).expect("failed to encode an arbitrary value");
+    assert_eq!(size as usize, buf.len());
+    assert_eq!(value.size().ok(), Some(size));
+
+    let decoded = binn_ir::decode(&mut &buf[..]).expect("failed to decode an encoded value").expect("missing value");
+    let mut decoded_buf = vec![];
+    decoded.encode(&mut decoded_buf).expect("failed to encode a decoded value");
+    assert_eq!(buf, decoded_buf);
+});
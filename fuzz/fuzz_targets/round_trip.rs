@@ -0,0 +1,26 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Round trip: decode → encode → decode
+//!
+//! Values are compared via their encoded bytes, since floating point values can be `NaN`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let value = match binn_ir::decode(&mut &data[..]) {
+        Ok(Some(value)) => value,
+        Ok(None) | Err(_) => return,
+    };
+
+    let mut buf = vec![];
+    let size = value.encode(&mut buf).expect("failed to encode a decoded value");
+    assert_eq!(size as usize, buf.len());
+    assert_eq!(value.size().ok(), Some(size));
+
+    let decoded = binn_ir::decode(&mut &buf[..]).expect("failed to decode an encoded value").expect("missing value");
+    let mut decoded_buf = vec![];
+    decoded.encode(&mut decoded_buf).expect("failed to encode a decoded value");
+    assert_eq!(buf, decoded_buf);
+});
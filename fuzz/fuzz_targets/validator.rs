@@ -0,0 +1,22 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Differential: validator vs. decoder
+//!
+//! If the decoder accepts the whole input as one value, the validator must not report any violations. The validator must never panic,
+//! and it only returns an error for containers nested too deeply -- which the decoder must reject too.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let mut source = data;
+    let decoded = matches!(binn_ir::decode(&mut source), Ok(Some(_))) && source.is_empty();
+
+    match binn_ir::validate(data) {
+        Ok(report) => if decoded {
+            assert!(report.is_valid(), "{:?}", report);
+        },
+        Err(err) => assert!(decoded == false, "{}", err),
+    };
+});
@@ -0,0 +1,26 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Build script
+//!
+//! - If `BINN_SRC_DIR` is set, it must point to a checkout of <https://github.com/liteserver/binn>. Its `src/binn.c` is compiled and linked
+//!   statically.
+//! - Otherwise, an installed `libbinn` is linked. Set `BINN_LIB_DIR` if it's not in a default search path.
+
+use std::{env, path::PathBuf};
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=BINN_SRC_DIR");
+    println!("cargo:rerun-if-env-changed=BINN_LIB_DIR");
+
+    if let Some(dir) = env::var_os("BINN_SRC_DIR") {
+        let src = PathBuf::from(dir).join("src");
+        println!("cargo:rerun-if-changed={}", src.join("binn.c").display());
+        cc::Build::new().file(src.join("binn.c")).include(&src).warnings(false).compile("binn");
+        return;
+    }
+
+    if let Some(dir) = env::var_os("BINN_LIB_DIR") {
+        println!("cargo:rustc-link-search=native={}", PathBuf::from(dir).display());
+    }
+    println!("cargo:rustc-link-lib=binn");
+}
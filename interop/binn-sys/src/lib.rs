@@ -0,0 +1,79 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Bindings to the reference C implementation of Binn
+//!
+//! Only functions which are needed by interop tests are declared. They work on raw buffers -- or opaque `binn` handles -- so there is no
+//! dependency on the layout of C structs.
+//!
+//! See `binn.h` for documentation. Positions of list items and object/map pairs start at `1`.
+
+#![allow(non_camel_case_types)]
+
+use std::os::raw::{c_char, c_int, c_void};
+
+/// # Opaque handle of a value which is being written
+#[repr(C)]
+pub struct binn {
+    _private: [u8; 0],
+}
+
+/// # C `BOOL`
+pub type BOOL = c_int;
+
+pub const BINN_NULL: c_int = 0x00;
+pub const BINN_TRUE: c_int = 0x01;
+pub const BINN_FALSE: c_int = 0x02;
+
+pub const BINN_UINT8: c_int = 0x20;
+pub const BINN_INT8: c_int = 0x21;
+pub const BINN_UINT16: c_int = 0x40;
+pub const BINN_INT16: c_int = 0x41;
+pub const BINN_UINT32: c_int = 0x60;
+pub const BINN_INT32: c_int = 0x61;
+pub const BINN_FLOAT32: c_int = 0x62;
+pub const BINN_UINT64: c_int = 0x80;
+pub const BINN_INT64: c_int = 0x81;
+pub const BINN_FLOAT64: c_int = 0x82;
+
+pub const BINN_STRING: c_int = 0xA0;
+pub const BINN_DATETIME: c_int = 0xA1;
+pub const BINN_DATE: c_int = 0xA2;
+pub const BINN_TIME: c_int = 0xA3;
+pub const BINN_DECIMAL: c_int = 0xA4;
+
+pub const BINN_BLOB: c_int = 0xC0;
+
+pub const BINN_LIST: c_int = 0xE0;
+pub const BINN_MAP: c_int = 0xE1;
+pub const BINN_OBJECT: c_int = 0xE2;
+
+/// # Max length of an object key, not counting the null terminator
+pub const BINN_MAX_KEY_LEN: usize = 255;
+
+extern "C" {
+
+    pub fn binn_list() -> *mut binn;
+    pub fn binn_map() -> *mut binn;
+    pub fn binn_object() -> *mut binn;
+    pub fn binn_free(item: *mut binn);
+
+    pub fn binn_list_add(list: *mut binn, type_: c_int, pvalue: *mut c_void, size: c_int) -> BOOL;
+    pub fn binn_map_set(map: *mut binn, id: c_int, type_: c_int, pvalue: *mut c_void, size: c_int) -> BOOL;
+    pub fn binn_object_set(obj: *mut binn, key: *const c_char, type_: c_int, pvalue: *mut c_void, size: c_int) -> BOOL;
+
+    /// # Works on both handles and buffers
+    pub fn binn_ptr(ptr: *mut c_void) -> *mut c_void;
+    /// # Works on both handles and buffers
+    pub fn binn_size(ptr: *mut c_void) -> c_int;
+    /// # Works on both handles and buffers
+    pub fn binn_type(ptr: *mut c_void) -> c_int;
+    /// # Works on both handles and buffers
+    pub fn binn_count(ptr: *mut c_void) -> c_int;
+
+    pub fn binn_is_valid(ptr: *mut c_void, ptype: *mut c_int, pcount: *mut c_int, psize: *mut c_int) -> BOOL;
+
+    pub fn binn_list_read(list: *mut c_void, pos: c_int, ptype: *mut c_int, psize: *mut c_int) -> *mut c_void;
+    pub fn binn_map_read_pair(ptr: *mut c_void, pos: c_int, pid: *mut c_int, ptype: *mut c_int, psize: *mut c_int) -> *mut c_void;
+    pub fn binn_object_read_pair(ptr: *mut c_void, pos: c_int, pkey: *mut c_char, ptype: *mut c_int, psize: *mut c_int) -> *mut c_void;
+
+}
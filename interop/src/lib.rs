@@ -0,0 +1,238 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Interop with the reference C implementation of Binn
+//!
+//! - [`encode_with_c()`][fn:encode_with_c] encodes a value via the C library.
+//! - [`decode_with_c()`][fn:decode_with_c] decodes a buffer via the C library.
+//! - [`normalize()`][fn:normalize] makes values comparable, whichever implementation encoded them.
+//!
+//! [fn:encode_with_c]: fn.encode_with_c.html
+//! [fn:decode_with_c]: fn.decode_with_c.html
+//! [fn:normalize]: fn.normalize.html
+
+#![cfg(feature="c-interop-tests")]
+#![warn(missing_docs)]
+
+use {
+    std::{
+        ffi::{CStr, CString},
+        os::raw::{c_char, c_int, c_void},
+        ptr, slice,
+    },
+
+    binn_ir::{List, Map, Object, ObjectKey, Value},
+    binn_sys::*,
+};
+
+/// # Result type
+pub type Result<T> = std::result::Result<T, String>;
+
+/// # Encodes a container via the C library
+///
+/// The C library can only make buffers of containers. So an error is returned for other values.
+pub fn encode_with_c(value: &Value) -> Result<Vec<u8>> {
+    unsafe {
+        let item = new_container(value)?;
+        let result = match item.is_null() {
+            true => Err(String::from("failed to make new container")),
+            false => {
+                let (ptr, size) = (binn_ptr(item as *mut c_void), binn_size(item as *mut c_void));
+                Ok(slice::from_raw_parts(ptr as *const u8, size as usize).to_vec())
+            },
+        };
+        binn_free(item);
+        result
+    }
+}
+
+/// # Decodes a buffer via the C library
+pub fn decode_with_c(bytes: &[u8]) -> Result<Value> {
+    // C API takes mutable pointers, even for reading
+    let mut bytes = bytes.to_vec();
+    let ptr = bytes.as_mut_ptr() as *mut c_void;
+    unsafe {
+        let (mut type_, mut count, mut size) = (0, 0, 0);
+        match binn_is_valid(ptr, &mut type_, &mut count, &mut size) != 0 && size as usize == bytes.len() {
+            true => read(type_, ptr, size),
+            false => Err(format!("C library rejected buffer: {:02x?}", bytes)),
+        }
+    }
+}
+
+/// # Normalizes a value
+///
+/// The C implementation compresses integers into smallest types. So all integers are converted into [`U64`][Value#U64] if they're
+/// not negative, or [`I64`][Value#I64] otherwise. Containers are normalized recursively.
+///
+/// [Value#U64]: ../binn_ir/enum.Value.html#variant.U64
+/// [Value#I64]: ../binn_ir/enum.Value.html#variant.I64
+pub fn normalize(value: &Value) -> Value {
+    fn int(n: i128) -> Value {
+        match n >= 0 {
+            true => Value::U64(n as u64),
+            false => Value::I64(n as i64),
+        }
+    }
+
+    match value {
+        Value::U8(n) => int(i128::from(*n)),
+        Value::I8(n) => int(i128::from(*n)),
+        Value::U16(n) => int(i128::from(*n)),
+        Value::I16(n) => int(i128::from(*n)),
+        Value::U32(n) => int(i128::from(*n)),
+        Value::I32(n) => int(i128::from(*n)),
+        Value::U64(n) => int(i128::from(*n)),
+        Value::I64(n) => int(i128::from(*n)),
+        Value::List(list) => Value::List(list.iter().map(normalize).collect()),
+        Value::Map(map) => Value::Map(map.iter().map(|(k, v)| (*k, normalize(v))).collect()),
+        Value::Object(object) => Value::Object(object.iter().map(|(k, v)| (k.clone(), normalize(v))).collect()),
+        _ => value.clone(),
+    }
+}
+
+/// # Makes new C container from a value
+///
+/// Caller must free the result.
+unsafe fn new_container(value: &Value) -> Result<*mut binn> {
+    let (item, result) = match value {
+        Value::List(list) => {
+            let item = binn_list();
+            (item, list.iter().try_for_each(|v| add(v, |type_, pvalue, size| binn_list_add(item, type_, pvalue, size))))
+        },
+        Value::Map(map) => {
+            let item = binn_map();
+            (item, map.iter().try_for_each(|(k, v)| add(v, |type_, pvalue, size| binn_map_set(item, *k, type_, pvalue, size))))
+        },
+        Value::Object(object) => {
+            let item = binn_object();
+            let result = object.iter().try_for_each(|(k, v)| {
+                let key = c_string(k.as_str())?;
+                add(v, |type_, pvalue, size| binn_object_set(item, key.as_ptr(), type_, pvalue, size))
+            });
+            (item, result)
+        },
+        _ => return Err(format!("not a container: {:?}", value)),
+    };
+    match result {
+        Ok(()) => Ok(item),
+        Err(err) => {
+            binn_free(item);
+            Err(err)
+        },
+    }
+}
+
+/// # Adds a value into a container, via a C function
+unsafe fn add<F>(value: &Value, f: F) -> Result<()> where F: FnOnce(c_int, *mut c_void, c_int) -> BOOL {
+    macro_rules! add_number { ($type: expr, $n: expr) => {{
+        let mut n = *$n;
+        f($type, &mut n as *mut _ as *mut c_void, 0)
+    }}}
+    macro_rules! add_str { ($type: expr, $s: expr) => {{
+        let s = c_string($s)?;
+        f($type, s.as_ptr() as *mut c_void, 0)
+    }}}
+
+    let ok = match value {
+        Value::Null => f(BINN_NULL, ptr::null_mut(), 0),
+        Value::True => f(BINN_TRUE, ptr::null_mut(), 0),
+        Value::False => f(BINN_FALSE, ptr::null_mut(), 0),
+        Value::U8(n) => add_number!(BINN_UINT8, n),
+        Value::I8(n) => add_number!(BINN_INT8, n),
+        Value::U16(n) => add_number!(BINN_UINT16, n),
+        Value::I16(n) => add_number!(BINN_INT16, n),
+        Value::U32(n) => add_number!(BINN_UINT32, n),
+        Value::I32(n) => add_number!(BINN_INT32, n),
+        Value::U64(n) => add_number!(BINN_UINT64, n),
+        Value::I64(n) => add_number!(BINN_INT64, n),
+        Value::Float(n) => add_number!(BINN_FLOAT32, n),
+        Value::Double(n) => add_number!(BINN_FLOAT64, n),
+        Value::Text(s) => add_str!(BINN_STRING, s),
+        Value::DateTime(s) => add_str!(BINN_DATETIME, s),
+        Value::Date(s) => add_str!(BINN_DATE, s),
+        Value::Time(s) => add_str!(BINN_TIME, s),
+        Value::DecimalStr(s) => add_str!(BINN_DECIMAL, s),
+        Value::Blob(blob) => f(BINN_BLOB, blob.as_ptr() as *mut c_void, blob.len() as c_int),
+        Value::List(_) | Value::Map(_) | Value::Object(_) => {
+            let item = new_container(value)?;
+            let ok = f(binn_type(item as *mut c_void), binn_ptr(item as *mut c_void), binn_size(item as *mut c_void));
+            binn_free(item);
+            ok
+        },
+    };
+    match ok != 0 {
+        true => Ok(()),
+        false => Err(format!("C library failed to add: {:?}", value)),
+    }
+}
+
+/// # Makes new C string
+fn c_string(s: &str) -> Result<CString> {
+    CString::new(s).map_err(|err| err.to_string())
+}
+
+/// # Reads a value, which has been read from a C container
+unsafe fn read(type_: c_int, ptr: *mut c_void, size: c_int) -> Result<Value> {
+    /// # Extended type of booleans, which the C library converts `BINN_TRUE` and `BINN_FALSE` into
+    const BINN_BOOL: c_int = 0x80061;
+
+    if ptr.is_null() && !matches!(type_, BINN_NULL | BINN_TRUE | BINN_FALSE) {
+        return Err(format!("missing data of type: 0x{:02x}", type_));
+    }
+
+    let string = || String::from_utf8(slice::from_raw_parts(ptr as *const u8, size as usize).to_vec()).map_err(|err| err.to_string());
+    Ok(match type_ {
+        BINN_NULL => Value::Null,
+        BINN_TRUE => Value::True,
+        BINN_FALSE => Value::False,
+        BINN_BOOL => match *(ptr as *const c_int) != 0 {
+            true => Value::True,
+            false => Value::False,
+        },
+        BINN_UINT8 => Value::U8(*(ptr as *const u8)),
+        BINN_INT8 => Value::I8(*(ptr as *const i8)),
+        BINN_UINT16 => Value::U16(*(ptr as *const u16)),
+        BINN_INT16 => Value::I16(*(ptr as *const i16)),
+        BINN_UINT32 => Value::U32(*(ptr as *const u32)),
+        BINN_INT32 => Value::I32(*(ptr as *const i32)),
+        BINN_FLOAT32 => Value::Float(*(ptr as *const f32)),
+        BINN_UINT64 => Value::U64(*(ptr as *const u64)),
+        BINN_INT64 => Value::I64(*(ptr as *const i64)),
+        BINN_FLOAT64 => Value::Double(*(ptr as *const f64)),
+        BINN_STRING => Value::Text(string()?),
+        BINN_DATETIME => Value::DateTime(string()?),
+        BINN_DATE => Value::Date(string()?),
+        BINN_TIME => Value::Time(string()?),
+        BINN_DECIMAL => Value::DecimalStr(string()?),
+        BINN_BLOB => Value::Blob(slice::from_raw_parts(ptr as *const u8, size as usize).to_vec()),
+        BINN_LIST => {
+            let mut list = List::new();
+            for pos in 1..=binn_count(ptr) {
+                let (mut type_, mut size) = (0, 0);
+                let item = binn_list_read(ptr, pos, &mut type_, &mut size);
+                list.push(read(type_, item, size)?);
+            }
+            Value::List(list)
+        },
+        BINN_MAP => {
+            let mut map = Map::new();
+            for pos in 1..=binn_count(ptr) {
+                let (mut id, mut type_, mut size) = (0, 0, 0);
+                let item = binn_map_read_pair(ptr, pos, &mut id, &mut type_, &mut size);
+                map.insert(id, read(type_, item, size)?);
+            }
+            Value::Map(map)
+        },
+        BINN_OBJECT => {
+            let mut object = Object::new();
+            for pos in 1..=binn_count(ptr) {
+                let (mut key, mut type_, mut size) = ([0 as c_char; BINN_MAX_KEY_LEN + 1], 0, 0);
+                let item = binn_object_read_pair(ptr, pos, key.as_mut_ptr(), &mut type_, &mut size);
+                let key = CStr::from_ptr(key.as_ptr()).to_str().map_err(|err| err.to_string())?;
+                object.insert(ObjectKey::from(key), read(type_, item, size)?);
+            }
+            Value::Object(object)
+        },
+        _ => return Err(format!("unsupported type: 0x{:02x}", type_)),
+    })
+}
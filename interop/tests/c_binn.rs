@@ -0,0 +1,90 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Cross-checks with the C implementation
+
+#![cfg(feature="c-interop-tests")]
+
+use {
+    binn_ir::{CompatLevel, Map, Object, Value},
+    binn_ir_interop::{decode_with_c, encode_with_c, normalize},
+};
+
+/// # Examples from the specification
+const SPEC_EXAMPLES: &[&[u8]] = &[
+    b"\xE2\x11\x01\x05hello\xA0\x05world\x00",
+    b"\xE0\x0B\x03\x20\x7B\x41\xFE\x38\x40\x03\x15",
+    b"\xE1\x1A\x02\x00\x00\x00\x01\xA0\x03add\x00\x00\x00\x00\x02\xE0\x09\x02\x41\xCF\xC7\x40\x1A\x85",
+    b"\xE0\x2B\x02\xE2\x14\x02\x02id\x20\x01\x04name\xA0\x04John\x00\xE2\x14\x02\x02id\x20\x02\x04name\xA0\x04Eric\x00",
+];
+
+/// # Sample values
+fn samples() -> Vec<Value> {
+    let scalars = vec![
+        Value::Null, Value::True, Value::False,
+        Value::U8(u8::MAX), Value::I8(i8::MIN), Value::U16(u16::MAX), Value::I16(i16::MIN),
+        Value::U32(u32::MAX), Value::I32(i32::MIN), Value::U64(u64::MAX), Value::I64(i64::MIN),
+        Value::U64(1), Value::I64(-1), Value::I32(300), Value::U64(70_000),
+        Value::Float(-1.5), Value::Double(1e300),
+        Value::Text(String::new()), Value::Text("Binn".repeat(100)), Value::Text("tiếng Việt".into()),
+        Value::DateTime("2021-03-14T00:00:00Z".into()), Value::Date("2021-03-14".into()), Value::Time("13:14:15".into()),
+        Value::DecimalStr("-1.5e-10".into()),
+        Value::Blob(vec![]), Value::Blob((0..=255).collect()), Value::Blob(vec![7; 1000]),
+    ];
+
+    let map = scalars.iter().cloned().enumerate().map(|(i, v)| (i as i32 - 3, v)).collect::<Map>();
+    let object = scalars.iter().cloned().enumerate().map(|(i, v)| (format!("key-{}", i), v)).collect::<Object>();
+    let nested = (0..16).fold(Value::List(vec![]), |value, i| match i % 3 {
+        0 => Value::List(vec![value, Value::U8(i)]),
+        1 => binn_ir::map_from(i32::from(i), value),
+        _ => binn_ir::object_from("-".repeat(255), value),
+    });
+
+    vec![
+        Value::List(vec![]), binn_ir::map(), binn_ir::object(),
+        Value::List(scalars), Value::Map(map), Value::Object(object), nested,
+    ]
+}
+
+#[test]
+fn encoded_by_rust_decoded_by_c() {
+    for value in samples() {
+        let mut buf = vec![];
+        value.encode(&mut buf).unwrap();
+        assert_eq!(normalize(&decode_with_c(&buf).unwrap()), normalize(&value), "{:02x?}", buf);
+    }
+}
+
+#[test]
+fn encoded_by_c_decoded_by_rust() {
+    for value in samples() {
+        let buf = encode_with_c(&value).unwrap();
+        assert_eq!(normalize(&binn_ir::decode(&mut &buf[..]).unwrap().unwrap()), normalize(&value), "{:02x?}", buf);
+    }
+}
+
+#[test]
+fn same_bytes_with_compat_level_c_binn() {
+    for value in samples() {
+        let mut buf = vec![];
+        value.encode_with_compat(&mut buf, CompatLevel::CBinn).unwrap();
+        assert_eq!(buf, encode_with_c(&value).unwrap(), "{:?}", value);
+    }
+}
+
+#[test]
+fn spec_examples() {
+    for bytes in SPEC_EXAMPLES {
+        let value = binn_ir::decode(&mut &bytes[..]).unwrap().unwrap();
+        assert_eq!(normalize(&decode_with_c(bytes).unwrap()), normalize(&value));
+        assert_eq!(encode_with_c(&value).unwrap().len(), bytes.len());
+    }
+}
+
+#[test]
+fn invalid_data() {
+    for bytes in SPEC_EXAMPLES {
+        let bytes = &bytes[..bytes.len() - 1];
+        assert!(decode_with_c(bytes).is_err());
+        assert!(binn_ir::decode(&mut &bytes[..]).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Appending to encoded containers
+
+use {
+    alloc::vec::Vec,
+    core::convert::TryFrom,
+    std::io::{self, ErrorKind},
+
+    crate::{
+        Header, IoResult, MapKey, Size, Value,
+        value_enum::{HeaderBytes, size_of_container, size_of_object_key},
+    },
+};
+
+/// # Appends an item to an encoded list
+///
+/// The buffer must hold exactly one encoded list. The item is encoded at the end of the buffer, then size and item count of the list are
+/// patched in place. Other items are neither decoded nor moved -- except when the size or item count grows from 1 byte to 4 bytes, in
+/// which case they are shifted by 3 bytes.
+///
+/// Result: new size of the list.
+///
+/// ## Errors
+///
+/// If the buffer does not hold exactly one list, or the list becomes too large, an error of
+/// [`InvalidData`][io::ErrorKind#InvalidData] is returned. On errors, the buffer is left unchanged.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Encoder, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// buf.encode_list(vec![Value::U8(0)])?;
+///
+/// binn_ir::append_to_list(&mut buf, 1_u8)?;
+/// binn_ir::append_to_list(&mut buf, "two")?;
+/// assert_eq!(
+///     binn_ir::decode_list(&mut &buf[..])?,
+///     Some(vec![Value::U8(0), Value::U8(1), Value::from("two")]),
+/// );
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [io::ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+pub fn append_to_list<V>(buf: &mut Vec<u8>, value: V) -> IoResult<Size> where V: Into<Value> {
+    let header = container_header(buf, crate::value::LIST)?;
+
+    let mut item = Vec::new();
+    value.into().encode(&mut item)?;
+    append_item(buf, &header, &item)
+}
+
+/// # Appends an item to an encoded map
+///
+/// This works like [`append_to_list()`][fn:append_to_list]. Existing keys are scanned -- their values are skipped, not decoded -- to reject
+/// duplicate keys.
+///
+/// [fn:append_to_list]: fn.append_to_list.html
+pub fn append_to_map<K, V>(buf: &mut Vec<u8>, key: K, value: V) -> IoResult<Size> where K: Into<MapKey>, V: Into<Value> {
+    let header = container_header(buf, crate::value::MAP)?;
+    let key = key.into();
+
+    let mut items = &buf[header.size() as usize..];
+    while !items.is_empty() {
+        if items.len() < core::mem::size_of::<MapKey>() {
+            return Err(io::Error::new(ErrorKind::InvalidData, __!("truncated map key")));
+        }
+        if MapKey::from_be_bytes([items[0], items[1], items[2], items[3]]) == key {
+            return Err(io::Error::new(ErrorKind::InvalidData, __!("duplicate key: {}", key)));
+        }
+        items = &items[core::mem::size_of::<MapKey>()..];
+        skip_item(&mut items)?;
+    }
+
+    let mut item = key.to_be_bytes().to_vec();
+    value.into().encode(&mut item)?;
+    append_item(buf, &header, &item)
+}
+
+/// # Appends an item to an encoded object
+///
+/// This works like [`append_to_list()`][fn:append_to_list]. Existing keys are scanned -- their values are skipped, not decoded -- to reject
+/// duplicate keys.
+///
+/// ## Notes
+///
+/// The key is appended after existing ones. So unlike [`Value::encode()`][enum:Value#encode()], keys of the resulting object might not be
+/// sorted. Decoders don't rely on the order.
+///
+/// [fn:append_to_list]: fn.append_to_list.html
+/// [enum:Value#encode()]: enum.Value.html#method.encode
+pub fn append_to_object<K, V>(buf: &mut Vec<u8>, key: K, value: V) -> IoResult<Size> where K: AsRef<str>, V: Into<Value> {
+    let header = container_header(buf, crate::value::OBJECT)?;
+    let key = key.as_ref();
+    size_of_object_key(key)?;
+
+    let mut items = &buf[header.size() as usize..];
+    while let Some((len, rest)) = items.split_first() {
+        let len = usize::from(*len);
+        if rest.len() < len {
+            return Err(io::Error::new(ErrorKind::InvalidData, __!("truncated object key")));
+        }
+        if &rest[..len] == key.as_bytes() {
+            return Err(io::Error::new(ErrorKind::InvalidData, __!("duplicate key: {:?}", key)));
+        }
+        items = &rest[len..];
+        skip_item(&mut items)?;
+    }
+
+    let mut item = Vec::with_capacity(key.len() + 1);
+    item.push(key.len() as u8);
+    item.extend_from_slice(key.as_bytes());
+    value.into().encode(&mut item)?;
+    append_item(buf, &header, &item)
+}
+
+/// # Decodes header of the container which fills the whole buffer
+fn container_header(buf: &[u8], type_byte: u8) -> IoResult<Header> {
+    let header = match crate::decode_header(&mut &buf[..])? {
+        Some(header) => header,
+        None => return Err(io::Error::new(ErrorKind::InvalidData, __!("buffer is empty"))),
+    };
+    if header.type_byte() != type_byte {
+        return Err(io::Error::new(ErrorKind::InvalidData, __!("expected type 0x{:02x}, got: 0x{:02x}", type_byte, header.type_byte())));
+    }
+    match header.declared_size() {
+        Some(size) if size as usize == buf.len() => Ok(header),
+        size => Err(io::Error::new(ErrorKind::InvalidData, __!("declared size {:?} does not match buffer length {}", size, buf.len()))),
+    }
+}
+
+/// # Skips an item of a container
+pub(crate) fn skip_item(items: &mut &[u8]) -> IoResult<()> {
+    match crate::skip_value(items) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(io::Error::new(ErrorKind::InvalidData, __!("missing value"))),
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => Err(io::Error::new(ErrorKind::InvalidData, __!("truncated value"))),
+        Err(err) => Err(err),
+    }
+}
+
+/// # Appends an encoded item, then rewrites the container's header
+fn append_item(buf: &mut Vec<u8>, header: &Header, item: &[u8]) -> IoResult<Size> {
+    let count = header.item_count().unwrap_or_default().checked_add(1)
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, __!("too many items")))?;
+    let items = u64::from(header.declared_size().unwrap_or_default() - header.size()) + item.len() as u64;
+    let (size, new_header) = container_header_bytes(header.type_byte(), count, items)?;
+
+    let new_header = new_header.as_bytes();
+    buf.reserve(item.len() + new_header.len().saturating_sub(header.size() as usize));
+    buf.splice(..header.size() as usize, new_header.iter().copied());
+    buf.extend_from_slice(item);
+    Ok(size)
+}
+
+/// # Makes header of a container, from its item count, and total size of its items (including their keys)
+///
+/// Result: size of the whole container, and the header.
+pub(crate) fn container_header_bytes(type_byte: u8, count: Size, items: u64) -> IoResult<(Size, HeaderBytes)> {
+    let size = Size::try_from(items).ok().and_then(|items| size_of_container(count as usize, items).ok())
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, __!("data too large (bytes): {} item(s) of {} byte(s)", count, items)))?;
+    Ok((size, HeaderBytes::of_container(type_byte, size, count)))
+}
@@ -0,0 +1,394 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Arena decoding
+
+use {
+    alloc::string::String,
+    bumpalo::Bump,
+    core::{mem, str},
+    std::{
+        collections::HashSet,
+        io::{self, ErrorKind, Read},
+    },
+
+    crate::{
+        DepthLimitExceeded, IoResult, List, Map, MapKey, Object, ObjectKey, Size, Value,
+        storage::Storage,
+        value::{self, DEFAULT_MAX_DEPTH, OBJECT_KEY_MAX_LEN},
+        value_enum::{add_to_read, decode_fixed_value, read_map_key, read_object_key_into, verify_container_size},
+    },
+};
+
+/// # A value, whose strings, blobs and containers are allocated inside an arena
+///
+/// This is the result of [`decode_in()`][fn:decode_in]. Items of maps and objects are sorted by their keys, like [`Map`][type:Map] and
+/// [`Object`][type:Object].
+///
+/// [fn:decode_in]: fn.decode_in.html
+/// [type:Map]: type.Map.html
+/// [type:Object]: type.Object.html
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(missing_docs)]
+pub enum ArenaValue<'a> {
+    Null,
+    True,
+    False,
+    U8(u8),
+    I8(i8),
+    U16(u16),
+    I16(i16),
+    U32(u32),
+    I32(i32),
+    Float(f32),
+    U64(u64),
+    I64(i64),
+    Double(f64),
+    Text(&'a str),
+    DateTime(&'a str),
+    Date(&'a str),
+    Time(&'a str),
+    DecimalStr(&'a str),
+    Blob(&'a [u8]),
+    List(&'a [ArenaValue<'a>]),
+    Map(&'a [(MapKey, ArenaValue<'a>)]),
+    Object(&'a [(&'a str, ArenaValue<'a>)]),
+}
+
+impl<'a> ArenaValue<'a> {
+
+    /// # Gets an item of a map
+    ///
+    /// If this value is not a map, `None` is returned.
+    pub fn map_get(&self, key: MapKey) -> Option<&ArenaValue<'a>> {
+        match self {
+            ArenaValue::Map(map) => map.binary_search_by_key(&key, |(k, _)| *k).ok().map(|i| &map[i].1),
+            _ => None,
+        }
+    }
+
+    /// # Gets an item of an object
+    ///
+    /// If this value is not an object, `None` is returned.
+    pub fn object_get(&self, key: &str) -> Option<&ArenaValue<'a>> {
+        match self {
+            ArenaValue::Object(object) => object.binary_search_by_key(&key, |(k, _)| *k).ok().map(|i| &object[i].1),
+            _ => None,
+        }
+    }
+
+}
+
+impl From<&ArenaValue<'_>> for Value {
+
+    fn from(v: &ArenaValue) -> Self {
+        match v {
+            ArenaValue::Null => Value::Null,
+            ArenaValue::True => Value::True,
+            ArenaValue::False => Value::False,
+            ArenaValue::U8(u) => Value::U8(*u),
+            ArenaValue::I8(i) => Value::I8(*i),
+            ArenaValue::U16(u) => Value::U16(*u),
+            ArenaValue::I16(i) => Value::I16(*i),
+            ArenaValue::U32(u) => Value::U32(*u),
+            ArenaValue::I32(i) => Value::I32(*i),
+            ArenaValue::Float(f) => Value::Float(*f),
+            ArenaValue::U64(u) => Value::U64(*u),
+            ArenaValue::I64(i) => Value::I64(*i),
+            ArenaValue::Double(d) => Value::Double(*d),
+            ArenaValue::Text(t) => Value::Text(String::from(*t)),
+            ArenaValue::DateTime(dt) => Value::DateTime(String::from(*dt)),
+            ArenaValue::Date(d) => Value::Date(String::from(*d)),
+            ArenaValue::Time(t) => Value::Time(String::from(*t)),
+            ArenaValue::DecimalStr(ds) => Value::DecimalStr(String::from(*ds)),
+            ArenaValue::Blob(bytes) => Value::Blob(bytes.to_vec()),
+            ArenaValue::List(list) => Value::List(list.iter().map(Value::from).collect::<List>()),
+            ArenaValue::Map(map) => Value::Map(map.iter().map(|(k, v)| (*k, Value::from(v))).collect::<Map>()),
+            ArenaValue::Object(object) => Value::Object(object.iter().map(|(k, v)| (ObjectKey::from(*k), Value::from(v))).collect::<Object>()),
+        }
+    }
+
+}
+
+impl From<ArenaValue<'_>> for Value {
+
+    fn from(v: ArenaValue) -> Self {
+        Self::from(&v)
+    }
+
+}
+
+/// # Decodes a value, allocating all of its strings, blobs and containers inside an arena
+///
+/// When decoding lots of small documents, this cuts down allocations a lot: the arena can be [reset][bumpalo::Bump#reset] after each
+/// batch.
+///
+/// If it returns `Ok(None)`, it means there's no more data to decode.
+///
+/// ## Examples
+///
+/// ```
+/// use bumpalo::Bump;
+/// use binn_ir::{ArenaValue, Encoder};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// buf.encode(&binn_ir::object_from("name", "moss"))?;
+///
+/// let arena = Bump::new();
+/// let value = binn_ir::decode_in(&mut &buf[..], &arena)?.unwrap();
+/// assert_eq!(value.object_get("name"), Some(&ArenaValue::Text("moss")));
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [bumpalo::Bump#reset]: https://docs.rs/bumpalo/3/bumpalo/struct.Bump.html#method.reset
+pub fn decode_in<'a, R>(source: &mut R, arena: &'a Bump) -> IoResult<Option<ArenaValue<'a>>> where R: Read {
+    Ok(decode_value_in(source, arena, &mut Options { interner: None, max_depth: DEFAULT_MAX_DEPTH }, 0)?.map(|(value, _)| value))
+}
+
+/// # Decodes a value inside an arena, sharing object keys via an interner
+///
+/// This is the same as [`decode_in()`][fn:decode_in], except that each distinct object key is allocated only once, then shared by all
+/// objects -- including ones of later documents decoded with the same interner.
+///
+/// ## Examples
+///
+/// ```
+/// use bumpalo::Bump;
+/// use binn_ir::{Encoder, KeyInterner};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// for id in 0..9 {
+///     buf.encode(&binn_ir::object_from("id", id))?;
+/// }
+///
+/// let arena = Bump::new();
+/// let mut interner = KeyInterner::new();
+/// let mut source = &buf[..];
+/// while let Some(_) = binn_ir::decode_in_with_interner(&mut source, &arena, &mut interner)? {}
+/// assert_eq!(interner.len(), 1);
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [fn:decode_in]: fn.decode_in.html
+pub fn decode_in_with_interner<'a, R>(source: &mut R, arena: &'a Bump, interner: &mut KeyInterner<'a>) -> IoResult<Option<ArenaValue<'a>>>
+where R: Read {
+    Ok(decode_value_in(source, arena, &mut Options { interner: Some(interner), max_depth: DEFAULT_MAX_DEPTH }, 0)?.map(|(value, _)| value))
+}
+
+/// # Decodes a value inside an arena, with a custom limit of nested containers
+///
+/// This is the same as [`decode_in()`][fn:decode_in] -- or [`decode_in_with_interner()`][fn:decode_in_with_interner] if an interner is
+/// given -- except that containers can be nested at most `max_depth` levels deep, instead of [`DEFAULT_MAX_DEPTH`][value::DEFAULT_MAX_DEPTH].
+/// Exceeding the limit results in a [`DepthLimitExceeded`][struct:DepthLimitExceeded] error.
+///
+/// ## Examples
+///
+/// ```
+/// use bumpalo::Bump;
+/// use binn_ir::{DepthLimitExceeded, Encoder, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// buf.encode(&Value::List(vec![Value::List(vec![])]))?;
+///
+/// let arena = Bump::new();
+/// let err = binn_ir::decode_in_with_max_depth(&mut &buf[..], &arena, None, 1).unwrap_err();
+/// assert_eq!(DepthLimitExceeded::find(&err).map(|e| e.max_depth()), Some(1));
+/// assert!(binn_ir::decode_in_with_max_depth(&mut &buf[..], &arena, None, 2)?.is_some());
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [fn:decode_in]: fn.decode_in.html
+/// [fn:decode_in_with_interner]: fn.decode_in_with_interner.html
+/// [value::DEFAULT_MAX_DEPTH]: value/constant.DEFAULT_MAX_DEPTH.html
+/// [struct:DepthLimitExceeded]: struct.DepthLimitExceeded.html
+pub fn decode_in_with_max_depth<'a, R>(source: &mut R, arena: &'a Bump, interner: Option<&mut KeyInterner<'a>>, max_depth: usize)
+-> IoResult<Option<ArenaValue<'a>>> where R: Read {
+    Ok(decode_value_in(source, arena, &mut Options { interner, max_depth }, 0)?.map(|(value, _)| value))
+}
+
+/// # Interner of object keys, for [`decode_in_with_interner()`][fn:decode_in_with_interner]
+///
+/// [fn:decode_in_with_interner]: fn.decode_in_with_interner.html
+#[derive(Debug, Default)]
+pub struct KeyInterner<'a> {
+    keys: HashSet<&'a str>,
+}
+
+impl<'a> KeyInterner<'a> {
+
+    /// # Makes new interner
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Number of distinct keys
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// # Checks if there are no keys
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// # Interns a key
+    fn intern(&mut self, key: &str, arena: &'a Bump) -> &'a str {
+        match self.keys.get(key) {
+            Some(key) => key,
+            None => {
+                let key = arena.alloc_str(key);
+                self.keys.insert(key);
+                key
+            },
+        }
+    }
+
+}
+
+/// # Options of decoding
+struct Options<'i, 'a> {
+    interner: Option<&'i mut KeyInterner<'a>>,
+    max_depth: usize,
+}
+
+/// # Decodes a value
+///
+/// Result: the value, and its encoded size.
+fn decode_value_in<'a, R>(source: &mut R, arena: &'a Bump, options: &mut Options<'_, 'a>, depth: usize)
+-> IoResult<Option<(ArenaValue<'a>, u64)>> where R: Read {
+    let header = match crate::decode_header(source)? {
+        Some(header) => header,
+        None => return Ok(None),
+    };
+    if header.second_type_byte().is_some() {
+        return Err(io::Error::new(ErrorKind::InvalidData, __!("user defined types are not supported: {}", header.type_byte())));
+    }
+
+    let type_byte = header.type_byte();
+    let value = match (header.storage(), header.declared_size(), header.item_count()) {
+        (Storage::String, Some(size), _) => {
+            let s = read_str_in(size, source, arena)?;
+            match read_u8(source)? {
+                0 => (),
+                other => return Err(io::Error::new(ErrorKind::InvalidData, __!("expected to read a null terminator ('\\0'), got: {}", &other))),
+            };
+            match type_byte {
+                value::TEXT => ArenaValue::Text(s),
+                value::DATE_TIME => ArenaValue::DateTime(s),
+                value::DATE => ArenaValue::Date(s),
+                value::TIME => ArenaValue::Time(s),
+                value::DECIMAL_STR => ArenaValue::DecimalStr(s),
+                _ => return Err(io::Error::new(ErrorKind::InvalidData, __!("data type is either invalid or not supported: {}", &type_byte))),
+            }
+        },
+        (Storage::Blob, Some(size), _) => match type_byte {
+            value::BLOB => ArenaValue::Blob(read_bytes_in(size, source, arena)?),
+            _ => return Err(io::Error::new(ErrorKind::InvalidData, __!("data type is either invalid or not supported: {}", &type_byte))),
+        },
+        (Storage::Container, Some(size), Some(item_count)) => {
+            if depth >= options.max_depth {
+                return Err(DepthLimitExceeded::new_io_error(options.max_depth));
+            }
+            let mut read = u64::from(header.size());
+            let value = match type_byte {
+                value::LIST => {
+                    let mut list = bumpalo::collections::Vec::with_capacity_in(item_count.min(size) as usize, arena);
+                    for item_index in 0..item_count {
+                        list.push(decode_item_in(source, arena, options, size, &mut read, item_index, depth + 1)?);
+                    }
+                    ArenaValue::List(list.into_bump_slice())
+                },
+                value::MAP => {
+                    let mut map = bumpalo::collections::Vec::with_capacity_in(item_count.min(size) as usize, arena);
+                    for item_index in 0..item_count {
+                        let key = read_map_key(source)?;
+                        add_to_read(&mut read, mem::size_of::<MapKey>() as u64, size)?;
+                        map.push((key, decode_item_in(source, arena, options, size, &mut read, item_index, depth + 1)?));
+                    }
+                    map.sort_unstable_by_key(|(k, _)| *k);
+                    if let Some(pair) = map.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+                        return Err(io::Error::new(ErrorKind::InvalidData, __!("duplicate key: {}", &pair[0].0)));
+                    }
+                    ArenaValue::Map(map.into_bump_slice())
+                },
+                value::OBJECT => {
+                    let mut object = bumpalo::collections::Vec::with_capacity_in(item_count.min(size) as usize, arena);
+                    let mut key_buf = [0_u8; OBJECT_KEY_MAX_LEN];
+                    for item_index in 0..item_count {
+                        let (key, bytes_of_key) = read_object_key_into(source, &mut key_buf)?;
+                        let key = match &mut options.interner {
+                            Some(interner) => interner.intern(key, arena),
+                            None => arena.alloc_str(key),
+                        };
+                        add_to_read(&mut read, u64::from(bytes_of_key), size)?;
+                        object.push((key, decode_item_in(source, arena, options, size, &mut read, item_index, depth + 1)?));
+                    }
+                    object.sort_unstable_by_key(|(k, _)| *k);
+                    if let Some(pair) = object.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+                        return Err(io::Error::new(ErrorKind::InvalidData, __!("duplicate key: {:?}", &pair[0].0)));
+                    }
+                    ArenaValue::Object(object.into_bump_slice())
+                },
+                _ => return Err(io::Error::new(ErrorKind::InvalidData, __!("data type is either invalid or not supported: {}", &type_byte))),
+            };
+            verify_container_size(read, size)?;
+            value
+        },
+        (storage, _, _) => match decode_fixed_value(type_byte, storage, source)? {
+            Value::Null => ArenaValue::Null,
+            Value::True => ArenaValue::True,
+            Value::False => ArenaValue::False,
+            Value::U8(u) => ArenaValue::U8(u),
+            Value::I8(i) => ArenaValue::I8(i),
+            Value::U16(u) => ArenaValue::U16(u),
+            Value::I16(i) => ArenaValue::I16(i),
+            Value::U32(u) => ArenaValue::U32(u),
+            Value::I32(i) => ArenaValue::I32(i),
+            Value::Float(f) => ArenaValue::Float(f),
+            Value::U64(u) => ArenaValue::U64(u),
+            Value::I64(i) => ArenaValue::I64(i),
+            Value::Double(d) => ArenaValue::Double(d),
+            _ => return Err(io::Error::new(ErrorKind::InvalidData, __!("data type is either invalid or not supported: {}", &type_byte))),
+        },
+    };
+
+    Ok(Some((value, header.value_size())))
+}
+
+/// # Decodes an item of a container
+fn decode_item_in<'a, R>(
+    source: &mut R, arena: &'a Bump, options: &mut Options<'_, 'a>, size: Size, read: &mut u64, item_index: Size, depth: usize,
+) -> IoResult<ArenaValue<'a>> where R: Read {
+    match decode_value_in(source, arena, options, depth)? {
+        Some((value, value_size)) => {
+            add_to_read(read, value_size, size)?;
+            Ok(value)
+        },
+        None => Err(io::Error::new(ErrorKind::InvalidData, __!("missing item #{}", &item_index))),
+    }
+}
+
+/// # Reads a `u8`
+fn read_u8<R>(source: &mut R) -> IoResult<u8> where R: Read {
+    let mut buf = [0];
+    source.read_exact(&mut buf).map(|()| buf[0])
+}
+
+/// # Reads some bytes into the arena
+fn read_bytes_in<'a, R>(len: Size, source: &mut R, arena: &'a Bump) -> IoResult<&'a [u8]> where R: Read {
+    let result = arena.alloc_slice_fill_copy(len as usize, 0_u8);
+    source.read_exact(result)?;
+    Ok(result)
+}
+
+/// # Reads a string into the arena
+fn read_str_in<'a, R>(len: Size, source: &mut R, arena: &'a Bump) -> IoResult<&'a str> where R: Read {
+    str::from_utf8(read_bytes_in(len, source, arena)?).map_err(|err|
+        io::Error::new(ErrorKind::InvalidData, __!("failed to decode UTF-8: {}", &err))
+    )
+}
@@ -0,0 +1,139 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Async decoder
+
+use {
+    core::{
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll},
+    },
+    std::io::{self, ErrorKind},
+
+    futures_io::AsyncRead,
+
+    crate::{DecodeConfig, IoResult, PartialDecoder, Value},
+};
+
+/// # Size of a chunk to read from source
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// # An async decoder
+///
+/// This decoder reads from an [`AsyncRead`][futures_io/AsyncRead] source. It buffers partially-read values internally, via a
+/// [`PartialDecoder`][struct:PartialDecoder].
+///
+/// ## Cancellation safety
+///
+/// Futures returned by [`decode()`][#decode()] are cancellation-safe: bytes are moved into the decoder's buffer as soon as they are read. So
+/// dropping a future in the middle of a value (for example, when another branch of `select!` wins) does not desynchronize the stream. Next
+/// call to [`decode()`][#decode()] resumes where the dropped one stopped.
+///
+/// [futures_io/AsyncRead]: https://docs.rs/futures-io/0.3/futures_io/trait.AsyncRead.html
+/// [struct:PartialDecoder]: struct.PartialDecoder.html
+/// [#decode()]: #method.decode
+#[derive(Debug)]
+pub struct AsyncDecoder<R> {
+    source: R,
+    decoder: PartialDecoder,
+    eof: bool,
+}
+
+impl<R> AsyncDecoder<R> {
+
+    /// # Makes new decoder
+    pub const fn new(source: R) -> Self {
+        Self::with_config(source, DecodeConfig::new())
+    }
+
+    /// # Makes new decoder, with a config
+    pub const fn with_config(source: R, config: DecodeConfig) -> Self {
+        Self {
+            source,
+            decoder: PartialDecoder::with_config(config),
+            eof: false,
+        }
+    }
+
+    /// # Gets a reference to the source
+    pub const fn get_ref(&self) -> &R {
+        &self.source
+    }
+
+    /// # Buffered bytes, which have been read from source, but not decoded yet
+    pub fn buffered(&self) -> &[u8] {
+        self.decoder.buffered()
+    }
+
+    /// # Unwraps the source
+    ///
+    /// Buffered bytes are dropped.
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+
+}
+
+impl<R> AsyncDecoder<R> where R: AsyncRead + Unpin {
+
+    /// # Decodes next value
+    ///
+    /// If it returns `Ok(None)`, it means there's no more data to decode. If source ends in the middle of a value, an error of
+    /// [`UnexpectedEof`][std::io/ErrorKind#UnexpectedEof] is returned.
+    ///
+    /// The returned future is cancellation-safe. See [Cancellation safety][#cancellation-safety].
+    ///
+    /// [std::io/ErrorKind#UnexpectedEof]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.UnexpectedEof
+    /// [#cancellation-safety]: #cancellation-safety
+    pub fn decode(&mut self) -> Decode<'_, R> {
+        Decode { decoder: self }
+    }
+
+    /// # Polls next value
+    fn poll_decode(&mut self, cx: &mut Context) -> Poll<IoResult<Option<Value>>> {
+        let mut chunk = [0; CHUNK_SIZE];
+        loop {
+            if let Some(value) = self.decoder.decode()? {
+                return Poll::Ready(Ok(Some(value)));
+            }
+            if self.eof {
+                return Poll::Ready(match self.decoder.buffered().is_empty() {
+                    true => Ok(None),
+                    false => Err(io::Error::new(
+                        ErrorKind::UnexpectedEof, __!("source ended in the middle of a value, after {} byte(s)", self.decoder.buffered().len()),
+                    )),
+                });
+            }
+
+            match Pin::new(&mut self.source).poll_read(cx, &mut chunk) {
+                Poll::Ready(Ok(0)) => self.eof = true,
+                Poll::Ready(Ok(count)) => self.decoder.feed(&chunk[..count]),
+                Poll::Ready(Err(err)) => match err.kind() {
+                    ErrorKind::Interrupted => continue,
+                    _ => return Poll::Ready(Err(err)),
+                },
+                Poll::Pending => return Poll::Pending,
+            };
+        }
+    }
+
+}
+
+/// # Future of [`AsyncDecoder::decode()`][AsyncDecoder::decode()]
+///
+/// [AsyncDecoder::decode()]: struct.AsyncDecoder.html#method.decode
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Decode<'a, R> {
+    decoder: &'a mut AsyncDecoder<R>,
+}
+
+impl<R> Future for Decode<'_, R> where R: AsyncRead + Unpin {
+
+    type Output = IoResult<Option<Value>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        self.decoder.poll_decode(cx)
+    }
+
+}
@@ -0,0 +1,96 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Test data generators, for benchmarks
+//!
+//! This module is available with feature `bench-utils`. All generators are deterministic: same arguments always produce same values, so
+//! results of different runs can be compared.
+
+use {
+    alloc::{
+        format,
+        string::String,
+        vec::Vec,
+    },
+
+    crate::{Blob, List, Map, MapKey, Object, ObjectKey, Value},
+};
+
+/// # Makes some small scalars
+///
+/// Result contains all scalar types: null, booleans, integers, floating point numbers, and short strings -- in turn.
+pub fn small_scalars(count: usize) -> Vec<Value> {
+    (0..count).map(scalar).collect()
+}
+
+/// # Makes a scalar, based on an index
+fn scalar(index: usize) -> Value {
+    let n = index as u64;
+    match index % 12 {
+        0 => Value::Null,
+        1 => Value::True,
+        2 => Value::False,
+        3 => Value::U8(n as u8),
+        4 => Value::I8(-(n as i8 & 0x7f)),
+        5 => Value::U16(n as u16),
+        6 => Value::I16(-(n as i16 & 0x7fff)),
+        7 => Value::U32(n as u32),
+        8 => Value::I64(-(n as i64)),
+        9 => Value::U64(n.wrapping_mul(0x9e37_79b9_7f4a_7c15)),
+        10 => Value::Double(n as f64 / 3.0),
+        _ => Value::Text(format!("text #{}", index)),
+    }
+}
+
+/// # Makes a large list
+///
+/// The list contains `len` scalars. See [`small_scalars()`][fn:small_scalars].
+///
+/// [fn:small_scalars]: fn.small_scalars.html
+pub fn large_list(len: usize) -> Value {
+    Value::List(small_scalars(len))
+}
+
+/// # Makes nested lists
+///
+/// Innermost list is empty. There are `depth` lists wrapping it, so encoded result has `depth + 1` lists in total.
+///
+/// To make documents which can be decoded back, `depth` should be less than [`DEFAULT_MAX_DEPTH`][value::DEFAULT_MAX_DEPTH].
+///
+/// [value::DEFAULT_MAX_DEPTH]: ../value/constant.DEFAULT_MAX_DEPTH.html
+pub fn deep_nesting(depth: usize) -> Value {
+    (0..depth).fold(Value::List(List::new()), |value, _| Value::List(alloc::vec![value]))
+}
+
+/// # Makes a big blob
+///
+/// Bytes are a repeating pattern of `0..=255`.
+pub fn big_blob(len: usize) -> Value {
+    Value::Blob((0..len).map(|i| i as u8).collect::<Blob>())
+}
+
+/// # Makes an object-heavy document
+///
+/// Result is a list of `count` objects. Each of them has `fields` fields, with some scalars, a small map, and a nested object.
+pub fn object_heavy(count: usize, fields: usize) -> Value {
+    Value::List((0..count).map(|index| document(index, fields)).collect())
+}
+
+/// # Makes a document
+fn document(index: usize, fields: usize) -> Value {
+    let mut object = Object::new();
+    object.insert(ObjectKey::from("id"), Value::U64(index as u64));
+    object.insert(ObjectKey::from("name"), Value::Text(format!("document #{}", index)));
+    for field in 0..fields {
+        object.insert(ObjectKey::from(format!("field-{}", field)), scalar(index.wrapping_add(field)));
+    }
+
+    let tags = (0..4).map(|i| (i as MapKey, Value::Text(String::from(["alpha", "beta", "gamma", "delta"][i])))).collect::<Map>();
+    object.insert(ObjectKey::from("tags"), Value::Map(tags));
+
+    let mut meta = Object::new();
+    meta.insert(ObjectKey::from("created"), Value::DateTime(String::from("2021-03-14T00:00:00Z")));
+    meta.insert(ObjectKey::from("revision"), Value::U32(index as u32));
+    object.insert(ObjectKey::from("meta"), Value::Object(meta));
+
+    Value::Object(object)
+}
@@ -0,0 +1,215 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Command-line tool
+//!
+//! Requires feature `cli`.
+
+#![deny(unsafe_code)]
+
+use {
+    std::{
+        env,
+        fs::File,
+        io::{self, BufRead, BufReader, BufWriter, ErrorKind, Write},
+        process,
+    },
+
+    binn_ir::{CompatLevel, IoResult, Map, Object, ObjectKey, Value},
+    serde_json::{Number, Value as Json},
+};
+
+const USAGE: &str = concat!(
+    "Usage: binn <command> [FILE]\n",
+    "\n",
+    "Reads from FILE, or stdin if FILE is missing or `-`. Writes to stdout.\n",
+    "\n",
+    "Commands:\n",
+    "  inspect     Prints values as trees, with types and sizes\n",
+    "  to-json     Converts values to JSON, one per line\n",
+    "  from-json   Converts JSON values (separated by whitespace) to Binn\n",
+    "  validate    Validates a buffer which holds exactly one value\n",
+    "  help        Prints this message\n",
+    "\n",
+    "Notes on JSON:\n",
+    "  - Blobs become arrays of bytes; dates/times and decimal strings become strings.\n",
+    "  - Map keys become strings. Back from JSON, objects whose keys are all 32-bit integers become maps.\n",
+    "  - Floating point numbers which JSON does not support (NaN, infinity) become null.\n",
+    "  - From JSON, integers are encoded into smallest types which can hold them.\n",
+);
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let (command, path) = match args.as_slice() {
+        [command] => (command.as_str(), None),
+        [command, path] => (command.as_str(), Some(path.as_str()).filter(|path| *path != "-")),
+        _ => usage_error(),
+    };
+
+    let result = match command {
+        "inspect" => inspect(&mut open(path)),
+        "to-json" => to_json(&mut open(path)),
+        "from-json" => from_json(&mut open(path)),
+        "validate" => validate(&mut open(path)),
+        "help" | "-h" | "--help" => {
+            print!("{}", USAGE);
+            Ok(true)
+        },
+        _ => usage_error(),
+    };
+    match result {
+        Ok(true) => (),
+        Ok(false) => process::exit(1),
+        Err(err) => match err.kind() {
+            ErrorKind::BrokenPipe => (),
+            _ => {
+                eprintln!("{}", err);
+                process::exit(1);
+            },
+        },
+    };
+}
+
+/// # Prints usage to stderr, then exits
+fn usage_error() -> ! {
+    eprint!("{}", USAGE);
+    process::exit(2);
+}
+
+/// # Opens input
+fn open(path: Option<&str>) -> Box<dyn BufRead> {
+    match path {
+        None => Box::new(BufReader::new(io::stdin())),
+        Some(path) => match File::open(path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(err) => {
+                eprintln!("failed to open {:?}: {}", path, err);
+                process::exit(1);
+            },
+        },
+    }
+}
+
+/// # Decodes all values from input
+fn decode_all(input: &mut dyn BufRead, mut f: impl FnMut(Value) -> IoResult<()>) -> IoResult<()> {
+    let mut input = input;
+    while let Some(value) = binn_ir::decode(&mut input)? {
+        f(value)?;
+    }
+    Ok(())
+}
+
+/// # Command: inspect
+fn inspect(input: &mut dyn BufRead) -> IoResult<bool> {
+    let mut out = BufWriter::new(io::stdout().lock());
+    decode_all(input, |value| print_tree(&mut out, None, &value, 0))?;
+    out.flush()?;
+    Ok(true)
+}
+
+/// # Prints a value as a tree
+fn print_tree(out: &mut dyn Write, label: Option<&str>, value: &Value, depth: usize) -> IoResult<()> {
+    let indent = "  ".repeat(depth);
+    let label = label.map(|label| format!("{} ", label)).unwrap_or_default();
+    let size = value.size()?;
+    match value {
+        Value::List(list) => {
+            writeln!(out, "{}{}List ({} item(s), {} byte(s))", indent, label, list.len(), size)?;
+            for (index, item) in list.iter().enumerate() {
+                print_tree(out, Some(&format!("[{}]", index)), item, depth + 1)?;
+            }
+        },
+        Value::Map(map) => {
+            writeln!(out, "{}{}Map ({} item(s), {} byte(s))", indent, label, map.len(), size)?;
+            for (key, item) in map {
+                print_tree(out, Some(&format!("{{{}}}", key)), item, depth + 1)?;
+            }
+        },
+        Value::Object(object) => {
+            writeln!(out, "{}{}Object ({} item(s), {} byte(s))", indent, label, object.len(), size)?;
+            let mut keys = object.keys().collect::<Vec<_>>();
+            keys.sort();
+            for key in keys {
+                print_tree(out, Some(&format!("[{:?}]", key.as_str())), &object[key], depth + 1)?;
+            }
+        },
+        Value::Blob(blob) => writeln!(out, "{}{}Blob ({} byte(s)): {:02x?}", indent, label, blob.len(), blob)?,
+        _ => writeln!(out, "{}{}{:?}", indent, label, value)?,
+    };
+    Ok(())
+}
+
+/// # Command: to-json
+fn to_json(input: &mut dyn BufRead) -> IoResult<bool> {
+    let mut out = BufWriter::new(io::stdout().lock());
+    decode_all(input, |value| {
+        serde_json::to_writer(&mut out, &json_of(&value)).map_err(io::Error::from)?;
+        writeln!(out)
+    })?;
+    out.flush()?;
+    Ok(true)
+}
+
+/// # Converts a value to JSON
+fn json_of(value: &Value) -> Json {
+    match value {
+        Value::Null => Json::Null,
+        Value::True => Json::Bool(true),
+        Value::False => Json::Bool(false),
+        Value::U8(n) => Json::from(*n),
+        Value::I8(n) => Json::from(*n),
+        Value::U16(n) => Json::from(*n),
+        Value::I16(n) => Json::from(*n),
+        Value::U32(n) => Json::from(*n),
+        Value::I32(n) => Json::from(*n),
+        Value::U64(n) => Json::from(*n),
+        Value::I64(n) => Json::from(*n),
+        Value::Float(n) => Number::from_f64(f64::from(*n)).map(Json::Number).unwrap_or(Json::Null),
+        Value::Double(n) => Number::from_f64(*n).map(Json::Number).unwrap_or(Json::Null),
+        Value::Text(s) | Value::DateTime(s) | Value::Date(s) | Value::Time(s) | Value::DecimalStr(s) => Json::String(s.clone()),
+        Value::Blob(blob) => Json::Array(blob.iter().map(|b| Json::from(*b)).collect()),
+        Value::List(list) => Json::Array(list.iter().map(json_of).collect()),
+        Value::Map(map) => Json::Object(map.iter().map(|(k, v)| (k.to_string(), json_of(v))).collect()),
+        Value::Object(object) => Json::Object(object.iter().map(|(k, v)| (k.to_string(), json_of(v))).collect()),
+    }
+}
+
+/// # Command: from-json
+fn from_json(input: &mut dyn BufRead) -> IoResult<bool> {
+    let mut out = BufWriter::new(io::stdout().lock());
+    for json in serde_json::Deserializer::from_reader(input).into_iter::<Json>() {
+        value_of(&json?).encode_with_compat(&mut out, CompatLevel::CBinn)?;
+    }
+    out.flush()?;
+    Ok(true)
+}
+
+/// # Converts JSON to a value
+fn value_of(json: &Json) -> Value {
+    match json {
+        Json::Null => Value::Null,
+        Json::Bool(b) => Value::from(*b),
+        Json::Number(n) => match (n.as_u64(), n.as_i64(), n.as_f64()) {
+            (Some(n), _, _) => Value::U64(n),
+            (None, Some(n), _) => Value::I64(n),
+            (None, None, n) => Value::Double(n.unwrap_or(f64::NAN)),
+        },
+        Json::String(s) => Value::Text(s.clone()),
+        Json::Array(array) => Value::List(array.iter().map(value_of).collect()),
+        Json::Object(object) => match !object.is_empty() && object.keys().all(|key| key.parse::<i32>().map(|k| k.to_string() == *key).unwrap_or(false)) {
+            true => Value::Map(object.iter().map(|(k, v)| (k.parse().unwrap_or_default(), value_of(v))).collect::<Map>()),
+            false => Value::Object(object.iter().map(|(k, v)| (ObjectKey::from(k.as_str()), value_of(v))).collect::<Object>()),
+        },
+    }
+}
+
+/// # Command: validate
+fn validate(input: &mut dyn BufRead) -> IoResult<bool> {
+    let mut bytes = vec![];
+    input.read_to_end(&mut bytes)?;
+    let report = binn_ir::validate(&bytes)?;
+    match report.is_valid() {
+        true => println!("valid ({} byte(s))", bytes.len()),
+        false => report.violations().iter().for_each(|violation| println!("{}", violation)),
+    };
+    Ok(report.is_valid())
+}
@@ -0,0 +1,307 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Binn decoder
+
+use {
+    alloc::{
+        string::String,
+        vec::Vec,
+    },
+    core::{
+        mem,
+        ops::Range,
+    },
+    std::io::{self, ErrorKind, Read},
+
+    crate::{
+        DepthLimitExceeded, IoResult, List, Map, MapKey, Object, ObjectKey, Size, Value,
+        storage::Storage,
+        value::{self, DEFAULT_MAX_DEPTH, OBJECT_KEY_MAX_LEN},
+        value_enum::{self, add_to_read, decode_fixed_value, read_map_key, read_object_key_into, verify_container_size},
+    },
+};
+
+/// # A reusable decoder
+///
+/// This decoder keeps scratch buffers for keys of maps and objects. Together with [`decode_into()`][fn:decode_into] -- which reuses
+/// allocations of an existing value -- it can decode similar documents in a tight loop with very few allocations.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{BinnDecoder, Encoder, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// for name in &["moss", "roy", "jen"] {
+///     buf.encode(&binn_ir::object_from("name", *name))?;
+/// }
+///
+/// let mut decoder = BinnDecoder::new(&buf[..]);
+/// let mut value = Value::Null;
+/// let mut names = vec![];
+/// while decoder.decode_into(&mut value)? {
+///     names.push(value.object_by(&["name"])?.as_text()?.to_string());
+/// }
+/// assert_eq!(names, ["moss", "roy", "jen"]);
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [fn:decode_into]: #method.decode_into
+#[derive(Debug)]
+pub struct BinnDecoder<R> {
+    source: R,
+    map_keys: Vec<MapKey>,
+    key_buf: Vec<u8>,
+    key_ranges: Vec<Range<usize>>,
+    max_depth: usize,
+}
+
+impl<R> BinnDecoder<R> where R: Read {
+
+    /// # Makes new decoder
+    pub fn new(source: R) -> Self {
+        Self {
+            source,
+            map_keys: Vec::new(),
+            key_buf: Vec::with_capacity(usize::from(u8::MAX)),
+            key_ranges: Vec::new(),
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// # Max depth of nested containers
+    ///
+    /// Default: [`DEFAULT_MAX_DEPTH`][value::DEFAULT_MAX_DEPTH].
+    ///
+    /// [value::DEFAULT_MAX_DEPTH]: value/constant.DEFAULT_MAX_DEPTH.html
+    pub const fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// # Sets max depth of nested containers
+    ///
+    /// Decoding containers nested deeper than this results in a [`DepthLimitExceeded`][struct:DepthLimitExceeded] error.
+    ///
+    /// [struct:DepthLimitExceeded]: struct.DepthLimitExceeded.html
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    /// # Gets a reference to the source
+    pub fn get_ref(&self) -> &R {
+        &self.source
+    }
+
+    /// # Gets a mutable reference to the source
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.source
+    }
+
+    /// # Unwraps this decoder, returning the source
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+
+    /// # Decodes a value
+    ///
+    /// If it returns `Ok(None)`, it means there's no more data to decode.
+    pub fn decode(&mut self) -> IoResult<Option<Value>> {
+        let mut result = Value::Null;
+        match self.decode_into(&mut result)? {
+            true => Ok(Some(result)),
+            false => Ok(None),
+        }
+    }
+
+    /// # Decodes a value into a target
+    ///
+    /// Allocations of the target -- such as strings, blobs, lists, maps, objects and their keys -- are reused where possible. Items of maps
+    /// and objects are decoded in place, into existing items of the same keys; so decoding documents of the same shape does not allocate new
+    /// nodes or keys. Items whose keys are missing from new data are removed.
+    ///
+    /// Result: `false` if there's no more data to decode; in which case, target is left untouched. If an error occurs, target's content is
+    /// unspecified.
+    pub fn decode_into(&mut self, target: &mut Value) -> IoResult<bool> {
+        // Leftovers of a previous error
+        self.map_keys.clear();
+        self.key_buf.clear();
+        self.key_ranges.clear();
+
+        Ok(self.decode_value_into(target, 0)?.is_some())
+    }
+
+    /// # Decodes a value into a target
+    ///
+    /// `depth` is the number of containers which contain the value.
+    ///
+    /// Result: encoded size of the value.
+    fn decode_value_into(&mut self, target: &mut Value, depth: usize) -> IoResult<Option<u64>> {
+        let header = match crate::decode_header(&mut self.source)? {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+
+        let type_byte = header.type_byte();
+        let unknown_type = || io::Error::new(ErrorKind::InvalidData, __!("data type is either invalid or not supported: {}", &type_byte));
+        if header.second_type_byte().is_some() {
+            value_enum::skip_bytes(header.value_size() - u64::from(header.size()), &mut self.source)?;
+            return Err(unknown_type());
+        }
+
+        match (header.storage(), header.declared_size(), header.item_count()) {
+            (Storage::String, Some(size), _) => {
+                let mut buf = match mem::replace(target, Value::Null) {
+                    Value::Text(s) | Value::DateTime(s) | Value::Date(s) | Value::Time(s) | Value::DecimalStr(s) => s.into_bytes(),
+                    _ => Vec::new(),
+                };
+                // 1 byte for null terminator
+                read_into(&mut self.source, &mut buf, u64::from(size) + 1)?;
+                match buf.pop() {
+                    Some(0) => (),
+                    other => return Err(io::Error::new(
+                        ErrorKind::InvalidData, __!("expected to read a null terminator ('\\0'), got: {:?}", &other),
+                    )),
+                };
+                let s = String::from_utf8(buf).map_err(|err| io::Error::new(ErrorKind::InvalidData, __!("failed to decode UTF-8: {}", &err)))?;
+                *target = match type_byte {
+                    value::TEXT => Value::Text(s),
+                    value::DATE_TIME => Value::DateTime(s),
+                    value::DATE => Value::Date(s),
+                    value::TIME => Value::Time(s),
+                    value::DECIMAL_STR => Value::DecimalStr(s),
+                    _ => return Err(unknown_type()),
+                };
+            },
+            (Storage::Blob, Some(size), _) => {
+                if type_byte != value::BLOB {
+                    value_enum::skip_bytes(u64::from(size), &mut self.source)?;
+                    return Err(unknown_type());
+                }
+                let mut buf = match mem::replace(target, Value::Null) {
+                    Value::Blob(blob) => blob,
+                    _ => Vec::new(),
+                };
+                read_into(&mut self.source, &mut buf, u64::from(size))?;
+                *target = Value::Blob(buf);
+            },
+            (Storage::Container, Some(size), Some(item_count)) => {
+                if depth >= self.max_depth {
+                    return Err(DepthLimitExceeded::new_io_error(self.max_depth));
+                }
+                let mut read = u64::from(header.size());
+                match type_byte {
+                    value::LIST => {
+                        let mut list = match mem::replace(target, Value::Null) {
+                            Value::List(list) => list,
+                            _ => List::new(),
+                        };
+                        list.truncate(item_count as usize);
+                        for item_index in 0..item_count {
+                            match list.get_mut(item_index as usize) {
+                                Some(item) => self.decode_item_into(item, size, &mut read, item_index, depth + 1)?,
+                                None => {
+                                    let mut item = Value::Null;
+                                    self.decode_item_into(&mut item, size, &mut read, item_index, depth + 1)?;
+                                    list.push(item);
+                                },
+                            };
+                        }
+                        *target = Value::List(list);
+                    },
+                    value::MAP => {
+                        let mut map = match mem::replace(target, Value::Null) {
+                            Value::Map(map) => map,
+                            _ => Map::new(),
+                        };
+                        // Keys of this map are kept on top of the stack, until all items are decoded
+                        let keys_start = self.map_keys.len();
+                        for item_index in 0..item_count {
+                            let key = read_map_key(&mut self.source)?;
+                            add_to_read(&mut read, mem::size_of::<MapKey>() as u64, size)?;
+
+                            self.map_keys.push(key);
+                            let item = map.entry(key).or_insert(Value::Null);
+                            self.decode_item_into(item, size, &mut read, item_index, depth + 1)?;
+                        }
+
+                        let keys = &mut self.map_keys[keys_start..];
+                        keys.sort_unstable();
+                        if let Some(keys) = keys.windows(2).find(|keys| keys[0] == keys[1]) {
+                            return Err(io::Error::new(ErrorKind::InvalidData, __!("duplicate key: {}", &keys[0])));
+                        }
+                        if map.len() > keys.len() {
+                            map.retain(|key, _| keys.binary_search(key).is_ok());
+                        }
+                        self.map_keys.truncate(keys_start);
+                        *target = Value::Map(map);
+                    },
+                    value::OBJECT => {
+                        let mut object = match mem::replace(target, Value::Null) {
+                            Value::Object(object) => object,
+                            _ => Object::new(),
+                        };
+                        // Keys of this object are kept on top of the stack, until all items are decoded
+                        let (key_buf_start, key_ranges_start) = (self.key_buf.len(), self.key_ranges.len());
+                        let mut key_buf = [0_u8; OBJECT_KEY_MAX_LEN];
+                        for item_index in 0..item_count {
+                            let (key, bytes_of_key) = read_object_key_into(&mut self.source, &mut key_buf)?;
+                            add_to_read(&mut read, u64::from(bytes_of_key), size)?;
+
+                            let key_start = self.key_buf.len();
+                            self.key_buf.extend_from_slice(key.as_bytes());
+                            self.key_ranges.push(key_start..self.key_buf.len());
+                            if !object.contains_key(key) {
+                                object.insert(ObjectKey::from(key), Value::Null);
+                            }
+                            let item = object.get_mut(key).ok_or_else(|| io::Error::other(__!()))?;
+                            self.decode_item_into(item, size, &mut read, item_index, depth + 1)?;
+                        }
+
+                        let key_buf = &self.key_buf;
+                        let key_ranges = &mut self.key_ranges[key_ranges_start..];
+                        key_ranges.sort_unstable_by(|a, b| key_buf[a.clone()].cmp(&key_buf[b.clone()]));
+                        if let Some(ranges) = key_ranges.windows(2).find(|ranges| key_buf[ranges[0].clone()] == key_buf[ranges[1].clone()]) {
+                            return Err(io::Error::new(
+                                ErrorKind::InvalidData, __!("duplicate key: {:?}", String::from_utf8_lossy(&key_buf[ranges[0].clone()])),
+                            ));
+                        }
+                        if object.len() > key_ranges.len() {
+                            object.retain(|key, _| key_ranges.binary_search_by(|range| key_buf[range.clone()].cmp(key.as_bytes())).is_ok());
+                        }
+                        self.key_buf.truncate(key_buf_start);
+                        self.key_ranges.truncate(key_ranges_start);
+                        *target = Value::Object(object);
+                    },
+                    _ => {
+                        value_enum::skip_bytes(header.value_size() - read, &mut self.source)?;
+                        return Err(unknown_type());
+                    },
+                };
+                verify_container_size(read, size)?;
+            },
+            (storage, _, _) => *target = decode_fixed_value(type_byte, storage, &mut self.source)?,
+        };
+
+        Ok(Some(header.value_size()))
+    }
+
+    /// # Decodes an item of a container into a target
+    fn decode_item_into(&mut self, target: &mut Value, size: Size, read: &mut u64, item_index: Size, depth: usize) -> IoResult<()> {
+        match self.decode_value_into(target, depth)? {
+            Some(value_size) => add_to_read(read, value_size, size),
+            None => Err(io::Error::new(ErrorKind::InvalidData, __!("missing item #{}", &item_index))),
+        }
+    }
+
+}
+
+/// # Clears a buffer, then reads exactly some bytes into it
+fn read_into<R>(source: &mut R, buf: &mut Vec<u8>, len: u64) -> IoResult<()> where R: Read {
+    buf.clear();
+    match source.take(len).read_to_end(buf)? {
+        read if read as u64 == len => Ok(()),
+        read => Err(io::Error::new(ErrorKind::UnexpectedEof, __!("expected to read {} bytes, but: {}", &len, &read))),
+    }
+}
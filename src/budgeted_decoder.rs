@@ -0,0 +1,96 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Budgeted decoder
+
+use {
+    std::io::Read,
+
+    crate::{DecodeConfig, IoResult, Value},
+};
+
+/// # A decoder with a budget
+///
+/// Each call to [`decode()`][#decode()] can decode at most `max_values` values, and consume at most `max_bytes` bytes. So a single
+/// malicious top-level container can't make one call consume unbounded CPU or memory -- even when the source is already limited via
+/// [`Read::take()`][std::io/Read#take()].
+///
+/// This is a shortcut for [`decode_with_config()`][fn:decode_with_config], with [`max_values`][DecodeConfig#max_values] and
+/// [`max_bytes`][DecodeConfig#max_bytes] set. To combine a budget with other options, set those fields in your own config.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Decoder, Encoder, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// for (count, ok) in &[(10, true), (100, false)] {
+///     let value = Value::List(vec![Value::Null; *count]);
+///     let mut buf = vec![];
+///     buf.encode(&value)?;
+///
+///     let mut decoder = (&buf[..]).with_budget(20, 1024);
+///     assert_eq!(decoder.decode().ok(), match ok {
+///         true => Some(Some(value)),
+///         false => None,
+///     });
+/// }
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [#decode()]: #method.decode
+/// [std::io/Read#take()]: https://doc.rust-lang.org/std/io/trait.Read.html#method.take
+/// [fn:decode_with_config]: fn.decode_with_config.html
+/// [DecodeConfig#max_values]: struct.DecodeConfig.html#structfield.max_values
+/// [DecodeConfig#max_bytes]: struct.DecodeConfig.html#structfield.max_bytes
+#[derive(Debug)]
+pub struct BudgetedDecoder<R> {
+    source: R,
+    config: DecodeConfig,
+}
+
+impl<R> BudgetedDecoder<R> {
+
+    /// # Makes new decoder
+    pub const fn new(source: R, max_values: u64, max_bytes: u64) -> Self {
+        let mut config = DecodeConfig::new();
+        config.max_values = Some(max_values);
+        config.max_bytes = Some(max_bytes);
+        Self {
+            source,
+            config,
+        }
+    }
+
+    /// # Gets a reference to the source
+    pub const fn get_ref(&self) -> &R {
+        &self.source
+    }
+
+    /// # Gets a mutable reference to the source
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.source
+    }
+
+    /// # Unwraps the source
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+
+}
+
+impl<R> BudgetedDecoder<R> where R: Read {
+
+    /// # Decodes a value, within the budget
+    ///
+    /// If it returns `Ok(None)`, it means there's no more data to decode.
+    ///
+    /// ## Notes
+    ///
+    /// When the budget is exceeded, decoding stops in the middle of the value. So the source is broken, and you should not decode next
+    /// values from it.
+    pub fn decode(&mut self) -> IoResult<Option<Value>> {
+        crate::decode_with_config(&mut self.source, &self.config)
+    }
+
+}
@@ -0,0 +1,38 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Encoding/decoding via `bytes::Buf` and `bytes::BufMut`
+
+use {
+    bytes::{Buf, BufMut},
+
+    crate::{IoResult, Size, Value},
+};
+
+/// # Encodes a value into a [`BufMut`][bytes::BufMut]
+///
+/// Result: total bytes that have been written.
+///
+/// ## Examples
+///
+/// ```
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = bytes::BytesMut::new();
+/// binn_ir::encode_into_buf(&mut buf, &binn_ir::Value::from("binn"))?;
+/// assert_eq!(binn_ir::decode_from_buf(&mut buf.freeze())?, Some(binn_ir::Value::from("binn")));
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [bytes::BufMut]: https://docs.rs/bytes/1/bytes/trait.BufMut.html
+pub fn encode_into_buf<B>(buf: &mut B, value: &Value) -> IoResult<Size> where B: BufMut {
+    value.encode(&mut buf.writer())
+}
+
+/// # Decodes a value from a [`Buf`][bytes::Buf]
+///
+/// If it returns `Ok(None)`, it means there's no more data to decode.
+///
+/// [bytes::Buf]: https://docs.rs/bytes/1/bytes/trait.Buf.html
+pub fn decode_from_buf<B>(source: &mut B) -> IoResult<Option<Value>> where B: Buf {
+    crate::decode(&mut source.reader())
+}
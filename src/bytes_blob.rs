@@ -0,0 +1,244 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Blobs backed by `bytes::Bytes`
+
+use {
+    alloc::{
+        collections::BTreeMap,
+        vec::Vec,
+    },
+    core::mem,
+    bytes::{Buf, Bytes},
+    std::io::{self, ErrorKind},
+
+    crate::{
+        DepthLimitExceeded, IoResult, MapKey, ObjectKey, Size, Value,
+        value::{BLOB, DEFAULT_MAX_DEPTH, LIST, MAP, OBJECT, OBJECT_KEY_MAX_LEN},
+        value_enum::{add_to_read, read_map_key, read_object_key_into, verify_container_size},
+    },
+};
+
+/// # Decodes a [`Blob`] from a buffer, without copying its data
+///
+/// The result is a reference-counted slice of `source`. On success, `source` is advanced past the blob. On failure, `source` is left
+/// untouched.
+///
+/// If it returns `Ok(None)`, it means there's no more data to decode.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::Encoder;
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// buf.encode_blob(vec![9; 1024])?;
+///
+/// let mut source = bytes::Bytes::from(buf);
+/// let blob = binn_ir::decode_blob_from_bytes(&mut source)?.unwrap();
+/// assert_eq!(blob, vec![9; 1024]);
+/// assert!(source.is_empty());
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [`Blob`]: enum.Value.html#variant.Blob
+pub fn decode_blob_from_bytes(source: &mut Bytes) -> IoResult<Option<Bytes>> {
+    let header = match crate::decode_header(&mut source.as_ref())? {
+        Some(header) => header,
+        None => return Ok(None),
+    };
+    let size = match (header.type_byte(), header.declared_size()) {
+        (BLOB, Some(size)) => size as usize,
+        (other, _) => return Err(io::Error::new(ErrorKind::InvalidData, __!("expected blob, got: {}", &other))),
+    };
+
+    let header_size = header.size() as usize;
+    match source.len() - header_size >= size {
+        true => {
+            source.advance(header_size);
+            Ok(Some(source.split_to(size)))
+        },
+        false => Err(io::Error::new(ErrorKind::UnexpectedEof, __!("expected {} bytes, but: {}", &size, source.len() - header_size))),
+    }
+}
+
+impl From<Bytes> for Value {
+
+    fn from(bytes: Bytes) -> Self {
+        Value::Blob(bytes.into())
+    }
+
+}
+
+/// # A value decoded from [`Bytes`][bytes::Bytes], whose blobs are not copied
+///
+/// Blobs -- including ones inside containers -- are reference-counted slices of the source. Other values are decoded as usual.
+///
+/// You can make one via [`decode_from_bytes()`][fn:decode_from_bytes].
+///
+/// [bytes::Bytes]: https://docs.rs/bytes/1/bytes/struct.Bytes.html
+/// [fn:decode_from_bytes]: fn.decode_from_bytes.html
+#[derive(Debug, Clone, PartialEq)]
+pub enum BytesValue {
+
+    /// # A value which is neither a blob nor a container
+    Other(Value),
+
+    /// # Blob
+    Blob(Bytes),
+
+    /// # List
+    List(Vec<BytesValue>),
+
+    /// # Map
+    Map(BTreeMap<MapKey, BytesValue>),
+
+    /// # Object
+    Object(BTreeMap<ObjectKey, BytesValue>),
+
+}
+
+impl From<BytesValue> for Value {
+
+    /// # Converts into a value
+    ///
+    /// Blobs are copied.
+    fn from(value: BytesValue) -> Self {
+        match value {
+            BytesValue::Other(value) => value,
+            BytesValue::Blob(bytes) => bytes.into(),
+            BytesValue::List(list) => Value::List(list.into_iter().map(Value::from).collect()),
+            BytesValue::Map(map) => Value::Map(map.into_iter().map(|(key, value)| (key, Value::from(value))).collect()),
+            BytesValue::Object(object) => Value::Object(object.into_iter().map(|(key, value)| (key, Value::from(value))).collect()),
+        }
+    }
+
+}
+
+/// # Decodes a value from a buffer, without copying its blobs
+///
+/// Blobs, at any depth, are reference-counted slices of `source`. On success, `source` is advanced past the value. On failure, `source` is
+/// left untouched.
+///
+/// Containers can be nested up to [`DEFAULT_MAX_DEPTH`][value::DEFAULT_MAX_DEPTH].
+///
+/// If it returns `Ok(None)`, it means there's no more data to decode.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{BytesValue, Encoder, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// buf.encode(&binn_ir::object_from("data", Value::Blob(vec![9; 1024])))?;
+///
+/// let source = bytes::Bytes::from(buf);
+/// let value = binn_ir::decode_from_bytes(&mut source.clone())?.unwrap();
+/// match value {
+///     BytesValue::Object(object) => match &object["data"] {
+///         // The blob shares memory with source
+///         BytesValue::Blob(blob) => assert_eq!(blob.as_ptr(), source[source.len() - 1024..].as_ptr()),
+///         _ => unreachable!(),
+///     },
+///     _ => unreachable!(),
+/// };
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [value::DEFAULT_MAX_DEPTH]: value/constant.DEFAULT_MAX_DEPTH.html
+pub fn decode_from_bytes(source: &mut Bytes) -> IoResult<Option<BytesValue>> {
+    if source.is_empty() {
+        return Ok(None);
+    }
+
+    let mut cursor = source.clone();
+    let result = decode_bytes_value(&mut cursor, 0)?;
+    *source = cursor;
+    Ok(Some(result))
+}
+
+/// # Decodes a value from a buffer, without copying its blobs
+///
+/// `depth` is the number of containers which the value is in.
+fn decode_bytes_value(source: &mut Bytes, depth: usize) -> IoResult<BytesValue> {
+    let header = match crate::decode_header(&mut source.as_ref())? {
+        Some(header) => header,
+        None => return Err(io::Error::new(ErrorKind::UnexpectedEof, __!("missing value"))),
+    };
+    let value_size = header.value_size();
+    if value_size > source.len() as u64 {
+        return Err(io::Error::new(ErrorKind::UnexpectedEof, __!("expected {} bytes, but: {}", value_size, source.len())));
+    }
+
+    let mut data = source.split_to(value_size as usize);
+    let type_byte = header.type_byte();
+    match type_byte {
+        BLOB => {
+            data.advance(header.size() as usize);
+            Ok(BytesValue::Blob(data))
+        },
+        LIST | MAP | OBJECT => {
+            if depth >= DEFAULT_MAX_DEPTH {
+                return Err(DepthLimitExceeded::new_io_error(DEFAULT_MAX_DEPTH));
+            }
+            data.advance(header.size() as usize);
+
+            let size = header.declared_size().unwrap_or_default();
+            let count = header.item_count().unwrap_or_default();
+            let mut read = u64::from(header.size());
+            let result = match type_byte {
+                LIST => BytesValue::List(
+                    (0..count).map(|item_index| decode_item(&mut data, size, &mut read, item_index, depth + 1)).collect::<IoResult<_>>()?
+                ),
+                MAP => {
+                    let mut map = BTreeMap::new();
+                    for item_index in 0..count {
+                        let key = read_map_key(&mut data.as_ref())?;
+                        data.advance(mem::size_of::<MapKey>());
+                        add_to_read(&mut read, mem::size_of::<MapKey>() as u64, size)?;
+                        if map.insert(key, decode_item(&mut data, size, &mut read, item_index, depth + 1)?).is_some() {
+                            return Err(io::Error::new(ErrorKind::InvalidData, __!("duplicate key: {}", key)));
+                        }
+                    }
+                    BytesValue::Map(map)
+                },
+                _ => {
+                    let mut object = BTreeMap::new();
+                    let mut key_buf = [0_u8; OBJECT_KEY_MAX_LEN];
+                    for item_index in 0..count {
+                        let (key, bytes_of_key) = read_object_key_into(&mut data.as_ref(), &mut key_buf)?;
+                        data.advance(bytes_of_key as usize);
+                        add_to_read(&mut read, u64::from(bytes_of_key), size)?;
+                        if object.insert(ObjectKey::from(key), decode_item(&mut data, size, &mut read, item_index, depth + 1)?).is_some() {
+                            return Err(io::Error::new(ErrorKind::InvalidData, __!("duplicate key: {:?}", key)));
+                        }
+                    }
+                    BytesValue::Object(object)
+                },
+            };
+            verify_container_size(read, size)?;
+            Ok(result)
+        },
+        _ => match crate::decode(&mut data.as_ref())? {
+            Some(value) => Ok(BytesValue::Other(value)),
+            None => Err(io::Error::new(ErrorKind::InvalidData, __!("unknown type: {}", type_byte))),
+        },
+    }
+}
+
+/// # Decodes an item of a container
+///
+/// `size` is the container's declared size, and `read` is total bytes read of the container so far.
+fn decode_item(source: &mut Bytes, size: Size, read: &mut u64, item_index: Size, depth: usize) -> IoResult<BytesValue> {
+    if source.is_empty() {
+        return Err(io::Error::new(ErrorKind::InvalidData, __!("missing item #{}", &item_index)));
+    }
+
+    let len = source.len();
+    let value = decode_bytes_value(source, depth)?;
+    add_to_read(read, (len - source.len()) as u64, size)?;
+    Ok(value)
+}
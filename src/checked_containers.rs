@@ -0,0 +1,305 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Checked containers
+
+use {
+    core::{
+        convert::TryFrom,
+        ops::Deref,
+    },
+
+    crate::{
+        Error, List, Map, MapKey, Object, ObjectKey, Result, Size, Value,
+        value_enum::{size_of_container, size_of_object_key},
+    },
+};
+
+/// # Size of a map key
+const MAP_KEY_SIZE: Size = core::mem::size_of::<MapKey>() as Size;
+
+/// # Adds/removes an item size to/from total size of items, then checks size of the container
+fn checked_items_size(count: usize, items: Size, added: Size, removed: Size) -> Result<Size> {
+    let items = items.checked_sub(removed).and_then(|items| items.checked_add(added)).ok_or_else(|| err!("data too large"))?;
+    size_of_container(count, items)?;
+    Ok(items)
+}
+
+/// # A [`List`][type:List] which can always be encoded
+///
+/// Its encoded size never exceeds [`MAX_DATA_SIZE`][value::MAX_DATA_SIZE]. Items are added via checked functions, which reject values
+/// that would break that. For reading, this type dereferences to the inner list.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{CheckedList, Value};
+///
+/// let mut list = CheckedList::new();
+/// list.push("moss")?;
+/// list.push(Value::Blob(vec![0; 10]))?;
+/// assert_eq!(list.len(), 2);
+/// assert_eq!(list.size()?, Value::from(list.clone()).size()?);
+/// # Ok::<_, binn_ir::Error>(())
+/// ```
+///
+/// [type:List]: type.List.html
+/// [value::MAX_DATA_SIZE]: value/constant.MAX_DATA_SIZE.html
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CheckedList {
+    list: List,
+    items: Size,
+}
+
+impl CheckedList {
+
+    /// # Makes new instance
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Pushes new value
+    ///
+    /// Returns an error if the list would become too large. In which case, the list is left unchanged.
+    pub fn push<V>(&mut self, value: V) -> Result<()> where V: Into<Value> {
+        let value = value.into();
+        self.items = checked_items_size(self.list.len() + 1, self.items, value.size()?, 0)?;
+        self.list.push(value);
+        Ok(())
+    }
+
+    /// # Removes last value
+    pub fn pop(&mut self) -> Option<Value> {
+        let result = self.list.pop()?;
+        self.items -= result.size().unwrap_or_default();
+        Some(result)
+    }
+
+    /// # Encoded size of this list
+    pub fn size(&self) -> Result<Size> {
+        size_of_container(self.list.len(), self.items)
+    }
+
+    /// # Takes inner list
+    pub fn into_inner(self) -> List {
+        self.list
+    }
+
+}
+
+impl Deref for CheckedList {
+
+    type Target = List;
+
+    fn deref(&self) -> &Self::Target {
+        &self.list
+    }
+
+}
+
+impl TryFrom<List> for CheckedList {
+
+    type Error = Error;
+
+    fn try_from(list: List) -> core::result::Result<Self, Self::Error> {
+        let mut result = Self::new();
+        result.list.reserve(list.len());
+        list.into_iter().try_for_each(|value| result.push(value))?;
+        Ok(result)
+    }
+
+}
+
+impl From<CheckedList> for Value {
+
+    fn from(list: CheckedList) -> Self {
+        Value::List(list.list)
+    }
+
+}
+
+/// # A [`Map`][type:Map] which can always be encoded
+///
+/// This is the same as [`CheckedList`][struct:CheckedList], for maps. Typed getters of [`MapExt`][trait:MapExt] are available via
+/// dereferencing.
+///
+/// [type:Map]: type.Map.html
+/// [struct:CheckedList]: struct.CheckedList.html
+/// [trait:MapExt]: trait.MapExt.html
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CheckedMap {
+    map: Map,
+    items: Size,
+}
+
+impl CheckedMap {
+
+    /// # Makes new instance
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Inserts new item
+    ///
+    /// Returns previous value (if it existed).
+    ///
+    /// Returns an error if the map would become too large. In which case, the map is left unchanged.
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> Result<Option<Value>> where K: Into<MapKey>, V: Into<Value> {
+        let (key, value) = (key.into(), value.into());
+        let (count, removed) = match self.map.get(&key) {
+            Some(old) => (self.map.len(), MAP_KEY_SIZE + old.size()?),
+            None => (self.map.len() + 1, 0),
+        };
+        self.items = checked_items_size(count, self.items, MAP_KEY_SIZE + value.size()?, removed)?;
+        Ok(self.map.insert(key, value))
+    }
+
+    /// # Removes an item
+    pub fn remove(&mut self, key: MapKey) -> Option<Value> {
+        let result = self.map.remove(&key)?;
+        self.items -= MAP_KEY_SIZE + result.size().unwrap_or_default();
+        Some(result)
+    }
+
+    /// # Encoded size of this map
+    pub fn size(&self) -> Result<Size> {
+        size_of_container(self.map.len(), self.items)
+    }
+
+    /// # Takes inner map
+    pub fn into_inner(self) -> Map {
+        self.map
+    }
+
+}
+
+impl Deref for CheckedMap {
+
+    type Target = Map;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+
+}
+
+impl TryFrom<Map> for CheckedMap {
+
+    type Error = Error;
+
+    fn try_from(map: Map) -> core::result::Result<Self, Self::Error> {
+        let mut result = Self::new();
+        map.into_iter().try_for_each(|(key, value)| result.insert(key, value).map(|_| ()))?;
+        Ok(result)
+    }
+
+}
+
+impl From<CheckedMap> for Value {
+
+    fn from(map: CheckedMap) -> Self {
+        Value::Map(map.map)
+    }
+
+}
+
+/// # An [`Object`][type:Object] which can always be encoded
+///
+/// This is the same as [`CheckedList`][struct:CheckedList], for objects. Besides size, keys are also checked: they must not be longer than
+/// [`OBJECT_KEY_MAX_LEN`][value::OBJECT_KEY_MAX_LEN] bytes. Typed getters of [`ObjectExt`][trait:ObjectExt] are available via
+/// dereferencing.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{CheckedObject, ObjectExt};
+///
+/// let mut object = CheckedObject::new();
+/// object.insert("name", "moss")?;
+/// assert!(object.insert("k".repeat(256), 0).is_err());
+///
+/// assert_eq!(object.get_text("name")?, Some("moss"));
+/// assert_eq!(object.len(), 1);
+/// # Ok::<_, binn_ir::Error>(())
+/// ```
+///
+/// [type:Object]: type.Object.html
+/// [struct:CheckedList]: struct.CheckedList.html
+/// [value::OBJECT_KEY_MAX_LEN]: value/constant.OBJECT_KEY_MAX_LEN.html
+/// [trait:ObjectExt]: trait.ObjectExt.html
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CheckedObject {
+    object: Object,
+    items: Size,
+}
+
+impl CheckedObject {
+
+    /// # Makes new instance
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Inserts new item
+    ///
+    /// Returns previous value (if it existed).
+    ///
+    /// Returns an error if the key is too long, or the object would become too large. In which case, the object is left unchanged.
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> Result<Option<Value>> where K: Into<ObjectKey>, V: Into<Value> {
+        let (key, value) = (crate::object_key(key)?, value.into());
+        let key_size = size_of_object_key(&key)?;
+        let (count, removed) = match self.object.get(&key) {
+            Some(old) => (self.object.len(), key_size + old.size()?),
+            None => (self.object.len() + 1, 0),
+        };
+        self.items = checked_items_size(count, self.items, key_size + value.size()?, removed)?;
+        Ok(self.object.insert(key, value))
+    }
+
+    /// # Removes an item
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let result = self.object.remove(key)?;
+        self.items -= size_of_object_key(key).unwrap_or_default() + result.size().unwrap_or_default();
+        Some(result)
+    }
+
+    /// # Encoded size of this object
+    pub fn size(&self) -> Result<Size> {
+        size_of_container(self.object.len(), self.items)
+    }
+
+    /// # Takes inner object
+    pub fn into_inner(self) -> Object {
+        self.object
+    }
+
+}
+
+impl Deref for CheckedObject {
+
+    type Target = Object;
+
+    fn deref(&self) -> &Self::Target {
+        &self.object
+    }
+
+}
+
+impl TryFrom<Object> for CheckedObject {
+
+    type Error = Error;
+
+    fn try_from(object: Object) -> core::result::Result<Self, Self::Error> {
+        let mut result = Self::new();
+        object.into_iter().try_for_each(|(key, value)| result.insert(key, value).map(|_| ()))?;
+        Ok(result)
+    }
+
+}
+
+impl From<CheckedObject> for Value {
+
+    fn from(object: CheckedObject) -> Self {
+        Value::Object(object.object)
+    }
+
+}
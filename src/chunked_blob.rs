@@ -0,0 +1,182 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Chunked blob
+
+use {
+    core::convert::TryFrom,
+    std::io::{self, ErrorKind, Read, Write},
+
+    crate::{IoResult, Object, Size, Value, value::{BLOB, MAX_DATA_SIZE}},
+};
+
+/// # Key of total length, in manifest
+const KEY_LEN: &str = "len";
+
+/// # Key of chunk size, in manifest
+const KEY_CHUNK_SIZE: &str = "chunk_size";
+
+/// # Key of chunk count, in manifest
+const KEY_CHUNK_COUNT: &str = "chunk_count";
+
+/// # Chunked blob
+///
+/// A single [`Blob`][Value::Blob] can hold at most [`MAX_DATA_SIZE`][value::MAX_DATA_SIZE] bytes. This helper splits larger payloads into
+/// chunks, and reassembles them later.
+///
+/// In a stream, a chunked blob is a manifest, followed by its chunks:
+///
+/// - The manifest is an [`Object`][Value::Object] of 3 keys: `len` (total length), `chunk_size` and `chunk_count`.
+/// - Each chunk is a blob of `chunk_size` bytes, except the last one, which holds the remaining bytes.
+///
+/// Chunks are written directly into the stream, instead of being wrapped inside a [`List`][Value::List], because a list is also limited by
+/// [`MAX_DATA_SIZE`][value::MAX_DATA_SIZE].
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::ChunkedBlob;
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let data = vec![9; 1000];
+///
+/// let mut buf = vec![];
+/// ChunkedBlob::new(data.len() as u64, 256)?.encode(&mut buf, &mut &data[..])?;
+///
+/// let mut source = &buf[..];
+/// let chunked_blob = ChunkedBlob::decode(&mut source)?.unwrap();
+/// assert_eq!(chunked_blob.chunk_count(), 4);
+///
+/// let mut sink = vec![];
+/// assert_eq!(chunked_blob.decode_into(&mut source, &mut sink)?, 1000);
+/// assert_eq!(sink, data);
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [Value::Blob]: enum.Value.html#variant.Blob
+/// [Value::List]: enum.Value.html#variant.List
+/// [Value::Object]: enum.Value.html#variant.Object
+/// [value::MAX_DATA_SIZE]: value/constant.MAX_DATA_SIZE.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChunkedBlob {
+    len: u64,
+    chunk_size: Size,
+}
+
+impl ChunkedBlob {
+
+    /// # Default chunk size: 16 MiB
+    pub const DEFAULT_CHUNK_SIZE: Size = 16 * 1024 * 1024;
+
+    /// # Makes new chunked blob
+    ///
+    /// Chunk size must be in range `1..=MAX_DATA_SIZE`.
+    pub fn new(len: u64, chunk_size: Size) -> IoResult<Self> {
+        match chunk_size {
+            1..=MAX_DATA_SIZE => Ok(Self { len, chunk_size }),
+            _ => Err(io::Error::new(ErrorKind::InvalidInput, __!("invalid chunk size: {}", &chunk_size))),
+        }
+    }
+
+    /// # Total length of data
+    pub const fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// # Checks if there's no data
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// # Chunk size
+    pub const fn chunk_size(&self) -> Size {
+        self.chunk_size
+    }
+
+    /// # Number of chunks
+    pub const fn chunk_count(&self) -> u64 {
+        let chunk_size = self.chunk_size as u64;
+        self.len / chunk_size + match self.len % chunk_size { 0 => 0, _ => 1 }
+    }
+
+    /// # Size of a chunk, at an index
+    fn size_of_chunk(&self, index: u64) -> u64 {
+        let chunk_size = u64::from(self.chunk_size);
+        match index + 1 == self.chunk_count() {
+            true => self.len - chunk_size * index,
+            false => chunk_size,
+        }
+    }
+
+    /// # Makes the manifest
+    fn manifest(&self) -> Value {
+        let mut manifest = Object::new();
+        manifest.insert(KEY_LEN.into(), Value::U64(self.len));
+        manifest.insert(KEY_CHUNK_SIZE.into(), Value::U32(self.chunk_size));
+        manifest.insert(KEY_CHUNK_COUNT.into(), Value::U64(self.chunk_count()));
+        manifest.into()
+    }
+
+    /// # Encodes the manifest, then all chunks whose data is read from a reader
+    ///
+    /// Exactly [`len()`][fn:len] bytes are copied from the reader, without being buffered in memory.
+    ///
+    /// Result: total bytes that have been written.
+    ///
+    /// [fn:len]: #method.len
+    pub fn encode<W, R>(&self, stream: &mut W, reader: &mut R) -> IoResult<u64> where W: Write, R: Read {
+        let mut result = u64::from(self.manifest().encode(stream)?);
+        for index in 0..self.chunk_count() {
+            result += u64::from(crate::encode_blob_from_reader(stream, reader, self.size_of_chunk(index))?);
+        }
+
+        Ok(result)
+    }
+
+    /// # Decodes a manifest
+    ///
+    /// If it returns `Ok(None)`, it means there's no more data to decode. Chunks can be decoded later via [`decode_into()`][fn:decode_into].
+    ///
+    /// [fn:decode_into]: #method.decode_into
+    pub fn decode<R>(source: &mut R) -> IoResult<Option<Self>> where R: Read {
+        let manifest = match crate::decode(source)? {
+            Some(manifest) => manifest,
+            None => return Ok(None),
+        };
+
+        let (len, chunk_size, chunk_count) = (
+            u64::try_from(manifest.object_by(&[KEY_LEN])?)?,
+            Size::try_from(manifest.object_by(&[KEY_CHUNK_SIZE])?)?,
+            u64::try_from(manifest.object_by(&[KEY_CHUNK_COUNT])?)?,
+        );
+        let result = Self::new(len, chunk_size).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
+        match result.chunk_count() == chunk_count {
+            true => Ok(Some(result)),
+            false => Err(io::Error::new(ErrorKind::InvalidData, __!("invalid chunk count: {}", &chunk_count))),
+        }
+    }
+
+    /// # Decodes all chunks, and copies their data into a sink
+    ///
+    /// Result: total bytes of data that have been copied.
+    pub fn decode_into<R, W>(&self, source: &mut R, sink: &mut W) -> IoResult<u64> where R: Read, W: Write {
+        let mut result = 0;
+        for index in 0..self.chunk_count() {
+            let expected = self.size_of_chunk(index);
+            match crate::decode_header(source)? {
+                Some(header) if header.type_byte() == BLOB && header.declared_size().map(u64::from) == Some(expected) => (),
+                Some(_) => return Err(io::Error::new(ErrorKind::InvalidData, __!("expected chunk #{} of {} bytes", &index, &expected))),
+                None => return Err(io::Error::new(ErrorKind::UnexpectedEof, __!("missing chunk #{}", &index))),
+            };
+            match io::copy(&mut source.take(expected), sink)? {
+                copied if copied == expected => result += copied,
+                copied => return Err(io::Error::new(
+                    ErrorKind::UnexpectedEof, __!("chunk #{}: expected to copy {} bytes, but: {}", &index, &expected, &copied),
+                )),
+            };
+        }
+
+        Ok(result)
+    }
+
+}
@@ -0,0 +1,198 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Type coercion
+//!
+//! Writers in dynamic languages are often sloppy about types: numbers are sent as strings, booleans as `"yes"`, integers as doubles... This
+//! module converts such values into what readers expect, following a set of [`Rules`][struct:Rules].
+//!
+//! Values which already have expected types are always accepted -- integers of any width and sign, as long as their values fit. Other
+//! conversions must be enabled explicitly.
+//!
+//! ## Examples
+//!
+//! ```
+//! use binn_ir::{Value, coerce::{self, Rules}};
+//!
+//! let value = Value::from(" 42 ");
+//! assert!(coerce::to_u64(&value, Rules::new()).is_err());
+//!
+//! let mut rules = Rules::new();
+//! rules.parse_numeric_strings = true;
+//! assert_eq!(coerce::to_u64(&value, rules)?, 42);
+//!
+//! assert_eq!(coerce::to_bool(&Value::from("Yes"), Rules::all())?, true);
+//! assert_eq!(coerce::to_i64(&Value::Double(-3.0), Rules::all())?, -3);
+//! # Ok::<_, binn_ir::Error>(())
+//! ```
+//!
+//! [struct:Rules]: struct.Rules.html
+
+use {
+    core::convert::TryFrom,
+
+    crate::{Result, Value},
+};
+
+/// # Rules of coercion
+///
+/// Default rules -- made by [`new()`][#new()] -- accept values of expected types only. This struct is marked as non-exhaustive, so new rules
+/// can be added later.
+///
+/// [#new()]: #method.new
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Rules {
+
+    /// # Parses numeric strings
+    ///
+    /// [`Text`][Value::Text] and [`DecimalStr`][Value::DecimalStr] are parsed as numbers. Leading and trailing whitespace is ignored.
+    ///
+    /// [Value::Text]: ../enum.Value.html#variant.Text
+    /// [Value::DecimalStr]: ../enum.Value.html#variant.DecimalStr
+    pub parse_numeric_strings: bool,
+
+    /// # Converts truthy strings to booleans
+    ///
+    /// These texts are accepted, case-insensitively, ignoring leading and trailing whitespace:
+    ///
+    /// - `true`, `yes`, `on`, `1`.
+    /// - `false`, `no`, `off`, `0`.
+    pub truthy_strings: bool,
+
+    /// # Converts floating point numbers without fractions to integers
+    ///
+    /// For example, a [`Double`][Value::Double] of `5.0` is accepted as an integer `5`.
+    ///
+    /// [Value::Double]: ../enum.Value.html#variant.Double
+    pub integral_floats: bool,
+
+    /// # Converts booleans to/from numbers
+    ///
+    /// `true` is `1`, `false` is `0`. Other numbers can't be converted to booleans.
+    pub bools_as_numbers: bool,
+
+}
+
+impl Default for Rules {
+
+    fn default() -> Self {
+        Self::new()
+    }
+
+}
+
+impl Rules {
+
+    /// # Makes new rules, which accept values of expected types only
+    pub const fn new() -> Self {
+        Self {
+            parse_numeric_strings: false,
+            truthy_strings: false,
+            integral_floats: false,
+            bools_as_numbers: false,
+        }
+    }
+
+    /// # Makes new rules, with all conversions enabled
+    pub const fn all() -> Self {
+        Self {
+            parse_numeric_strings: true,
+            truthy_strings: true,
+            integral_floats: true,
+            bools_as_numbers: true,
+        }
+    }
+
+}
+
+/// # Coerces a value to `u64`
+pub fn to_u64(value: &Value, rules: Rules) -> Result<u64> {
+    match value {
+        Value::U8(_) | Value::I8(_) | Value::U16(_) | Value::I16(_) | Value::U32(_) | Value::I32(_) | Value::U64(_) | Value::I64(_) =>
+            u64::try_from(value),
+        Value::Float(_) | Value::Double(_) if rules.integral_floats => {
+            let f = value.as_f64_lossy()?;
+            match (0.0..18_446_744_073_709_551_616.0).contains(&f) && (f as u64) as f64 == f {
+                true => Ok(f as u64),
+                false => Err(err!("Cannot coerce {} to u64", f)),
+            }
+        },
+        Value::Text(s) | Value::DecimalStr(s) if rules.parse_numeric_strings => match s.trim().parse() {
+            Ok(u) => Ok(u),
+            Err(_) => match rules.integral_floats {
+                true => to_u64(&Value::Double(to_f64(value, rules)?), rules),
+                false => Err(err!("Cannot coerce {:?} to u64", s)),
+            },
+        },
+        Value::True | Value::False if rules.bools_as_numbers => Ok(u64::from(value == &Value::True)),
+        _ => Err(err!("Cannot coerce value to u64")),
+    }
+}
+
+/// # Coerces a value to `i64`
+pub fn to_i64(value: &Value, rules: Rules) -> Result<i64> {
+    match value {
+        Value::U8(_) | Value::I8(_) | Value::U16(_) | Value::I16(_) | Value::U32(_) | Value::I32(_) | Value::U64(_) | Value::I64(_) =>
+            i64::try_from(value),
+        Value::Float(_) | Value::Double(_) if rules.integral_floats => {
+            let f = value.as_f64_lossy()?;
+            match (-9_223_372_036_854_775_808.0..9_223_372_036_854_775_808.0).contains(&f) && (f as i64) as f64 == f {
+                true => Ok(f as i64),
+                false => Err(err!("Cannot coerce {} to i64", f)),
+            }
+        },
+        Value::Text(s) | Value::DecimalStr(s) if rules.parse_numeric_strings => match s.trim().parse() {
+            Ok(i) => Ok(i),
+            Err(_) => match rules.integral_floats {
+                true => to_i64(&Value::Double(to_f64(value, rules)?), rules),
+                false => Err(err!("Cannot coerce {:?} to i64", s)),
+            },
+        },
+        Value::True | Value::False if rules.bools_as_numbers => Ok(i64::from(value == &Value::True)),
+        _ => Err(err!("Cannot coerce value to i64")),
+    }
+}
+
+/// # Coerces a value to `f64`
+///
+/// Integers are always accepted, even if they lose precision. See [`Value::as_f64_lossy()`][Value::as_f64_lossy()].
+///
+/// [Value::as_f64_lossy()]: ../enum.Value.html#method.as_f64_lossy
+pub fn to_f64(value: &Value, rules: Rules) -> Result<f64> {
+    match value {
+        Value::Text(s) | Value::DecimalStr(s) if rules.parse_numeric_strings => match s.trim().parse() {
+            Ok(f) => Ok(f),
+            Err(_) => Err(err!("Cannot coerce {:?} to f64", s)),
+        },
+        Value::True | Value::False if rules.bools_as_numbers => Ok(match value == &Value::True {
+            true => 1.0,
+            false => 0.0,
+        }),
+        _ => value.as_f64_lossy().map_err(|_| err!("Cannot coerce value to f64")),
+    }
+}
+
+/// # Coerces a value to `bool`
+pub fn to_bool(value: &Value, rules: Rules) -> Result<bool> {
+    match value {
+        Value::True => Ok(true),
+        Value::False => Ok(false),
+        Value::Text(s) if rules.truthy_strings => {
+            let s = s.trim();
+            if ["true", "yes", "on", "1"].iter().any(|t| s.eq_ignore_ascii_case(t)) {
+                Ok(true)
+            } else if ["false", "no", "off", "0"].iter().any(|t| s.eq_ignore_ascii_case(t)) {
+                Ok(false)
+            } else {
+                Err(err!("Cannot coerce {:?} to bool", s))
+            }
+        },
+        Value::U8(_) | Value::I8(_) | Value::U16(_) | Value::I16(_) | Value::U32(_) | Value::I32(_) | Value::U64(_) | Value::I64(_)
+        if rules.bools_as_numbers => match value.as_u64_lossless() {
+            Ok(0) => Ok(false),
+            Ok(1) => Ok(true),
+            _ => Err(err!("Cannot coerce integer to bool, only 0 and 1 are accepted")),
+        },
+        _ => Err(err!("Cannot coerce value to bool")),
+    }
+}
@@ -0,0 +1,97 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Objects whose keys are `compact_str::CompactString`s
+
+use {
+    alloc::collections::BTreeMap,
+    std::io::{self, ErrorKind, Read},
+
+    compact_str::CompactString,
+
+    crate::{
+        IoResult, ObjectKey, Value,
+        value::{OBJECT, OBJECT_KEY_MAX_LEN},
+        value_enum::{add_to_read, read_object_key_into, verify_container_size},
+    },
+};
+
+/// # An object whose keys are [`CompactString`][compact_str::CompactString]s
+///
+/// Since keys are at most 255 bytes, and are usually tiny, most of them are stored inline, without allocations.
+///
+/// You can decode one via [`decode_compact_object()`][fn:decode_compact_object], and convert it into a [`Value`][enum:Value].
+///
+/// [compact_str::CompactString]: https://docs.rs/compact_str/0.9/compact_str/struct.CompactString.html
+/// [fn:decode_compact_object]: fn.decode_compact_object.html
+/// [enum:Value]: enum.Value.html
+pub type CompactObject = BTreeMap<CompactString, Value>;
+
+impl From<CompactObject> for Value {
+
+    /// # Converts into an [`Object`](#variant.Object)
+    ///
+    /// Keys are copied.
+    fn from(object: CompactObject) -> Self {
+        Value::Object(object.into_iter().map(|(key, value)| (ObjectKey::from(key.as_str()), value)).collect())
+    }
+
+}
+
+/// # Decodes an [`Object`], whose keys are [`CompactString`][compact_str::CompactString]s
+///
+/// Only keys of the object itself are compact. Its values -- including nested objects -- are decoded as usual.
+///
+/// If it returns `Ok(None)`, it means there's no more data to decode.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Encoder, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut object = binn_ir::object_from("id", 1);
+/// object.object_insert("name", "moon")?;
+///
+/// let mut buf = vec![];
+/// buf.encode(&object)?;
+///
+/// let compact = binn_ir::decode_compact_object(&mut &buf[..])?.unwrap();
+/// assert!(compact.keys().all(|key| !key.is_heap_allocated()));
+/// assert_eq!(Value::from(compact), object);
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [`Object`]: enum.Value.html#variant.Object
+/// [compact_str::CompactString]: https://docs.rs/compact_str/0.9/compact_str/struct.CompactString.html
+pub fn decode_compact_object<R>(source: &mut R) -> IoResult<Option<CompactObject>> where R: Read {
+    let header = match crate::decode_header(source)? {
+        Some(header) => header,
+        None => return Ok(None),
+    };
+    let (size, item_count) = match (header.type_byte(), header.declared_size(), header.item_count()) {
+        (OBJECT, Some(size), Some(item_count)) => (size, item_count),
+        (other, _, _) => return Err(io::Error::new(ErrorKind::InvalidData, __!("expected object, got: {}", &other))),
+    };
+    let mut read = u64::from(header.size());
+
+    let mut result = CompactObject::new();
+    let mut key_buf = [0_u8; OBJECT_KEY_MAX_LEN];
+    for item_index in 0..item_count {
+        let (key, bytes_of_key) = read_object_key_into(source, &mut key_buf)?;
+        add_to_read(&mut read, u64::from(bytes_of_key), size)?;
+        let key = CompactString::from(key);
+
+        let value = match crate::decode(source)? {
+            Some(value) => value,
+            None => return Err(io::Error::new(ErrorKind::InvalidData, __!("missing item #{}/{}", &item_index, &item_count))),
+        };
+        add_to_read(&mut read, u64::from(value.size()?), size)?;
+        if let Some(old_value) = result.insert(key, value) {
+            return Err(io::Error::new(ErrorKind::InvalidData, __!("duplicate key of old value: {:?}", &old_value)));
+        }
+    }
+
+    verify_container_size(read, size)?;
+    Ok(Some(result))
+}
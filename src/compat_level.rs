@@ -0,0 +1,103 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Compatibility levels
+
+use {
+    core::convert::TryFrom,
+
+    crate::Value,
+};
+
+#[cfg(feature="std")]
+use crate::EncodeConfig;
+
+/// # Compatibility level of encoding
+///
+/// Encoded bytes of both levels decode into the same values, except for integer types (see [`CBinn`][#CBinn]).
+///
+/// [#CBinn]: #variant.CBinn
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompatLevel {
+
+    /// # Values are encoded as they are, with their own types
+    ///
+    /// This is the default level.
+    #[default]
+    Spec,
+
+    /// # Reproduces exact bytes of the reference C implementation (<https://github.com/liteserver/binn>)
+    ///
+    /// The C implementation compresses integers, by default. So integers are encoded into smallest types which can hold them:
+    ///
+    /// - Non-negative integers become unsigned: `u8`, `u16`, `u32` or `u64`. For example, an `I64` of `1` is encoded as an `U8`.
+    /// - Negative integers become `i8`, `i16`, `i32` or `i64`.
+    /// - Integers of 1 byte are kept as-is, so an `I8` of `1` stays an `I8`.
+    ///
+    /// Other choices are already the same: sizes and counts take 1 byte if they're less than `128`, 4 bytes otherwise. Empty containers
+    /// take 3 bytes.
+    ///
+    /// ## Notes
+    ///
+    /// The C implementation writes object and map items in order of insertion, while [`Map`][type:Map] and [`Object`][type:Object] are
+    /// sorted by keys. So for byte equality, C code should insert items in order of their keys.
+    ///
+    /// [type:Map]: type.Map.html
+    /// [type:Object]: type.Object.html
+    CBinn,
+
+}
+
+/// # Encode config which produces what the reference C implementation would encode
+///
+/// See [`CompatLevel::CBinn`][CompatLevel#CBinn].
+///
+/// [CompatLevel#CBinn]: enum.CompatLevel.html#variant.CBinn
+#[cfg(feature="std")]
+pub(crate) const C_BINN: EncodeConfig = EncodeConfig {
+    shrink_integers: true,
+    ..EncodeConfig::new()
+};
+
+/// # Stores an integer in the smallest type which holds it
+///
+/// Integers of 1 byte, and other values, are returned as-is.
+#[cfg(feature="std")]
+pub(crate) fn shrink_integer(value: &Value) -> Value {
+    match value {
+        Value::U16(u) => compress_unsigned(u64::from(*u)),
+        Value::U32(u) => compress_unsigned(u64::from(*u)),
+        Value::U64(u) => compress_unsigned(*u),
+        Value::I16(i) => compress_signed(i64::from(*i)),
+        Value::I32(i) => compress_signed(i64::from(*i)),
+        Value::I64(i) => compress_signed(*i),
+        _ => value.clone(),
+    }
+}
+
+/// # Compresses an unsigned integer
+pub(crate) fn compress_unsigned(u: u64) -> Value {
+    if let Ok(u) = u8::try_from(u) {
+        Value::U8(u)
+    } else if let Ok(u) = u16::try_from(u) {
+        Value::U16(u)
+    } else if let Ok(u) = u32::try_from(u) {
+        Value::U32(u)
+    } else {
+        Value::U64(u)
+    }
+}
+
+/// # Compresses a signed integer
+pub(crate) fn compress_signed(i: i64) -> Value {
+    if i >= 0 {
+        compress_unsigned(i as u64)
+    } else if let Ok(i) = i8::try_from(i) {
+        Value::I8(i)
+    } else if let Ok(i) = i16::try_from(i) {
+        Value::I16(i)
+    } else if let Ok(i) = i32::try_from(i) {
+        Value::I32(i)
+    } else {
+        Value::I64(i)
+    }
+}
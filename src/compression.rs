@@ -0,0 +1,257 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Compression
+
+use {
+    alloc::{boxed::Box, vec::Vec},
+    core::convert::TryFrom,
+    std::io::{self, ErrorKind, Read, Write},
+
+    crate::{DecodeConfig, IoResult, Size, Value, value::MAX_DATA_SIZE},
+};
+
+/// # Size of a compressed document's header, in bytes
+///
+/// That is: 1 byte for codec, 4 bytes for length of compressed data.
+const HEADER_SIZE: usize = 5;
+
+/// # Compression codec
+///
+/// Each codec requires a feature of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Codec {
+
+    /// # Deflate (raw, without zlib or gzip headers), via `flate2`
+    #[cfg(feature="flate2")]
+    Deflate,
+
+    /// # Zstandard, via `zstd`
+    #[cfg(feature="zstd")]
+    Zstd,
+
+}
+
+impl Codec {
+
+    /// # Tag of this codec, in a compressed document
+    const fn tag(&self) -> u8 {
+        match self {
+            #[cfg(feature="flate2")]
+            Codec::Deflate => 1,
+            #[cfg(feature="zstd")]
+            Codec::Zstd => 2,
+        }
+    }
+
+    /// # Finds codec of a tag
+    fn of_tag(tag: u8) -> Option<Self> {
+        match tag {
+            #[cfg(feature="flate2")]
+            1 => Some(Codec::Deflate),
+            #[cfg(feature="zstd")]
+            2 => Some(Codec::Zstd),
+            _ => None,
+        }
+    }
+
+    /// # Compresses data that `f` writes
+    fn compress<F>(&self, f: F) -> IoResult<Vec<u8>> where F: FnOnce(&mut dyn Write) -> IoResult<()> {
+        match self {
+            #[cfg(feature="flate2")]
+            Codec::Deflate => {
+                let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+                f(&mut encoder)?;
+                encoder.finish()
+            },
+            #[cfg(feature="zstd")]
+            Codec::Zstd => {
+                let mut encoder = zstd::stream::write::Encoder::new(Vec::new(), zstd::DEFAULT_COMPRESSION_LEVEL)?;
+                f(&mut encoder)?;
+                encoder.finish()
+            },
+        }
+    }
+
+    /// # Makes new decompressor of some bytes
+    fn decompressor<'a>(&self, bytes: &'a [u8]) -> IoResult<Box<dyn Read + 'a>> {
+        match self {
+            #[cfg(feature="flate2")]
+            Codec::Deflate => Ok(Box::new(flate2::read::DeflateDecoder::new(bytes))),
+            #[cfg(feature="zstd")]
+            Codec::Zstd => Ok(Box::new(zstd::stream::read::Decoder::with_buffer(bytes)?)),
+        }
+    }
+
+}
+
+/// # Encodes a value, compressed
+///
+/// A compressed document consists of a header and compressed data. The header holds codec (1 byte), followed by length of compressed data
+/// (a big-endian `u32`). The codec is recorded, so [`decode_compressed()`][fn:decode_compressed] doesn't need to be told.
+///
+/// Result: total bytes that have been written, including the header.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Codec, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let value = Value::List(vec![Value::from("some text"); 100]);
+///
+/// # #[cfg(feature="flate2")] {
+/// let mut buf = vec![];
+/// binn_ir::encode_compressed(&mut buf, &value, Codec::Deflate)?;
+/// assert!(buf.len() < value.size()? as usize / 10);
+///
+/// assert_eq!(binn_ir::decode_compressed(&mut buf.as_slice())?, Some(value));
+/// # }
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [fn:decode_compressed]: fn.decode_compressed.html
+pub fn encode_compressed<W>(stream: &mut W, value: &Value, codec: Codec) -> IoResult<Size> where W: Write {
+    let data = codec.compress(|mut encoder| value.encode(&mut encoder).map(|_| ()))?;
+    let len = match Size::try_from(data.len()) {
+        Ok(len) if len <= MAX_DATA_SIZE => len,
+        _ => return Err(io::Error::new(ErrorKind::InvalidData, __!("compressed data is too large: {} byte(s)", data.len()))),
+    };
+
+    let mut header = [codec.tag(), 0, 0, 0, 0];
+    header[1..].copy_from_slice(&len.to_be_bytes());
+    stream.write_all(&header)?;
+    stream.write_all(&data)?;
+
+    Ok(len + HEADER_SIZE as Size)
+}
+
+/// # Decodes a compressed value
+///
+/// See [`encode_compressed()`][fn:encode_compressed] for format of compressed documents.
+///
+/// If it returns `Ok(None)`, it means there's no more data to decode.
+///
+/// No limits are applied: a small document can decompress into a huge value. For untrusted sources, use
+/// [`decode_compressed_with_config()`][fn:decode_compressed_with_config] instead.
+///
+/// ## Errors
+///
+/// - [`UnexpectedEof`][io::ErrorKind#UnexpectedEof]: the document is truncated.
+/// - [`InvalidData`][io::ErrorKind#InvalidData]: the codec is unknown -- or its feature is disabled -- or the length is too large, or the
+///   compressed data does not hold exactly one value.
+///
+/// [fn:encode_compressed]: fn.encode_compressed.html
+/// [fn:decode_compressed_with_config]: fn.decode_compressed_with_config.html
+/// [io::ErrorKind#UnexpectedEof]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.UnexpectedEof
+/// [io::ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+pub fn decode_compressed<R>(stream: &mut R) -> IoResult<Option<Value>> where R: Read {
+    decode_compressed_with_config(stream, &DecodeConfig::new())
+}
+
+/// # Decodes a compressed value, with a config
+///
+/// This is the same as [`decode_compressed()`][fn:decode_compressed], except that limits of the config apply:
+///
+/// - [`max_bytes`][DecodeConfig#max_bytes] limits both the compressed data -- which is read into memory first -- and the decompressed
+///   value. So a small document can't decompress into a huge value.
+/// - Other limits and validations apply to the decompressed value, as in [`decode_with_config()`][fn:decode_with_config].
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Codec, DecodeConfig, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// # #[cfg(feature="zstd")] {
+/// let mut buf = vec![];
+/// binn_ir::encode_compressed(&mut buf, &Value::Blob(vec![0; 1_000_000]), Codec::Zstd)?;
+/// assert!(buf.len() < 1000);
+///
+/// let mut config = DecodeConfig::new();
+/// config.max_bytes = Some(100_000);
+/// assert!(binn_ir::decode_compressed_with_config(&mut &buf[..], &config).is_err());
+/// # }
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [fn:decode_compressed]: fn.decode_compressed.html
+/// [fn:decode_with_config]: fn.decode_with_config.html
+/// [DecodeConfig#max_bytes]: struct.DecodeConfig.html#structfield.max_bytes
+pub fn decode_compressed_with_config<R>(stream: &mut R, config: &DecodeConfig) -> IoResult<Option<Value>> where R: Read {
+    let mut header = [0; HEADER_SIZE];
+    if !crate::framing::read_header(stream, &mut header)? {
+        return Ok(None);
+    }
+
+    let codec = Codec::of_tag(header[0])
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, __!("unsupported codec: 0x{:02x}", header[0])))?;
+    let len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]);
+    if len > MAX_DATA_SIZE || config.max_bytes.map(|max_bytes| u64::from(len) > max_bytes).unwrap_or(false) {
+        return Err(io::Error::new(ErrorKind::InvalidData, __!("compressed data is too large: {} byte(s)", len)));
+    }
+
+    let mut data = Vec::new();
+    stream.take(u64::from(len)).read_to_end(&mut data)?;
+    if data.len() != len as usize {
+        return Err(io::Error::new(ErrorKind::UnexpectedEof, __!("expected {} byte(s) of compressed data, got: {}", len, data.len())));
+    }
+
+    let mut decompressor = codec.decompressor(&data)?;
+    match crate::decode_with_config(&mut decompressor, config)? {
+        Some(value) => match decompressor.read(&mut [0])? {
+            0 => Ok(Some(value)),
+            _ => Err(io::Error::new(ErrorKind::InvalidData, __!("trailing data after compressed value"))),
+        },
+        None => Err(io::Error::new(ErrorKind::InvalidData, __!("no value in compressed data"))),
+    }
+}
+
+/// # Compression
+impl Value {
+
+    /// # Makes a [`Blob`][Value#Blob] of compressed data, from a reader
+    ///
+    /// All data is read, then compressed with standard format of the codec. So the blob can be decompressed by any tool which supports that
+    /// codec.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use std::io::Read;
+    /// use binn_ir::{Codec, Value};
+    ///
+    /// # fn test() -> binn_ir::IoResult<()> {
+    /// let text = "some text ".repeat(100);
+    ///
+    /// # #[cfg(feature="zstd")] {
+    /// let blob = Value::compressed_blob(&mut text.as_bytes(), Codec::Zstd)?;
+    /// assert!(blob.as_blob()?.len() < text.len() / 10);
+    ///
+    /// let mut decompressed = String::new();
+    /// zstd::stream::read::Decoder::new(blob.as_blob()?)?.read_to_string(&mut decompressed)?;
+    /// assert_eq!(decompressed, text);
+    /// # }
+    /// # Ok(()) }
+    /// # test().unwrap();
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// [`InvalidData`][io::ErrorKind#InvalidData] is returned if compressed data is larger than
+    /// [`MAX_DATA_SIZE`][value::MAX_DATA_SIZE].
+    ///
+    /// [Value#Blob]: #variant.Blob
+    /// [io::ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    /// [value::MAX_DATA_SIZE]: value/constant.MAX_DATA_SIZE.html
+    pub fn compressed_blob<R>(reader: &mut R, codec: Codec) -> IoResult<Self> where R: Read {
+        let data = codec.compress(|encoder| io::copy(reader, encoder).map(|_| ()))?;
+        match Size::try_from(data.len()) {
+            Ok(len) if len <= MAX_DATA_SIZE => Ok(Value::Blob(data)),
+            _ => Err(io::Error::new(ErrorKind::InvalidData, __!("compressed data is too large: {} byte(s)", data.len()))),
+        }
+    }
+
+}
@@ -3,7 +3,7 @@
 //! # Container functions
 
 use {
-    crate::{Blob, List, Map, MapKey, Object, ObjectKey, Value},
+    crate::{Blob, List, Map, MapKey, Object, ObjectKey, Result, Value, value::OBJECT_KEY_MAX_LEN},
 };
 
 /// # Makes new blob
@@ -68,3 +68,36 @@ pub fn object_from<K, V>(key: K, value: V) -> Value where K: Into<ObjectKey>, V:
 pub fn object_insert<K, V>(object: &mut Object, key: K, value: V) -> Option<Value> where K: Into<ObjectKey>, V: Into<Value> {
     object.insert(key.into(), value.into())
 }
+
+/// # Makes new object key, after validating its length
+///
+/// Keys longer than [`OBJECT_KEY_MAX_LEN`][value::OBJECT_KEY_MAX_LEN] bytes can't be encoded. This function rejects them right away,
+/// rather than at encoding time.
+///
+/// ## Examples
+///
+/// ```
+/// assert_eq!(binn_ir::object_key("name")?, "name");
+/// assert!(binn_ir::object_key("k".repeat(256)).is_err());
+/// # Ok::<_, binn_ir::Error>(())
+/// ```
+///
+/// [value::OBJECT_KEY_MAX_LEN]: value/constant.OBJECT_KEY_MAX_LEN.html
+pub fn object_key<K>(key: K) -> Result<ObjectKey> where K: Into<ObjectKey> {
+    let key = key.into();
+    match key.len() <= OBJECT_KEY_MAX_LEN {
+        true => Ok(key),
+        false => Err(err!("key length is limited to {} bytes, got: {}", OBJECT_KEY_MAX_LEN, key.len())),
+    }
+}
+
+/// # Inserts new item into an object, after validating its key
+///
+/// See [`object_key()`][fn:object_key].
+///
+/// Returns previous value (if it existed).
+///
+/// [fn:object_key]: fn.object_key.html
+pub fn object_insert_checked<K, V>(object: &mut Object, key: K, value: V) -> Result<Option<Value>> where K: Into<ObjectKey>, V: Into<Value> {
+    Ok(object.insert(object_key(key)?, value.into()))
+}
@@ -0,0 +1,505 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Container readers
+
+use {
+    std::io::{self, ErrorKind, Read},
+
+    crate::{IoResult, MapKey, ObjectKey, Size, Value},
+};
+
+/// # Makes new error of a missing item
+fn missing_item() -> io::Error {
+    io::Error::new(ErrorKind::UnexpectedEof, __!("missing item"))
+}
+
+/// # A reader of a [`List`][Value::List], which decodes its items one by one
+///
+/// Only one item is kept in memory at a time, so very large lists can be consumed with little memory. Items are decoded via
+/// [`decode()`][fn:decode].
+///
+/// This is also an iterator. Once an error is returned, the iterator stops.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{ListReader, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// Value::List((0..100_u8).map(Value::U8).collect()).encode(&mut buf)?;
+/// Value::from("next").encode(&mut buf)?;
+///
+/// let mut source = &buf[..];
+/// let mut reader = ListReader::new(&mut source)?;
+/// assert_eq!(reader.len(), 100);
+/// assert_eq!(reader.next_item()?, Some(Value::U8(0)));
+/// assert_eq!(reader.by_ref().count(), 99);
+/// assert_eq!(reader.next_item()?, None);
+///
+/// assert_eq!(binn_ir::decode(&mut source)?, Some(Value::from("next")));
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [Value::List]: enum.Value.html#variant.List
+/// [fn:decode]: fn.decode.html
+#[derive(Debug)]
+pub struct ListReader<R> {
+    source: R,
+    len: Size,
+    remaining: Size,
+    failed: bool,
+}
+
+impl<R> ListReader<R> where R: Read {
+
+    /// # Makes new instance
+    ///
+    /// Source must be positioned at the list's header. The header is decoded right away.
+    pub fn new(mut source: R) -> IoResult<Self> {
+        let (_, len) = crate::decode_list_header(&mut source)?.ok_or_else(|| io::Error::new(ErrorKind::UnexpectedEof, __!("missing list")))?;
+        Ok(Self {
+            source,
+            len,
+            remaining: len,
+            failed: false,
+        })
+    }
+
+    /// # Decodes next item
+    ///
+    /// Returns `Ok(None)` after the last item. Then source is positioned right after the list.
+    pub fn next_item(&mut self) -> IoResult<Option<Value>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        let item = crate::decode(&mut self.source)?.ok_or_else(missing_item)?;
+        self.remaining -= 1;
+        Ok(Some(item))
+    }
+
+    /// # Skips next item, without decoding it
+    ///
+    /// See [`skip_value()`][fn:skip_value]. Returns `false` if there are no more items.
+    ///
+    /// [fn:skip_value]: fn.skip_value.html
+    pub fn skip_item(&mut self) -> IoResult<bool> {
+        if self.remaining == 0 {
+            return Ok(false);
+        }
+        crate::skip_value(&mut self.source)?.ok_or_else(missing_item)?;
+        self.remaining -= 1;
+        Ok(true)
+    }
+
+}
+
+impl<R> ListReader<R> {
+
+    /// # Item count of the list
+    pub const fn len(&self) -> Size {
+        self.len
+    }
+
+    /// # Checks if the list is empty
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// # Count of items which have not been read
+    pub const fn remaining(&self) -> Size {
+        self.remaining
+    }
+
+    /// # Gets a reference to the source
+    pub const fn get_ref(&self) -> &R {
+        &self.source
+    }
+
+    /// # Gets a mutable reference to the source
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.source
+    }
+
+    /// # Unwraps the source
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+
+}
+
+impl<R> Iterator for ListReader<R> where R: Read {
+
+    type Item = IoResult<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let result = self.next_item().transpose();
+        self.failed = matches!(result, Some(Err(_)));
+        result
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.failed {
+            true => (0, Some(0)),
+            false => (0, Some(self.remaining as usize)),
+        }
+    }
+
+}
+
+impl<R> core::iter::FusedIterator for ListReader<R> where R: Read {}
+
+/// # Entries of a map, or an object
+#[derive(Debug)]
+struct Entries<R> {
+    source: R,
+    len: Size,
+    remaining: Size,
+    value_pending: bool,
+    failed: bool,
+}
+
+impl<R> Entries<R> where R: Read {
+
+    /// # Makes new instance, from source and item count
+    fn new(source: R, len: Size) -> Self {
+        Self {
+            source,
+            len,
+            remaining: len,
+            value_pending: false,
+            failed: false,
+        }
+    }
+
+    /// # Decodes next key, via given function
+    ///
+    /// If the value of previous key has not been read, it is skipped.
+    fn next_key<K, F>(&mut self, read_key: F) -> IoResult<Option<K>> where F: FnOnce(&mut R) -> IoResult<K> {
+        if self.value_pending {
+            self.skip_value()?;
+        }
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        let key = read_key(&mut self.source)?;
+        self.remaining -= 1;
+        self.value_pending = true;
+        Ok(Some(key))
+    }
+
+    /// # Decodes value of current key
+    fn read_value(&mut self) -> IoResult<Value> {
+        match self.value_pending {
+            true => {
+                let value = crate::decode(&mut self.source)?.ok_or_else(missing_item)?;
+                self.value_pending = false;
+                Ok(value)
+            },
+            false => Err(io::Error::new(ErrorKind::InvalidInput, __!("there is no key whose value has not been read"))),
+        }
+    }
+
+    /// # Skips value of current key, if any
+    fn skip_value(&mut self) -> IoResult<()> {
+        if self.value_pending {
+            crate::skip_value(&mut self.source)?.ok_or_else(missing_item)?;
+            self.value_pending = false;
+        }
+        Ok(())
+    }
+
+    /// # Decodes next entry, via given function for keys
+    fn next_entry<K, F>(&mut self, read_key: F) -> IoResult<Option<(K, Value)>> where F: FnOnce(&mut R) -> IoResult<K> {
+        match self.next_key(read_key)? {
+            Some(key) => Ok(Some((key, self.read_value()?))),
+            None => Ok(None),
+        }
+    }
+
+    /// # Gets next item for iterators
+    fn next<K, F>(&mut self, read_key: F) -> Option<IoResult<(K, Value)>> where F: FnOnce(&mut R) -> IoResult<K> {
+        if self.failed {
+            return None;
+        }
+
+        let result = self.next_entry(read_key).transpose();
+        self.failed = matches!(result, Some(Err(_)));
+        result
+    }
+
+    /// # Size hint for iterators
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.failed {
+            true => (0, Some(0)),
+            false => (0, Some(self.remaining as usize)),
+        }
+    }
+
+}
+
+/// # A reader of an [`Object`][Value::Object], which decodes its entries one by one
+///
+/// This is the same as [`ListReader`][struct:ListReader], for objects. Entries can be decoded as a whole, via
+/// [`next_entry()`][#next_entry()]. Or keys can be decoded first, via [`next_key()`][#next_key()], so you can decide whether to read
+/// their values. That way, you can stop as soon as you find a key, without decoding the rest of the object.
+///
+/// As an iterator, it yields entries. Once an error is returned, the iterator stops.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{ObjectReader, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut object = binn_ir::object_from("data", vec![0_u8; 1024]);
+/// object.object_insert("id", 9)?;
+/// object.object_insert("name", "moon")?;
+/// let mut buf = vec![];
+/// object.encode(&mut buf)?;
+///
+/// let mut reader = ObjectReader::new(&buf[..])?;
+/// let mut id = None;
+/// while let Some(key) = reader.next_key()? {
+///     // Values of other keys are skipped, without being decoded
+///     if key == "id" {
+///         id = Some(reader.read_value()?);
+///         break;
+///     }
+/// }
+/// assert_eq!(id, Some(Value::I32(9)));
+/// assert_eq!(reader.next_entry()?, Some(("name".into(), Value::from("moon"))));
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [Value::Object]: enum.Value.html#variant.Object
+/// [struct:ListReader]: struct.ListReader.html
+/// [#next_entry()]: #method.next_entry
+/// [#next_key()]: #method.next_key
+#[derive(Debug)]
+pub struct ObjectReader<R> {
+    entries: Entries<R>,
+}
+
+impl<R> ObjectReader<R> where R: Read {
+
+    /// # Makes new instance
+    ///
+    /// Source must be positioned at the object's header. The header is decoded right away.
+    pub fn new(mut source: R) -> IoResult<Self> {
+        let (_, len) = crate::decode_object_header(&mut source)?
+            .ok_or_else(|| io::Error::new(ErrorKind::UnexpectedEof, __!("missing object")))?;
+        Ok(Self {
+            entries: Entries::new(source, len),
+        })
+    }
+
+    /// # Decodes next entry
+    ///
+    /// Returns `Ok(None)` after the last entry. Then source is positioned right after the object.
+    pub fn next_entry(&mut self) -> IoResult<Option<(ObjectKey, Value)>> {
+        self.entries.next_entry(crate::decode_object_key)
+    }
+
+    /// # Decodes next key
+    ///
+    /// Its value can then be decoded via [`read_value()`][#read_value()], or skipped via [`skip_value()`][#skip_value()]. If you do neither,
+    /// the value is skipped on next call to this function, or to [`next_entry()`][#next_entry()].
+    ///
+    /// Returns `Ok(None)` after the last key.
+    ///
+    /// [#read_value()]: #method.read_value
+    /// [#skip_value()]: #method.skip_value
+    /// [#next_entry()]: #method.next_entry
+    pub fn next_key(&mut self) -> IoResult<Option<ObjectKey>> {
+        self.entries.next_key(crate::decode_object_key)
+    }
+
+    /// # Decodes value of the key which has just been decoded via [`next_key()`][#next_key()]
+    ///
+    /// An error is returned if there is no such key, or its value has already been read.
+    ///
+    /// [#next_key()]: #method.next_key
+    pub fn read_value(&mut self) -> IoResult<Value> {
+        self.entries.read_value()
+    }
+
+    /// # Skips value of the key which has just been decoded via [`next_key()`][#next_key()], without decoding it
+    ///
+    /// If there is no such key, this function does nothing.
+    ///
+    /// [#next_key()]: #method.next_key
+    pub fn skip_value(&mut self) -> IoResult<()> {
+        self.entries.skip_value()
+    }
+
+}
+
+impl<R> ObjectReader<R> {
+
+    /// # Item count of the object
+    pub const fn len(&self) -> Size {
+        self.entries.len
+    }
+
+    /// # Checks if the object is empty
+    pub const fn is_empty(&self) -> bool {
+        self.entries.len == 0
+    }
+
+    /// # Count of keys which have not been read
+    pub const fn remaining(&self) -> Size {
+        self.entries.remaining
+    }
+
+    /// # Gets a reference to the source
+    pub const fn get_ref(&self) -> &R {
+        &self.entries.source
+    }
+
+    /// # Gets a mutable reference to the source
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.entries.source
+    }
+
+    /// # Unwraps the source
+    pub fn into_inner(self) -> R {
+        self.entries.source
+    }
+
+}
+
+impl<R> Iterator for ObjectReader<R> where R: Read {
+
+    type Item = IoResult<(ObjectKey, Value)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next(crate::decode_object_key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+
+}
+
+impl<R> core::iter::FusedIterator for ObjectReader<R> where R: Read {}
+
+/// # A reader of a [`Map`][Value::Map], which decodes its entries one by one
+///
+/// This is the same as [`ObjectReader`][struct:ObjectReader], for maps.
+///
+/// [Value::Map]: enum.Value.html#variant.Map
+/// [struct:ObjectReader]: struct.ObjectReader.html
+#[derive(Debug)]
+pub struct MapReader<R> {
+    entries: Entries<R>,
+}
+
+impl<R> MapReader<R> where R: Read {
+
+    /// # Makes new instance
+    ///
+    /// Source must be positioned at the map's header. The header is decoded right away.
+    pub fn new(mut source: R) -> IoResult<Self> {
+        let (_, len) = crate::decode_map_header(&mut source)?.ok_or_else(|| io::Error::new(ErrorKind::UnexpectedEof, __!("missing map")))?;
+        Ok(Self {
+            entries: Entries::new(source, len),
+        })
+    }
+
+    /// # Decodes next entry
+    ///
+    /// See [`ObjectReader::next_entry()`][ObjectReader#next_entry()].
+    ///
+    /// [ObjectReader#next_entry()]: struct.ObjectReader.html#method.next_entry
+    pub fn next_entry(&mut self) -> IoResult<Option<(MapKey, Value)>> {
+        self.entries.next_entry(crate::decode_map_key)
+    }
+
+    /// # Decodes next key
+    ///
+    /// See [`ObjectReader::next_key()`][ObjectReader#next_key()].
+    ///
+    /// [ObjectReader#next_key()]: struct.ObjectReader.html#method.next_key
+    pub fn next_key(&mut self) -> IoResult<Option<MapKey>> {
+        self.entries.next_key(crate::decode_map_key)
+    }
+
+    /// # Decodes value of the key which has just been decoded via [`next_key()`][#next_key()]
+    ///
+    /// See [`ObjectReader::read_value()`][ObjectReader#read_value()].
+    ///
+    /// [#next_key()]: #method.next_key
+    /// [ObjectReader#read_value()]: struct.ObjectReader.html#method.read_value
+    pub fn read_value(&mut self) -> IoResult<Value> {
+        self.entries.read_value()
+    }
+
+    /// # Skips value of the key which has just been decoded via [`next_key()`][#next_key()], without decoding it
+    ///
+    /// See [`ObjectReader::skip_value()`][ObjectReader#skip_value()].
+    ///
+    /// [#next_key()]: #method.next_key
+    /// [ObjectReader#skip_value()]: struct.ObjectReader.html#method.skip_value
+    pub fn skip_value(&mut self) -> IoResult<()> {
+        self.entries.skip_value()
+    }
+
+}
+
+impl<R> MapReader<R> {
+
+    /// # Item count of the map
+    pub const fn len(&self) -> Size {
+        self.entries.len
+    }
+
+    /// # Checks if the map is empty
+    pub const fn is_empty(&self) -> bool {
+        self.entries.len == 0
+    }
+
+    /// # Count of keys which have not been read
+    pub const fn remaining(&self) -> Size {
+        self.entries.remaining
+    }
+
+    /// # Gets a reference to the source
+    pub const fn get_ref(&self) -> &R {
+        &self.entries.source
+    }
+
+    /// # Gets a mutable reference to the source
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.entries.source
+    }
+
+    /// # Unwraps the source
+    pub fn into_inner(self) -> R {
+        self.entries.source
+    }
+
+}
+
+impl<R> Iterator for MapReader<R> where R: Read {
+
+    type Item = IoResult<(MapKey, Value)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next(crate::decode_map_key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+
+}
+
+impl<R> core::iter::FusedIterator for MapReader<R> where R: Read {}
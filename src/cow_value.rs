@@ -0,0 +1,454 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Copy-on-write value
+
+use {
+    alloc::{
+        collections::BTreeMap,
+        vec::Vec,
+    },
+    core::convert::TryFrom,
+
+    crate::{
+        List, Map, MapKey, ObjectKey, Path, PathSegment, Result, SharedValue, Size, Value,
+        value_enum::{size_of_container, size_of_object_key},
+    },
+};
+
+#[cfg(feature="std")]
+use {
+    std::io::Write,
+
+    crate::{
+        IoResult,
+        value_enum::write_container_header,
+    },
+};
+
+/// # Copy-on-write value
+///
+/// This starts from a shared base value, and records modifications -- via [paths][struct:Path] -- instead of copying the base. Unchanged
+/// branches are read from the base directly; only changed ones are built anew. So it's cheap to make many variants of one large
+/// template, each differing in a handful of fields.
+///
+/// - [`set()`][#set()] replaces items of lists, or inserts/replaces items of maps and objects.
+/// - [`remove()`][#remove()] removes items of maps and objects.
+///
+/// [`size()`][#size()] and [`encode()`][#encode()] work without copying the base. [`to_value()`][#to_value()] builds the whole tree.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{CowValue, Path, PathSegment, SharedValue, Value};
+///
+/// # #[cfg(feature="std")]
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut template = binn_ir::object();
+/// template.object_insert("method", "get")?;
+/// template.object_insert("params", binn_ir::object())?;
+/// template.object_insert("payload", Value::Blob(vec![0; 10_000]))?;
+/// let template = SharedValue::new(template);
+///
+/// let mut request = CowValue::new(template.clone());
+/// request.set(&Path::from(vec![PathSegment::ObjectKey("params".into()), PathSegment::ObjectKey("id".into())]), 99_u8)?;
+/// request.remove(&Path::from(vec![PathSegment::ObjectKey("method".into())]))?;
+///
+/// let mut buf = vec![];
+/// request.encode(&mut buf)?;
+///
+/// let expected = request.to_value();
+/// assert_eq!(expected.object_by(&["params", "id"])?, &Value::U8(99));
+/// assert!(expected.object_maybe_by(&["method"])?.is_none());
+/// assert_eq!(binn_ir::decode(&mut &buf[..])?, Some(expected));
+/// # Ok(()) }
+/// # #[cfg(feature="std")]
+/// # test().unwrap();
+/// ```
+///
+/// [struct:Path]: struct.Path.html
+/// [#set()]: #method.set
+/// [#remove()]: #method.remove
+/// [#size()]: #method.size
+/// [#encode()]: #method.encode
+/// [#to_value()]: #method.to_value
+#[derive(Debug, Clone)]
+pub struct CowValue {
+    base: SharedValue,
+    patch: Patch,
+}
+
+/// # A modification
+#[derive(Debug, Clone)]
+enum Patch {
+
+    /// # The value is replaced
+    Set(Value),
+
+    /// # The value is removed
+    Remove,
+
+    /// # Some items of the value are modified
+    Items(BTreeMap<PathSegment, Patch>),
+
+}
+
+impl CowValue {
+
+    /// # Makes new instance
+    pub fn new<V>(base: V) -> Self where V: Into<SharedValue> {
+        Self {
+            base: base.into(),
+            patch: Patch::Items(BTreeMap::new()),
+        }
+    }
+
+    /// # Base value
+    pub fn base(&self) -> &SharedValue {
+        &self.base
+    }
+
+    /// # Checks if there are any modifications
+    pub fn is_modified(&self) -> bool {
+        !matches!(&self.patch, Patch::Items(items) if items.is_empty())
+    }
+
+    /// # Sets a value
+    ///
+    /// - For lists, the item must already exist.
+    /// - For maps and objects, the item is inserted, or replaced.
+    ///
+    /// An empty path replaces the whole value.
+    ///
+    /// ## Errors
+    ///
+    /// If the path does not match the value: a parent does not exist, or is not the container that the path expects.
+    pub fn set<V>(&mut self, path: &Path, value: V) -> Result<()> where V: Into<Value> {
+        set_patch(&mut self.patch, Some(&self.base), path.segments(), value.into())
+    }
+
+    /// # Removes an item of a map or an object
+    ///
+    /// ## Errors
+    ///
+    /// If the path is empty, points to an item of a list, or does not match the value.
+    pub fn remove(&mut self, path: &Path) -> Result<()> {
+        remove_patch(&mut self.patch, Some(&self.base), path.segments())
+    }
+
+    /// # Calculates size of the modified value
+    pub fn size(&self) -> Result<Size> {
+        size_of_patch(Some(&self.base), &self.patch)
+    }
+
+    /// # Encodes the modified value into a stream
+    ///
+    /// Returns the number of bytes written.
+    #[cfg(feature="std")]
+    pub fn encode<W>(&self, stream: &mut W) -> IoResult<Size> where W: Write {
+        encode_patch(Some(&self.base), &self.patch, stream)
+    }
+
+    /// # Builds the modified value
+    ///
+    /// Unchanged branches are cloned from the base.
+    pub fn to_value(&self) -> Value {
+        build(Some(&self.base), &self.patch)
+    }
+
+}
+
+impl From<SharedValue> for CowValue {
+
+    fn from(base: SharedValue) -> Self {
+        Self::new(base)
+    }
+
+}
+
+/// # Key of an item
+enum Key<'a> {
+    Index,
+    Map(MapKey),
+    Object(&'a str),
+}
+
+/// # An item of a modified container
+enum Item<'a> {
+
+    /// # Unchanged item
+    Base(&'a Value),
+
+    /// # Modified item, with its base (if any)
+    Patched(Option<&'a Value>, &'a Patch),
+
+}
+
+impl Item<'_> {
+
+    /// # Calculates size
+    fn size(&self) -> Result<Size> {
+        match self {
+            Item::Base(value) => value.size(),
+            Item::Patched(base, patch) => size_of_patch(*base, patch),
+        }
+    }
+
+}
+
+/// # Finds an item of a container
+fn child<'a>(container: &'a Value, segment: &PathSegment) -> Result<Option<&'a Value>> {
+    match (container, segment) {
+        (Value::List(list), PathSegment::Index(index)) => Ok(list.get(*index as usize)),
+        (Value::Map(map), PathSegment::MapKey(key)) => Ok(map.get(key)),
+        (Value::Object(object), PathSegment::ObjectKey(key)) => Ok(object.get(key)),
+        _ => Err(err!("path segment {} does not match value", segment)),
+    }
+}
+
+/// # Finds a mutable item of a container
+fn child_mut<'a>(container: &'a mut Value, segment: &PathSegment) -> Result<Option<&'a mut Value>> {
+    match (container, segment) {
+        (Value::List(list), PathSegment::Index(index)) => Ok(list.get_mut(*index as usize)),
+        (Value::Map(map), PathSegment::MapKey(key)) => Ok(map.get_mut(key)),
+        (Value::Object(object), PathSegment::ObjectKey(key)) => Ok(object.get_mut(key)),
+        _ => Err(err!("path segment {} does not match value", segment)),
+    }
+}
+
+/// # Finds parent of the last segment, in an owned value
+fn parent_mut<'a>(mut value: &'a mut Value, parents: &[PathSegment]) -> Result<&'a mut Value> {
+    for segment in parents {
+        value = child_mut(value, segment)?.ok_or_else(|| err!("value not found at segment {}", segment))?;
+    }
+    Ok(value)
+}
+
+/// # Sets a value in a patch
+fn set_patch(patch: &mut Patch, base: Option<&Value>, segments: &[PathSegment], value: Value) -> Result<()> {
+    let (segment, rest) = match segments.split_first() {
+        Some(split) => split,
+        None => {
+            *patch = Patch::Set(value);
+            return Ok(());
+        },
+    };
+
+    let items = match (patch, base) {
+        (Patch::Set(current), _) => return set_value(current, segments, value),
+        (Patch::Items(items), Some(base)) => (items, base),
+        _ => return Err(err!("value not found at segment {}", segment)),
+    };
+    let (items, base) = items;
+
+    let child_base = child(base, segment)?;
+    if let Some(child_patch) = items.get_mut(segment) {
+        return match (child_patch, rest.is_empty()) {
+            (Patch::Remove, false) => Err(err!("value not found at segment {}", segment)),
+            (child_patch, _) => set_patch(child_patch, child_base, rest, value),
+        };
+    }
+
+    match (child_base, rest.is_empty()) {
+        (Some(child_base), false) => {
+            let mut child_patch = Patch::Items(BTreeMap::new());
+            set_patch(&mut child_patch, Some(child_base), rest, value)?;
+            items.insert(segment.clone(), child_patch);
+        },
+        (None, false) => return Err(err!("value not found at segment {}", segment)),
+        (None, true) if matches!(segment, PathSegment::Index(_)) => return Err(err!("index is out of bounds: {}", segment)),
+        (_, true) => drop(items.insert(segment.clone(), Patch::Set(value))),
+    };
+    Ok(())
+}
+
+/// # Sets a value inside an owned value
+fn set_value(current: &mut Value, segments: &[PathSegment], value: Value) -> Result<()> {
+    let (last, parents) = segments.split_last().ok_or_else(|| err!("empty path"))?;
+    match (parent_mut(current, parents)?, last) {
+        (Value::List(list), PathSegment::Index(index)) => match list.get_mut(*index as usize) {
+            Some(item) => *item = value,
+            None => return Err(err!("index is out of bounds: {}", last)),
+        },
+        (Value::Map(map), PathSegment::MapKey(key)) => drop(map.insert(*key, value)),
+        (Value::Object(object), PathSegment::ObjectKey(key)) => drop(object.insert(key.clone(), value)),
+        _ => return Err(err!("path segment {} does not match value", last)),
+    };
+    Ok(())
+}
+
+/// # Removes a value in a patch
+fn remove_patch(patch: &mut Patch, base: Option<&Value>, segments: &[PathSegment]) -> Result<()> {
+    let (segment, rest) = segments.split_first().ok_or_else(|| err!("empty path"))?;
+    if let PathSegment::Index(_) = segment {
+        if rest.is_empty() {
+            return Err(err!("items of lists can't be removed: {}", segment));
+        }
+    }
+
+    let (items, base) = match (patch, base) {
+        (Patch::Set(current), _) => return remove_value(current, segments),
+        (Patch::Items(items), Some(base)) => (items, base),
+        _ => return Err(err!("value not found at segment {}", segment)),
+    };
+
+    let child_base = child(base, segment)?;
+    match (items.get_mut(segment), rest.is_empty()) {
+        (Some(Patch::Remove), _) => Err(err!("value not found at segment {}", segment)),
+        (Some(child_patch), false) => remove_patch(child_patch, child_base, rest),
+        (Some(_), true) => {
+            match child_base {
+                Some(_) => drop(items.insert(segment.clone(), Patch::Remove)),
+                None => drop(items.remove(segment)),
+            };
+            Ok(())
+        },
+        (None, _) => match (child_base, rest.is_empty()) {
+            (Some(child_base), false) => {
+                let mut child_patch = Patch::Items(BTreeMap::new());
+                remove_patch(&mut child_patch, Some(child_base), rest)?;
+                items.insert(segment.clone(), child_patch);
+                Ok(())
+            },
+            (Some(_), true) => {
+                items.insert(segment.clone(), Patch::Remove);
+                Ok(())
+            },
+            (None, _) => Err(err!("value not found at segment {}", segment)),
+        },
+    }
+}
+
+/// # Removes a value inside an owned value
+fn remove_value(current: &mut Value, segments: &[PathSegment]) -> Result<()> {
+    let (last, parents) = segments.split_last().ok_or_else(|| err!("empty path"))?;
+    let removed = match (parent_mut(current, parents)?, last) {
+        (Value::Map(map), PathSegment::MapKey(key)) => map.remove(key),
+        (Value::Object(object), PathSegment::ObjectKey(key)) => object.remove(key),
+        _ => return Err(err!("path segment {} does not match value", last)),
+    };
+    match removed {
+        Some(_) => Ok(()),
+        None => Err(err!("value not found at segment {}", last)),
+    }
+}
+
+/// # Collects items of a modified container, in order of encoding
+fn items<'a>(base: &'a Value, patches: &'a BTreeMap<PathSegment, Patch>) -> Result<Vec<(Key<'a>, Item<'a>)>> {
+    let item = |base: &'a Value, segment: PathSegment| match patches.get(&segment) {
+        Some(patch) => Item::Patched(Some(base), patch),
+        None => Item::Base(base),
+    };
+
+    let result = match base {
+        Value::List(list) => list.iter().enumerate().map(|(index, v)| (Key::Index, item(v, PathSegment::Index(index as Size)))).collect(),
+        Value::Map(map) => {
+            let mut items = map.iter().filter(|(k, _)| !patches.contains_key(&PathSegment::MapKey(**k)))
+                .map(|(k, v)| (*k, Item::Base(v))).collect::<BTreeMap<_, _>>();
+            for (segment, patch) in patches {
+                if let (PathSegment::MapKey(k), false) = (segment, matches!(patch, Patch::Remove)) {
+                    items.insert(*k, Item::Patched(map.get(k), patch));
+                }
+            }
+            items.into_iter().map(|(k, item)| (Key::Map(k), item)).collect()
+        },
+        Value::Object(object) => {
+            let mut items = object.iter().map(|(k, v)| (k.as_str(), Item::Base(v))).collect::<BTreeMap<_, _>>();
+            for (segment, patch) in patches {
+                if let PathSegment::ObjectKey(k) = segment {
+                    match patch {
+                        Patch::Remove => drop(items.remove(k.as_str())),
+                        _ => drop(items.insert(k.as_str(), Item::Patched(object.get(k), patch))),
+                    };
+                }
+            }
+            items.into_iter().map(|(k, item)| (Key::Object(k), item)).collect()
+        },
+        _ => return Err(err!("modified value is not a container")),
+    };
+    Ok(result)
+}
+
+/// # Calculates size of a patch
+fn size_of_patch(base: Option<&Value>, patch: &Patch) -> Result<Size> {
+    let (base, patches) = match (base, patch) {
+        (_, Patch::Set(value)) => return value.size(),
+        (Some(base), Patch::Items(patches)) if patches.is_empty() => return base.size(),
+        (Some(base), Patch::Items(patches)) => (base, patches),
+        _ => return Err(err!("value has been removed")),
+    };
+
+    let items = items(base, patches)?;
+    let mut total: u64 = 0;
+    for (key, item) in &items {
+        let key_size = match key {
+            Key::Index => 0,
+            Key::Map(_) => core::mem::size_of::<MapKey>() as Size,
+            Key::Object(key) => size_of_object_key(key)?,
+        };
+        total += u64::from(key_size) + u64::from(item.size()?);
+    }
+    let total = Size::try_from(total).map_err(|_| err_code!("data too large (bytes)", total))?;
+    size_of_container(items.len(), total)
+}
+
+/// # Encodes a patch
+#[cfg(feature="std")]
+fn encode_patch<W>(base: Option<&Value>, patch: &Patch, stream: &mut W) -> IoResult<Size> where W: Write {
+    let (base, patches) = match (base, patch) {
+        (_, Patch::Set(value)) => return value.encode(stream),
+        (Some(base), Patch::Items(patches)) if patches.is_empty() => return base.encode(stream),
+        (Some(base), Patch::Items(patches)) => (base, patches),
+        _ => return Err(err!("value has been removed").into()),
+    };
+
+    let size = size_of_patch(Some(base), patch)?;
+    let items = items(base, patches)?;
+    let type_byte = match base {
+        Value::List(_) => crate::value::LIST,
+        Value::Map(_) => crate::value::MAP,
+        _ => crate::value::OBJECT,
+    };
+    write_container_header(type_byte, size, items.len() as Size, stream)?;
+    for (key, item) in items {
+        match key {
+            Key::Index => (),
+            Key::Map(key) => stream.write_all(&key.to_be_bytes())?,
+            Key::Object(key) => {
+                stream.write_all(&[key.len() as u8])?;
+                stream.write_all(key.as_bytes())?;
+            },
+        };
+        match item {
+            Item::Base(value) => value.encode(stream)?,
+            Item::Patched(base, patch) => encode_patch(base, patch, stream)?,
+        };
+    }
+    Ok(size)
+}
+
+/// # Builds a value from a patch
+fn build(base: Option<&Value>, patch: &Patch) -> Value {
+    let (base, patches) = match (base, patch) {
+        (Some(base), Patch::Items(patches)) if !patches.is_empty() => (base, patches),
+        (Some(base), Patch::Items(_)) => return base.clone(),
+        (_, Patch::Set(value)) => return value.clone(),
+        _ => return Value::Null,
+    };
+
+    // Base is a container, since patches have been verified against it
+    let items = items(base, patches).unwrap_or_default().into_iter().map(|(key, item)| (key, match item {
+        Item::Base(value) => value.clone(),
+        Item::Patched(base, patch) => build(base, patch),
+    }));
+    match base {
+        Value::List(_) => Value::List(items.map(|(_, v)| v).collect::<List>()),
+        Value::Map(_) => Value::Map(items.filter_map(|(k, v)| match k {
+            Key::Map(k) => Some((k, v)),
+            _ => None,
+        }).collect::<Map>()),
+        _ => Value::Object(items.filter_map(|(k, v)| match k {
+            Key::Object(k) => Some((ObjectKey::from(k), v)),
+            _ => None,
+        }).collect()),
+    }
+}
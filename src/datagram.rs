@@ -0,0 +1,114 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Datagrams
+
+use {
+    alloc::vec::Vec,
+    core::fmt::{self, Display, Formatter},
+    std::{
+        error,
+        io::{self, ErrorKind},
+    },
+
+    crate::{IoResult, Value},
+};
+
+/// # Encodes a value into a datagram
+///
+/// The datagram is the encoded value, with nothing else -- suitable for protocols which send one value per UDP packet. Size of the value is
+/// verified before encoding: if it's larger than `max_len` -- such as the path MTU, minus IP and UDP headers -- an error of
+/// [`InvalidInput`][std::io/ErrorKind#InvalidInput] is returned, which carries a [`DatagramTooLarge`][struct:DatagramTooLarge].
+///
+/// Datagrams can be decoded via [`decode_datagram()`][fn:decode_datagram].
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{DatagramTooLarge, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let value = Value::from("hello");
+/// let datagram = binn_ir::encode_datagram(&value, 1472)?;
+/// assert_eq!(binn_ir::decode_datagram(&datagram)?, value);
+///
+/// let err = binn_ir::encode_datagram(&Value::Blob(vec![0; 2000]), 1472).unwrap_err();
+/// assert_eq!(DatagramTooLarge::find(&err).map(|e| e.size()), Some(2005));
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [std::io/ErrorKind#InvalidInput]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput
+/// [struct:DatagramTooLarge]: struct.DatagramTooLarge.html
+/// [fn:decode_datagram]: fn.decode_datagram.html
+pub fn encode_datagram(value: &Value, max_len: usize) -> IoResult<Vec<u8>> {
+    let size = value.size()? as usize;
+    if size > max_len {
+        return Err(DatagramTooLarge::new_io_error(size, max_len));
+    }
+
+    let mut result = Vec::with_capacity(size);
+    value.encode(&mut result)?;
+    Ok(result)
+}
+
+/// # Decodes a value from a datagram
+///
+/// The datagram must hold exactly one value: an empty datagram, or trailing bytes after the value, are errors of
+/// [`InvalidData`][std::io/ErrorKind#InvalidData].
+///
+/// [std::io/ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+pub fn decode_datagram(datagram: &[u8]) -> IoResult<Value> {
+    let mut datagram = datagram;
+    match crate::decode(&mut datagram)? {
+        Some(value) => match datagram.is_empty() {
+            true => Ok(value),
+            false => Err(io::Error::new(ErrorKind::InvalidData, __!("trailing data in datagram: {} byte(s)", datagram.len()))),
+        },
+        None => Err(io::Error::new(ErrorKind::InvalidData, __!("empty datagram"))),
+    }
+}
+
+/// # Error of a value which is too large for a datagram
+///
+/// See [`encode_datagram()`][fn:encode_datagram].
+///
+/// [fn:encode_datagram]: fn.encode_datagram.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DatagramTooLarge {
+    size: usize,
+    max_len: usize,
+}
+
+impl DatagramTooLarge {
+
+    /// # Makes new I/O error
+    fn new_io_error(size: usize, max_len: usize) -> io::Error {
+        io::Error::new(ErrorKind::InvalidInput, Self { size, max_len })
+    }
+
+    /// # Size of the value, in bytes
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// # Max length of a datagram, in bytes
+    pub const fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// # Finds this error inside an I/O error
+    pub fn find(err: &io::Error) -> Option<&Self> {
+        err.get_ref()?.downcast_ref()
+    }
+
+}
+
+impl Display for DatagramTooLarge {
+
+    fn fmt(&self, f: &mut Formatter) -> core::result::Result<(), fmt::Error> {
+        write!(f, "[{}] value needs {} byte(s), which exceeds max length of datagram: {}", crate::TAG, self.size, self.max_len)
+    }
+
+}
+
+impl error::Error for DatagramTooLarge {}
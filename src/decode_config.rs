@@ -0,0 +1,185 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Decode config
+
+use crate::{Result, Value, value::DEFAULT_MAX_DEPTH};
+
+/// # Decode config
+///
+/// Default config is the same as what [`decode()`][fn:decode] uses: no optional validation is performed.
+///
+/// This struct is marked as non-exhaustive, so new options can be added later. To make one, start with [`new()`][fn:new] (or
+/// [`Default`][core::default/Default]), then set the fields you want.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{DecodeConfig, Encoder, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// buf.encode(&Value::Date("2019-02-29".into()))?;
+///
+/// assert!(binn_ir::decode(&mut &buf[..])?.is_some());
+///
+/// let mut config = DecodeConfig::new();
+/// config.validate_temporal = true;
+/// assert!(binn_ir::decode_with_config(&mut &buf[..], &config).is_err());
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [fn:decode]: fn.decode.html
+/// [fn:new]: #method.new
+/// [core::default/Default]: https://doc.rust-lang.org/core/default/trait.Default.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct DecodeConfig {
+
+    /// # Validates formats of [`Date`][Value::Date], [`Time`][Value::Time] and [`DateTime`][Value::DateTime]
+    ///
+    /// See [`Value::validate_temporal()`][Value::validate_temporal()].
+    ///
+    /// [Value::Date]: enum.Value.html#variant.Date
+    /// [Value::Time]: enum.Value.html#variant.Time
+    /// [Value::DateTime]: enum.Value.html#variant.DateTime
+    /// [Value::validate_temporal()]: enum.Value.html#method.validate_temporal
+    pub validate_temporal: bool,
+
+    /// # Validates syntax of [`DecimalStr`][Value::DecimalStr]
+    ///
+    /// See [`Value::validate_decimal_str()`][Value::validate_decimal_str()].
+    ///
+    /// [Value::DecimalStr]: enum.Value.html#variant.DecimalStr
+    /// [Value::validate_decimal_str()]: enum.Value.html#method.validate_decimal_str
+    pub validate_decimal_str: bool,
+
+    /// # Strict mode
+    ///
+    /// This turns on all validations, regardless of other fields:
+    ///
+    /// - [`validate_temporal`][#validate_temporal].
+    /// - [`validate_decimal_str`][#validate_decimal_str].
+    /// - Object keys must not be empty. Their lengths are always limited to [`OBJECT_KEY_MAX_LEN`][value::OBJECT_KEY_MAX_LEN] bytes.
+    ///
+    /// Strings are always verified to be valid UTF-8, with or without this mode.
+    ///
+    /// [#validate_temporal]: #structfield.validate_temporal
+    /// [#validate_decimal_str]: #structfield.validate_decimal_str
+    /// [value::OBJECT_KEY_MAX_LEN]: value/constant.OBJECT_KEY_MAX_LEN.html
+    pub strict: bool,
+
+    /// # Max number of values that one decode call can decode
+    ///
+    /// Every value counts, including containers and all of their items, recursively. If exceeded, an error of
+    /// [`InvalidData`][std::io/ErrorKind#InvalidData] is returned.
+    ///
+    /// [std::io/ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    pub max_values: Option<u64>,
+
+    /// # Max number of bytes that one decode call can consume
+    ///
+    /// Declared sizes of strings, blobs and containers are checked _before_ reading their data, so a single malicious value can't make the
+    /// decoder allocate more than this. If exceeded, an error of [`InvalidData`][std::io/ErrorKind#InvalidData] is returned.
+    ///
+    /// [std::io/ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    pub max_bytes: Option<u64>,
+
+    /// # Max nesting depth of containers
+    ///
+    /// Default: [`DEFAULT_MAX_DEPTH`][value::DEFAULT_MAX_DEPTH]. If exceeded, an error carrying
+    /// [`DepthLimitExceeded`][struct:DepthLimitExceeded] is returned.
+    ///
+    /// [value::DEFAULT_MAX_DEPTH]: value/constant.DEFAULT_MAX_DEPTH.html
+    /// [struct:DepthLimitExceeded]: struct.DepthLimitExceeded.html
+    pub max_depth: usize,
+
+}
+
+impl Default for DecodeConfig {
+
+    fn default() -> Self {
+        Self::new()
+    }
+
+}
+
+impl DecodeConfig {
+
+    /// # Makes new default config
+    pub const fn new() -> Self {
+        Self {
+            validate_temporal: false,
+            validate_decimal_str: false,
+            strict: false,
+            max_values: None,
+            max_bytes: None,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// # Makes new config, with [strict mode][#strict] turned on
+    ///
+    /// [#strict]: #structfield.strict
+    pub const fn strict() -> Self {
+        Self {
+            strict: true,
+            ..Self::new()
+        }
+    }
+
+    /// # Checks if a value -- once encoded -- can be decoded within limits of this config
+    ///
+    /// Senders can use this to reject a value before sending it to a peer which decodes with this config. Limits are checked via
+    /// [`Value::deep_len()`][Value::deep_len()], [`Value::max_depth()`][Value::max_depth()] and [`Value::size()`][Value::size()].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::{DecodeConfig, Value};
+    ///
+    /// let mut config = DecodeConfig::new();
+    /// config.max_values = Some(2);
+    ///
+    /// assert!(config.check_limits(&Value::List(vec![Value::Null])).is_ok());
+    /// assert!(config.check_limits(&Value::List(vec![Value::Null, Value::Null])).is_err());
+    /// ```
+    ///
+    /// [Value::deep_len()]: enum.Value.html#method.deep_len
+    /// [Value::max_depth()]: enum.Value.html#method.max_depth
+    /// [Value::size()]: enum.Value.html#method.size
+    pub fn check_limits(&self, value: &Value) -> Result<()> {
+        if let Some(max_values) = self.max_values {
+            let len = value.deep_len();
+            if len as u64 > max_values {
+                return Err(err!("value count exceeds limit of {}: {}", max_values, len));
+            }
+        }
+        if let Some(max_bytes) = self.max_bytes {
+            let size = value.size()?;
+            if u64::from(size) > max_bytes {
+                return Err(err!("size exceeds limit of {} byte(s): {}", max_bytes, size));
+            }
+        }
+        match value.max_depth() {
+            depth if depth > self.max_depth => Err(err!("depth exceeds limit of {}: {}", self.max_depth, depth)),
+            _ => Ok(()),
+        }
+    }
+
+    /// # Checks if temporal values should be validated
+    pub(crate) const fn validates_temporal(&self) -> bool {
+        self.strict || self.validate_temporal
+    }
+
+    /// # Checks if decimal strings should be validated
+    pub(crate) const fn validates_decimal_str(&self) -> bool {
+        self.strict || self.validate_decimal_str
+    }
+
+    /// # Checks if empty object keys should be rejected
+    pub(crate) const fn rejects_empty_object_keys(&self) -> bool {
+        self.strict
+    }
+
+}
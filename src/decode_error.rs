@@ -0,0 +1,175 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Decode error
+
+use {
+    core::fmt::{self, Display, Formatter},
+    std::{error, io},
+
+    crate::{Path, PathSegment},
+};
+
+/// # Decode error
+///
+/// Errors from decoding functions carry this struct as their inner errors. It tells where the failure occurred:
+///
+/// - [`offset()`][#offset()]: number of bytes consumed by the decoder, counted from where decoding started. That is the position right after
+///   the bytes which caused the failure.
+/// - [`path()`][#path()]: path of container indexes/keys, from the root value to the value which failed to decode.
+///
+/// Error kind of the original error is kept.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{DecodeError, PathSegment, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// Value::from(vec![Value::Null, Value::Text("x".into())]).encode(&mut buf)?;
+/// // Replace the text's null terminator
+/// let len = buf.len();
+/// buf[len - 1] = 1;
+///
+/// let err = binn_ir::decode(&mut &buf[..]).unwrap_err();
+/// let err = err.get_ref().and_then(|err| err.downcast_ref::<DecodeError>()).unwrap();
+/// assert_eq!(err.offset(), buf.len() as u64);
+/// assert_eq!(err.path().segments(), &[PathSegment::Index(1)]);
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [#offset()]: #method.offset
+/// [#path()]: #method.path
+#[derive(Debug)]
+pub struct DecodeError {
+    offset: u64,
+    path: Path,
+    source: io::Error,
+}
+
+impl DecodeError {
+
+    /// # Byte offset where the failure occurred
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// # Path to the value which failed to decode
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// # Original error
+    pub fn source_error(&self) -> &io::Error {
+        &self.source
+    }
+
+}
+
+impl Display for DecodeError {
+
+    fn fmt(&self, f: &mut Formatter) -> core::result::Result<(), fmt::Error> {
+        write!(f, "at byte {}, path {}: {}", self.offset, self.path, self.source)
+    }
+
+}
+
+impl error::Error for DecodeError {
+
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.source)
+    }
+
+}
+
+/// # Error of exceeding max nesting depth of containers
+///
+/// When containers are nested too deeply, decoders return an error of [`InvalidData`][std::io/ErrorKind#InvalidData], which carries this
+/// struct. For functions which [locate errors][struct:DecodeError], it is the inner error of
+/// [`DecodeError::source_error()`][DecodeError#source_error()]. Either way, [`find()`][#find()] finds it.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{DecodeConfig, DepthLimitExceeded, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// Value::List(vec![Value::List(vec![])]).encode(&mut buf)?;
+///
+/// let mut config = DecodeConfig::new();
+/// config.max_depth = 1;
+/// let err = binn_ir::decode_with_config(&mut &buf[..], &config).unwrap_err();
+/// assert!(DepthLimitExceeded::find(&err).is_some());
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [std::io/ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+/// [struct:DecodeError]: struct.DecodeError.html
+/// [DecodeError#source_error()]: struct.DecodeError.html#method.source_error
+/// [#find()]: #method.find
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DepthLimitExceeded {
+    max_depth: usize,
+}
+
+impl DepthLimitExceeded {
+
+    /// # Makes new I/O error
+    pub(crate) fn new_io_error(max_depth: usize) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, Self { max_depth })
+    }
+
+    /// # Max depth which has been exceeded
+    pub const fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// # Finds this error inside an I/O error
+    ///
+    /// The I/O error can carry this error directly, or via a [`DecodeError`][struct:DecodeError].
+    ///
+    /// [struct:DecodeError]: struct.DecodeError.html
+    pub fn find(err: &io::Error) -> Option<&Self> {
+        let inner = err.get_ref()?;
+        match inner.downcast_ref::<DecodeError>() {
+            Some(decode_error) => Self::find(decode_error.source_error()),
+            None => inner.downcast_ref(),
+        }
+    }
+
+}
+
+impl Display for DepthLimitExceeded {
+
+    fn fmt(&self, f: &mut Formatter) -> core::result::Result<(), fmt::Error> {
+        write!(f, "[{}] containers are nested deeper than {}", crate::TAG, self.max_depth)
+    }
+
+}
+
+impl error::Error for DepthLimitExceeded {}
+
+/// # Locates an error
+///
+/// If the error is already located, `segment` is inserted at the front of its path. Otherwise a new [`DecodeError`][struct:DecodeError] is
+/// made, with `offset` and `segment` (if any).
+///
+/// [struct:DecodeError]: struct.DecodeError.html
+pub(crate) fn locate(mut err: io::Error, offset: u64, segment: Option<PathSegment>) -> io::Error {
+    if let Some(decode_error) = err.get_mut().and_then(|e| e.downcast_mut::<DecodeError>()) {
+        if let Some(segment) = segment {
+            decode_error.path.push_front(segment);
+        }
+        return err;
+    }
+
+    let kind = err.kind();
+    let mut path = Path::new();
+    if let Some(segment) = segment {
+        path.push(segment);
+    }
+    io::Error::new(kind, DecodeError { offset, path, source: err })
+}
@@ -4,9 +4,9 @@
 
 use {
     alloc::string::String,
-    std::io::Read,
+    std::io::{Read, Write},
 
-    crate::{Blob, IoResult, List, Map, Object, Value},
+    crate::{Blob, BudgetedDecoder, DecodeConfig, Header, IoResult, List, Map, MapKey, Object, ObjectKey, Size, StreamLayer, Value, Values},
 };
 
 /// # Decoder
@@ -31,11 +31,92 @@ use {
 /// [Value::List]: enum.Value.html#variant.List
 pub trait Decoder: Read + Sized {
 
+    /// # Wraps this decoder in a layer
+    ///
+    /// See [`StreamLayer`][trait:StreamLayer].
+    ///
+    /// [trait:StreamLayer]: trait.StreamLayer.html
+    fn layer<L>(self, layer: L) -> IoResult<L::Reader<Self>> where L: StreamLayer {
+        layer.wrap_reader(self)
+    }
+
+    /// # Makes an iterator over top-level values
+    ///
+    /// See [`Values`][struct:Values]. To keep using this decoder afterwards, call this function on a mutable reference to it.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::{Decoder, Value};
+    ///
+    /// # fn test() -> binn_ir::IoResult<()> {
+    /// let mut buf = vec![];
+    /// for i in 0..3_u8 {
+    ///     Value::U8(i).encode(&mut buf)?;
+    /// }
+    ///
+    /// let mut source = std::io::Cursor::new(buf);
+    /// let values = (&mut source).iter().collect::<binn_ir::IoResult<Vec<_>>>()?;
+    /// assert_eq!(values, vec![Value::U8(0), Value::U8(1), Value::U8(2)]);
+    /// # Ok(()) }
+    /// # test().unwrap();
+    /// ```
+    ///
+    /// [struct:Values]: struct.Values.html
+    fn iter(self) -> Values<Self> {
+        Values::new(self)
+    }
+
     /// # Decodes a value
     fn decode(&mut self) -> IoResult<Option<Value>> {
         crate::decode(self)
     }
 
+    /// # Decodes a value, with a config
+    ///
+    /// See [`decode_with_config()`][fn:decode_with_config].
+    ///
+    /// [fn:decode_with_config]: fn.decode_with_config.html
+    fn decode_with_config(&mut self, config: &DecodeConfig) -> IoResult<Option<Value>> {
+        crate::decode_with_config(self, config)
+    }
+
+    /// # Makes a decoder with a budget
+    ///
+    /// See [`BudgetedDecoder`][struct:BudgetedDecoder].
+    ///
+    /// [struct:BudgetedDecoder]: struct.BudgetedDecoder.html
+    fn with_budget(self, max_values: u64, max_bytes: u64) -> BudgetedDecoder<Self> {
+        BudgetedDecoder::new(self, max_values, max_bytes)
+    }
+
+    /// # Decodes a header
+    ///
+    /// See [`decode_header()`][fn:decode_header].
+    ///
+    /// [fn:decode_header]: fn.decode_header.html
+    fn decode_header(&mut self) -> IoResult<Option<Header>> {
+        crate::decode_header(self)
+    }
+
+    /// # Skips a value
+    ///
+    /// See [`skip_value()`][fn:skip_value].
+    ///
+    /// [fn:skip_value]: fn.skip_value.html
+    fn skip_value(&mut self) -> IoResult<Option<u64>> {
+        crate::skip_value(self)
+    }
+
+    /// # Skips some values
+    ///
+    /// See [`skip_n()`][fn:skip_n].
+    ///
+    /// [fn:skip_n]: fn.skip_n.html
+    fn skip_n(&mut self, count: usize) -> IoResult<usize> {
+        crate::skip_n(self, count)
+    }
+
     /// # Decodes a null
     fn decode_null(&mut self) -> IoResult<Option<()>> {
         crate::decode_null(self)
@@ -139,6 +220,16 @@ pub trait Decoder: Read + Sized {
         crate::decode_blob(self)
     }
 
+    /// # Decodes a [`Blob`][Value::Blob], and copies its data into a sink
+    ///
+    /// See [`decode_blob_into()`][fn:decode_blob_into].
+    ///
+    /// [Value::Blob]: enum.Value.html#variant.Blob
+    /// [fn:decode_blob_into]: fn.decode_blob_into.html
+    fn decode_blob_into<W>(&mut self, sink: &mut W) -> IoResult<Option<u64>> where W: Write {
+        crate::decode_blob_into(self, sink)
+    }
+
     /// # Decodes a [`List`][Value::List]
     ///
     /// [Value::List]: enum.Value.html#variant.List
@@ -160,6 +251,60 @@ pub trait Decoder: Read + Sized {
         crate::decode_object(self)
     }
 
+    /// # Decodes header of a [`List`][Value::List]
+    ///
+    /// See [`decode_list_header()`][fn:decode_list_header].
+    ///
+    /// [Value::List]: enum.Value.html#variant.List
+    /// [fn:decode_list_header]: fn.decode_list_header.html
+    fn decode_list_header(&mut self) -> IoResult<Option<(Size, Size)>> {
+        crate::decode_list_header(self)
+    }
+
+    /// # Decodes header of a [`Map`][Value::Map]
+    ///
+    /// See [`decode_map_header()`][fn:decode_map_header].
+    ///
+    /// [Value::Map]: enum.Value.html#variant.Map
+    /// [fn:decode_map_header]: fn.decode_map_header.html
+    fn decode_map_header(&mut self) -> IoResult<Option<(Size, Size)>> {
+        crate::decode_map_header(self)
+    }
+
+    /// # Decodes header of an [`Object`][Value::Object]
+    ///
+    /// See [`decode_object_header()`][fn:decode_object_header].
+    ///
+    /// [Value::Object]: enum.Value.html#variant.Object
+    /// [fn:decode_object_header]: fn.decode_object_header.html
+    fn decode_object_header(&mut self) -> IoResult<Option<(Size, Size)>> {
+        crate::decode_object_header(self)
+    }
+
+    /// # Decodes a key of a [`Map`][Value::Map]'s item
+    ///
+    /// [Value::Map]: enum.Value.html#variant.Map
+    fn decode_map_key(&mut self) -> IoResult<MapKey> {
+        crate::decode_map_key(self)
+    }
+
+    /// # Decodes a key of an [`Object`][Value::Object]'s item
+    ///
+    /// [Value::Object]: enum.Value.html#variant.Object
+    fn decode_object_key(&mut self) -> IoResult<ObjectKey> {
+        crate::decode_object_key(self)
+    }
+
+    /// # Decodes an [`Object`][Value::Object], with only some keys
+    ///
+    /// See [`decode_object_with_keys()`][fn:decode_object_with_keys].
+    ///
+    /// [Value::Object]: enum.Value.html#variant.Object
+    /// [fn:decode_object_with_keys]: fn.decode_object_with_keys.html
+    fn decode_object_with_keys(&mut self, keys: &[&str]) -> IoResult<Option<Object>> {
+        crate::decode_object_with_keys(self, keys)
+    }
+
 }
 
 impl<T> Decoder for T where T: Read {}
@@ -4,9 +4,9 @@
 
 use {
     alloc::string::String,
-    std::io::{self, ErrorKind, Read},
+    std::io::{self, ErrorKind, Read, Write},
 
-    crate::{Blob, IoResult, List, Map, Object, Value},
+    crate::{Blob, DecodeConfig, HashObject, Header, IoResult, List, Map, MapKey, Object, ObjectKey, Size, Value},
 };
 
 /// # Decodes a value from source
@@ -16,6 +16,48 @@ pub fn decode<R>(source: &mut R) -> IoResult<Option<Value>> where R: Read {
     crate::decode_value(None, source)
 }
 
+/// # Decodes a value from source, with a config
+///
+/// If it returns `Ok(None)`, it means there's no more data to decode.
+pub fn decode_with_config<R>(source: &mut R, config: &DecodeConfig) -> IoResult<Option<Value>> where R: Read {
+    crate::decode_value_with_config(None, source, config)
+}
+
+/// # Skips a value from source
+///
+/// The value is not decoded: this function uses its [header][struct:Header] to find out the size, then skips the data. So it is cheap, but
+/// the data is _not_ verified.
+///
+/// Result: total bytes that have been skipped. If it returns `Ok(None)`, it means there's no more data.
+///
+/// [struct:Header]: struct.Header.html
+pub fn skip_value<R>(source: &mut R) -> IoResult<Option<u64>> where R: Read {
+    match crate::decode_header(source)? {
+        Some(header) => {
+            let value_size = header.value_size();
+            crate::value_enum::skip_bytes(value_size - u64::from(header.size()), source)?;
+            Ok(Some(value_size))
+        },
+        None => Ok(None),
+    }
+}
+
+/// # Skips some values from source
+///
+/// See [`skip_value()`][fn:skip_value].
+///
+/// Result: number of values that have been skipped. It can be less than `count` if the source has no more data.
+///
+/// [fn:skip_value]: fn.skip_value.html
+pub fn skip_n<R>(source: &mut R, count: usize) -> IoResult<usize> where R: Read {
+    for i in 0..count {
+        if skip_value(source)?.is_none() {
+            return Ok(i);
+        }
+    }
+    Ok(count)
+}
+
 /// # Decodes a [`Null`]
 ///
 /// [`Null`]: enum.Value.html#variant.Null
@@ -196,6 +238,44 @@ pub fn decode_blob<R>(source: &mut R) -> IoResult<Option<Blob>> where R: Read {
     }
 }
 
+/// # Decodes a [`Blob`], and copies its data into a sink
+///
+/// Data is streamed directly into the sink, instead of being buffered in memory. This is useful for large blobs.
+///
+/// Result: total bytes of data that have been copied. If it returns `Ok(None)`, it means there's no more data to decode.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::Encoder;
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// buf.encode_blob(vec![9; 1024])?;
+///
+/// let mut sink = vec![];
+/// assert_eq!(binn_ir::decode_blob_into(&mut &buf[..], &mut sink)?, Some(1024));
+/// assert_eq!(sink, vec![9; 1024]);
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [`Blob`]: enum.Value.html#variant.Blob
+pub fn decode_blob_into<R, W>(source: &mut R, sink: &mut W) -> IoResult<Option<u64>> where R: Read, W: Write {
+    let size = match crate::decode_header(source)? {
+        Some(header) => match (header.type_byte(), header.declared_size()) {
+            (crate::value::BLOB, Some(size)) => u64::from(size),
+            (other, _) => return Err(io::Error::new(ErrorKind::InvalidData, __!("expected blob, got: {}", &other))),
+        },
+        None => return Ok(None),
+    };
+
+    match io::copy(&mut source.take(size), sink)? {
+        copied if copied == size => Ok(Some(copied)),
+        copied => Err(io::Error::new(ErrorKind::UnexpectedEof, __!("expected to copy {} bytes, but: {}", &size, &copied))),
+    }
+}
+
 /// # Decodes a [`List`]
 ///
 /// [`List`]: enum.Value.html#variant.List
@@ -228,3 +308,173 @@ pub fn decode_object<R>(source: &mut R) -> IoResult<Option<Object>> where R: Rea
         None => Ok(None),
     }
 }
+
+/// # Decodes an [`Object`] into a [`HashObject`][type:HashObject]
+///
+/// If it returns `Ok(None)`, it means there's no more data to decode.
+///
+/// [`Object`]: enum.Value.html#variant.Object
+/// [type:HashObject]: type.HashObject.html
+pub fn decode_hash_object<R>(source: &mut R) -> IoResult<Option<HashObject>> where R: Read {
+    decode_object(source).map(|object| object.map(|object| object.into_iter().collect()))
+}
+
+/// # Decodes a container header, and verifies its type
+fn decode_container_header<R>(source: &mut R, expected: u8) -> IoResult<Option<Header>> where R: Read {
+    match crate::decode_header(source)? {
+        Some(header) => match header.type_byte() == expected {
+            true => Ok(Some(header)),
+            false => Err(io::Error::new(ErrorKind::InvalidData, __!("expected: {}, got: {}", &expected, &header.type_byte()))),
+        },
+        None => Ok(None),
+    }
+}
+
+/// # Gets declared size and item count of a container header
+fn size_and_item_count(header: Option<Header>) -> Option<(Size, Size)> {
+    header.map(|header| (header.declared_size().unwrap_or_default(), header.item_count().unwrap_or_default()))
+}
+
+/// # Decodes header of a [`List`]
+///
+/// Result: total size of the list (including its header), and item count. Source is positioned at the first item, which can be decoded via
+/// [`decode()`][fn:decode].
+///
+/// If it returns `Ok(None)`, it means there's no more data to decode.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Decoder, Encoder, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let list = Value::List(vec![Value::True, Value::U8(9)]);
+/// let mut buf = vec![];
+/// buf.encode(&list)?;
+///
+/// let mut source = &buf[..];
+/// assert_eq!(source.decode_list_header()?, Some((list.size()?, 2)));
+/// assert_eq!(source.decode()?, Some(Value::True));
+/// assert_eq!(source.decode()?, Some(Value::U8(9)));
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [`List`]: enum.Value.html#variant.List
+/// [fn:decode]: fn.decode.html
+pub fn decode_list_header<R>(source: &mut R) -> IoResult<Option<(Size, Size)>> where R: Read {
+    decode_container_header(source, crate::value::LIST).map(size_and_item_count)
+}
+
+/// # Decodes header of a [`Map`]
+///
+/// Result: total size of the map (including its header), and item count. Source is positioned at the first item, which can be decoded via
+/// [`decode_map_key()`][fn:decode_map_key] and [`decode()`][fn:decode].
+///
+/// If it returns `Ok(None)`, it means there's no more data to decode.
+///
+/// [`Map`]: enum.Value.html#variant.Map
+/// [fn:decode]: fn.decode.html
+/// [fn:decode_map_key]: fn.decode_map_key.html
+pub fn decode_map_header<R>(source: &mut R) -> IoResult<Option<(Size, Size)>> where R: Read {
+    decode_container_header(source, crate::value::MAP).map(size_and_item_count)
+}
+
+/// # Decodes header of an [`Object`]
+///
+/// Result: total size of the object (including its header), and item count. Source is positioned at the first item, which can be decoded
+/// via [`decode_object_key()`][fn:decode_object_key] and [`decode()`][fn:decode].
+///
+/// If it returns `Ok(None)`, it means there's no more data to decode.
+///
+/// [`Object`]: enum.Value.html#variant.Object
+/// [fn:decode]: fn.decode.html
+/// [fn:decode_object_key]: fn.decode_object_key.html
+pub fn decode_object_header<R>(source: &mut R) -> IoResult<Option<(Size, Size)>> where R: Read {
+    decode_container_header(source, crate::value::OBJECT).map(size_and_item_count)
+}
+
+/// # Decodes a key of a [`Map`]'s item
+///
+/// [`Map`]: enum.Value.html#variant.Map
+pub fn decode_map_key<R>(source: &mut R) -> IoResult<MapKey> where R: Read {
+    let mut buf = [0; core::mem::size_of::<MapKey>()];
+    source.read_exact(&mut buf)?;
+    Ok(MapKey::from_be_bytes(buf))
+}
+
+/// # Decodes a key of an [`Object`]'s item
+///
+/// [`Object`]: enum.Value.html#variant.Object
+pub fn decode_object_key<R>(source: &mut R) -> IoResult<ObjectKey> where R: Read {
+    crate::value_enum::read_object_key(source).map(|(key, _)| key)
+}
+
+/// # Decodes an [`Object`], with only some keys
+///
+/// Values of other keys are skipped via [`skip_value()`][fn:skip_value], so they are neither decoded nor verified. This is useful for
+/// extracting a few fields from large objects.
+///
+/// If it returns `Ok(None)`, it means there's no more data to decode.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Decoder, Encoder, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut object = binn_ir::object_from("id", 1);
+/// object.object_insert("name", "moon")?;
+/// object.object_insert("data", vec![0_u8; 1024])?;
+///
+/// let mut buf = vec![];
+/// buf.encode(&object)?;
+///
+/// let object = (&mut &buf[..]).decode_object_with_keys(&["id", "name", "not-found"])?.unwrap();
+/// assert_eq!(object.len(), 2);
+/// assert_eq!(object["name"], Value::from("moon"));
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [`Object`]: enum.Value.html#variant.Object
+/// [fn:skip_value]: fn.skip_value.html
+pub fn decode_object_with_keys<R>(source: &mut R, keys: &[&str]) -> IoResult<Option<Object>> where R: Read {
+    let (size, item_count, mut read) = match decode_container_header(source, crate::value::OBJECT)? {
+        Some(header) => (header.declared_size().unwrap_or_default(), header.item_count().unwrap_or_default(), u64::from(header.size())),
+        None => return Ok(None),
+    };
+
+    let mut result = Object::new();
+    for item_index in 0..item_count {
+        let (key, bytes_of_key) = crate::value_enum::read_object_key(source)?;
+        read += u64::from(bytes_of_key);
+
+        let value_size = match keys.contains(&key.as_str()) {
+            true => match decode(source)? {
+                Some(value) => {
+                    let value_size = value.size()?;
+                    if let Some(old_value) = result.insert(key, value) {
+                        return Err(io::Error::new(ErrorKind::InvalidData, __!("duplicate key of old value: {:?}", &old_value)));
+                    }
+                    u64::from(value_size)
+                },
+                None => return Err(io::Error::new(ErrorKind::InvalidData, __!("missing item #{}/{}", &item_index, &item_count))),
+            },
+            false => match skip_value(source)? {
+                Some(value_size) => value_size,
+                None => return Err(io::Error::new(ErrorKind::InvalidData, __!("missing item #{}/{}", &item_index, &item_count))),
+            },
+        };
+        read += value_size;
+        if read > u64::from(size) {
+            return Err(io::Error::new(ErrorKind::InvalidData, __!("expected to read less than {} bytes, got: {}", &size, &read)));
+        }
+    }
+
+    match read == u64::from(size) {
+        true => Ok(Some(result)),
+        false => Err(io::Error::new(ErrorKind::InvalidData, __!("size is declared: {}; but decoded: {}", &size, &read))),
+    }
+}
+
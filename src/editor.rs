@@ -0,0 +1,199 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Editor
+
+use {
+    alloc::vec::Vec,
+    std::io::{self, ErrorKind},
+
+    crate::{
+        IoResult, MapKey, Path, PathSegment, Value,
+        append::{container_header_bytes, skip_item},
+        storage::Storage,
+    },
+};
+
+/// # Editor of encoded data
+///
+/// This edits values inside an encoded buffer in place, without decoding the whole buffer. To find a value, items of containers along the
+/// path are skipped -- not decoded. When a value is replaced, only its own bytes are rewritten, then sizes of its parent containers are
+/// adjusted. Other bytes are left as-is (they might be shifted, if the new value has a different size).
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Editor, Encoder, Path, PathSegment, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut request = binn_ir::object_from("id", 1_u8);
+/// request.object_insert("params", binn_ir::object_from("name", "old"))?;
+///
+/// let mut buf = vec![];
+/// buf.encode(&request)?;
+///
+/// let name = Path::from(vec![PathSegment::ObjectKey("params".into()), PathSegment::ObjectKey("name".into())]);
+/// let mut editor = Editor::new(&mut buf);
+/// assert_eq!(editor.get(&name)?, Value::from("old"));
+/// editor.set(&name, "a much longer name")?;
+///
+/// request.object_mut_by(&["params"])?.object_insert("name", "a much longer name")?;
+/// assert_eq!(binn_ir::decode(&mut &buf[..])?, Some(request));
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Editor<'a> {
+    buf: &'a mut Vec<u8>,
+}
+
+/// # Location of a value
+struct Location {
+
+    /// # Offsets of parent containers, from outermost to innermost
+    parents: Vec<usize>,
+
+    /// # Offset of the value
+    offset: usize,
+
+    /// # Length of the value
+    len: usize,
+
+}
+
+impl<'a> Editor<'a> {
+
+    /// # Makes new instance
+    ///
+    /// The buffer should start with an encoded value. Trailing data -- if any -- is left untouched.
+    pub fn new(buf: &'a mut Vec<u8>) -> Self {
+        Self {
+            buf,
+        }
+    }
+
+    /// # Decodes a value
+    ///
+    /// ## Errors
+    ///
+    /// - If the path does not match the value: a parent does not exist, or is not the container that the path expects.
+    /// - [`InvalidData`][io::ErrorKind#InvalidData]: the data is invalid.
+    ///
+    /// [io::ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    pub fn get(&self, path: &Path) -> IoResult<Value> {
+        let location = locate(self.buf, path.segments())?;
+        match crate::decode(&mut &self.buf[location.offset..location.offset + location.len])? {
+            Some(value) => Ok(value),
+            None => Err(io::Error::new(ErrorKind::InvalidData, __!("missing value"))),
+        }
+    }
+
+    /// # Replaces a value
+    ///
+    /// The value must already exist. An empty path replaces the root value.
+    ///
+    /// ## Errors
+    ///
+    /// Same as [`get()`][#get()]. And if parent containers become too large, an error of [`InvalidData`][io::ErrorKind#InvalidData] is
+    /// returned. On errors, the buffer is left unchanged.
+    ///
+    /// [#get()]: #method.get
+    /// [io::ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    pub fn set<V>(&mut self, path: &Path, value: V) -> IoResult<()> where V: Into<Value> {
+        let location = locate(self.buf, path.segments())?;
+        let mut new_value = Vec::new();
+        value.into().encode(&mut new_value)?;
+
+        // New headers of parents, from innermost to outermost
+        let mut delta = new_value.len() as i64 - location.len as i64;
+        let mut headers = Vec::with_capacity(location.parents.len());
+        for offset in location.parents.iter().rev() {
+            let header = decode_header(&self.buf[*offset..])?;
+            let items = i64::from(header.declared_size().unwrap_or_default() - header.size()) + delta;
+            let (_, new_header) = container_header_bytes(header.type_byte(), header.item_count().unwrap_or_default(), items as u64)?;
+            delta += new_header.as_bytes().len() as i64 - i64::from(header.size());
+            headers.push((*offset, header.size() as usize, new_header));
+        }
+
+        self.buf.splice(location.offset..location.offset + location.len, new_value);
+        for (offset, len, header) in headers {
+            self.buf.splice(offset..offset + len, header.as_bytes().iter().copied());
+        }
+        Ok(())
+    }
+
+}
+
+/// # Decodes a header
+fn decode_header(bytes: &[u8]) -> IoResult<crate::Header> {
+    match crate::decode_header(&mut &bytes[..]) {
+        Ok(Some(header)) => Ok(header),
+        Ok(None) => Err(io::Error::new(ErrorKind::InvalidData, __!("missing value"))),
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => Err(io::Error::new(ErrorKind::InvalidData, __!("truncated header"))),
+        Err(err) => Err(err),
+    }
+}
+
+/// # Finds a value
+fn locate(buf: &[u8], segments: &[PathSegment]) -> IoResult<Location> {
+    let mut parents = Vec::with_capacity(segments.len());
+    let mut offset = 0;
+    let mut end = buf.len();
+    let mut header = decode_header(buf)?;
+
+    for segment in segments {
+        let container_end = offset + header.value_size() as usize;
+        if header.storage() != Storage::Container || container_end > end {
+            return Err(io::Error::new(ErrorKind::InvalidData, __!("invalid container at segment {}", segment)));
+        }
+        let expected = match segment {
+            PathSegment::Index(_) => crate::value::LIST,
+            PathSegment::MapKey(_) => crate::value::MAP,
+            PathSegment::ObjectKey(_) => crate::value::OBJECT,
+        };
+        if header.type_byte() != expected {
+            return Err(io::Error::from(err!("path segment {} does not match value", segment)));
+        }
+
+        parents.push(offset);
+        let mut items = &buf[offset + header.size() as usize..container_end];
+        let mut found = false;
+        for index in 0..header.item_count().unwrap_or_default() {
+            found = match segment {
+                PathSegment::Index(i) => *i == index,
+                PathSegment::MapKey(key) => match items.get(..core::mem::size_of::<MapKey>()) {
+                    Some(bytes) => {
+                        let found = MapKey::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) == *key;
+                        items = &items[bytes.len()..];
+                        found
+                    },
+                    None => return Err(io::Error::new(ErrorKind::InvalidData, __!("truncated map key"))),
+                },
+                PathSegment::ObjectKey(key) => match items.split_first() {
+                    Some((len, rest)) if rest.len() >= usize::from(*len) => {
+                        let found = &rest[..usize::from(*len)] == key.as_bytes();
+                        items = &rest[usize::from(*len)..];
+                        found
+                    },
+                    _ => return Err(io::Error::new(ErrorKind::InvalidData, __!("truncated object key"))),
+                },
+            };
+            if found {
+                break;
+            }
+            skip_item(&mut items)?;
+        }
+        if !found {
+            return Err(io::Error::from(err!("value not found at segment {}", segment)));
+        }
+
+        offset = container_end - items.len();
+        end = container_end;
+        header = decode_header(items)?;
+    }
+
+    let len = header.value_size() as usize;
+    match offset + len <= end {
+        true => Ok(Location { parents, offset, len }),
+        false => Err(io::Error::new(ErrorKind::InvalidData, __!("truncated value"))),
+    }
+}
@@ -0,0 +1,231 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Encode config
+
+use {
+    core::{
+        convert::TryFrom,
+        mem,
+    },
+
+    crate::{
+        MapKey, Result, Size, Value,
+        value_enum::{size_of_container, size_of_object_key},
+    },
+};
+
+#[cfg(feature="std")]
+use {
+    std::io::Write,
+
+    crate::{
+        IoResult,
+        value_enum::write_container_header,
+    },
+};
+
+/// # Encode config
+///
+/// Default config is the same as what [`Value::encode()`][Value::encode()] uses: values are encoded as they are.
+///
+/// This struct is marked as non-exhaustive, so new options can be added later. To make one, start with [`new()`][fn:new] (or
+/// [`Default`][core::default/Default]), then set the fields you want.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{EncodeConfig, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let value = Value::List(vec![Value::U64(300), Value::I32(-1)]);
+///
+/// let mut config = EncodeConfig::new();
+/// config.shrink_integers = true;
+///
+/// let mut buf = vec![];
+/// value.encode_with_config(&mut buf, &config)?;
+/// assert_eq!(buf.len(), 8);
+/// assert_eq!(binn_ir::decode(&mut &buf[..])?, Some(Value::List(vec![Value::U16(300), Value::I8(-1)])));
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [Value::encode()]: enum.Value.html#method.encode
+/// [fn:new]: #method.new
+/// [core::default/Default]: https://doc.rust-lang.org/core/default/trait.Default.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct EncodeConfig {
+
+    /// # Stores each integer in the smallest type which holds its value
+    ///
+    /// This is what the reference C implementation does (see [`CompatLevel::CBinn`][CompatLevel#CBinn]):
+    ///
+    /// - Non-negative integers become unsigned: `u8`, `u16`, `u32` or `u64`. For example, an `U64` of `300` is encoded as an `U16`.
+    /// - Negative integers become `i8`, `i16`, `i32` or `i64`.
+    /// - Integers of 1 byte are kept as-is.
+    ///
+    /// Decoded values then have different types than the original ones. Readers can use
+    /// [`Value::as_i64_lossless()`][Value::as_i64_lossless()] and [`Value::as_u64_lossless()`][Value::as_u64_lossless()], which accept
+    /// integers of any type.
+    ///
+    /// [CompatLevel#CBinn]: enum.CompatLevel.html#variant.CBinn
+    /// [Value::as_i64_lossless()]: enum.Value.html#method.as_i64_lossless
+    /// [Value::as_u64_lossless()]: enum.Value.html#method.as_u64_lossless
+    pub shrink_integers: bool,
+
+    /// # Canonicalizes floating point numbers
+    ///
+    /// NaNs and negative zero are written the same way by every producer, so canonical encodings -- and their hashes -- are stable. See
+    /// [`Value::canonicalize_floats()`][Value::canonicalize_floats()] for details.
+    ///
+    /// [Value::canonicalize_floats()]: enum.Value.html#method.canonicalize_floats
+    pub canonicalize_floats: bool,
+
+    /// # Omits items of maps and objects whose values are [`Null`][Value::Null]
+    ///
+    /// Items of lists are kept, since their positions matter.
+    ///
+    /// [Value::Null]: enum.Value.html#variant.Null
+    pub skip_nulls: bool,
+
+    /// # Omits items of maps and objects whose values are empty containers
+    ///
+    /// Containers are checked after their own items have been omitted, so a map containing only a `Null` is omitted too -- if
+    /// [`skip_nulls`][#skip_nulls] is also set. Items of lists are kept, since their positions matter. The root value is always encoded.
+    ///
+    /// [#skip_nulls]: #structfield.skip_nulls
+    pub skip_empty_containers: bool,
+
+}
+
+impl Default for EncodeConfig {
+
+    fn default() -> Self {
+        Self::new()
+    }
+
+}
+
+impl EncodeConfig {
+
+    /// # Makes new default config
+    pub const fn new() -> Self {
+        Self {
+            shrink_integers: false,
+            canonicalize_floats: false,
+            skip_nulls: false,
+            skip_empty_containers: false,
+        }
+    }
+
+    /// # Checks if this config encodes values as they are
+    pub(crate) fn is_default(&self) -> bool {
+        self == &Self::new()
+    }
+
+    /// # Converts a scalar into the form which this config encodes
+    ///
+    /// Result: `None` for containers, and for values which this config keeps as they are.
+    fn convert_scalar(&self, value: &Value) -> Option<Value> {
+        match value {
+            Value::U16(_) | Value::U32(_) | Value::U64(_) | Value::I16(_) | Value::I32(_) | Value::I64(_) if self.shrink_integers =>
+                Some(crate::compat_level::shrink_integer(value)),
+            Value::Float(_) | Value::Double(_) if self.canonicalize_floats => {
+                let mut value = value.clone();
+                value.canonicalize_floats();
+                Some(value)
+            },
+            _ => None,
+        }
+    }
+
+    /// # Checks if an item of a map or an object is encoded
+    ///
+    /// Containers are checked after their own items have been omitted.
+    fn keeps(&self, item: &Value) -> bool {
+        match item {
+            Value::Null => !self.skip_nulls,
+            Value::List(list) => !self.skip_empty_containers || !list.is_empty(),
+            Value::Map(map) => !self.skip_empty_containers || map.values().any(|v| self.keeps(v)),
+            Value::Object(object) => !self.skip_empty_containers || object.values().any(|v| self.keeps(v)),
+            _ => true,
+        }
+    }
+
+    /// # Calculates size of a value, once encoded with this config
+    ///
+    /// Values are converted on the fly; containers are never copied.
+    pub(crate) fn size_of(&self, value: &Value) -> Result<Size> {
+        let mut items = 0_u64;
+        let count = match value {
+            Value::List(list) => {
+                for item in list {
+                    items += u64::from(self.size_of(item)?);
+                }
+                list.len()
+            },
+            Value::Map(map) => {
+                let mut count = 0;
+                for item in map.values().filter(|v| self.keeps(v)) {
+                    items += mem::size_of::<MapKey>() as u64 + u64::from(self.size_of(item)?);
+                    count += 1;
+                }
+                count
+            },
+            Value::Object(object) => {
+                let mut count = 0;
+                for (key, item) in object.iter().filter(|(_, v)| self.keeps(v)) {
+                    items += u64::from(size_of_object_key(key)?) + u64::from(self.size_of(item)?);
+                    count += 1;
+                }
+                count
+            },
+            _ => return self.convert_scalar(value).as_ref().unwrap_or(value).size(),
+        };
+        let items = Size::try_from(items).map_err(|_| err_code!("data too large (bytes)", items))?;
+        size_of_container(count, items)
+    }
+
+    /// # Encodes a value into a stream, with this config
+    ///
+    /// Values are converted on the fly; containers are never copied.
+    ///
+    /// Result: number of bytes written.
+    #[cfg(feature="std")]
+    pub(crate) fn encode<W>(&self, value: &Value, stream: &mut W) -> IoResult<Size> where W: Write {
+        match value {
+            Value::List(list) => {
+                let size = self.size_of(value)?;
+                write_container_header(crate::value::LIST, size, list.len() as Size, stream)?;
+                for item in list {
+                    self.encode(item, stream)?;
+                }
+                Ok(size)
+            },
+            Value::Map(map) => {
+                // Item count has been verified by size_of()
+                let size = self.size_of(value)?;
+                write_container_header(crate::value::MAP, size, map.values().filter(|v| self.keeps(v)).count() as Size, stream)?;
+                for (key, item) in map.iter().filter(|(_, v)| self.keeps(v)) {
+                    stream.write_all(&key.to_be_bytes())?;
+                    self.encode(item, stream)?;
+                }
+                Ok(size)
+            },
+            Value::Object(object) => {
+                // Item count and lengths of keys have been verified by size_of()
+                let size = self.size_of(value)?;
+                write_container_header(crate::value::OBJECT, size, object.values().filter(|v| self.keeps(v)).count() as Size, stream)?;
+                for (key, item) in object.iter().filter(|(_, v)| self.keeps(v)) {
+                    stream.write_all(&[key.len() as u8])?;
+                    stream.write_all(key.as_bytes())?;
+                    self.encode(item, stream)?;
+                }
+                Ok(size)
+            },
+            _ => self.convert_scalar(value).as_ref().unwrap_or(value).encode(stream),
+        }
+    }
+
+}
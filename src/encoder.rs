@@ -3,15 +3,23 @@
 //! # Encoder
 
 use {
-    alloc::string::String,
-    std::io::Write,
+    std::io::{Read, Write},
 
-    crate::{Blob, IoResult, List, Map, Object, Size, Value},
+    crate::{Blob, CompatLevel, EncodeConfig, IoResult, List, Map, Object, Size, StreamLayer, Value},
 };
 
 /// # Encoder
 pub trait Encoder: Write + Sized {
 
+    /// # Wraps this encoder in a layer
+    ///
+    /// See [`StreamLayer`][trait:StreamLayer].
+    ///
+    /// [trait:StreamLayer]: trait.StreamLayer.html
+    fn layer<L>(self, layer: L) -> IoResult<L::Writer<Self>> where L: StreamLayer {
+        layer.wrap_writer(self)
+    }
+
     /// # Encodes a value
     ///
     /// Result: total bytes that have been written.
@@ -19,6 +27,20 @@ pub trait Encoder: Write + Sized {
         value.encode(self)
     }
 
+    /// # Encodes a value, with a compatibility level
+    ///
+    /// Result: total bytes that have been written.
+    fn encode_with_compat(&mut self, value: &Value, compat: CompatLevel) -> IoResult<Size> {
+        value.encode_with_compat(self, compat)
+    }
+
+    /// # Encodes a value, with a config
+    ///
+    /// Result: total bytes that have been written.
+    fn encode_with_config(&mut self, value: &Value, config: &EncodeConfig) -> IoResult<Size> {
+        value.encode_with_config(self, config)
+    }
+
     /// # Encodes a null
     ///
     /// Result: total bytes that have been written.
@@ -110,7 +132,7 @@ pub trait Encoder: Write + Sized {
     /// # Encodes a text
     ///
     /// Result: total bytes that have been written.
-    fn encode_text<T>(&mut self, s: T) -> IoResult<Size> where T: Into<String> {
+    fn encode_text<T>(&mut self, s: T) -> IoResult<Size> where T: AsRef<str> {
         crate::encode_text(self, s)
     }
 
@@ -119,7 +141,7 @@ pub trait Encoder: Write + Sized {
     /// Result: total bytes that have been written.
     ///
     /// [Value::DateTime]: enum.Value.html#variant.DateTime
-    fn encode_date_time<T>(&mut self, s: T) -> IoResult<Size> where T: Into<String> {
+    fn encode_date_time<T>(&mut self, s: T) -> IoResult<Size> where T: AsRef<str> {
         crate::encode_date_time(self, s)
     }
 
@@ -128,7 +150,7 @@ pub trait Encoder: Write + Sized {
     /// Result: total bytes that have been written.
     ///
     /// [Value::Date]: enum.Value.html#variant.Date
-    fn encode_date<T>(&mut self, s: T) -> IoResult<Size> where T: Into<String> {
+    fn encode_date<T>(&mut self, s: T) -> IoResult<Size> where T: AsRef<str> {
         crate::encode_date(self, s)
     }
 
@@ -137,7 +159,7 @@ pub trait Encoder: Write + Sized {
     /// Result: total bytes that have been written.
     ///
     /// [Value::Time]: enum.Value.html#variant.Time
-    fn encode_time<T>(&mut self, s: T) -> IoResult<Size> where T: Into<String> {
+    fn encode_time<T>(&mut self, s: T) -> IoResult<Size> where T: AsRef<str> {
         crate::encode_time(self, s)
     }
 
@@ -146,7 +168,7 @@ pub trait Encoder: Write + Sized {
     /// Result: total bytes that have been written.
     ///
     /// [Value::DecimalStr]: enum.Value.html#variant.DecimalStr
-    fn encode_decimal_str<T>(&mut self, s: T) -> IoResult<Size> where T: Into<String> {
+    fn encode_decimal_str<T>(&mut self, s: T) -> IoResult<Size> where T: AsRef<str> {
         crate::encode_decimal_str(self, s)
     }
 
@@ -159,6 +181,16 @@ pub trait Encoder: Write + Sized {
         crate::encode_blob(self, bytes)
     }
 
+    /// # Encodes a [`Blob`][Value::Blob], whose data is read from a reader
+    ///
+    /// See [`encode_blob_from_reader()`][fn:encode_blob_from_reader].
+    ///
+    /// [Value::Blob]: enum.Value.html#variant.Blob
+    /// [fn:encode_blob_from_reader]: fn.encode_blob_from_reader.html
+    fn encode_blob_from_reader<R>(&mut self, reader: &mut R, len: u64) -> IoResult<Size> where R: Read {
+        crate::encode_blob_from_reader(self, reader, len)
+    }
+
     /// # Encodes a [`List`][Value::List]
     ///
     /// Result: total bytes that have been written.
@@ -3,10 +3,13 @@
 //! # Encoding functions
 
 use {
-    alloc::string::String,
-    std::io::Write,
+    alloc::vec::Vec,
+    std::io::{self, ErrorKind, Read, Write},
 
-    crate::{Blob, IoResult, List, Map, Object, Size, Value},
+    crate::{
+        Blob, HashObject, IoResult, List, Map, Object, Size, Value,
+        value_enum::{encode_object_items, size_of_object_items},
+    },
 };
 
 /// # Encodes a value
@@ -133,8 +136,8 @@ pub fn encode_double<W>(stream: &mut W, d: f64) -> IoResult<Size> where W: Write
 /// Result: total bytes that have been written.
 ///
 /// [`Text`]: enum.Value.html#variant.Text
-pub fn encode_text<W, T>(stream: &mut W, s: T) -> IoResult<Size> where W: Write, T: Into<String> {
-    Value::Text(s.into()).encode(stream)
+pub fn encode_text<W, T>(stream: &mut W, s: T) -> IoResult<Size> where W: Write, T: AsRef<str> {
+    crate::value_enum::encode_value_str(crate::value::TEXT, s.as_ref(), stream)
 }
 
 /// # Encodes a [`DateTime`]
@@ -142,8 +145,8 @@ pub fn encode_text<W, T>(stream: &mut W, s: T) -> IoResult<Size> where W: Write,
 /// Result: total bytes that have been written.
 ///
 /// [`DateTime`]: enum.Value.html#variant.DateTime
-pub fn encode_date_time<W, T>(stream: &mut W, s: T) -> IoResult<Size> where W: Write, T: Into<String> {
-    Value::DateTime(s.into()).encode(stream)
+pub fn encode_date_time<W, T>(stream: &mut W, s: T) -> IoResult<Size> where W: Write, T: AsRef<str> {
+    crate::value_enum::encode_value_str(crate::value::DATE_TIME, s.as_ref(), stream)
 }
 
 /// # Encodes a [`Date`]
@@ -151,8 +154,8 @@ pub fn encode_date_time<W, T>(stream: &mut W, s: T) -> IoResult<Size> where W: W
 /// Result: total bytes that have been written.
 ///
 /// [`Date`]: enum.Value.html#variant.Date
-pub fn encode_date<W, T>(stream: &mut W, s: T) -> IoResult<Size> where W: Write, T: Into<String> {
-    Value::Date(s.into()).encode(stream)
+pub fn encode_date<W, T>(stream: &mut W, s: T) -> IoResult<Size> where W: Write, T: AsRef<str> {
+    crate::value_enum::encode_value_str(crate::value::DATE, s.as_ref(), stream)
 }
 
 /// # Encodes a [`Time`]
@@ -160,8 +163,8 @@ pub fn encode_date<W, T>(stream: &mut W, s: T) -> IoResult<Size> where W: Write,
 /// Result: total bytes that have been written.
 ///
 /// [`Time`]: enum.Value.html#variant.Time
-pub fn encode_time<W, T>(stream: &mut W, s: T) -> IoResult<Size> where W: Write, T: Into<String> {
-    Value::Time(s.into()).encode(stream)
+pub fn encode_time<W, T>(stream: &mut W, s: T) -> IoResult<Size> where W: Write, T: AsRef<str> {
+    crate::value_enum::encode_value_str(crate::value::TIME, s.as_ref(), stream)
 }
 
 /// # Encodes a [`DecimalStr`]
@@ -169,8 +172,8 @@ pub fn encode_time<W, T>(stream: &mut W, s: T) -> IoResult<Size> where W: Write,
 /// Result: total bytes that have been written.
 ///
 /// [`DecimalStr`]: enum.Value.html#variant.DecimalStr
-pub fn encode_decimal_str<W, T>(stream: &mut W, s: T) -> IoResult<Size> where W: Write, T: Into<String> {
-    Value::DecimalStr(s.into()).encode(stream)
+pub fn encode_decimal_str<W, T>(stream: &mut W, s: T) -> IoResult<Size> where W: Write, T: AsRef<str> {
+    crate::value_enum::encode_value_str(crate::value::DECIMAL_STR, s.as_ref(), stream)
 }
 
 /// # Encodes a [`Blob`]
@@ -182,6 +185,43 @@ pub fn encode_blob<W, T>(stream: &mut W, bytes: T) -> IoResult<Size> where W: Wr
     Value::Blob(bytes.into()).encode(stream)
 }
 
+/// # Encodes a [`Blob`], whose data is read from a reader
+///
+/// Exactly `len` bytes are copied from the reader into the stream, without being buffered in memory. This is useful for embedding large
+/// files.
+///
+/// Result: total bytes that have been written.
+///
+/// ## Errors
+///
+/// If the reader ends before `len` bytes could be read, an error of kind [`UnexpectedEof`][ErrorKind::UnexpectedEof] is returned. In that
+/// case, the stream already contains a partial blob, and should be discarded.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::Encoder;
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let data = vec![9; 1024];
+///
+/// let mut buf = vec![];
+/// buf.encode_blob_from_reader(&mut &data[..], 1024)?;
+/// assert_eq!(binn_ir::decode_blob(&mut &buf[..])?, Some(data));
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [`Blob`]: enum.Value.html#variant.Blob
+/// [ErrorKind::UnexpectedEof]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.UnexpectedEof
+pub fn encode_blob_from_reader<W, R>(stream: &mut W, reader: &mut R, len: u64) -> IoResult<Size> where W: Write, R: Read {
+    let header_size = crate::value_enum::encode_blob_header(len, stream)?;
+    match io::copy(&mut reader.take(len), stream)? {
+        copied if copied == len => Ok(header_size + len as Size),
+        copied => Err(io::Error::new(ErrorKind::UnexpectedEof, __!("expected to copy {} bytes, but: {}", &len, &copied))),
+    }
+}
+
 /// # Encodes a [`List`]
 ///
 /// Result: total bytes that have been written.
@@ -208,3 +248,36 @@ pub fn encode_map<W, T>(stream: &mut W, map: T) -> IoResult<Size> where W: Write
 pub fn encode_object<W, T>(stream: &mut W, object: T) -> IoResult<Size> where W: Write, T: Into<Object> {
     Value::Object(object.into()).encode(stream)
 }
+
+/// # Encodes a [`HashObject`][type:HashObject] as an [`Object`]
+///
+/// Items are encoded in order of their keys, so the output is the same as the one of an equal `Object`.
+///
+/// Result: total bytes that have been written.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{HashObject, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let object = (0..100).map(|i| (format!("key-{}", i), Value::from(i))).collect::<HashObject>();
+///
+/// let mut buf = vec![];
+/// binn_ir::encode_hash_object(&mut buf, &object)?;
+///
+/// let mut expected = vec![];
+/// Value::from(object).encode(&mut expected)?;
+/// assert_eq!(buf, expected);
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [type:HashObject]: type.HashObject.html
+/// [`Object`]: enum.Value.html#variant.Object
+pub fn encode_hash_object<W>(stream: &mut W, object: &HashObject) -> IoResult<Size> where W: Write {
+    let mut items = object.iter().collect::<Vec<_>>();
+    items.sort_unstable_by_key(|(key, _)| *key);
+    let size = size_of_object_items(items.len(), items.iter().copied())?;
+    encode_object_items(size, items.len(), items, stream)
+}
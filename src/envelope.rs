@@ -0,0 +1,160 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Envelope
+
+use {
+    core::ops::RangeInclusive,
+    std::io::{self, ErrorKind, Read, Write},
+
+    crate::{Encoder, IoResult, Object, Size, Value},
+};
+
+/// # Envelope
+///
+/// An envelope wraps a payload with a header, which identifies the format of a file (or a message). It consists of, in order:
+///
+/// - A magic number: a [`U64`][Value::U64].
+/// - A format version: a [`U32`][Value::U32].
+/// - Metadata: an [`Object`][Value::Object].
+/// - The payload: any value.
+///
+/// When reading, the magic number must match, and the version must be one that the reader supports. Metadata is free form: names, hashes,
+/// timestamps...
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Envelope, Value};
+///
+/// const MAGIC_NUMBER: u64 = 0xABCD;
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut envelope = Envelope::new(MAGIC_NUMBER, 2, Value::Blob(b"the sun".to_vec()));
+/// binn_ir::object_insert(envelope.metadata_mut(), "name", "the-sun");
+///
+/// let mut file = vec![];
+/// envelope.write_to(&mut file)?;
+///
+/// // This reader supports versions 1 and 2
+/// let read = Envelope::read_from(&mut &file[..], MAGIC_NUMBER, 1..=2)?.unwrap();
+/// assert_eq!(read.version(), 2);
+/// assert_eq!(read, envelope);
+///
+/// // Older readers reject newer files
+/// assert!(Envelope::read_from(&mut &file[..], MAGIC_NUMBER, 1..=1).is_err());
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [Value::U64]: enum.Value.html#variant.U64
+/// [Value::U32]: enum.Value.html#variant.U32
+/// [Value::Object]: enum.Value.html#variant.Object
+#[derive(Debug, Clone, PartialEq)]
+pub struct Envelope {
+    magic: u64,
+    version: u32,
+    metadata: Object,
+    payload: Value,
+}
+
+impl Envelope {
+
+    /// # Makes new instance, with empty metadata
+    pub fn new<V>(magic: u64, version: u32, payload: V) -> Self where V: Into<Value> {
+        Self {
+            magic,
+            version,
+            metadata: Object::new(),
+            payload: payload.into(),
+        }
+    }
+
+    /// # Magic number
+    pub const fn magic(&self) -> u64 {
+        self.magic
+    }
+
+    /// # Format version
+    pub const fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// # Metadata
+    pub fn metadata(&self) -> &Object {
+        &self.metadata
+    }
+
+    /// # Metadata, as mutable
+    pub fn metadata_mut(&mut self) -> &mut Object {
+        &mut self.metadata
+    }
+
+    /// # Payload
+    pub fn payload(&self) -> &Value {
+        &self.payload
+    }
+
+    /// # Payload, as mutable
+    pub fn payload_mut(&mut self) -> &mut Value {
+        &mut self.payload
+    }
+
+    /// # Takes the payload
+    pub fn into_payload(self) -> Value {
+        self.payload
+    }
+
+    /// # Writes this envelope into a stream
+    ///
+    /// Result: total bytes that have been written.
+    pub fn write_to<W>(&self, stream: &mut W) -> IoResult<Size> where W: Write {
+        let sizes = [
+            stream.encode_u64(self.magic)?,
+            stream.encode_u32(self.version)?,
+            crate::value_enum::encode_object_ref(&self.metadata, stream)?,
+            self.payload.encode(stream)?,
+        ];
+        sizes.iter().try_fold(0, |result: Size, size| result.checked_add(*size))
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, __!("envelope is too large")))
+    }
+
+    /// # Reads an envelope from a stream
+    ///
+    /// `versions` are the format versions that the caller supports.
+    ///
+    /// If it returns `Ok(None)`, it means there's no more data to read.
+    ///
+    /// ## Errors
+    ///
+    /// [`InvalidData`][io::ErrorKind#InvalidData]: the magic number mismatches, the version is not supported, or a part of the envelope is missing.
+    /// Other errors of decoding are returned as-is.
+    ///
+    /// [io::ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    pub fn read_from<R>(stream: &mut R, magic: u64, versions: RangeInclusive<u32>) -> IoResult<Option<Self>> where R: Read {
+        match crate::decode_u64(stream)? {
+            Some(actual) if actual == magic => (),
+            Some(actual) => return Err(io::Error::new(
+                ErrorKind::InvalidData, __!("magic number mismatches, expected: 0x{:x}, got: 0x{:x}", magic, actual),
+            )),
+            None => return Ok(None),
+        };
+
+        let version = match crate::decode_u32(stream)? {
+            Some(version) if versions.contains(&version) => version,
+            Some(version) => return Err(io::Error::new(
+                ErrorKind::InvalidData, __!("unsupported version {}, supported: {}..={}", version, versions.start(), versions.end()),
+            )),
+            None => return Err(io::Error::new(ErrorKind::InvalidData, __!("missing version"))),
+        };
+        let metadata = crate::decode_object(stream)?.ok_or_else(|| io::Error::new(ErrorKind::InvalidData, __!("missing metadata")))?;
+        let payload = crate::decode(stream)?.ok_or_else(|| io::Error::new(ErrorKind::InvalidData, __!("missing payload")))?;
+
+        Ok(Some(Self {
+            magic,
+            version,
+            metadata,
+            payload,
+        }))
+    }
+
+}
@@ -11,26 +11,64 @@ use {
 
 #[cfg(feature="std")]
 use {
-    alloc::string::ToString,
     std::io,
+
+    crate::DecodeError,
 };
 
+#[cfg(feature="backtrace")]
+use std::backtrace::Backtrace;
+
 /// # Error
+///
+/// With feature `backtrace`, a [`Backtrace`][std::backtrace/Backtrace] is captured when an error is made. See
+/// [`backtrace()`][#backtrace()].
+///
+/// [std::backtrace/Backtrace]: https://doc.rust-lang.org/std/backtrace/struct.Backtrace.html
+/// [#backtrace()]: #method.backtrace
 #[derive(Debug)]
 pub struct Error {
     line: u32,
     module_path: &'static str,
     msg: Option<Cow<'static, str>>,
+    context: Option<i128>,
+    #[cfg(feature="backtrace")]
+    backtrace: alloc::boxed::Box<Backtrace>,
 }
 
 impl Error {
 
     /// # Makes new instance
+    #[cfg(not(feature="backtrace"))]
     pub (crate) const fn new(line: u32, module_path: &'static str, msg: Option<Cow<'static, str>>) -> Self {
         Self {
             line,
             module_path,
             msg,
+            context: None,
+        }
+    }
+
+    /// # Makes new instance
+    #[cfg(feature="backtrace")]
+    pub (crate) fn new(line: u32, module_path: &'static str, msg: Option<Cow<'static, str>>) -> Self {
+        Self {
+            line,
+            module_path,
+            msg,
+            context: None,
+            backtrace: alloc::boxed::Box::new(Backtrace::capture()),
+        }
+    }
+
+    /// # Makes new instance with a static code and a numeric context
+    ///
+    /// This does not allocate -- unless feature `backtrace` is enabled, in which case a backtrace is captured and boxed, like for any other
+    /// error.
+    pub (crate) fn with_context(line: u32, module_path: &'static str, code: &'static str, context: i128) -> Self {
+        Self {
+            context: Some(context),
+            ..Self::new(line, module_path, Some(Cow::Borrowed(code)))
         }
     }
 
@@ -45,30 +83,99 @@ impl Error {
     }
 
     /// # Error message
+    ///
+    /// For errors made with a numeric context, this is the static code, without the context. See [`context()`][#context()].
+    ///
+    /// [#context()]: #method.context
     pub fn msg(&self) -> Option<&str> {
         self.msg.as_deref()
     }
 
+    /// # Numeric context
+    ///
+    /// Some errors carry a static code (see [`msg()`][#msg()]) and a number -- such as a size, or an out-of-range value -- instead of a
+    /// formatted message. Without feature `backtrace`, they are made without any heap allocation.
+    ///
+    /// [#msg()]: #method.msg
+    pub const fn context(&self) -> Option<i128> {
+        self.context
+    }
+
+    /// # Backtrace
+    ///
+    /// The backtrace is captured via [`Backtrace::capture()`][std::backtrace/Backtrace#capture()], so it is only actually collected if
+    /// environment variable `RUST_LIB_BACKTRACE` (or `RUST_BACKTRACE`) is set. Otherwise it is disabled, and capturing costs almost nothing.
+    ///
+    /// [std::backtrace/Backtrace#capture()]: https://doc.rust-lang.org/std/backtrace/struct.Backtrace.html#method.capture
+    #[cfg(feature="backtrace")]
+    pub fn backtrace(&self) -> &Backtrace {
+        &self.backtrace
+    }
+
+    /// # Finds this error inside an I/O error
+    ///
+    /// The I/O error can carry this error directly (see `From<Error> for io::Error`), or via a
+    /// [`DecodeError`][struct:DecodeError] -- which is what decoding functions return.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::{DecodeConfig, Error, Value};
+    ///
+    /// # fn test() -> binn_ir::IoResult<()> {
+    /// let mut buf = vec![];
+    /// Value::Date("not a date".into()).encode(&mut buf)?;
+    ///
+    /// let err = binn_ir::decode_with_config(&mut &buf[..], &DecodeConfig::strict()).unwrap_err();
+    /// assert!(Error::find(&err).is_some());
+    /// # Ok(()) }
+    /// # test().unwrap();
+    /// ```
+    ///
+    /// [struct:DecodeError]: struct.DecodeError.html
+    #[cfg(feature="std")]
+    pub fn find(err: &io::Error) -> Option<&Self> {
+        let inner = err.get_ref()?;
+        match inner.downcast_ref::<DecodeError>() {
+            Some(decode_error) => Self::find(decode_error.source_error()),
+            None => inner.downcast_ref(),
+        }
+    }
+
 }
 
 impl Display for Error {
 
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-        match self.msg.as_ref() {
-            Some(msg) => write!(
+        match (self.msg.as_ref(), self.context) {
+            (Some(msg), Some(context)) => write!(
+                f, "[{tag}][{module_path}-{line}] {msg}: {context}", tag=crate::TAG, line=self.line, module_path=self.module_path, msg=msg,
+                context=context,
+            ),
+            (Some(msg), None) => write!(
                 f, "[{tag}][{module_path}-{line}] {msg}", tag=crate::TAG, line=self.line, module_path=self.module_path, msg=msg,
             ),
-            None => write!(f, "[{tag}][{module_path}-{line}]", tag=crate::TAG, line=self.line, module_path=self.module_path),
+            (None, _) => write!(f, "[{tag}][{module_path}-{line}]", tag=crate::TAG, line=self.line, module_path=self.module_path),
         }
     }
 
 }
 
+#[cfg(feature="std")]
+impl std::error::Error for Error {}
+
+/// # Converts an error into an I/O error
+///
+/// The result has kind [`Other`][std::io/ErrorKind#Other], and carries the original error as its inner error. So you can recover it via
+/// [`io::Error::get_ref()`][std::io/Error#get_ref()] and `downcast_ref::<Error>()`.
+///
+/// [std::io/ErrorKind#Other]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.Other
+/// [std::io/Error#get_ref()]: https://doc.rust-lang.org/std/io/struct.Error.html#method.get_ref
 #[cfg(feature="std")]
 impl From<Error> for io::Error {
 
     fn from(err: Error) -> Self {
-        io::Error::new(io::ErrorKind::Other, err.to_string())
+        io::Error::other(err)
     }
 
 }
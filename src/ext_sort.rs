@@ -0,0 +1,257 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # External sort
+
+use {
+    alloc::{
+        string::String,
+        vec::{self, Vec},
+    },
+    core::{
+        cmp::Ordering,
+        sync::atomic::{self, AtomicU64},
+    },
+    std::{
+        fs::{self, File, OpenOptions},
+        io::{self, BufReader, BufWriter, ErrorKind, Read, Write},
+        path::{Path, PathBuf},
+        process,
+    },
+
+    crate::{IoResult, Value, value_enum::ordering},
+};
+
+/// # Counter, used for making names of spill files
+static SPILL_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// # Sorts a stream of encoded objects by a key, within a memory budget
+///
+/// Objects are decoded from `source` until it's exhausted. They are grouped into _runs_, each of which has a total encoded size of (at most)
+/// `mem_budget` bytes. Each run is sorted in memory, then spilled into a temporary file inside `temp_dir`. The result is an iterator, which
+/// merges all runs and yields objects in order.
+///
+/// If all objects fit into the memory budget, nothing is written to disk.
+///
+/// ## Parameters
+///
+/// - `key_path`: path to the key inside each object. Objects missing the key come first. Keys are compared by their values, so `U8(5)` and
+///   `I64(5)` are equal. Objects having equal keys keep their original order.
+/// - `temp_dir`: a directory to store spill files. They are removed when the iterator is dropped.
+/// - `mem_budget`: maximum total size of a run, in bytes. A single object larger than this still makes its own run.
+///
+/// ## Errors
+///
+/// An error is returned if `key_path` is empty, or the source contains something other than an object.
+///
+/// ## Examples
+///
+/// ```
+/// use core::convert::TryFrom;
+/// use binn_ir::Encoder;
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// for id in &[3_u8, 1, 2] {
+///     buf.encode(&binn_ir::object_from("id", *id))?;
+/// }
+///
+/// let sorted = binn_ir::ext_sort(&mut &buf[..], &["id"], std::env::temp_dir(), 1024)?
+///     .map(|object| object.and_then(|object| Ok(u8::try_from(object.object_by(&["id"])?)?)))
+///     .collect::<binn_ir::IoResult<Vec<_>>>()?;
+/// assert_eq!(sorted, [1, 2, 3]);
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+pub fn ext_sort<R, P>(source: &mut R, key_path: &[&str], temp_dir: P, mem_budget: usize) -> IoResult<ExtSort>
+where R: Read, P: AsRef<Path> {
+    if key_path.is_empty() {
+        return Err(io::Error::new(ErrorKind::InvalidInput, __!("key path must not be empty")));
+    }
+
+    let mut result = ExtSort {
+        key_path: key_path.iter().map(|k| String::from(*k)).collect(),
+        runs: Vec::new(),
+        heads: Vec::new(),
+        spill_files: Vec::new(),
+        failed: false,
+    };
+
+    let mut run = Vec::new();
+    let mut run_size: usize = 0;
+    let mut index: usize = 0;
+    while let Some(value) = crate::decode(source)? {
+        key_of(&value, &result.key_path).map_err(|err| io::Error::new(ErrorKind::InvalidData, __!("object #{}: {}", index, err)))?;
+        index += 1;
+
+        let size = value.size()? as usize;
+        if !run.is_empty() && run_size.saturating_add(size) > mem_budget {
+            result.spill(&mut run, temp_dir.as_ref())?;
+            run_size = 0;
+        }
+        run_size = run_size.saturating_add(size);
+        run.push(value);
+    }
+
+    match result.spill_files.is_empty() {
+        true => {
+            result.sort(&mut run);
+            result.runs.push(Run::Memory(run.into_iter()));
+        },
+        false => if !run.is_empty() {
+            result.spill(&mut run, temp_dir.as_ref())?;
+        },
+    };
+
+    result.heads = Vec::with_capacity(result.runs.len());
+    for run in result.runs.iter_mut() {
+        result.heads.push(run.next()?);
+    }
+
+    Ok(result)
+}
+
+/// # Result of [`ext_sort()`][fn:ext_sort]
+///
+/// This is an iterator, which yields sorted objects. Once an error is returned, the iterator stops.
+///
+/// [fn:ext_sort]: fn.ext_sort.html
+#[derive(Debug)]
+pub struct ExtSort {
+    key_path: Vec<String>,
+    runs: Vec<Run>,
+    heads: Vec<Option<Value>>,
+    spill_files: Vec<PathBuf>,
+    failed: bool,
+}
+
+impl ExtSort {
+
+    /// # Number of spill files that have been written
+    pub fn spill_file_count(&self) -> usize {
+        self.spill_files.len()
+    }
+
+    /// # Sorts a run
+    fn sort(&self, run: &mut [Value]) {
+        // Note: this sort is stable
+        run.sort_by(|first, second| cmp_keys(&self.key_path, first, second));
+    }
+
+    /// # Sorts a run, then writes it into a new spill file
+    fn spill(&mut self, run: &mut Vec<Value>, temp_dir: &Path) -> IoResult<()> {
+        self.sort(run);
+
+        let path = temp_dir.join(alloc::format!(
+            "{code_name}-{pid}-{id}.binn", code_name=crate::CODE_NAME, pid=process::id(), id=SPILL_FILE_COUNTER.fetch_add(1, atomic::Ordering::Relaxed),
+        ));
+        let file = OpenOptions::new().write(true).create_new(true).open(&path)?;
+        self.spill_files.push(path.clone());
+
+        let mut stream = BufWriter::new(file);
+        for value in run.drain(..) {
+            value.encode(&mut stream)?;
+        }
+        stream.flush()?;
+
+        self.runs.push(Run::File(BufReader::new(File::open(path)?)));
+        Ok(())
+    }
+
+}
+
+impl Iterator for ExtSort {
+
+    type Item = IoResult<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        // Ties go to the earlier run, which keeps the sort stable
+        let mut min: Option<usize> = None;
+        for (i, head) in self.heads.iter().enumerate() {
+            if let Some(head) = head {
+                min = match min.and_then(|m| self.heads[m].as_ref().map(|v| (m, v))) {
+                    Some((m, current)) => match cmp_keys(&self.key_path, head, current) {
+                        Ordering::Less => Some(i),
+                        _ => Some(m),
+                    },
+                    None => Some(i),
+                };
+            }
+        }
+
+        let min = min?;
+        match self.runs[min].next() {
+            Ok(next) => Some(Ok(core::mem::replace(&mut self.heads[min], next)?)),
+            Err(err) => {
+                self.failed = true;
+                Some(Err(err))
+            },
+        }
+    }
+
+}
+
+impl Drop for ExtSort {
+
+    fn drop(&mut self) {
+        // Close files first
+        self.runs.clear();
+        for path in self.spill_files.iter() {
+            fs::remove_file(path).ok();
+        }
+    }
+
+}
+
+/// # A sorted run
+#[derive(Debug)]
+enum Run {
+    Memory(vec::IntoIter<Value>),
+    File(BufReader<File>),
+}
+
+impl Run {
+
+    /// # Takes next value
+    fn next(&mut self) -> IoResult<Option<Value>> {
+        match self {
+            Run::Memory(values) => Ok(values.next()),
+            Run::File(file) => crate::decode(file),
+        }
+    }
+
+}
+
+/// # Finds key of an object
+fn key_of<'a>(value: &'a Value, key_path: &[String]) -> crate::Result<Option<&'a Value>> {
+    let mut value = value;
+    for (nth, key) in key_path.iter().enumerate() {
+        match value {
+            Value::Object(object) => match object.get(key.as_str()) {
+                Some(v) => value = v,
+                None => return Ok(None),
+            },
+            _ => return Err(match nth {
+                0 => err!("Value is not an Object"),
+                _ => err!("Value at {:?} is not an Object", &key_path[..nth]),
+            }),
+        };
+    }
+
+    Ok(Some(value))
+}
+
+/// # Compares keys of 2 objects
+///
+/// Missing keys come first.
+fn cmp_keys(key_path: &[String], first: &Value, second: &Value) -> Ordering {
+    match (key_of(first, key_path).ok().flatten(), key_of(second, key_path).ok().flatten()) {
+        (Some(first), Some(second)) => ordering::cmp_values(first, second),
+        (Some(_), None) => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (None, None) => Ordering::Equal,
+    }
+}
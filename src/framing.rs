@@ -0,0 +1,271 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Framing
+
+use {
+    alloc::vec::Vec,
+    std::io::{self, ErrorKind, Read, Write},
+
+    crate::{DecodeConfig, IoResult, Size, Value, value::MAX_DATA_SIZE},
+};
+
+/// # Size of a frame header, in bytes
+///
+/// That is: 4 bytes for length of the payload, 4 bytes for its checksum.
+pub const FRAME_HEADER_SIZE: usize = 8;
+
+/// # Writes a value in a checksummed frame
+///
+/// A frame consists of a header and a payload. The header holds length of the payload (a big-endian `u32`), followed by its CRC-32C
+/// checksum (a big-endian `u32`). The payload is the encoded value.
+///
+/// Frames can be read back via [`read_framed()`][fn:read_framed].
+///
+/// Result: total bytes that have been written, including the header.
+///
+/// ## Examples
+///
+/// ```
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut log = vec![];
+/// binn_ir::write_framed(&mut log, &"first".into())?;
+/// binn_ir::write_framed(&mut log, &"second".into())?;
+///
+/// let mut log = log.as_slice();
+/// assert_eq!(binn_ir::read_framed(&mut log)?.unwrap().as_text()?, "first");
+/// assert_eq!(binn_ir::read_framed(&mut log)?.unwrap().as_text()?, "second");
+/// assert!(binn_ir::read_framed(&mut log)?.is_none());
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [fn:read_framed]: fn.read_framed.html
+pub fn write_framed<W>(stream: &mut W, value: &Value) -> IoResult<Size> where W: Write {
+    let mut payload = Vec::with_capacity(value.size()? as usize);
+    let size = value.encode(&mut payload)?;
+
+    let mut header = [0; FRAME_HEADER_SIZE];
+    header[..4].copy_from_slice(&size.to_be_bytes());
+    header[4..].copy_from_slice(&crc32c(&payload).to_be_bytes());
+    stream.write_all(&header)?;
+    stream.write_all(&payload)?;
+
+    Ok(size + FRAME_HEADER_SIZE as Size)
+}
+
+/// # Reads a value from a checksummed frame
+///
+/// See [`write_framed()`][fn:write_framed] for format of frames.
+///
+/// If it returns `Ok(None)`, it means there's no more data to read.
+///
+/// ## Errors
+///
+/// - [`UnexpectedEof`][io::ErrorKind#UnexpectedEof]: the frame is truncated.
+/// - [`InvalidData`][io::ErrorKind#InvalidData]: the length is too large, the checksum mismatches, or the payload does not hold exactly
+///   one value.
+///
+/// [fn:write_framed]: fn.write_framed.html
+/// [io::ErrorKind#UnexpectedEof]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.UnexpectedEof
+/// [io::ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+pub fn read_framed<R>(stream: &mut R) -> IoResult<Option<Value>> where R: Read {
+    let mut header = [0; FRAME_HEADER_SIZE];
+    if !read_header(stream, &mut header)? {
+        return Ok(None);
+    }
+
+    let (size, checksum) = parse_frame_header(&header)?;
+
+    // Size is not trusted yet, so the buffer grows as data comes in
+    let mut payload = Vec::new();
+    stream.take(u64::from(size)).read_to_end(&mut payload)?;
+    if payload.len() != size as usize {
+        return Err(io::Error::new(ErrorKind::UnexpectedEof, __!("expected {} byte(s) of payload, got: {}", size, payload.len())));
+    }
+    decode_frame_payload(&payload, checksum).map(Some)
+}
+
+/// # Parses a frame header
+///
+/// Result: length of the payload, and its checksum.
+pub(crate) fn parse_frame_header(header: &[u8; FRAME_HEADER_SIZE]) -> IoResult<(Size, u32)> {
+    let size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+    let checksum = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+    match size > MAX_DATA_SIZE {
+        true => Err(io::Error::new(ErrorKind::InvalidData, __!("frame length is too large: {}", size))),
+        false => Ok((size, checksum)),
+    }
+}
+
+/// # Verifies a frame payload against its checksum, then decodes it
+pub(crate) fn decode_frame_payload(payload: &[u8], checksum: u32) -> IoResult<Value> {
+    match crc32c(payload) {
+        actual if actual == checksum => (),
+        actual => return Err(io::Error::new(
+            ErrorKind::InvalidData, __!("checksum mismatches, expected: 0x{:08x}, got: 0x{:08x}", checksum, actual),
+        )),
+    };
+
+    let mut payload = payload;
+    match crate::decode(&mut payload)? {
+        Some(value) => match payload.is_empty() {
+            true => Ok(value),
+            false => Err(io::Error::new(ErrorKind::InvalidData, __!("trailing data in frame: {} byte(s)", payload.len()))),
+        },
+        None => Err(io::Error::new(ErrorKind::InvalidData, __!("empty frame"))),
+    }
+}
+
+/// # Size of a message prefix, in bytes
+pub const MESSAGE_PREFIX_SIZE: usize = 4;
+
+/// # Writes a value as a length-prefixed message
+///
+/// The message is a prefix -- length of the encoded value, as a big-endian `u32` -- followed by the encoded value. The length is
+/// independent of the value's own size, so receivers can allocate a buffer before decoding, and skip messages which they fail to decode.
+///
+/// Messages can be read back via [`read_message()`][fn:read_message].
+///
+/// Result: total bytes that have been written, including the prefix.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::Value;
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut socket = vec![];
+/// binn_ir::write_message(&mut socket, &Value::U8(1))?;
+/// assert_eq!(socket, [0, 0, 0, 2, 0x20, 1]);
+///
+/// assert_eq!(binn_ir::read_message(&mut socket.as_slice())?, Some(Value::U8(1)));
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [fn:read_message]: fn.read_message.html
+pub fn write_message<W>(stream: &mut W, value: &Value) -> IoResult<Size> where W: Write {
+    let size = value.size()?;
+    stream.write_all(&size.to_be_bytes())?;
+    match value.encode(stream)? {
+        written if written == size => Ok(size + MESSAGE_PREFIX_SIZE as Size),
+        written => Err(io::Error::from(err!("expected to write {} byte(s); result: {}", size, written))),
+    }
+}
+
+/// # Reads a value from a length-prefixed message
+///
+/// This is the same as [`read_message_with_config()`][fn:read_message_with_config], with default config.
+///
+/// [fn:read_message_with_config]: fn.read_message_with_config.html
+pub fn read_message<R>(stream: &mut R) -> IoResult<Option<Value>> where R: Read {
+    read_message_with_config(stream, &DecodeConfig::new())
+}
+
+/// # Reads a value from a length-prefixed message, with a config
+///
+/// See [`write_message()`][fn:write_message] for format of messages.
+///
+/// If it returns `Ok(None)`, it means there's no more data to read.
+///
+/// The whole message is consumed before decoding. So if the message is invalid -- or its length exceeds
+/// [`max_bytes`][DecodeConfig#max_bytes] -- an error is returned, but the stream stays at the start of next message, and the caller can
+/// just keep reading.
+///
+/// ## Errors
+///
+/// - [`UnexpectedEof`][io::ErrorKind#UnexpectedEof]: the message is truncated.
+/// - [`InvalidData`][io::ErrorKind#InvalidData]: the length is too large, or the message does not hold exactly one value. Other errors of
+///   decoding are returned as-is.
+///
+/// [fn:write_message]: fn.write_message.html
+/// [DecodeConfig#max_bytes]: struct.DecodeConfig.html#structfield.max_bytes
+/// [io::ErrorKind#UnexpectedEof]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.UnexpectedEof
+/// [io::ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+pub fn read_message_with_config<R>(stream: &mut R, config: &DecodeConfig) -> IoResult<Option<Value>> where R: Read {
+    let mut prefix = [0; MESSAGE_PREFIX_SIZE];
+    if !read_header(stream, &mut prefix)? {
+        return Ok(None);
+    }
+
+    let size = u32::from_be_bytes(prefix);
+    if size > MAX_DATA_SIZE {
+        return Err(io::Error::new(ErrorKind::InvalidData, __!("message length is too large: {}", size)));
+    }
+    if let Some(max_bytes) = config.max_bytes.filter(|max_bytes| u64::from(size) > *max_bytes) {
+        crate::value_enum::skip_bytes(u64::from(size), stream)?;
+        return Err(io::Error::new(ErrorKind::InvalidData, __!("message length exceeds limit of {} byte(s): {}", max_bytes, size)));
+    }
+
+    let mut payload = Vec::new();
+    stream.take(u64::from(size)).read_to_end(&mut payload)?;
+    if payload.len() != size as usize {
+        return Err(io::Error::new(ErrorKind::UnexpectedEof, __!("expected {} byte(s) of message, got: {}", size, payload.len())));
+    }
+
+    let mut payload = payload.as_slice();
+    match crate::decode_with_config(&mut payload, config)? {
+        Some(value) => match payload.is_empty() {
+            true => Ok(Some(value)),
+            false => Err(io::Error::new(ErrorKind::InvalidData, __!("trailing data in message: {} byte(s)", payload.len()))),
+        },
+        None => Err(io::Error::new(ErrorKind::InvalidData, __!("empty message"))),
+    }
+}
+
+/// # Reads a header
+///
+/// Result: `false` if there's no more data.
+pub(crate) fn read_header<R>(stream: &mut R, header: &mut [u8]) -> IoResult<bool> where R: Read {
+    let mut count = 0;
+    while count < header.len() {
+        match stream.read(&mut header[count..]) {
+            Ok(0) => return match count {
+                0 => Ok(false),
+                _ => Err(io::Error::new(ErrorKind::UnexpectedEof, __!("expected {} byte(s) of header, got: {}", header.len(), count))),
+            },
+            Ok(read) => count += read,
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+    }
+    Ok(true)
+}
+
+/// # Table of CRC-32C (Castagnoli)
+const CRC32C_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < table.len() {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = match crc & 1 {
+                0 => crc >> 1,
+                _ => (crc >> 1) ^ 0x82f6_3b78,
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// # Calculates CRC-32C (Castagnoli) of some bytes
+fn crc32c(bytes: &[u8]) -> u32 {
+    crc32c_update(0, bytes)
+}
+
+/// # Updates CRC-32C (Castagnoli) of some previous bytes, with next bytes
+pub(crate) fn crc32c_update(crc: u32, bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!crc, |crc, b| CRC32C_TABLE[usize::from(crc as u8 ^ b)] ^ (crc >> 8))
+}
+
+#[test]
+fn test_crc32c() {
+    assert_eq!(crc32c(b""), 0);
+    assert_eq!(crc32c(b"123456789"), 0xe306_9283);
+    assert_eq!(crc32c(&[0; 32]), 0x8a91_36aa);
+    assert_eq!(crc32c_update(crc32c(b"1234"), b"56789"), 0xe306_9283);
+}
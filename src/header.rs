@@ -0,0 +1,197 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Header
+
+use {
+    core::cmp::Ordering,
+    std::io::{self, BufRead, ErrorKind, Read},
+
+    crate::{
+        IoResult, Size,
+        cmp::CmpTo,
+        storage::Storage,
+        value::MAX_DATA_SIZE,
+        value_enum::{SUB_TYPE_SIZE_MASK, read_size_and_its_length},
+    },
+};
+
+/// # Max size of a header, in bytes
+///
+/// That is: 2 bytes for type, 4 bytes for size, 4 bytes for item count.
+const MAX_SIZE: usize = 10;
+
+/// # Header of an encoded value
+///
+/// A header consists of the type, and -- depending on value's [storage][enum:Storage] -- a size and an item count. It does _not_ contain
+/// data of the value.
+///
+/// [enum:Storage]: storage/enum.Storage.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Header {
+    type_byte: u8,
+    second_type_byte: Option<u8>,
+    declared_size: Option<Size>,
+    item_count: Option<Size>,
+    size: Size,
+}
+
+impl Header {
+
+    /// # Type byte
+    ///
+    /// For 2-byte types (user defined types), this is the first byte.
+    pub const fn type_byte(&self) -> u8 {
+        self.type_byte
+    }
+
+    /// # Second type byte
+    ///
+    /// This is only available for 2-byte types (user defined types).
+    pub const fn second_type_byte(&self) -> Option<u8> {
+        self.second_type_byte
+    }
+
+    /// # Storage
+    pub const fn storage(&self) -> Storage {
+        Storage::of_type_byte(self.type_byte)
+    }
+
+    /// # Declared size
+    ///
+    /// - For strings and blobs, this is the size of data. For strings, null terminator does _not_ count.
+    /// - For containers, this is the size of the whole container, including its header.
+    /// - Other values don't have a declared size.
+    pub const fn declared_size(&self) -> Option<Size> {
+        self.declared_size
+    }
+
+    /// # Item count
+    ///
+    /// This is only available for containers.
+    pub const fn item_count(&self) -> Option<Size> {
+        self.item_count
+    }
+
+    /// # Size of this header, in bytes
+    pub const fn size(&self) -> Size {
+        self.size
+    }
+
+    /// # Calculates size of the whole value, including this header
+    pub fn value_size(&self) -> u64 {
+        match (self.storage(), self.declared_size) {
+            (Storage::Container, Some(size)) => u64::from(size),
+            // 1 byte for null terminator
+            (Storage::String, Some(size)) => u64::from(self.size) + u64::from(size) + 1,
+            (_, Some(size)) => u64::from(self.size) + u64::from(size),
+            (storage, None) => u64::from(self.size) + storage.fixed_data_size().map(u64::from).unwrap_or_default(),
+        }
+    }
+
+}
+
+/// # Decodes a header from source
+///
+/// On success, source is positioned at value's data, which is right after the header. If it returns `Ok(None)`, it means there's no more
+/// data to decode.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Encoder, storage::Storage};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// buf.encode(&binn_ir::object_from("id", 1))?;
+///
+/// let header = binn_ir::decode_header(&mut &buf[..])?.unwrap();
+/// assert_eq!(header.type_byte(), binn_ir::value::OBJECT);
+/// assert_eq!(header.storage(), Storage::Container);
+/// assert_eq!(header.declared_size(), Some(buf.len() as u32));
+/// assert_eq!(header.item_count(), Some(1));
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+pub fn decode_header<R>(source: &mut R) -> IoResult<Option<Header>> where R: Read {
+    let mut type_byte = [0];
+    match source.read_exact(&mut type_byte) {
+        Ok(()) => (),
+        Err(err) => return match err.kind() {
+            ErrorKind::UnexpectedEof => Ok(None),
+            _ => Err(err),
+        },
+    };
+    let type_byte = type_byte[0];
+
+    let mut size_of_header: Size = 1;
+    let second_type_byte = match type_byte & SUB_TYPE_SIZE_MASK {
+        0 => None,
+        _ => {
+            let mut buf = [0];
+            source.read_exact(&mut buf)?;
+            size_of_header += 1;
+            Some(buf[0])
+        },
+    };
+
+    let storage = Storage::of_type_byte(type_byte);
+    let (declared_size, item_count) = match storage {
+        Storage::String | Storage::Blob | Storage::Container => {
+            let (size, bytes_of_size) = read_size_and_its_length(source)?;
+            size_of_header += bytes_of_size;
+            match storage {
+                Storage::Container => {
+                    let (item_count, bytes_of_item_count) = read_size_and_its_length(source)?;
+                    size_of_header += bytes_of_item_count;
+                    if size < size_of_header {
+                        return Err(io::Error::new(ErrorKind::InvalidData, __!("invalid declared size: {}", &size)));
+                    }
+                    (Some(size), Some(item_count))
+                },
+                _ => match size.cmp_to(&MAX_DATA_SIZE) {
+                    Ordering::Greater => return Err(io::Error::new(ErrorKind::InvalidData, __!("invalid declared size: {}", &size))),
+                    _ => (Some(size), None),
+                },
+            }
+        },
+        _ => (None, None),
+    };
+
+    Ok(Some(Header {
+        type_byte,
+        second_type_byte,
+        declared_size,
+        item_count,
+        size: size_of_header,
+    }))
+}
+
+/// # Peeks a header from source, without consuming it
+///
+/// This function only looks at the buffered data of source, via [`BufRead::fill_buf()`][std::io/BufRead#fill_buf()]. Source's position is
+/// left unchanged, so a following call to [`decode()`][fn:decode] still decodes the whole value.
+///
+/// If it returns `Ok(None)`, it means there's no more data to decode.
+///
+/// ## Notes
+///
+/// A header takes at most 10 bytes, and the whole header must be in the buffered data: a header can't be assembled across refills without
+/// consuming it. Readers may return fewer bytes than that in the middle of a valid stream -- for example a [`BufReader`][std::io/BufReader]
+/// near the end of its internal buffer, or chained readers. So if the buffered data ends in the middle of a header, an error of
+/// [`ErrorKind::WouldBlock`][std::io/ErrorKind#WouldBlock] is returned: source might have more data, but peeking it would require
+/// consuming buffered data. In that case, you can still decode the value normally.
+///
+/// [fn:decode]: fn.decode.html
+/// [std::io/BufRead#fill_buf()]: https://doc.rust-lang.org/std/io/trait.BufRead.html#tymethod.fill_buf
+/// [std::io/BufReader]: https://doc.rust-lang.org/std/io/struct.BufReader.html
+/// [std::io/ErrorKind#WouldBlock]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.WouldBlock
+pub fn peek_header<R>(source: &mut R) -> IoResult<Option<Header>> where R: BufRead {
+    let buf = source.fill_buf()?;
+    let mut bytes = &buf[..buf.len().min(MAX_SIZE)];
+    match decode_header(&mut bytes) {
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => Err(io::Error::new(
+            ErrorKind::WouldBlock, __!("header is not fully buffered: {} byte(s) available", buf.len()),
+        )),
+        other => other,
+    }
+}
@@ -0,0 +1,452 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Inspection of encoded data
+//!
+//! - [`explain()`][fn:explain] prints an annotated breakdown of encoded bytes.
+//! - [`offset_map()`][fn:offset_map] locates all values inside an encoded buffer.
+//!
+//! [fn:explain]: fn.explain.html
+//! [fn:offset_map]: fn.offset_map.html
+
+use {
+    alloc::{
+        borrow::Cow,
+        string::String,
+        vec::Vec,
+    },
+    core::{
+        convert::TryFrom,
+        fmt::Write,
+        str,
+    },
+
+    crate::{
+        MapKey, ObjectKey, Path, PathSegment, Result, Size,
+        storage::Storage,
+        value::{self, DEFAULT_MAX_DEPTH},
+        value_enum::{SUB_TYPE_SIZE_MASK, read_size_at},
+    },
+};
+
+/// # Max bytes to show in hex column
+const MAX_HEX_BYTES: usize = 8;
+
+/// # Max characters to show of a string
+const MAX_STR_CHARS: usize = 64;
+
+/// # Explains encoded data
+///
+/// Each line of result shows a field: its offset, its bytes in hex, and what it means. Fields are type bytes, sizes, item counts, keys, and
+/// data. Items of containers are indented, and labeled with their indexes or keys.
+///
+/// The buffer may contain several values. If some data is invalid -- for example, a truncated write -- explanation stops there, with a line
+/// starting with `!!`.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::inspect;
+///
+/// let bytes = [0xe0, 0x06, 0x02, 0x20, 0x7b, 0x01];
+/// assert_eq!(inspect::explain(&bytes), concat!(
+///     "00000000  e0                       list\n",
+///     "00000001  06                         size: 6\n",
+///     "00000002  02                         count: 2\n",
+///     "00000003  20                         [0] u8\n",
+///     "00000004  7b                           data: 123\n",
+///     "00000005  01                         [1] true\n",
+/// ));
+///
+/// assert!(inspect::explain(&bytes[..4]).ends_with("!! container size is too large: 6\n"));
+/// ```
+pub fn explain(bytes: &[u8]) -> String {
+    let mut explainer = Explainer { bytes, result: String::new() };
+    let mut offset = 0;
+    while offset < bytes.len() {
+        offset = match explainer.explain_value(offset, bytes.len(), 0, "") {
+            Some(end) => end,
+            None => break,
+        };
+    }
+    explainer.result
+}
+
+/// # An entry of an [offset map][fn:offset_map]: path of a value, its offset, its length, and its type byte
+///
+/// Offset and length include the value's type, but not its key (if it's an item of a map or an object).
+///
+/// [fn:offset_map]: fn.offset_map.html
+pub type OffsetMapEntry = (Path, usize, usize, u8);
+
+/// # Makes an offset map of an encoded value
+///
+/// The buffer must contain exactly one value. Result contains that value and all of its nested items -- in order of their offsets -- so
+/// tooling can locate specific fields, and patch them in place.
+///
+/// Only the structure is checked: types, sizes, item counts and keys. For a full check, see [`validate()`][fn:validate].
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Path, PathSegment, inspect, value};
+///
+/// // {"id": 1, "tags": [true]}
+/// let bytes = [0xe2, 0x11, 0x02, 0x02, b'i', b'd', 0x20, 0x01, 0x04, b't', b'a', b'g', b's', 0xe0, 0x04, 0x01, 0x01];
+/// let map = inspect::offset_map(&bytes)?;
+/// assert_eq!(map.len(), 4);
+/// assert_eq!(map[0], (Path::new(), 0, bytes.len(), value::OBJECT));
+///
+/// let (path, offset, len, type_byte) = &map[3];
+/// assert_eq!(path.to_string(), r#"$["tags"][0]"#);
+/// assert_eq!((*offset, *len, *type_byte), (16, 1, value::TRUE));
+/// # Ok::<_, binn_ir::Error>(())
+/// ```
+///
+/// ## Errors
+///
+/// If the value is truncated, has invalid structure, or is followed by trailing data. Error context is the offset where things went wrong.
+///
+/// [fn:validate]: ../fn.validate.html
+pub fn offset_map(bytes: &[u8]) -> Result<Vec<OffsetMapEntry>> {
+    let mut result = Vec::new();
+    let end = map_value(bytes, 0, bytes.len(), &mut Path::new(), &mut result)?;
+    match end == bytes.len() {
+        true => Ok(result),
+        false => Err(err_code!("trailing data, at byte", end)),
+    }
+}
+
+/// # Maps a value which starts at `offset`, and must end at or before `limit`
+///
+/// Result: end offset of the value.
+fn map_value(bytes: &[u8], offset: usize, limit: usize, path: &mut Path, result: &mut Vec<OffsetMapEntry>) -> Result<usize> {
+    if path.segments().len() > DEFAULT_MAX_DEPTH {
+        return Err(err_code!("containers are nested too deeply, at byte", offset));
+    }
+
+    let bytes = &bytes[..limit];
+    let type_byte = *bytes.get(offset).ok_or_else(|| err_code!("missing value, at byte", offset))?;
+    let type_len = match type_byte & SUB_TYPE_SIZE_MASK {
+        0 => 1,
+        _ => 2,
+    };
+    let data_offset = offset + type_len;
+    if data_offset > limit {
+        return Err(err_code!("truncated type, at byte", offset));
+    }
+
+    let index = result.len();
+    result.push((path.clone(), offset, 0, type_byte));
+
+    let storage = Storage::of_type_byte(type_byte);
+    let end = match storage.fixed_data_size() {
+        Some(data_size) => data_offset + usize::from(data_size),
+        None => {
+            let (size, size_len) = read_size_at(bytes, data_offset).ok_or_else(|| err_code!("truncated size, at byte", data_offset))?;
+            match storage {
+                Storage::String => data_offset.checked_add(size_len + size + 1),
+                Storage::Blob => data_offset.checked_add(size_len + size),
+                _ => match size < type_len + size_len {
+                    true => return Err(err_code!("invalid container size, at byte", data_offset)),
+                    false => {
+                        let end = offset.checked_add(size).filter(|end| *end <= limit)
+                            .ok_or_else(|| err_code!("container size is too large, at byte", data_offset))?;
+                        if type_len == 1 && matches!(type_byte, value::LIST | value::MAP | value::OBJECT) {
+                            map_items(bytes, type_byte, data_offset + size_len, end, path, result)?;
+                        }
+                        Some(end)
+                    },
+                },
+            }.ok_or_else(|| err_code!("data size is too large, at byte", data_offset))?
+        },
+    };
+    if end > limit {
+        return Err(err_code!("truncated data, at byte", data_offset));
+    }
+
+    result[index].2 = end - offset;
+    Ok(end)
+}
+
+/// # Maps items of a container, which start at `offset` (with item count), and must end exactly at `end`
+fn map_items(bytes: &[u8], type_byte: u8, offset: usize, end: usize, path: &mut Path, result: &mut Vec<OffsetMapEntry>) -> Result<()> {
+    let bytes = &bytes[..end];
+    let (count, count_len) = read_size_at(bytes, offset).ok_or_else(|| err_code!("truncated item count, at byte", offset))?;
+
+    let mut offset = offset + count_len;
+    let mut index: usize = 0;
+    while offset < end {
+        let segment = match type_byte {
+            value::MAP => {
+                let key = bytes.get(offset..offset + 4).ok_or_else(|| err_code!("truncated map key, at byte", offset))?;
+                offset += 4;
+                PathSegment::MapKey(MapKey::from_be_bytes([key[0], key[1], key[2], key[3]]))
+            },
+            value::OBJECT => {
+                let key_len = usize::from(bytes[offset]);
+                let key = bytes.get(offset + 1..offset + 1 + key_len).ok_or_else(|| err_code!("truncated object key, at byte", offset))?;
+                let key = str::from_utf8(key).map_err(|_| err_code!("invalid object key, at byte", offset))?;
+                offset += 1 + key_len;
+                PathSegment::ObjectKey(ObjectKey::from(key))
+            },
+            _ => PathSegment::Index(Size::try_from(index).map_err(|_| err_code!("too many items, at byte", offset))?),
+        };
+
+        path.push(segment);
+        let item_end = map_value(bytes, offset, end, path, result);
+        path.pop();
+        offset = item_end?;
+        index += 1;
+    }
+
+    match index == count {
+        true => Ok(()),
+        false => Err(err_code!("item count mismatches, at byte", offset)),
+    }
+}
+
+/// # Explainer
+struct Explainer<'a> {
+    bytes: &'a [u8],
+    result: String,
+}
+
+impl Explainer<'_> {
+
+    /// # Adds a line for a field, which spans `offset..end`
+    fn field<S>(&mut self, offset: usize, end: usize, depth: usize, msg: S) where S: AsRef<str> {
+        let bytes = &self.bytes[offset..end];
+        let mut hex = String::new();
+        for (i, b) in bytes.iter().take(MAX_HEX_BYTES).enumerate() {
+            match i + 1 == MAX_HEX_BYTES && bytes.len() > MAX_HEX_BYTES {
+                true => hex.push_str(".."),
+                false => write!(hex, "{:02x} ", b).unwrap(),
+            };
+        }
+        writeln!(self.result, "{:08x}  {:<24} {:indent$}{}", offset, hex, "", msg.as_ref(), indent=depth * 2).unwrap();
+    }
+
+    /// # Adds a line for an error, at `depth`
+    fn error<S>(&mut self, depth: usize, msg: S) where S: AsRef<str> {
+        writeln!(self.result, "{:35}{:indent$}!! {}", "", "", msg.as_ref(), indent=depth * 2).unwrap();
+    }
+
+    /// # Explains a value which starts at `offset`, and must end at or before `limit`
+    ///
+    /// Result: end offset of the value, or `None` if explanation stopped.
+    fn explain_value(&mut self, offset: usize, limit: usize, depth: usize, label: &str) -> Option<usize> {
+        let type_byte = match self.bytes[..limit].get(offset) {
+            Some(type_byte) => *type_byte,
+            None => {
+                self.error(depth, "missing value");
+                return None;
+            },
+        };
+        let type_len = match type_byte & SUB_TYPE_SIZE_MASK {
+            0 => 1,
+            _ => 2,
+        };
+        if offset + type_len > limit {
+            self.error(depth, "truncated type");
+            return None;
+        }
+        let data_offset = offset + type_len;
+
+        let storage = Storage::of_type_byte(type_byte);
+        let name = match type_len {
+            1 => type_name(type_byte),
+            _ => None,
+        };
+        let name = match name {
+            Some(name) => Cow::Borrowed(name),
+            None => Cow::Owned(alloc::format!("unknown type (storage: {:?})", storage)),
+        };
+        self.field(offset, data_offset, depth, alloc::format!("{}{}", label, name));
+        let depth = depth + 1;
+
+        if let Some(data_size) = storage.fixed_data_size() {
+            let end = data_offset + usize::from(data_size);
+            if end > limit {
+                self.error(depth, alloc::format!("expected {} byte(s) of data, got: {}", data_size, limit - data_offset));
+                return None;
+            }
+            if data_size > 0 {
+                let data = fixed_data(type_len, type_byte, &self.bytes[data_offset..end]);
+                self.field(data_offset, end, depth, alloc::format!("data: {}", data));
+            }
+            return Some(end);
+        }
+
+        let (size, size_len) = self.read_size(data_offset, limit, depth)?;
+        self.field(data_offset, data_offset + size_len, depth, alloc::format!("size: {}", size));
+        match storage {
+            Storage::String => {
+                let str_offset = data_offset + size_len;
+                let end = match str_offset.checked_add(size).map(|end| end + 1) {
+                    Some(end) if end <= limit => end,
+                    _ => {
+                        self.error(depth, alloc::format!("string size is too large: {}", size));
+                        return None;
+                    },
+                };
+                let s = String::from_utf8_lossy(&self.bytes[str_offset..end - 1]);
+                let msg = match s.chars().count() > MAX_STR_CHARS {
+                    true => alloc::format!("data: {:?}..", s.chars().take(MAX_STR_CHARS).collect::<String>()),
+                    false => alloc::format!("data: {:?}", s),
+                };
+                self.field(str_offset, end - 1, depth, msg);
+                let terminator = self.bytes[end - 1];
+                self.field(end - 1, end, depth, match terminator {
+                    0 => Cow::Borrowed("null terminator"),
+                    _ => Cow::Owned(alloc::format!("!! expected null terminator, got: 0x{:02x}", terminator)),
+                });
+                Some(end)
+            },
+            Storage::Blob => match data_offset.checked_add(size_len + size) {
+                Some(end) if end <= limit => {
+                    if size > 0 {
+                        self.field(data_offset + size_len, end, depth, alloc::format!("data: {} byte(s)", size));
+                    }
+                    Some(end)
+                },
+                _ => {
+                    self.error(depth, alloc::format!("blob size is too large: {}", size));
+                    None
+                },
+            },
+            _ => {
+                // Container's size includes its type and the size itself
+                let end = match offset.checked_add(size) {
+                    _ if size < type_len + size_len => {
+                        self.error(depth, alloc::format!("invalid container size: {}", size));
+                        return None;
+                    },
+                    Some(end) if end <= limit => end,
+                    _ => {
+                        self.error(depth, alloc::format!("container size is too large: {}", size));
+                        return None;
+                    },
+                };
+                match type_len == 1 && matches!(type_byte, value::LIST | value::MAP | value::OBJECT) {
+                    true => self.explain_items(type_byte, data_offset + size_len, end, depth)?,
+                    false => if data_offset + size_len < end {
+                        self.field(data_offset + size_len, end, depth, alloc::format!("data: {} byte(s)", end - data_offset - size_len));
+                    },
+                };
+                Some(end)
+            },
+        }
+    }
+
+    /// # Explains items of a container, which start at `offset` (with item count), and must end exactly at `end`
+    ///
+    /// Result: `None` if explanation stopped.
+    fn explain_items(&mut self, type_byte: u8, offset: usize, end: usize, depth: usize) -> Option<()> {
+        if depth > DEFAULT_MAX_DEPTH {
+            self.error(depth, "containers are nested too deeply");
+            return None;
+        }
+
+        let (count, count_len) = self.read_size(offset, end, depth)?;
+        self.field(offset, offset + count_len, depth, alloc::format!("count: {}", count));
+
+        let mut offset = offset + count_len;
+        let mut index: usize = 0;
+        while offset < end {
+            let label = match type_byte {
+                value::MAP => match self.bytes[..end].get(offset..offset + 4) {
+                    Some(key) => {
+                        let key = i32::from_be_bytes([key[0], key[1], key[2], key[3]]);
+                        self.field(offset, offset + 4, depth, alloc::format!("key: {}", key));
+                        offset += 4;
+                        alloc::format!("{{{}}} ", key)
+                    },
+                    None => {
+                        self.error(depth, "truncated map key");
+                        return None;
+                    },
+                },
+                value::OBJECT => {
+                    let key_len = usize::from(self.bytes[offset]);
+                    match self.bytes[..end].get(offset + 1..offset + 1 + key_len) {
+                        Some(key) => {
+                            let key = String::from_utf8_lossy(key);
+                            self.field(offset, offset + 1 + key_len, depth, alloc::format!("key: {:?}", key));
+                            offset += 1 + key_len;
+                            alloc::format!("[{:?}] ", key)
+                        },
+                        None => {
+                            self.error(depth, "truncated object key");
+                            return None;
+                        },
+                    }
+                },
+                _ => alloc::format!("[{}] ", index),
+            };
+
+            offset = self.explain_value(offset, end, depth, &label)?;
+            index += 1;
+        }
+
+        if index != count {
+            self.error(depth, alloc::format!("item count is declared: {}, but got: {}", count, index));
+        }
+        Some(())
+    }
+
+    /// # Reads a size (or an item count), or adds an error if it's truncated
+    fn read_size(&mut self, offset: usize, limit: usize, depth: usize) -> Option<(usize, usize)> {
+        let result = read_size_at(&self.bytes[..limit], offset);
+        if result.is_none() {
+            self.error(depth, "truncated size");
+        }
+        result
+    }
+
+}
+
+/// # Name of a type
+fn type_name(type_byte: u8) -> Option<&'static str> {
+    Some(match type_byte {
+        value::NULL => "null",
+        value::TRUE => "true",
+        value::FALSE => "false",
+        value::U8 => "u8",
+        value::I8 => "i8",
+        value::U16 => "u16",
+        value::I16 => "i16",
+        value::U32 => "u32",
+        value::I32 => "i32",
+        value::FLOAT => "float",
+        value::U64 => "u64",
+        value::I64 => "i64",
+        value::DOUBLE => "double",
+        value::TEXT => "text",
+        value::DATE_TIME => "date-time",
+        value::DATE => "date",
+        value::TIME => "time",
+        value::DECIMAL_STR => "decimal string",
+        value::BLOB => "blob",
+        value::LIST => "list",
+        value::MAP => "map",
+        value::OBJECT => "object",
+        _ => return None,
+    })
+}
+
+/// # Formats fixed-size data
+fn fixed_data(type_len: usize, type_byte: u8, data: &[u8]) -> String {
+    let mut bytes = [0; 8];
+    bytes[8 - data.len()..].copy_from_slice(data);
+    let n = u64::from_be_bytes(bytes);
+    match (type_len, type_byte) {
+        (1, value::U8) | (1, value::U16) | (1, value::U32) | (1, value::U64) => alloc::format!("{}", n),
+        (1, value::I8) => alloc::format!("{}", n as u8 as i8),
+        (1, value::I16) => alloc::format!("{}", n as u16 as i16),
+        (1, value::I32) => alloc::format!("{}", n as u32 as i32),
+        (1, value::I64) => alloc::format!("{}", n as i64),
+        (1, value::FLOAT) => alloc::format!("{:?}", f32::from_bits(n as u32)),
+        (1, value::DOUBLE) => alloc::format!("{:?}", f64::from_bits(n)),
+        _ => alloc::format!("0x{:0width$x}", n, width=data.len() * 2),
+    }
+}
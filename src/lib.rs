@@ -67,6 +67,8 @@
 //! # Ok::<_, binn_ir::Error>(())
 //! ```
 //!
+//! [`Envelope`][struct:Envelope] implements this layout -- magic number, version, metadata and payload -- so you don't have to.
+//!
 //! ## Thanks
 //!
 //! Special thanks to [Binn]'s authors for their hard work.
@@ -78,6 +80,7 @@
 //!
 //! [std::io/Read]: https://doc.rust-lang.org/std/io/trait.Read.html
 //! [std::io/Read#take()]: https://doc.rust-lang.org/std/io/trait.Read.html#method.take
+//! [struct:Envelope]: struct.Envelope.html
 
 #![warn(missing_docs)]
 #![no_std]
@@ -135,6 +138,15 @@ macro_rules! err {
     };
 }
 
+/// # Makes new Error with a static code and a numeric context, without heap allocation
+///
+/// The context is converted into `i128`, so it must be a primitive integer.
+macro_rules! err_code {
+    ($code: literal, $context: expr) => {
+        crate::Error::with_context(line!(), module_path!(), $code, $context as i128)
+    };
+}
+
 #[test]
 fn test_macro_err() {
     use alloc::borrow::Cow;
@@ -143,11 +155,15 @@ fn test_macro_err() {
 
     fn eq(first: Error, second: Error) -> bool {
         first.line() == second.line() && first.module_path() == second.module_path() && first.msg() == second.msg()
+            && first.context() == second.context()
     }
 
     assert!(eq(err!(), Error::new(line!(), module_path!(), None)));
     assert!(eq(err!("test"), Error::new(line!(), module_path!(), Some(Cow::Borrowed(s_test!())))));
     assert!(eq(err!("{s:?}", s=s_test!()), Error::new(line!(), module_path!(), Some(Cow::Owned(alloc::format!("{:?}", s_test!()))))));
+
+    assert!(alloc::format!("{}", err_code!("test", -1_i8)).ends_with("] test: -1"));
+    assert!(eq(err_code!("test", 1_u8), Error::with_context(line!(), module_path!(), s_test!(), 1)));
 }
 
 /// # Wrapper for format!(), which prefixes your optional message with: crate::TAG, module_path!(), line!()
@@ -162,41 +178,173 @@ macro_rules! __ {
     };
 }
 
+mod checked_containers;
 mod cmp;
+mod compat_level;
 mod container_functions;
+mod cow_value;
 mod error;
+mod map_ext;
+mod object_ext;
+mod path;
+mod schema;
+mod shared_value;
+mod slice_writer;
+mod stats;
 mod types;
+mod validator;
 mod value_enum;
+mod walk;
 
+#[cfg(feature="bumpalo")]
+mod arena;
+#[cfg(feature="std")]
+mod append;
+#[cfg(feature="futures-io")]
+mod async_decoder;
+#[cfg(feature="bytes")]
+mod buf;
+#[cfg(feature="bytes")]
+mod bytes_blob;
+#[cfg(feature="std")]
+mod binn_decoder;
+#[cfg(feature="std")]
+mod budgeted_decoder;
+#[cfg(feature="std")]
+mod chunked_blob;
+#[cfg(feature="compact_str")]
+mod compact_object;
+#[cfg(any(feature="flate2", feature="zstd"))]
+mod compression;
+#[cfg(feature="std")]
+mod container_readers;
+#[cfg(feature="std")]
+mod datagram;
+#[cfg(feature="std")]
+mod decode_config;
+#[cfg(feature="std")]
+mod decode_error;
 #[cfg(feature="std")]
 mod decoder;
 #[cfg(feature="std")]
 mod decoding_functions;
 #[cfg(feature="std")]
+mod editor;
+#[cfg(feature="std")]
+mod encode_config;
+#[cfg(feature="std")]
 mod encoder;
 #[cfg(feature="std")]
 mod encoding_functions;
+#[cfg(feature="std")]
+mod envelope;
+#[cfg(feature="std")]
+mod ext_sort;
+#[cfg(feature="std")]
+mod framing;
+#[cfg(feature="std")]
+mod header;
+#[cfg(feature="std")]
+mod partial_decoder;
+#[cfg(feature="std")]
+mod random_access;
+#[cfg(feature="std")]
+mod record_log;
+#[cfg(feature="serde")]
+mod serde_codec;
+#[cfg(feature="std")]
+mod stream_layer;
+#[cfg(feature="std")]
+mod tracking_reader;
+#[cfg(feature="heapless")]
+mod value_ref;
+#[cfg(feature="std")]
+mod values;
 
 pub use self::{
+    checked_containers::*,
+    compat_level::*,
     container_functions::*,
+    cow_value::*,
     error::*,
+    map_ext::*,
+    object_ext::*,
+    path::*,
+    schema::*,
+    shared_value::*,
+    stats::*,
     types::*,
+    validator::*,
     value_enum::*,
+    walk::*,
 };
 
 #[cfg(feature="std")]
 pub use self::{
+    append::*,
+    binn_decoder::*,
+    budgeted_decoder::*,
+    chunked_blob::*,
+    container_readers::*,
+    datagram::*,
+    decode_config::*,
+    decode_error::*,
     decoder::*,
     decoding_functions::*,
+    editor::*,
+    encode_config::*,
     encoder::*,
     encoding_functions::*,
+    envelope::*,
+    ext_sort::*,
+    framing::*,
+    header::*,
+    partial_decoder::*,
+    random_access::*,
+    record_log::*,
+    stream_layer::*,
+    tracking_reader::*,
+    values::*,
+};
+
+#[cfg(feature="bumpalo")]
+pub use self::arena::*;
+
+#[cfg(feature="futures-io")]
+pub use self::async_decoder::*;
+
+#[cfg(feature="bytes")]
+pub use self::{
+    buf::*,
+    bytes_blob::*,
 };
 
+#[cfg(feature="compact_str")]
+pub use self::compact_object::*;
+
+#[cfg(any(feature="flate2", feature="zstd"))]
+pub use self::compression::*;
+
+#[cfg(feature="serde")]
+pub use self::serde_codec::*;
+
+#[cfg(feature="heapless")]
+pub use self::value_ref::*;
+
+pub mod coerce;
+pub mod inspect;
+pub mod rpc;
 pub mod specification;
 pub mod storage;
 pub mod value;
 pub mod version_info;
 
+#[cfg(feature="bench-utils")]
+pub mod bench_utils;
+
+#[cfg(feature="testing")]
+pub mod testing;
+
 /// # Result type used in this crate
 pub type Result<T> = core::result::Result<T, Error>;
 
@@ -0,0 +1,101 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Map extension
+
+use {
+    core::convert::TryFrom,
+
+    crate::{List, Map, MapKey, Object, Result, Value},
+};
+
+/// # Extension for [`Map`][type:Map]
+///
+/// This is the same as [`ObjectExt`][trait:ObjectExt], for maps.
+///
+/// Getters return `Ok(None)` if the key does not exist, and an error if the item has another type.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Map, MapExt, Value};
+///
+/// let mut map = Map::new();
+/// binn_ir::map_insert(&mut map, 0, 99_u8);
+/// binn_ir::map_insert(&mut map, 1, binn_ir::object_from("name", "moss"));
+///
+/// assert_eq!(map.get_i64(0)?, Some(99));
+/// assert!(map.get_blob(0).is_err());
+///
+/// assert_eq!(map.take_object(1)?.map(|object| object.len()), Some(1));
+/// assert_eq!(map.take_object(1)?, None);
+/// # Ok::<_, binn_ir::Error>(())
+/// ```
+///
+/// [type:Map]: type.Map.html
+/// [trait:ObjectExt]: trait.ObjectExt.html
+pub trait MapExt {
+
+    /// # Gets an integer
+    ///
+    /// Integers of any width are accepted, as long as they fit into `i64`.
+    fn get_i64(&self, key: MapKey) -> Result<Option<i64>>;
+
+    /// # Gets a text
+    fn get_text(&self, key: MapKey) -> Result<Option<&str>>;
+
+    /// # Gets a blob
+    fn get_blob(&self, key: MapKey) -> Result<Option<&[u8]>>;
+
+    /// # Gets a list
+    fn get_list(&self, key: MapKey) -> Result<Option<&List>>;
+
+    /// # Gets a map
+    fn get_map(&self, key: MapKey) -> Result<Option<&Map>>;
+
+    /// # Gets an object
+    fn get_object(&self, key: MapKey) -> Result<Option<&Object>>;
+
+    /// # Takes an object
+    ///
+    /// If the item is not an object, an error is returned, and the item is left in place.
+    fn take_object(&mut self, key: MapKey) -> Result<Option<Object>>;
+
+}
+
+impl MapExt for Map {
+
+    fn get_i64(&self, key: MapKey) -> Result<Option<i64>> {
+        self.get(&key).map(i64::try_from).transpose()
+    }
+
+    fn get_text(&self, key: MapKey) -> Result<Option<&str>> {
+        self.get(&key).map(Value::as_text).transpose()
+    }
+
+    fn get_blob(&self, key: MapKey) -> Result<Option<&[u8]>> {
+        self.get(&key).map(Value::as_blob).transpose()
+    }
+
+    fn get_list(&self, key: MapKey) -> Result<Option<&List>> {
+        self.get(&key).map(Value::as_list).transpose()
+    }
+
+    fn get_map(&self, key: MapKey) -> Result<Option<&Map>> {
+        self.get(&key).map(Value::as_map).transpose()
+    }
+
+    fn get_object(&self, key: MapKey) -> Result<Option<&Object>> {
+        self.get(&key).map(Value::as_object).transpose()
+    }
+
+    fn take_object(&mut self, key: MapKey) -> Result<Option<Object>> {
+        match self.get_object(key)? {
+            Some(_) => match self.remove(&key) {
+                Some(Value::Object(object)) => Ok(Some(object)),
+                _ => Err(err!()),
+            },
+            None => Ok(None),
+        }
+    }
+
+}
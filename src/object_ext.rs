@@ -0,0 +1,88 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Object extension
+
+use crate::{List, Map, Object, ObjectKey, Result, Value};
+
+/// # Extension for [`Object`][type:Object]
+///
+/// This gives typed access to items of objects, without wrapping them in [`Value`][enum:Value].
+///
+/// Getters return `Ok(None)` if the key does not exist, and an error if the item has another type.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Object, ObjectExt, Value};
+///
+/// let mut object = Object::new();
+/// object.insert_checked("name", "moss")?;
+/// object.insert_checked("tags", vec![Value::from("green")])?;
+///
+/// assert_eq!(object.get_text("name")?, Some("moss"));
+/// assert_eq!(object.get_list("tags")?.map(|tags| tags.len()), Some(1));
+/// assert_eq!(object.get_text("missing")?, None);
+/// assert!(object.get_object("name").is_err());
+///
+/// assert!(object.insert_checked("k".repeat(256), 0).is_err());
+/// # Ok::<_, binn_ir::Error>(())
+/// ```
+///
+/// [type:Object]: type.Object.html
+/// [enum:Value]: enum.Value.html
+pub trait ObjectExt {
+
+    /// # Gets a text
+    fn get_text(&self, key: &str) -> Result<Option<&str>>;
+
+    /// # Gets a blob
+    fn get_blob(&self, key: &str) -> Result<Option<&[u8]>>;
+
+    /// # Gets a list
+    fn get_list(&self, key: &str) -> Result<Option<&List>>;
+
+    /// # Gets a map
+    fn get_map(&self, key: &str) -> Result<Option<&Map>>;
+
+    /// # Gets an object
+    fn get_object(&self, key: &str) -> Result<Option<&Object>>;
+
+    /// # Inserts an item, after validating its key
+    ///
+    /// Keys longer than [`OBJECT_KEY_MAX_LEN`][value::OBJECT_KEY_MAX_LEN] bytes can't be encoded. This function rejects them right away,
+    /// rather than at encoding time.
+    ///
+    /// Returns previous value (if it existed).
+    ///
+    /// [value::OBJECT_KEY_MAX_LEN]: value/constant.OBJECT_KEY_MAX_LEN.html
+    fn insert_checked<K, V>(&mut self, key: K, value: V) -> Result<Option<Value>> where K: Into<ObjectKey>, V: Into<Value>;
+
+}
+
+impl ObjectExt for Object {
+
+    fn get_text(&self, key: &str) -> Result<Option<&str>> {
+        self.get(key).map(Value::as_text).transpose()
+    }
+
+    fn get_blob(&self, key: &str) -> Result<Option<&[u8]>> {
+        self.get(key).map(Value::as_blob).transpose()
+    }
+
+    fn get_list(&self, key: &str) -> Result<Option<&List>> {
+        self.get(key).map(Value::as_list).transpose()
+    }
+
+    fn get_map(&self, key: &str) -> Result<Option<&Map>> {
+        self.get(key).map(Value::as_map).transpose()
+    }
+
+    fn get_object(&self, key: &str) -> Result<Option<&Object>> {
+        self.get(key).map(Value::as_object).transpose()
+    }
+
+    fn insert_checked<K, V>(&mut self, key: K, value: V) -> Result<Option<Value>> where K: Into<ObjectKey>, V: Into<Value> {
+        crate::object_insert_checked(self, key, value)
+    }
+
+}
@@ -0,0 +1,176 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Partial decoder
+
+use {
+    alloc::vec::Vec,
+    core::convert::TryFrom,
+    std::io::{self, ErrorKind, Read},
+
+    crate::{DecodeConfig, IoResult, Value},
+};
+
+/// # Size of a chunk to read from source
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// # A decoder which can be fed bytes incrementally
+///
+/// Bytes are fed via [`feed()`][#feed()], or read from a (possibly non-blocking) source via [`read_from()`][#read_from()]. Then
+/// [`decode()`][#decode()] returns the next value as soon as all of its bytes have arrived; until then, it returns `Ok(None)`, and resumes
+/// with next calls.
+///
+/// This is useful for non-blocking sockets, and event loops such as `mio`/`epoll`: callers don't need to know where values end, nor to
+/// buffer them first.
+///
+/// ## Notes
+///
+/// - Only bytes of the current value are buffered. Each value is decoded once its declared size is available. Decoded bytes are dropped
+///   lazily: the buffer is compacted once per [`feed()`][#feed()] or [`read_from()`][#read_from()], not once per value.
+/// - With [`max_bytes`][DecodeConfig#max_bytes] of the config, values whose declared sizes exceed the limit are rejected as soon as their
+///   headers arrive, before their data is buffered. [`read_from()`][#read_from()] also stops reading once that many bytes are buffered.
+/// - If a value fails to decode, its bytes are still dropped, so next values can be decoded.
+/// - If a header is invalid -- or declares a size exceeding the limit -- the error is returned, and buffered data is left unchanged. It's not
+///   possible to find where next value starts, so you should drop the decoder.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Encoder, PartialDecoder, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// buf.encode_text("first")?;
+/// buf.encode_u8(2)?;
+///
+/// let mut decoder = PartialDecoder::new();
+/// decoder.feed(&buf[..3]);
+/// assert_eq!(decoder.decode()?, None);
+///
+/// decoder.feed(&buf[3..]);
+/// assert_eq!(decoder.decode()?, Some(Value::Text("first".into())));
+/// assert_eq!(decoder.decode()?, Some(Value::U8(2)));
+/// assert_eq!(decoder.decode()?, None);
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [#feed()]: #method.feed
+/// [#read_from()]: #method.read_from
+/// [#decode()]: #method.decode
+/// [DecodeConfig#max_bytes]: struct.DecodeConfig.html#structfield.max_bytes
+#[derive(Debug, Default, Clone)]
+pub struct PartialDecoder {
+    buf: Vec<u8>,
+    offset: usize,
+    config: DecodeConfig,
+}
+
+impl PartialDecoder {
+
+    /// # Makes new decoder
+    pub const fn new() -> Self {
+        Self::with_config(DecodeConfig::new())
+    }
+
+    /// # Makes new decoder, with a config
+    pub const fn with_config(config: DecodeConfig) -> Self {
+        Self {
+            buf: Vec::new(),
+            offset: 0,
+            config,
+        }
+    }
+
+    /// # Buffered bytes, which have not been decoded yet
+    pub fn buffered(&self) -> &[u8] {
+        &self.buf[self.offset..]
+    }
+
+    /// # Drops decoded bytes
+    fn compact(&mut self) {
+        if self.offset > 0 {
+            self.buf.drain(..self.offset);
+            self.offset = 0;
+        }
+    }
+
+    /// # Checks if buffered bytes have reached max bytes of config
+    fn is_full(&self) -> bool {
+        match self.config.max_bytes {
+            Some(max_bytes) => self.buffered().len() as u64 >= max_bytes,
+            None => false,
+        }
+    }
+
+    /// # Feeds some bytes
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.compact();
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// # Reads available bytes from source
+    ///
+    /// This function reads until source reports end of data, or [`WouldBlock`][std::io/ErrorKind#WouldBlock]. Interrupted reads are retried.
+    ///
+    /// Result:
+    ///
+    /// - `Some(n)`: source has reached end of data, after `n` bytes.
+    /// - `None`: source would block, or buffered bytes have reached [`max_bytes`][DecodeConfig#max_bytes] of the config. Some bytes might
+    ///   have been read though. In the latter case, source might still have data: you should [decode][#decode()] values, then call this
+    ///   function again.
+    ///
+    /// [std::io/ErrorKind#WouldBlock]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.WouldBlock
+    /// [DecodeConfig#max_bytes]: struct.DecodeConfig.html#structfield.max_bytes
+    /// [#decode()]: #method.decode
+    pub fn read_from<R>(&mut self, source: &mut R) -> IoResult<Option<usize>> where R: Read {
+        self.compact();
+
+        let mut chunk = [0; CHUNK_SIZE];
+        let mut result = 0;
+        loop {
+            if self.is_full() {
+                return Ok(None);
+            }
+            match source.read(&mut chunk) {
+                Ok(0) => return Ok(Some(result)),
+                Ok(count) => {
+                    self.buf.extend_from_slice(&chunk[..count]);
+                    result += count;
+                },
+                Err(err) => match err.kind() {
+                    ErrorKind::WouldBlock => return Ok(None),
+                    ErrorKind::Interrupted => continue,
+                    _ => return Err(err),
+                },
+            };
+        }
+    }
+
+    /// # Decodes next value, if all of its bytes are available
+    ///
+    /// If it returns `Ok(None)`, more bytes are needed.
+    pub fn decode(&mut self) -> IoResult<Option<Value>> {
+        let value_size = match crate::decode_header(&mut self.buffered()) {
+            Ok(Some(header)) => header.value_size(),
+            Ok(None) => return Ok(None),
+            Err(err) => return match err.kind() {
+                ErrorKind::UnexpectedEof => Ok(None),
+                _ => Err(err),
+            },
+        };
+        if let Some(max_bytes) = self.config.max_bytes {
+            if value_size > max_bytes {
+                return Err(io::Error::new(ErrorKind::InvalidData, __!("decode budget exceeded: more than {} byte(s)", max_bytes)));
+            }
+        }
+        let value_size = match usize::try_from(value_size) {
+            Ok(value_size) if value_size <= self.buffered().len() => value_size,
+            _ => return Ok(None),
+        };
+
+        let result = crate::decode_with_config(&mut &self.buffered()[..value_size], &self.config);
+        self.offset += value_size;
+        result
+    }
+
+}
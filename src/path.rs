@@ -0,0 +1,114 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Path
+
+use {
+    alloc::vec::Vec,
+    core::fmt::{self, Display, Formatter},
+
+    crate::{MapKey, ObjectKey, Size},
+};
+
+/// # A segment of a [`Path`][struct:Path]
+///
+/// [struct:Path]: struct.Path.html
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PathSegment {
+
+    /// # Index of an item in a [`List`](enum.Value.html#variant.List)
+    Index(Size),
+
+    /// # Key of an item in a [`Map`](enum.Value.html#variant.Map)
+    MapKey(MapKey),
+
+    /// # Key of an item in an [`Object`](enum.Value.html#variant.Object)
+    ObjectKey(ObjectKey),
+
+}
+
+impl Display for PathSegment {
+
+    fn fmt(&self, f: &mut Formatter) -> core::result::Result<(), fmt::Error> {
+        match self {
+            PathSegment::Index(index) => write!(f, "[{}]", index),
+            PathSegment::MapKey(key) => write!(f, "{{{}}}", key),
+            PathSegment::ObjectKey(key) => write!(f, "[{:?}]", key.as_str()),
+        }
+    }
+
+}
+
+/// # Path to a value inside containers
+///
+/// An empty path points to the root value.
+///
+/// ## Display format
+///
+/// A path is displayed as `$`, followed by its segments: `[0]` for list indexes, `{0}` for map keys, and `["key"]` for object keys. For
+/// example: `$["users"][0]{-1}`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+impl Path {
+
+    /// # Makes new empty path
+    pub const fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+        }
+    }
+
+    /// # Segments
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// # Checks if this path points to the root value
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// # Appends a segment
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+
+    /// # Removes last segment
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.segments.pop()
+    }
+
+    /// # Inserts a segment at the front
+    #[cfg(feature="std")]
+    pub(crate) fn push_front(&mut self, segment: PathSegment) {
+        self.segments.insert(0, segment);
+    }
+
+}
+
+impl Display for Path {
+
+    fn fmt(&self, f: &mut Formatter) -> core::result::Result<(), fmt::Error> {
+        f.write_str("$")?;
+        self.segments.iter().try_for_each(|segment| segment.fmt(f))
+    }
+
+}
+
+impl From<Vec<PathSegment>> for Path {
+
+    fn from(segments: Vec<PathSegment>) -> Self {
+        Self { segments }
+    }
+
+}
+
+impl From<Path> for Vec<PathSegment> {
+
+    fn from(path: Path) -> Self {
+        path.segments
+    }
+
+}
@@ -0,0 +1,151 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Random access
+
+use {
+    std::io::{self, ErrorKind, Read, Seek, SeekFrom},
+
+    crate::{Header, IoResult, MapKey, Size, Value, storage::Storage},
+};
+
+/// # Decodes a value at an offset
+///
+/// Source is positioned at `offset`, then a value is decoded from there. On success, source is positioned right after the value.
+///
+/// If it returns `Ok(None)`, it means there's no data at `offset`.
+///
+/// ## Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use binn_ir::{Encoder, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// buf.encode(&Value::List((0..100).map(|i| Value::from(i.to_string())).collect()))?;
+///
+/// let mut file = Cursor::new(buf);
+/// let offset = binn_ir::seek_to_item(&mut file, 0, 42)?.unwrap();
+/// assert_eq!(binn_ir::decode_at(&mut file, offset)?, Some(Value::from("42")));
+/// assert_eq!(binn_ir::seek_to_item(&mut file, 0, 100)?, None);
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+pub fn decode_at<R>(source: &mut R, offset: u64) -> IoResult<Option<Value>> where R: Read + Seek {
+    source.seek(SeekFrom::Start(offset))?;
+    crate::decode(source)
+}
+
+/// # Seeks to an item of a container
+///
+/// The container is at `container_offset`. Preceding items are skipped via their declared sizes -- they're neither read nor decoded. For
+/// maps and objects, `n` is index of the entry, and the result points to its value (after its key).
+///
+/// Result: offset of the item. On success, source is positioned there. If the container has less than `n + 1` items, `Ok(None)` is
+/// returned.
+///
+/// ## Errors
+///
+/// [`InvalidData`][io::ErrorKind#InvalidData]: the value at `container_offset` is not a container, or the container is invalid.
+///
+/// [io::ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+pub fn seek_to_item<R>(source: &mut R, container_offset: u64, n: Size) -> IoResult<Option<u64>> where R: Read + Seek {
+    let header = container_header(source, container_offset)?;
+    if n >= header.item_count().unwrap_or_default() {
+        return Ok(None);
+    }
+
+    for _ in 0..n {
+        skip_key(source, header.type_byte())?;
+        skip_value(source)?;
+    }
+    skip_key(source, header.type_byte())?;
+    source.stream_position().map(Some)
+}
+
+/// # Seeks to an item of a map
+///
+/// This works like [`seek_to_item()`][fn:seek_to_item]. The result points to value of `key`, or is `Ok(None)` if the key does not exist.
+///
+/// [fn:seek_to_item]: fn.seek_to_item.html
+pub fn seek_to_map_key<R>(source: &mut R, container_offset: u64, key: MapKey) -> IoResult<Option<u64>> where R: Read + Seek {
+    let header = container_header(source, container_offset)?;
+    expect_type(&header, crate::value::MAP)?;
+
+    for _ in 0..header.item_count().unwrap_or_default() {
+        let mut buf = [0; core::mem::size_of::<MapKey>()];
+        source.read_exact(&mut buf)?;
+        if MapKey::from_be_bytes(buf) == key {
+            return source.stream_position().map(Some);
+        }
+        skip_value(source)?;
+    }
+    Ok(None)
+}
+
+/// # Seeks to an item of an object
+///
+/// This works like [`seek_to_item()`][fn:seek_to_item]. The result points to value of `key`, or is `Ok(None)` if the key does not exist.
+///
+/// [fn:seek_to_item]: fn.seek_to_item.html
+pub fn seek_to_object_key<R, K>(source: &mut R, container_offset: u64, key: K) -> IoResult<Option<u64>> where R: Read + Seek, K: AsRef<str> {
+    let header = container_header(source, container_offset)?;
+    expect_type(&header, crate::value::OBJECT)?;
+
+    let key = key.as_ref().as_bytes();
+    let mut buf = [0; crate::value::OBJECT_KEY_MAX_LEN];
+    for _ in 0..header.item_count().unwrap_or_default() {
+        let mut len = [0];
+        source.read_exact(&mut len)?;
+        let buf = &mut buf[..usize::from(len[0])];
+        source.read_exact(buf)?;
+        if buf == key {
+            return source.stream_position().map(Some);
+        }
+        skip_value(source)?;
+    }
+    Ok(None)
+}
+
+/// # Decodes header of a container at an offset
+fn container_header<R>(source: &mut R, offset: u64) -> IoResult<Header> where R: Read + Seek {
+    source.seek(SeekFrom::Start(offset))?;
+    match crate::decode_header(source)? {
+        Some(header) if header.storage() == Storage::Container => Ok(header),
+        Some(header) => Err(io::Error::new(ErrorKind::InvalidData, __!("not a container: 0x{:02x}", header.type_byte()))),
+        None => Err(io::Error::new(ErrorKind::InvalidData, __!("no container at offset {}", offset))),
+    }
+}
+
+/// # Checks type of a container
+fn expect_type(header: &Header, type_byte: u8) -> IoResult<()> {
+    match header.type_byte() == type_byte {
+        true => Ok(()),
+        false => Err(io::Error::new(ErrorKind::InvalidData, __!("expected type 0x{:02x}, got: 0x{:02x}", type_byte, header.type_byte()))),
+    }
+}
+
+/// # Skips key of an item
+fn skip_key<R>(source: &mut R, type_byte: u8) -> IoResult<()> where R: Read + Seek {
+    let len = match type_byte {
+        crate::value::MAP => core::mem::size_of::<MapKey>() as i64,
+        crate::value::OBJECT => {
+            let mut len = [0];
+            source.read_exact(&mut len)?;
+            i64::from(len[0])
+        },
+        _ => return Ok(()),
+    };
+    source.seek(SeekFrom::Current(len)).map(|_| ())
+}
+
+/// # Skips a value, via its declared size
+fn skip_value<R>(source: &mut R) -> IoResult<()> where R: Read + Seek {
+    match crate::decode_header(source)? {
+        Some(header) => {
+            let len = header.value_size() - u64::from(header.size());
+            source.seek(SeekFrom::Current(len as i64)).map(|_| ())
+        },
+        None => Err(io::Error::new(ErrorKind::UnexpectedEof, __!("missing value"))),
+    }
+}
@@ -0,0 +1,438 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Record log
+
+use {
+    alloc::vec::Vec,
+    std::{
+        fs::{File, OpenOptions},
+        io::{self, BufWriter, ErrorKind, Read, Write},
+        path::Path,
+    },
+
+    crate::{
+        FRAME_HEADER_SIZE, IoResult, Size, Value,
+        framing::{decode_frame_payload, parse_frame_header},
+        value::MAX_DATA_SIZE,
+    },
+};
+
+/// # Size of each read from source, in bytes
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// # Max size of a value's header, in bytes
+///
+/// That is: 1 byte for type, 4 bytes for size, 4 bytes for item count. Records written by [`write_framed()`][fn:write_framed] never have
+/// 2-byte types.
+///
+/// [fn:write_framed]: fn.write_framed.html
+const MAX_VALUE_HEADER_SIZE: usize = 9;
+
+/// # A reader of a log of framed records
+///
+/// A log is a sequence of frames, as written by [`write_framed()`][fn:write_framed]. Each record is returned with its offset -- the
+/// position of its frame, counting from where the source was when this reader was made.
+///
+/// ## Recovery
+///
+/// By default, a corrupt record is an error. When made via [`with_recovery()`][#with_recovery()], this reader skips corrupt data instead:
+/// it scans forward, byte by byte, until it finds a frame whose length and checksum are valid, and whose payload holds exactly one value.
+/// A truncated record at the end of the log -- such as one written partly before a crash -- is skipped too. Total bytes which have been
+/// skipped are available via [`skipped_bytes()`][#skipped_bytes()].
+///
+/// Before a frame's checksum is verified, its payload is checked cheaply: it must start with a known type, and the value's own size must be
+/// the frame's length. So most corrupt offsets are rejected right away, without reading their payloads. Data of a frame which passes this
+/// check is kept in memory while it's being verified: up to [`max_record_size()`][#max_record_size()] bytes.
+///
+/// This is also an iterator. Once an error is returned, the iterator stops.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{LogReader, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut log = vec![];
+/// binn_ir::write_framed(&mut log, &Value::from("first"))?;
+/// let second = log.len();
+/// binn_ir::write_framed(&mut log, &Value::from("second"))?;
+/// let third = log.len();
+/// binn_ir::write_framed(&mut log, &Value::from("third"))?;
+///
+/// // Corrupt second record
+/// log[second + 10] ^= 0xff;
+///
+/// let mut reader = LogReader::new(&log[..]);
+/// assert_eq!(reader.next_record()?, Some((0, Value::from("first"))));
+/// assert!(reader.next_record().is_err());
+///
+/// let mut reader = LogReader::with_recovery(&log[..]);
+/// let records = reader.by_ref().map(|record| record.map(|(_, value)| value)).collect::<binn_ir::IoResult<Vec<_>>>()?;
+/// assert_eq!(records, vec![Value::from("first"), Value::from("third")]);
+/// assert_eq!(reader.skipped_bytes() as usize, third - second);
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [fn:write_framed]: fn.write_framed.html
+/// [#with_recovery()]: #method.with_recovery
+/// [#skipped_bytes()]: #method.skipped_bytes
+/// [#max_record_size()]: #method.max_record_size
+#[derive(Debug)]
+pub struct LogReader<R> {
+    source: R,
+    recovery: bool,
+    max_record_size: Size,
+    buf: Vec<u8>,
+    start: usize,
+    offset: u64,
+    skipped_bytes: u64,
+    eof: bool,
+    failed: bool,
+}
+
+impl<R> LogReader<R> {
+
+    /// # Makes new instance, which returns an error on corrupt records
+    pub const fn new(source: R) -> Self {
+        Self {
+            source,
+            recovery: false,
+            max_record_size: MAX_DATA_SIZE,
+            buf: Vec::new(),
+            start: 0,
+            offset: 0,
+            skipped_bytes: 0,
+            eof: false,
+            failed: false,
+        }
+    }
+
+    /// # Makes new instance, which skips corrupt records
+    pub const fn with_recovery(source: R) -> Self {
+        let mut result = Self::new(source);
+        result.recovery = true;
+        result
+    }
+
+    /// # Max size of a record, in bytes
+    ///
+    /// Default is [`MAX_DATA_SIZE`][value::MAX_DATA_SIZE]. A frame with a larger length is corrupt: an error, or -- with recovery -- skipped.
+    ///
+    /// [value::MAX_DATA_SIZE]: value/constant.MAX_DATA_SIZE.html
+    pub const fn max_record_size(&self) -> Size {
+        self.max_record_size
+    }
+
+    /// # Sets max size of a record, in bytes
+    ///
+    /// If records are known to be small, a small limit makes recovery cheaper: frames with corrupt lengths are never read into memory.
+    pub fn set_max_record_size(&mut self, max_record_size: Size) {
+        self.max_record_size = max_record_size;
+    }
+
+    /// # Offset of next record
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// # Total bytes which have been skipped, while recovering from corrupt data
+    pub const fn skipped_bytes(&self) -> u64 {
+        self.skipped_bytes
+    }
+
+    /// # Gets a reference to the source
+    ///
+    /// Note that this reader buffers data, so source's position can be ahead of [`offset()`][#offset()].
+    ///
+    /// [#offset()]: #method.offset
+    pub const fn get_ref(&self) -> &R {
+        &self.source
+    }
+
+    /// # Unwraps the source
+    ///
+    /// Buffered data which has not been read is lost.
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+
+    /// # Length of buffered data which has not been read
+    fn available(&self) -> usize {
+        self.buf.len() - self.start
+    }
+
+    /// # Consumes some buffered data
+    fn consume(&mut self, len: usize) {
+        self.start += len;
+        self.offset += len as u64;
+    }
+
+}
+
+impl<R> LogReader<R> where R: Read {
+
+    /// # Reads next record
+    ///
+    /// Result: offset of the record, and its value. If it returns `Ok(None)`, it means there are no more records.
+    pub fn next_record(&mut self) -> IoResult<Option<(u64, Value)>> {
+        loop {
+            let err = match self.fill(FRAME_HEADER_SIZE)? {
+                true => match self.read_frame()? {
+                    Ok(value) => return Ok(Some(value)),
+                    Err(err) => err,
+                },
+                false => match self.available() {
+                    0 => return Ok(None),
+                    available => io::Error::new(ErrorKind::UnexpectedEof, __!("truncated frame header: {} byte(s)", available)),
+                },
+            };
+
+            if !self.recovery {
+                return Err(err);
+            }
+            self.consume(1);
+            self.skipped_bytes += 1;
+        }
+    }
+
+    /// # Reads a frame at current position
+    ///
+    /// The header must have been buffered. Errors of source are returned as they are. Errors of data are returned inside `Ok`.
+    fn read_frame(&mut self) -> IoResult<Result<(u64, Value), io::Error>> {
+        let mut header = [0; FRAME_HEADER_SIZE];
+        header.copy_from_slice(&self.buf[self.start..self.start + FRAME_HEADER_SIZE]);
+        let (size, checksum) = match parse_frame_header(&header) {
+            Ok((size, _)) if size > self.max_record_size => return Ok(Err(io::Error::new(
+                ErrorKind::InvalidData, __!("frame length is larger than max record size ({}): {}", self.max_record_size, size),
+            ))),
+            Ok((size, checksum)) => (size as usize, checksum),
+            Err(err) => return Ok(Err(err)),
+        };
+
+        // Check the payload cheaply, before reading all of it
+        if !self.fill(FRAME_HEADER_SIZE + size.min(MAX_VALUE_HEADER_SIZE))? {
+            return Ok(Err(io::Error::new(ErrorKind::UnexpectedEof, __!("truncated frame: {} of {} byte(s)", self.available(), size))));
+        }
+        let prefix = &self.buf[self.start + FRAME_HEADER_SIZE..];
+        if let Err(err) = check_payload_header(&prefix[..prefix.len().min(size)], size) {
+            return Ok(Err(err));
+        }
+
+        if !self.fill(FRAME_HEADER_SIZE + size)? {
+            return Ok(Err(io::Error::new(ErrorKind::UnexpectedEof, __!("truncated frame: {} of {} byte(s)", self.available(), size))));
+        }
+        let payload = &self.buf[self.start + FRAME_HEADER_SIZE..self.start + FRAME_HEADER_SIZE + size];
+        let value = match decode_frame_payload(payload, checksum) {
+            Ok(value) => value,
+            Err(err) => return Ok(Err(err)),
+        };
+
+        let offset = self.offset;
+        self.consume(FRAME_HEADER_SIZE + size);
+        Ok(Ok((offset, value)))
+    }
+
+    /// # Buffers data, until there are at least `len` bytes which have not been read
+    ///
+    /// Returns `false` if source ends before that.
+    fn fill(&mut self, len: usize) -> IoResult<bool> {
+        while self.available() < len {
+            if self.eof {
+                return Ok(false);
+            }
+            if self.start > 0 {
+                self.buf.drain(..self.start);
+                self.start = 0;
+            }
+
+            let old_len = self.buf.len();
+            self.buf.resize(old_len + READ_CHUNK_SIZE, 0);
+            let read = match self.source.read(&mut self.buf[old_len..]) {
+                Ok(read) => read,
+                Err(err) => {
+                    self.buf.truncate(old_len);
+                    match err.kind() {
+                        ErrorKind::Interrupted => continue,
+                        _ => return Err(err),
+                    };
+                },
+            };
+            self.buf.truncate(old_len + read);
+            self.eof = read == 0;
+        }
+        Ok(true)
+    }
+
+}
+
+/// # Checks that a payload starts with a known type, and that the value's own size is the payload's length
+///
+/// `payload` can be a prefix of the payload, which holds at least the value's header.
+fn check_payload_header(payload: &[u8], size: usize) -> IoResult<()> {
+    let header = match crate::decode_header(&mut &payload[..])? {
+        Some(header) => header,
+        None => return Err(io::Error::new(ErrorKind::InvalidData, __!("empty frame"))),
+    };
+    match header.type_byte() {
+        crate::value::NULL | crate::value::TRUE | crate::value::FALSE | crate::value::U8 | crate::value::I8 | crate::value::U16 |
+        crate::value::I16 | crate::value::U32 | crate::value::I32 | crate::value::FLOAT | crate::value::U64 | crate::value::I64 |
+        crate::value::DOUBLE | crate::value::TEXT | crate::value::DATE_TIME | crate::value::DATE | crate::value::TIME |
+        crate::value::DECIMAL_STR | crate::value::BLOB | crate::value::LIST | crate::value::MAP | crate::value::OBJECT => (),
+        other => return Err(io::Error::new(ErrorKind::InvalidData, __!("unknown type of payload: {}", other))),
+    };
+    match header.value_size() == size as u64 {
+        true => Ok(()),
+        false => Err(io::Error::new(ErrorKind::InvalidData, __!("value size mismatches frame length: {} vs {}", header.value_size(), size))),
+    }
+}
+
+impl<R> Iterator for LogReader<R> where R: Read {
+
+    type Item = IoResult<(u64, Value)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let result = self.next_record().transpose();
+        self.failed = matches!(result, Some(Err(_)));
+        result
+    }
+
+}
+
+impl<R> core::iter::FusedIterator for LogReader<R> where R: Read {}
+
+/// # Policy of flushing records of a [`LogWriter`][struct:LogWriter]
+///
+/// [struct:LogWriter]: struct.LogWriter.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FlushPolicy {
+
+    /// # Records are buffered
+    ///
+    /// They are written to the file when the buffer is full, or when [`flush()`][LogWriter#flush()] or [`sync()`][LogWriter#sync()] is
+    /// called, or when the writer is dropped.
+    ///
+    /// [LogWriter#flush()]: struct.LogWriter.html#method.flush
+    /// [LogWriter#sync()]: struct.LogWriter.html#method.sync
+    Buffered,
+
+    /// # Each record is written to the file right away
+    ///
+    /// Records survive crashes of the process, but not of the operating system.
+    Flush,
+
+    /// # Each record is written to the file, then synchronized to disk via [`File::sync_data()`][std::fs/File#sync_data()]
+    ///
+    /// This is the most durable, and the slowest, policy.
+    ///
+    /// [std::fs/File#sync_data()]: https://doc.rust-lang.org/std/fs/struct.File.html#method.sync_data
+    Sync,
+
+}
+
+/// # A writer of a log of framed records
+///
+/// Records are appended to a file, via [`write_framed()`][fn:write_framed]. They can be read back via [`LogReader`][struct:LogReader]:
+/// offsets returned by [`append()`][#append()] are the same as ones returned by the reader, when it reads the file from the start.
+///
+/// If writing fails -- or the process crashes -- in the middle of a record, the file ends with a truncated record. A
+/// [recovering][LogReader#with_recovery()] reader skips it.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{FlushPolicy, LogReader, LogWriter, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let path = std::env::temp_dir().join(format!("binn-ir-log-writer-doc-{}", std::process::id()));
+///
+/// let mut writer = LogWriter::open(&path, FlushPolicy::Flush)?;
+/// let first = writer.append(&Value::from("first"))?;
+/// let second = writer.append(&Value::from("second"))?;
+/// drop(writer);
+///
+/// let mut reader = LogReader::new(std::fs::File::open(&path)?);
+/// assert_eq!(reader.next_record()?, Some((first, Value::from("first"))));
+/// assert_eq!(reader.next_record()?, Some((second, Value::from("second"))));
+/// assert_eq!(reader.next_record()?, None);
+/// # std::fs::remove_file(&path)?;
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [fn:write_framed]: fn.write_framed.html
+/// [struct:LogReader]: struct.LogReader.html
+/// [LogReader#with_recovery()]: struct.LogReader.html#method.with_recovery
+/// [#append()]: #method.append
+#[derive(Debug)]
+pub struct LogWriter {
+    file: BufWriter<File>,
+    policy: FlushPolicy,
+    offset: u64,
+}
+
+impl LogWriter {
+
+    /// # Opens a log file for appending, creating it if it does not exist
+    pub fn open<P>(path: P, policy: FlushPolicy) -> IoResult<Self> where P: AsRef<Path> {
+        Self::new(OpenOptions::new().append(true).create(true).open(path)?, policy)
+    }
+
+    /// # Makes new instance, from a file which has been opened for appending
+    ///
+    /// Offsets of new records start at current length of the file.
+    pub fn new(file: File, policy: FlushPolicy) -> IoResult<Self> {
+        Ok(Self {
+            offset: file.metadata()?.len(),
+            file: BufWriter::new(file),
+            policy,
+        })
+    }
+
+    /// # Appends a record
+    ///
+    /// Result: offset of the record.
+    pub fn append(&mut self, value: &Value) -> IoResult<u64> {
+        let offset = self.offset;
+        let size = crate::write_framed(&mut self.file, value)?;
+        self.offset += u64::from(size);
+        match self.policy {
+            FlushPolicy::Buffered => (),
+            FlushPolicy::Flush => self.flush()?,
+            FlushPolicy::Sync => self.sync()?,
+        };
+        Ok(offset)
+    }
+
+    /// # Offset of next record, which is also length of the log
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// # Flush policy
+    pub const fn policy(&self) -> FlushPolicy {
+        self.policy
+    }
+
+    /// # Writes buffered records to the file
+    pub fn flush(&mut self) -> IoResult<()> {
+        self.file.flush()
+    }
+
+    /// # Writes buffered records to the file, then synchronizes it to disk
+    pub fn sync(&mut self) -> IoResult<()> {
+        self.file.flush()?;
+        self.file.get_ref().sync_data()
+    }
+
+    /// # Writes buffered records to the file, then unwraps it
+    pub fn into_inner(self) -> IoResult<File> {
+        self.file.into_inner().map_err(io::IntoInnerError::into_error)
+    }
+
+}
@@ -0,0 +1,402 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Binn-RPC
+//!
+//! This module defines a small convention for remote procedure calls. Both requests and responses are [objects][Value::Object]:
+//!
+//! | Message | Key | Value |
+//! |---|---|---|
+//! | Request | `"id"` | Request ID, an unsigned integer. |
+//! | | `"method"` | Method name, a [`Text`][Value::Text]. |
+//! | | `"params"` | Parameters, any value. |
+//! | Response | `"id"` | ID of the request. |
+//! | | `"result"` | Result, any value. Only for successful calls. |
+//! | | `"error"` | A [`Fault`][struct:Fault], as an object: `"code"` -- an [`I32`][Value::I32], `"message"` -- a [`Text`][Value::Text], and an
+//! | | | optional `"data"` -- any value. Only for failed calls. |
+//!
+//! A response holds either `"result"` or `"error"`, never both. Requests may be sent in any order, so responses are matched to requests
+//! by their IDs. Integers of any width are accepted for IDs and codes; other unknown keys are ignored.
+//!
+//! ## Examples
+//!
+//! ```
+//! use binn_ir::{Value, rpc::{self, Request, Response}};
+//!
+//! # #[cfg(feature="std")]
+//! # fn test() -> binn_ir::IoResult<()> {
+//! let mut socket = vec![];
+//! rpc::encode_request(&mut socket, &Request::new(1, "add", vec![Value::U8(1), Value::U8(2)]))?;
+//!
+//! let request = rpc::decode_request(&mut &socket[..])?.unwrap();
+//! assert_eq!(request.method, "add");
+//!
+//! let mut socket = vec![];
+//! rpc::encode_response(&mut socket, &Response::ok(request.id, 3_u8))?;
+//! assert_eq!(rpc::decode_response(&mut &socket[..])?.unwrap().result, Ok(Value::U8(3)));
+//! # Ok(()) }
+//! # #[cfg(feature="std")]
+//! # test().unwrap();
+//! ```
+//!
+//! [Value::Object]: ../enum.Value.html#variant.Object
+//! [Value::Text]: ../enum.Value.html#variant.Text
+//! [Value::I32]: ../enum.Value.html#variant.I32
+//! [struct:Fault]: struct.Fault.html
+
+use {
+    alloc::string::String,
+    core::convert::TryFrom,
+
+    crate::{Error, Object, Value},
+};
+
+#[cfg(feature="std")]
+use {
+    alloc::{vec, vec::Vec},
+    std::io::{self, ErrorKind, Read, Write},
+
+    crate::{
+        IoResult, Size,
+        value_enum::{encode_value_str, size_of_container, size_of_data, size_of_object_key, write_container_header},
+    },
+};
+
+/// # Key of request/response IDs
+const ID: &str = "id";
+
+/// # Key of method names
+const METHOD: &str = "method";
+
+/// # Key of parameters
+const PARAMS: &str = "params";
+
+/// # Key of results
+const RESULT: &str = "result";
+
+/// # Key of faults
+const ERROR: &str = "error";
+
+/// # Key of fault codes
+const CODE: &str = "code";
+
+/// # Key of fault messages
+const MESSAGE: &str = "message";
+
+/// # Key of fault data
+const DATA: &str = "data";
+
+/// # A request
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+
+    /// # Request ID
+    pub id: u64,
+
+    /// # Method name
+    pub method: String,
+
+    /// # Parameters
+    pub params: Value,
+
+}
+
+impl Request {
+
+    /// # Makes new instance
+    pub fn new<S, V>(id: u64, method: S, params: V) -> Self where S: Into<String>, V: Into<Value> {
+        Self {
+            id,
+            method: method.into(),
+            params: params.into(),
+        }
+    }
+
+}
+
+impl From<Request> for Value {
+
+    fn from(request: Request) -> Self {
+        let mut result = Object::new();
+        crate::object_insert(&mut result, ID, request.id);
+        crate::object_insert(&mut result, METHOD, request.method);
+        crate::object_insert(&mut result, PARAMS, request.params);
+        Value::Object(result)
+    }
+
+}
+
+impl TryFrom<Value> for Request {
+
+    type Error = Error;
+
+    fn try_from(value: Value) -> core::result::Result<Self, Self::Error> {
+        let mut object = into_object(value)?;
+        Ok(Self {
+            id: take_id(&mut object)?,
+            method: match object.remove(METHOD) {
+                Some(Value::Text(method)) => method,
+                Some(_) => return Err(err!("method is not a text")),
+                None => return Err(err!("missing method")),
+            },
+            params: object.remove(PARAMS).ok_or_else(|| err!("missing params"))?,
+        })
+    }
+
+}
+
+/// # A fault of a failed call
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fault {
+
+    /// # Code
+    pub code: i32,
+
+    /// # Message
+    pub message: String,
+
+    /// # Optional data
+    pub data: Option<Value>,
+
+}
+
+impl Fault {
+
+    /// # Makes new instance, without data
+    pub fn new<S>(code: i32, message: S) -> Self where S: Into<String> {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+}
+
+impl From<Fault> for Value {
+
+    fn from(fault: Fault) -> Self {
+        let mut result = Object::new();
+        crate::object_insert(&mut result, CODE, fault.code);
+        crate::object_insert(&mut result, MESSAGE, fault.message);
+        if let Some(data) = fault.data {
+            crate::object_insert(&mut result, DATA, data);
+        }
+        Value::Object(result)
+    }
+
+}
+
+impl TryFrom<Value> for Fault {
+
+    type Error = Error;
+
+    fn try_from(value: Value) -> core::result::Result<Self, Self::Error> {
+        let mut object = into_object(value)?;
+        Ok(Self {
+            code: i32::try_from(object.remove(CODE).ok_or_else(|| err!("missing code"))?)?,
+            message: match object.remove(MESSAGE) {
+                Some(Value::Text(message)) => message,
+                Some(_) => return Err(err!("message is not a text")),
+                None => return Err(err!("missing message")),
+            },
+            data: object.remove(DATA),
+        })
+    }
+
+}
+
+/// # A response
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+
+    /// # ID of the request
+    pub id: u64,
+
+    /// # Result, or a fault
+    pub result: core::result::Result<Value, Fault>,
+
+}
+
+impl Response {
+
+    /// # Makes new instance of a successful call
+    pub fn ok<V>(id: u64, result: V) -> Self where V: Into<Value> {
+        Self {
+            id,
+            result: Ok(result.into()),
+        }
+    }
+
+    /// # Makes new instance of a failed call
+    pub fn fault(id: u64, fault: Fault) -> Self {
+        Self {
+            id,
+            result: Err(fault),
+        }
+    }
+
+}
+
+impl From<Response> for Value {
+
+    fn from(response: Response) -> Self {
+        let mut result = Object::new();
+        crate::object_insert(&mut result, ID, response.id);
+        match response.result {
+            Ok(value) => crate::object_insert(&mut result, RESULT, value),
+            Err(fault) => crate::object_insert(&mut result, ERROR, fault),
+        };
+        Value::Object(result)
+    }
+
+}
+
+impl TryFrom<Value> for Response {
+
+    type Error = Error;
+
+    fn try_from(value: Value) -> core::result::Result<Self, Self::Error> {
+        let mut object = into_object(value)?;
+        let id = take_id(&mut object)?;
+        let result = match (object.remove(RESULT), object.remove(ERROR)) {
+            (Some(result), None) => Ok(result),
+            (None, Some(fault)) => Err(Fault::try_from(fault)?),
+            (Some(_), Some(_)) => return Err(err!("response has both result and error")),
+            (None, None) => return Err(err!("response has neither result nor error")),
+        };
+        Ok(Self { id, result })
+    }
+
+}
+
+/// # Converts a value into an object
+fn into_object(value: Value) -> crate::Result<Object> {
+    match value {
+        Value::Object(object) => Ok(object),
+        _ => Err(err!("value is not an object")),
+    }
+}
+
+/// # Takes ID from an object
+fn take_id(object: &mut Object) -> crate::Result<u64> {
+    u64::try_from(object.remove(ID).ok_or_else(|| err!("missing id"))?)
+}
+
+/// # Encodes a request
+///
+/// Result: total bytes that have been written.
+#[cfg(feature="std")]
+pub fn encode_request<W>(stream: &mut W, request: &Request) -> IoResult<Size> where W: Write {
+    let id = Value::U64(request.id);
+    Item::Object(vec![(ID, Item::Value(&id)), (METHOD, Item::Text(&request.method)), (PARAMS, Item::Value(&request.params))]).encode(stream)
+}
+
+/// # Decodes a request
+///
+/// If it returns `Ok(None)`, it means there's no more data to decode.
+///
+/// ## Errors
+///
+/// If the decoded value does not follow the [convention][self], an error of [`InvalidData`][io::ErrorKind#InvalidData] is returned.
+///
+/// [self]: index.html
+/// [io::ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+#[cfg(feature="std")]
+pub fn decode_request<R>(source: &mut R) -> IoResult<Option<Request>> where R: Read {
+    decode(source)
+}
+
+/// # Encodes a response
+///
+/// Result: total bytes that have been written.
+#[cfg(feature="std")]
+pub fn encode_response<W>(stream: &mut W, response: &Response) -> IoResult<Size> where W: Write {
+    let id = Value::U64(response.id);
+    let code;
+    // Keys are in canonical order
+    let items = match &response.result {
+        Ok(result) => vec![(ID, Item::Value(&id)), (RESULT, Item::Value(result))],
+        Err(fault) => {
+            code = Value::I32(fault.code);
+            let mut fault_items = vec![(CODE, Item::Value(&code))];
+            if let Some(data) = fault.data.as_ref() {
+                fault_items.push((DATA, Item::Value(data)));
+            }
+            fault_items.push((MESSAGE, Item::Text(&fault.message)));
+            vec![(ERROR, Item::Object(fault_items)), (ID, Item::Value(&id))]
+        },
+    };
+    Item::Object(items).encode(stream)
+}
+
+/// # Decodes a response
+///
+/// See [`decode_request()`][fn:decode_request] for details.
+///
+/// [fn:decode_request]: fn.decode_request.html
+#[cfg(feature="std")]
+pub fn decode_response<R>(source: &mut R) -> IoResult<Option<Response>> where R: Read {
+    decode(source)
+}
+
+/// # Decodes a message
+#[cfg(feature="std")]
+fn decode<R, T>(source: &mut R) -> IoResult<Option<T>> where R: Read, T: TryFrom<Value, Error=Error> {
+    match crate::decode(source)? {
+        Some(value) => T::try_from(value).map(Some).map_err(|err| io::Error::new(ErrorKind::InvalidData, err)),
+        None => Ok(None),
+    }
+}
+
+/// # An item of a message, which borrows its data
+///
+/// Messages are encoded from borrowed parts, so their payloads are never copied.
+#[cfg(feature="std")]
+enum Item<'a> {
+    Text(&'a str),
+    Value(&'a Value),
+    /// Keys must be in canonical order
+    Object(Vec<(&'static str, Item<'a>)>),
+}
+
+#[cfg(feature="std")]
+impl Item<'_> {
+
+    /// # Calculates size
+    fn size(&self) -> crate::Result<Size> {
+        match self {
+            // 1 byte for type, 1 byte for null terminator
+            Item::Text(s) => size_of_data(s.len(), 2),
+            Item::Value(value) => value.size(),
+            Item::Object(items) => {
+                let mut size = 0_u64;
+                for (key, item) in items {
+                    size += u64::from(size_of_object_key(key)?) + u64::from(item.size()?);
+                }
+                let size = Size::try_from(size).map_err(|_| err_code!("data too large (bytes)", size))?;
+                size_of_container(items.len(), size)
+            },
+        }
+    }
+
+    /// # Encodes into a stream
+    ///
+    /// Result: total bytes that have been written.
+    fn encode<W>(&self, stream: &mut W) -> IoResult<Size> where W: Write {
+        match self {
+            Item::Text(s) => encode_value_str(crate::value::TEXT, s, stream),
+            Item::Value(value) => value.encode(stream),
+            Item::Object(items) => {
+                let size = self.size()?;
+                write_container_header(crate::value::OBJECT, size, items.len() as Size, stream)?;
+                for (key, item) in items {
+                    stream.write_all(&[key.len() as u8])?;
+                    stream.write_all(key.as_bytes())?;
+                    item.encode(stream)?;
+                }
+                Ok(size)
+            },
+        }
+    }
+
+}
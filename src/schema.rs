@@ -0,0 +1,167 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Schema
+
+use {
+    alloc::{
+        boxed::Box,
+        collections::BTreeMap,
+    },
+
+    crate::{ObjectKey, Path, PathSegment, Result, Size, Value},
+};
+
+/// # Schema of a value
+///
+/// A schema describes shapes of containers. It is used to normalize incoming documents -- such as configurations, or API requests:
+///
+/// - [`apply_defaults()`][#apply_defaults()] fills missing fields of objects with their default values.
+/// - [`prune_unknown()`][#prune_unknown()] removes fields which are not described.
+///
+/// Types of non-container values are not checked.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Field, Schema, Value};
+///
+/// let mut fields = binn_ir::SchemaFields::new();
+/// fields.insert("name".into(), Field::new(Schema::Any));
+/// fields.insert("port".into(), Field::with_default(Schema::Any, 8080_u16));
+/// let schema = Schema::Object(fields);
+///
+/// let mut value = binn_ir::object_from("name", "moss");
+/// value.object_insert("debug", true)?;
+/// schema.apply_defaults(&mut value)?;
+/// schema.prune_unknown(&mut value)?;
+///
+/// let mut expected = binn_ir::object_from("name", "moss");
+/// expected.object_insert("port", 8080_u16)?;
+/// assert_eq!(value, expected);
+/// # Ok::<_, binn_ir::Error>(())
+/// ```
+///
+/// [#apply_defaults()]: #method.apply_defaults
+/// [#prune_unknown()]: #method.prune_unknown
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schema {
+
+    /// # Any value
+    Any,
+
+    /// # A list, whose items follow a schema
+    List(Box<Schema>),
+
+    /// # A map, whose values follow a schema
+    Map(Box<Schema>),
+
+    /// # An object, with known fields
+    Object(SchemaFields),
+
+}
+
+/// # Fields of an object [`Schema`][enum:Schema]
+///
+/// [enum:Schema]: enum.Schema.html
+pub type SchemaFields = BTreeMap<ObjectKey, Field>;
+
+/// # A field of an object [`Schema`][enum:Schema]
+///
+/// [enum:Schema]: enum.Schema.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+
+    /// # Schema of the field
+    pub schema: Schema,
+
+    /// # Default value of the field
+    ///
+    /// If this is `None`, the field is optional, and is left missing.
+    pub default: Option<Value>,
+
+}
+
+impl Field {
+
+    /// # Makes new field, without a default value
+    pub fn new(schema: Schema) -> Self {
+        Self {
+            schema,
+            default: None,
+        }
+    }
+
+    /// # Makes new field, with a default value
+    pub fn with_default<V>(schema: Schema, default: V) -> Self where V: Into<Value> {
+        Self {
+            schema,
+            default: Some(default.into()),
+        }
+    }
+
+}
+
+impl Schema {
+
+    /// # Fills missing fields of objects with their default values
+    ///
+    /// This works recursively: defaults are also filled inside inserted default values, and inside existing fields.
+    ///
+    /// ## Errors
+    ///
+    /// An error is returned if a container has another type than what the schema describes. Its message contains the path to the
+    /// offending value. In which case, the value may have been partly filled.
+    pub fn apply_defaults(&self, value: &mut Value) -> Result<()> {
+        self.walk(value, &mut Path::new(), &|fields, object| {
+            for (key, field) in fields {
+                if let Some(default) = field.default.as_ref() {
+                    if !object.contains_key(key.as_str()) {
+                        object.insert(key.clone(), default.clone());
+                    }
+                }
+            }
+        })
+    }
+
+    /// # Removes fields of objects which are not described by the schema
+    ///
+    /// This works recursively. See [`apply_defaults()`][#apply_defaults()] for errors.
+    ///
+    /// [#apply_defaults()]: #method.apply_defaults
+    pub fn prune_unknown(&self, value: &mut Value) -> Result<()> {
+        self.walk(value, &mut Path::new(), &|fields, object| object.retain(|key, _| fields.contains_key(key.as_str())))
+    }
+
+    /// # Walks a value, and calls `f` on every object, before walking its fields
+    fn walk<F>(&self, value: &mut Value, path: &mut Path, f: &F) -> Result<()> where F: Fn(&SchemaFields, &mut crate::Object) {
+        match (self, value) {
+            (Schema::Any, _) => Ok(()),
+            (Schema::List(schema), Value::List(list)) => list.iter_mut().enumerate().try_for_each(|(index, item)|
+                schema.walk_item(item, PathSegment::Index(index as Size), path, f)
+            ),
+            (Schema::Map(schema), Value::Map(map)) => map.iter_mut().try_for_each(|(key, item)|
+                schema.walk_item(item, PathSegment::MapKey(*key), path, f)
+            ),
+            (Schema::Object(fields), Value::Object(object)) => {
+                f(fields, object);
+                object.iter_mut().try_for_each(|(key, item)| match fields.get(key.as_str()) {
+                    Some(field) => field.schema.walk_item(item, PathSegment::ObjectKey(key.clone()), path, f),
+                    None => Ok(()),
+                })
+            },
+            (Schema::List(_), _) => Err(err!("Value at {} is not a list", path)),
+            (Schema::Map(_), _) => Err(err!("Value at {} is not a map", path)),
+            (Schema::Object(_), _) => Err(err!("Value at {} is not an object", path)),
+        }
+    }
+
+    /// # Walks an item of a container
+    fn walk_item<F>(&self, item: &mut Value, segment: PathSegment, path: &mut Path, f: &F) -> Result<()>
+    where F: Fn(&SchemaFields, &mut crate::Object) {
+        path.push(segment);
+        let result = self.walk(item, path, f);
+        path.pop();
+        result
+    }
+
+}
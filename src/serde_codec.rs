@@ -0,0 +1,207 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Serde support
+
+mod de;
+mod ser;
+
+use {
+    alloc::string::String,
+    core::fmt::{self, Display, Formatter},
+    std::io::{self, ErrorKind, Read, Write},
+
+    serde::{Serialize, de::DeserializeOwned},
+
+    crate::{IoResult, Size},
+};
+
+/// # Encodes a value of any type which implements [`Serialize`][serde::Serialize]
+///
+/// Data is mapped straight to the wire format, without making any [`Value`][enum:Value]:
+///
+/// | Rust | Binn |
+/// |---|---|
+/// | `bool`, integers, `f32`, `f64` | Their own types |
+/// | `char`, strings | [`Text`][Value::Text] |
+/// | Bytes (via `serialize_bytes()`) | [`Blob`][Value::Blob] |
+/// | `None`, `()`, unit structs | [`Null`][Value::Null] |
+/// | Sequences, tuples, tuple structs | [`List`][Value::List] |
+/// | Structs, maps with string keys | [`Object`][Value::Object] |
+/// | Maps with integer keys | [`Map`][Value::Map] |
+/// | Unit variants | [`Text`][Value::Text] of variant name |
+/// | Other variants | [`Object`][Value::Object] of 1 item: variant name and its data |
+///
+/// `Some` and newtype structs are encoded as their inner values. Integer keys of maps must fit in [`MapKey`][type:MapKey]. A map can't have
+/// both string and integer keys.
+///
+/// Headers of containers need sizes of their items, so the value is serialized twice: first to calculate the sizes, then to write everything
+/// straight into the stream. Nothing is buffered. The [`Serialize`][serde::Serialize] implementation must give the same data both times;
+/// otherwise an error of [`InvalidData`][std::io/ErrorKind#InvalidData] is returned, and the stream is left with some data written.
+///
+/// Result: total bytes that have been written.
+///
+/// ## Examples
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Song {
+///     title: String,
+///     year: u16,
+///     tags: Vec<String>,
+/// }
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let song = Song { title: "Yesterday".into(), year: 1965, tags: vec!["pop".into()] };
+/// let mut buf = vec![];
+/// binn_ir::to_writer(&mut buf, &song)?;
+///
+/// let value = binn_ir::decode(&mut &buf[..])?.unwrap();
+/// assert_eq!(value["title"].as_text()?, "Yesterday");
+/// assert_eq!(binn_ir::from_reader::<Song>(&mut &buf[..])?, song);
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [serde::Serialize]: https://docs.rs/serde/1/serde/trait.Serialize.html
+/// [enum:Value]: enum.Value.html
+/// [Value::Null]: enum.Value.html#variant.Null
+/// [Value::Text]: enum.Value.html#variant.Text
+/// [Value::Blob]: enum.Value.html#variant.Blob
+/// [Value::List]: enum.Value.html#variant.List
+/// [Value::Map]: enum.Value.html#variant.Map
+/// [Value::Object]: enum.Value.html#variant.Object
+/// [type:MapKey]: type.MapKey.html
+/// [std::io/ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+pub fn to_writer<W, T>(stream: &mut W, value: &T) -> IoResult<Size> where W: Write, T: ?Sized + Serialize {
+    let mut output = ser::Output::new();
+    value.serialize(ser::Serializer::new(&mut output))?;
+    output.start_writing(stream);
+    value.serialize(ser::Serializer::new(&mut output))?;
+    // Containers' sizes have been verified, and other values are small
+    Ok(output.written() as Size)
+}
+
+/// # Decodes a value of any type which implements [`DeserializeOwned`][serde::de::DeserializeOwned]
+///
+/// Data is mapped straight from the wire format, without making any [`Value`][enum:Value]. See [`to_writer()`][fn:to_writer] for how types
+/// are mapped. Besides those:
+///
+/// - Integers and floating point numbers can be decoded into any numeric types, as long as their values fit.
+/// - [`DateTime`][Value::DateTime], [`Date`][Value::Date], [`Time`][Value::Time] and [`DecimalStr`][Value::DecimalStr] are decoded as
+///   strings.
+/// - A [`Blob`][Value::Blob] can also be decoded into a sequence of bytes, such as `Vec<u8>`.
+///
+/// Exactly one value is read from source. Containers must be read fully: for example, a list of 3 items can't be decoded into a tuple of 2.
+///
+/// ## Partial decoding
+///
+/// Fields of objects which the target type does not know -- and other values deserialized as [`IgnoredAny`][serde::de::IgnoredAny] -- are
+/// skipped via their headers, like [`skip_value()`][fn:skip_value]. They are neither decoded nor verified. So extracting a few fields out of
+/// large objects costs about as much as decoding those fields only:
+///
+/// ```
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Summary {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut value = binn_ir::object_from("id", 1_u32);
+/// value.object_insert("name", "moon")?;
+/// value.object_insert("data", vec![binn_ir::object_from("pixels", vec![0_u8; 1024 * 1024])])?;
+///
+/// let mut buf = vec![];
+/// value.encode(&mut buf)?;
+/// assert_eq!(binn_ir::from_reader::<Summary>(&mut &buf[..])?, Summary { id: 1, name: "moon".into() });
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// ## Errors
+///
+/// If there's no data to decode, an error of [`UnexpectedEof`][std::io/ErrorKind#UnexpectedEof] is returned. Containers nested deeper than
+/// [`DEFAULT_MAX_DEPTH`][value::DEFAULT_MAX_DEPTH] are rejected.
+///
+/// [serde::de::DeserializeOwned]: https://docs.rs/serde/1/serde/de/trait.DeserializeOwned.html
+/// [enum:Value]: enum.Value.html
+/// [fn:to_writer]: fn.to_writer.html
+/// [fn:skip_value]: fn.skip_value.html
+/// [serde::de::IgnoredAny]: https://docs.rs/serde/1/serde/de/struct.IgnoredAny.html
+/// [Value::DateTime]: enum.Value.html#variant.DateTime
+/// [Value::Date]: enum.Value.html#variant.Date
+/// [Value::Time]: enum.Value.html#variant.Time
+/// [Value::DecimalStr]: enum.Value.html#variant.DecimalStr
+/// [Value::Blob]: enum.Value.html#variant.Blob
+/// [std::io/ErrorKind#UnexpectedEof]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.UnexpectedEof
+/// [value::DEFAULT_MAX_DEPTH]: value/constant.DEFAULT_MAX_DEPTH.html
+pub fn from_reader<T>(source: &mut impl Read) -> IoResult<T> where T: DeserializeOwned {
+    T::deserialize(&mut de::Deserializer::new(source)).map_err(io::Error::from)
+}
+
+/// # Error of serializers and deserializers
+///
+/// Serde requires its own error traits, which can't be implemented for [`io::Error`][std::io/Error]. So this wraps one.
+///
+/// [std::io/Error]: https://doc.rust-lang.org/std/io/struct.Error.html
+#[derive(Debug)]
+struct Error(io::Error);
+
+impl Display for Error {
+
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        self.0.fmt(f)
+    }
+
+}
+
+impl std::error::Error for Error {}
+
+impl serde::ser::Error for Error {
+
+    fn custom<T>(msg: T) -> Self where T: Display {
+        Self(io::Error::new(ErrorKind::InvalidData, __!("{}", msg)))
+    }
+
+}
+
+impl serde::de::Error for Error {
+
+    fn custom<T>(msg: T) -> Self where T: Display {
+        Self(io::Error::new(ErrorKind::InvalidData, __!("{}", msg)))
+    }
+
+}
+
+impl From<io::Error> for Error {
+
+    fn from(err: io::Error) -> Self {
+        Self(err)
+    }
+
+}
+
+impl From<crate::Error> for Error {
+
+    fn from(err: crate::Error) -> Self {
+        Self(err.into())
+    }
+
+}
+
+impl From<Error> for io::Error {
+
+    fn from(err: Error) -> Self {
+        err.0
+    }
+
+}
+
+/// # Makes new error of invalid data
+fn invalid_data(msg: String) -> Error {
+    Error(io::Error::new(ErrorKind::InvalidData, msg))
+}
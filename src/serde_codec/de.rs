@@ -0,0 +1,361 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Deserializer
+
+use {
+    alloc::vec::Vec,
+    core::str,
+    std::io::{self, ErrorKind, Read},
+
+    serde::de::{
+        self, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor,
+        value::{I32Deserializer, SeqDeserializer, StrDeserializer},
+    },
+
+    crate::{
+        DepthLimitExceeded, MapKey, Size, TrackingReader,
+        value::{self, DEFAULT_MAX_DEPTH, MAX_DATA_SIZE},
+        value_enum::{read_size_and_its_length, verify_container_size},
+    },
+    super::{Error, invalid_data},
+};
+
+type Result<T> = core::result::Result<T, Error>;
+
+/// # Deserializer
+///
+/// Strings, blobs and object keys are read into a scratch buffer, which is reused. Visitors which keep them make their own copies.
+///
+/// Bytes read from source are counted, so that containers can be verified against their declared sizes.
+pub(super) struct Deserializer<'a, R> {
+    source: TrackingReader<&'a mut R>,
+    peeked_type: Option<u8>,
+    scratch: Vec<u8>,
+    depth: usize,
+}
+
+impl<'a, R> Deserializer<'a, R> where R: Read {
+
+    /// # Makes new instance
+    pub(super) fn new(source: &'a mut R) -> Self {
+        Self {
+            source: TrackingReader::new(source),
+            peeked_type: None,
+            scratch: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    /// # Reads some bytes
+    fn read<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut buf = [0; N];
+        self.source.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// # Peeks type of next value
+    fn peek_type(&mut self) -> Result<u8> {
+        match self.peeked_type {
+            Some(type_byte) => Ok(type_byte),
+            None => {
+                let [type_byte] = self.read()?;
+                self.peeked_type = Some(type_byte);
+                Ok(type_byte)
+            },
+        }
+    }
+
+    /// # Reads type of next value
+    fn read_type(&mut self) -> Result<u8> {
+        match self.peeked_type.take() {
+            Some(type_byte) => Ok(type_byte),
+            None => self.read().map(|[type_byte]| type_byte),
+        }
+    }
+
+    /// # Reads data of given length into scratch buffer
+    fn read_scratch(&mut self, len: Size) -> Result<&[u8]> {
+        if len > MAX_DATA_SIZE {
+            return Err(invalid_data(__!("invalid declared size: {}", len)));
+        }
+        self.scratch.clear();
+        let read = self.source.by_ref().take(u64::from(len)).read_to_end(&mut self.scratch)?;
+        match read == len as usize {
+            true => Ok(&self.scratch),
+            false => Err(invalid_data(__!("expected to read {} bytes, but: {}", len, read))),
+        }
+    }
+
+    /// # Reads data of a string, after its type
+    fn read_str(&mut self) -> Result<&str> {
+        let (size, _) = read_size_and_its_length(&mut self.source)?;
+        self.read_scratch(size)?;
+        match self.read()? {
+            [0] => str::from_utf8(&self.scratch).map_err(|err| invalid_data(__!("failed to decode UTF-8: {}", err))),
+            [other] => Err(invalid_data(__!("expected to read a null terminator ('\\0'), got: {}", other))),
+        }
+    }
+
+    /// # Reads data of a blob, after its type
+    fn read_blob(&mut self) -> Result<&[u8]> {
+        let (size, _) = read_size_and_its_length(&mut self.source)?;
+        self.read_scratch(size)
+    }
+
+    /// # Reads an object key
+    fn read_object_key(&mut self) -> Result<&str> {
+        let [len] = self.read()?;
+        self.read_scratch(Size::from(len))?;
+        str::from_utf8(&self.scratch).map_err(|err| invalid_data(__!("failed to decode UTF-8: {}", err)))
+    }
+
+    /// # Reads header of a container, after its type
+    ///
+    /// Result: position of the container -- in bytes read from source --, its declared size, and its item count.
+    fn read_container_header(&mut self) -> Result<(u64, Size, Size)> {
+        // Type has been read
+        let position = self.source.count() - 1;
+        let (size, _) = read_size_and_its_length(&mut self.source)?;
+        let (count, _) = read_size_and_its_length(&mut self.source)?;
+        match u64::from(size) < self.source.count() - position {
+            true => Err(invalid_data(__!("invalid declared size: {}", size))),
+            false => Ok((position, size, count)),
+        }
+    }
+
+    /// # Verifies a container, at given position, against its declared size
+    fn verify_container_size(&self, position: u64, size: Size) -> Result<()> {
+        verify_container_size(self.source.count() - position, size).map_err(Error::from)
+    }
+
+    /// # Enters a container, or an enum
+    ///
+    /// Callers must decrease `depth` when they leave it.
+    fn enter_container(&mut self) -> Result<()> {
+        match self.depth < DEFAULT_MAX_DEPTH {
+            true => {
+                self.depth += 1;
+                Ok(())
+            },
+            false => Err(DepthLimitExceeded::new_io_error(DEFAULT_MAX_DEPTH).into()),
+        }
+    }
+
+    /// # Visits items of a container, after its type
+    fn visit_container<'de, V>(&mut self, type_byte: u8, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        let (position, size, count) = self.read_container_header()?;
+        self.enter_container()?;
+        let mut items = Items { de: self, type_byte, count };
+        let result = match type_byte {
+            value::LIST => visitor.visit_seq(&mut items),
+            _ => visitor.visit_map(&mut items),
+        };
+        let count = items.count;
+        self.depth -= 1;
+
+        let result = result?;
+        match count {
+            0 => {
+                self.verify_container_size(position, size)?;
+                Ok(result)
+            },
+            _ => Err(invalid_data(__!("container has {} item(s) left", count))),
+        }
+    }
+
+}
+
+impl<'de, R> de::Deserializer<'de> for &mut Deserializer<'_, R> where R: Read {
+
+    type Error = Error;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        match self.read_type()? {
+            value::NULL => visitor.visit_unit(),
+            value::TRUE => visitor.visit_bool(true),
+            value::FALSE => visitor.visit_bool(false),
+            value::U8 => visitor.visit_u8(u8::from_be_bytes(self.read()?)),
+            value::I8 => visitor.visit_i8(i8::from_be_bytes(self.read()?)),
+            value::U16 => visitor.visit_u16(u16::from_be_bytes(self.read()?)),
+            value::I16 => visitor.visit_i16(i16::from_be_bytes(self.read()?)),
+            value::U32 => visitor.visit_u32(u32::from_be_bytes(self.read()?)),
+            value::I32 => visitor.visit_i32(i32::from_be_bytes(self.read()?)),
+            value::FLOAT => visitor.visit_f32(f32::from_bits(u32::from_be_bytes(self.read()?))),
+            value::U64 => visitor.visit_u64(u64::from_be_bytes(self.read()?)),
+            value::I64 => visitor.visit_i64(i64::from_be_bytes(self.read()?)),
+            value::DOUBLE => visitor.visit_f64(f64::from_bits(u64::from_be_bytes(self.read()?))),
+            value::TEXT | value::DATE_TIME | value::DATE | value::TIME | value::DECIMAL_STR => visitor.visit_str(self.read_str()?),
+            value::BLOB => visitor.visit_bytes(self.read_blob()?),
+            type_byte @ (value::LIST | value::MAP | value::OBJECT) => self.visit_container(type_byte, visitor),
+            other => Err(invalid_data(__!("data type is either invalid or not supported: {}", other))),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        match self.peek_type()? {
+            value::NULL => {
+                self.peeked_type = None;
+                visitor.visit_none()
+            },
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        match self.peek_type()? {
+            value::BLOB => {
+                self.peeked_type = None;
+                let mut bytes = SeqDeserializer::<_, Error>::new(self.read_blob()?.iter().copied());
+                let result = visitor.visit_seq(&mut bytes)?;
+                bytes.end()?;
+                Ok(result)
+            },
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _: usize, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _: &'static str, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(self, _: &'static str, _: &'static [&'static str], visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        match self.read_type()? {
+            value::TEXT => visitor.visit_enum(StrDeserializer::new(self.read_str()?)),
+            value::OBJECT => match self.read_container_header()? {
+                (position, size, 1) => {
+                    self.enter_container()?;
+                    let result = visitor.visit_enum(Enum { de: &mut *self });
+                    self.depth -= 1;
+                    let result = result?;
+                    self.verify_container_size(position, size)?;
+                    Ok(result)
+                },
+                (_, _, count) => Err(invalid_data(__!("expected an object of 1 item for an enum, got: {} item(s)", count))),
+            },
+            other => Err(invalid_data(__!("expected a text or an object for an enum, got: {}", other))),
+        }
+    }
+
+    /// # Skips a value
+    ///
+    /// The value is skipped via its header, like [`skip_value()`][fn:skip_value]: it is neither decoded nor verified. Fields of structs which
+    /// are not known are skipped this way, so decoding a few fields out of large objects does not cost decoding the rest.
+    ///
+    /// [fn:skip_value]: ../fn.skip_value.html
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        let skipped = match self.peeked_type.take() {
+            Some(type_byte) => crate::skip_value(&mut [type_byte].chain(&mut self.source))?,
+            None => crate::skip_value(&mut self.source)?,
+        };
+        match skipped {
+            Some(_) => visitor.visit_unit(),
+            None => Err(io::Error::from(ErrorKind::UnexpectedEof).into()),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit unit_struct tuple_struct map struct
+        identifier
+    }
+
+}
+
+/// # Items of a container
+struct Items<'a, 'b, R> {
+    de: &'a mut Deserializer<'b, R>,
+    type_byte: u8,
+    count: Size,
+}
+
+impl<'de, R> SeqAccess<'de> for &mut Items<'_, '_, R> where R: Read {
+
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>> where T: DeserializeSeed<'de> {
+        match self.count {
+            0 => Ok(None),
+            _ => {
+                self.count -= 1;
+                seed.deserialize(&mut *self.de).map(Some)
+            },
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.count as usize)
+    }
+
+}
+
+impl<'de, R> MapAccess<'de> for &mut Items<'_, '_, R> where R: Read {
+
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>> where K: DeserializeSeed<'de> {
+        if self.count == 0 {
+            return Ok(None);
+        }
+
+        self.count -= 1;
+        match self.type_byte {
+            value::MAP => seed.deserialize(I32Deserializer::new(MapKey::from_be_bytes(self.de.read()?))).map(Some),
+            _ => seed.deserialize(StrDeserializer::new(self.de.read_object_key()?)).map(Some),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value> where V: DeserializeSeed<'de> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.count as usize)
+    }
+
+}
+
+/// # An enum, as an object of 1 item: variant name and its data
+struct Enum<'a, 'b, R> {
+    de: &'a mut Deserializer<'b, R>,
+}
+
+impl<'de, R> EnumAccess<'de> for Enum<'_, '_, R> where R: Read {
+
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self)> where V: DeserializeSeed<'de> {
+        let variant = seed.deserialize(StrDeserializer::<Error>::new(self.de.read_object_key()?))?;
+        Ok((variant, self))
+    }
+
+}
+
+impl<'de, R> VariantAccess<'de> for Enum<'_, '_, R> where R: Read {
+
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        de::Deserialize::deserialize(self.de)
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value> where T: DeserializeSeed<'de> {
+        seed.deserialize(self.de)
+    }
+
+    fn tuple_variant<V>(self, _: usize, visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        de::Deserializer::deserialize_seq(self.de, visitor)
+    }
+
+    fn struct_variant<V>(self, _: &'static [&'static str], visitor: V) -> Result<V::Value> where V: Visitor<'de> {
+        de::Deserializer::deserialize_map(self.de, visitor)
+    }
+
+}
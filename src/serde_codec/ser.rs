@@ -0,0 +1,627 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Serializer
+
+use {
+    alloc::vec::Vec,
+    core::convert::TryFrom,
+    std::io::{self, Write},
+
+    serde::ser::{
+        self, Impossible, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+        SerializeTupleStruct, SerializeTupleVariant,
+    },
+
+    crate::{
+        MapKey, Size,
+        append::container_header_bytes,
+        value::{self, OBJECT_KEY_MAX_LEN},
+        value_enum::{HeaderBytes, encode_blob_header, encode_value_str},
+    },
+    super::{Error, invalid_data},
+};
+
+type Result<T> = core::result::Result<T, Error>;
+
+/// # Output of serializers
+///
+/// A value is serialized twice. The first pass writes nothing: it only calculates headers of containers -- types, sizes and item counts --
+/// in order of their appearance. The second pass writes everything straight into the stream, using those headers.
+pub(super) struct Output<W> {
+    stream: Option<W>,
+    written: u64,
+    headers: Vec<(u8, Size, Size)>,
+    next_header: usize,
+}
+
+impl<W> Output<W> where W: Write {
+
+    /// # Makes new instance, for the first pass
+    pub(super) const fn new() -> Self {
+        Self {
+            stream: None,
+            written: 0,
+            headers: Vec::new(),
+            next_header: 0,
+        }
+    }
+
+    /// # Starts the second pass, writing into a stream
+    pub(super) fn start_writing(&mut self, stream: W) {
+        self.stream = Some(stream);
+        self.written = 0;
+        self.next_header = 0;
+    }
+
+    /// # Total bytes that have been written
+    pub(super) const fn written(&self) -> u64 {
+        self.written
+    }
+
+    /// # Begins a container
+    ///
+    /// `type_byte` can be unknown in the first pass, until the first key of a map is serialized.
+    ///
+    /// Result: index of its header, and position of its items.
+    fn begin_container(&mut self, type_byte: Option<u8>) -> Result<(usize, u64)> {
+        let index = match self.stream {
+            None => {
+                // Header is calculated at the end
+                self.headers.push((type_byte.unwrap_or(value::OBJECT), 0, 0));
+                self.headers.len() - 1
+            },
+            Some(_) => {
+                let index = self.next_header;
+                let (type_byte, size, count) = *self.headers.get(index).ok_or_else(unstable_data)?;
+                self.write_all(HeaderBytes::of_container(type_byte, size, count).as_bytes())?;
+                self.next_header += 1;
+                index
+            },
+        };
+        Ok((index, self.written))
+    }
+
+    /// # Ends a container
+    fn end_container(&mut self, (index, position): (usize, u64), type_byte: u8, count: usize) -> Result<()> {
+        let count = Size::try_from(count).map_err(|_| invalid_data(__!("too many items: {}", count)))?;
+        let (size, header) = container_header_bytes(type_byte, count, self.written - position)?;
+        match self.stream {
+            None => {
+                self.written += header.as_bytes().len() as u64;
+                self.headers[index] = (type_byte, size, count);
+                Ok(())
+            },
+            Some(_) => match self.headers[index] == (type_byte, size, count) {
+                true => Ok(()),
+                false => Err(unstable_data()),
+            },
+        }
+    }
+
+}
+
+impl<W> Write for Output<W> where W: Write {
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let result = match self.stream.as_mut() {
+            Some(stream) => stream.write(buf)?,
+            None => buf.len(),
+        };
+        self.written += result as u64;
+        Ok(result)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.stream.as_mut() {
+            Some(stream) => stream.flush(),
+            None => Ok(()),
+        }
+    }
+
+}
+
+/// # Makes new error of data which is serialized differently by the two passes
+fn unstable_data() -> Error {
+    invalid_data(__!("value is serialized differently each time"))
+}
+
+/// # Writes an object key
+fn write_object_key<W>(key: &str, out: &mut W) -> Result<()> where W: Write {
+    match key.len() <= OBJECT_KEY_MAX_LEN {
+        true => {
+            out.write_all(&[key.len() as u8])?;
+            out.write_all(key.as_bytes())?;
+            Ok(())
+        },
+        false => Err(invalid_data(__!("key length is limited to {} bytes, got: {}", OBJECT_KEY_MAX_LEN, key.len()))),
+    }
+}
+
+/// # Serializer
+pub(super) struct Serializer<'a, W> {
+    out: &'a mut Output<W>,
+}
+
+impl<'a, W> Serializer<'a, W> where W: Write {
+
+    /// # Makes new instance
+    pub(super) fn new(out: &'a mut Output<W>) -> Self {
+        Self {
+            out,
+        }
+    }
+
+    /// # Writes a type, and data of a fixed size
+    fn write(self, type_byte: u8, data: &[u8]) -> Result<()> {
+        self.out.write_all(&[type_byte])?;
+        self.out.write_all(data)?;
+        Ok(())
+    }
+
+}
+
+impl<'a, W> ser::Serializer for Serializer<'a, W> where W: Write {
+
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Container<'a, W>;
+    type SerializeTuple = Container<'a, W>;
+    type SerializeTupleStruct = Container<'a, W>;
+    type SerializeTupleVariant = Container<'a, W>;
+    type SerializeMap = Container<'a, W>;
+    type SerializeStruct = Container<'a, W>;
+    type SerializeStructVariant = Container<'a, W>;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.write(if v { value::TRUE } else { value::FALSE }, &[])
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.write(value::I8, &v.to_be_bytes())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.write(value::I16, &v.to_be_bytes())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.write(value::I32, &v.to_be_bytes())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.write(value::I64, &v.to_be_bytes())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.write(value::U8, &v.to_be_bytes())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.write(value::U16, &v.to_be_bytes())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.write(value::U32, &v.to_be_bytes())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.write(value::U64, &v.to_be_bytes())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        self.write(value::FLOAT, &v.to_bits().to_be_bytes())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        self.write(value::DOUBLE, &v.to_bits().to_be_bytes())
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        encode_value_str(value::TEXT, v, self.out)?;
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        encode_blob_header(v.len() as u64, self.out)?;
+        self.out.write_all(v)?;
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.write(value::NULL, &[])
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<()> where T: ?Sized + Serialize {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        self.write(value::NULL, &[])
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<()> {
+        self.write(value::NULL, &[])
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<()> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _: &'static str, value: &T) -> Result<()> where T: ?Sized + Serialize {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(self, _: &'static str, _: u32, variant: &'static str, value: &T) -> Result<()>
+    where T: ?Sized + Serialize {
+        let object = self.out.begin_container(Some(value::OBJECT))?;
+        write_object_key(variant, self.out)?;
+        value.serialize(Serializer::new(&mut *self.out))?;
+        self.out.end_container(object, value::OBJECT, 1)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq> {
+        Container::new(self.out, Some(value::LIST), None)
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple> {
+        Container::new(self.out, Some(value::LIST), None)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeTupleStruct> {
+        Container::new(self.out, Some(value::LIST), None)
+    }
+
+    fn serialize_tuple_variant(self, _: &'static str, _: u32, variant: &'static str, _: usize) -> Result<Self::SerializeTupleVariant> {
+        Container::new(self.out, Some(value::LIST), Some(variant))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap> {
+        // Type is decided by the first key
+        Container::new(self.out, None, None)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct> {
+        Container::new(self.out, Some(value::OBJECT), None)
+    }
+
+    fn serialize_struct_variant(self, _: &'static str, _: u32, variant: &'static str, _: usize) -> Result<Self::SerializeStructVariant> {
+        Container::new(self.out, Some(value::OBJECT), Some(variant))
+    }
+
+}
+
+/// # A container being serialized
+///
+/// Containers of variants are wrapped inside an object of 1 item: variant name and the container.
+pub(super) struct Container<'a, W> {
+    out: &'a mut Output<W>,
+    type_byte: Option<u8>,
+    variant: Option<(usize, u64)>,
+    container: (usize, u64),
+    count: usize,
+}
+
+impl<'a, W> Container<'a, W> where W: Write {
+
+    /// # Makes new instance
+    fn new(out: &'a mut Output<W>, type_byte: Option<u8>, variant: Option<&'static str>) -> Result<Self> {
+        let variant = match variant {
+            Some(variant) => {
+                let object = out.begin_container(Some(value::OBJECT))?;
+                write_object_key(variant, out)?;
+                Some(object)
+            },
+            None => None,
+        };
+        let container = out.begin_container(type_byte)?;
+        Ok(Self {
+            out,
+            type_byte,
+            variant,
+            container,
+            count: 0,
+        })
+    }
+
+    /// # Adds an item
+    fn add<T>(&mut self, value: &T) -> Result<()> where T: ?Sized + Serialize {
+        value.serialize(Serializer::new(&mut *self.out))?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// # Adds a field of an object
+    fn add_field<T>(&mut self, key: &str, value: &T) -> Result<()> where T: ?Sized + Serialize {
+        write_object_key(key, self.out)?;
+        self.add(value)
+    }
+
+    /// # Finishes the container
+    fn finish(self) -> Result<()> {
+        // Empty maps are objects
+        let type_byte = self.type_byte.unwrap_or(value::OBJECT);
+        self.out.end_container(self.container, type_byte, self.count)?;
+        match self.variant {
+            Some(object) => self.out.end_container(object, value::OBJECT, 1),
+            None => Ok(()),
+        }
+    }
+
+}
+
+impl<W> SerializeSeq for Container<'_, W> where W: Write {
+
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()> where T: ?Sized + Serialize {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+
+}
+
+impl<W> SerializeTuple for Container<'_, W> where W: Write {
+
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()> where T: ?Sized + Serialize {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+
+}
+
+impl<W> SerializeTupleStruct for Container<'_, W> where W: Write {
+
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()> where T: ?Sized + Serialize {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+
+}
+
+impl<W> SerializeTupleVariant for Container<'_, W> where W: Write {
+
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()> where T: ?Sized + Serialize {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+
+}
+
+impl<W> SerializeMap for Container<'_, W> where W: Write {
+
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()> where T: ?Sized + Serialize {
+        let type_byte = key.serialize(KeySerializer { out: &mut *self.out })?;
+        match self.type_byte {
+            None => self.type_byte = Some(type_byte),
+            Some(current) if current != type_byte => return Err(invalid_data(__!("a map can't have both string and integer keys"))),
+            Some(_) => (),
+        };
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()> where T: ?Sized + Serialize {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+
+}
+
+impl<W> SerializeStruct for Container<'_, W> where W: Write {
+
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()> where T: ?Sized + Serialize {
+        self.add_field(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+
+}
+
+impl<W> SerializeStructVariant for Container<'_, W> where W: Write {
+
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()> where T: ?Sized + Serialize {
+        self.add_field(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+
+}
+
+/// # Serializer of map keys
+///
+/// Strings are written as keys of an object, integers as keys of a map. Result is the type of container.
+struct KeySerializer<'a, W> {
+    out: &'a mut Output<W>,
+}
+
+impl<W> KeySerializer<'_, W> where W: Write {
+
+    /// # Writes a map key
+    fn write_map_key<T>(self, key: T) -> Result<u8> where T: Copy + core::fmt::Display, MapKey: TryFrom<T> {
+        let key = MapKey::try_from(key).map_err(|_| invalid_data(__!("map key is out of range: {}", key)))?;
+        self.out.write_all(&key.to_be_bytes())?;
+        Ok(value::MAP)
+    }
+
+}
+
+/// # Makes new error of unsupported keys
+fn unsupported_key() -> Error {
+    invalid_data(__!("map keys must be strings or integers"))
+}
+
+impl<W> ser::Serializer for KeySerializer<'_, W> where W: Write {
+
+    type Ok = u8;
+    type Error = Error;
+    type SerializeSeq = Impossible<u8, Error>;
+    type SerializeTuple = Impossible<u8, Error>;
+    type SerializeTupleStruct = Impossible<u8, Error>;
+    type SerializeTupleVariant = Impossible<u8, Error>;
+    type SerializeMap = Impossible<u8, Error>;
+    type SerializeStruct = Impossible<u8, Error>;
+    type SerializeStructVariant = Impossible<u8, Error>;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, _: bool) -> Result<u8> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<u8> {
+        self.write_map_key(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<u8> {
+        self.write_map_key(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<u8> {
+        self.write_map_key(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<u8> {
+        self.write_map_key(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<u8> {
+        self.write_map_key(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<u8> {
+        self.write_map_key(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<u8> {
+        self.write_map_key(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<u8> {
+        self.write_map_key(v)
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<u8> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<u8> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_char(self, v: char) -> Result<u8> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<u8> {
+        write_object_key(v, self.out)?;
+        Ok(value::OBJECT)
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<u8> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_none(self) -> Result<u8> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_some<T>(self, _: &T) -> Result<u8> where T: ?Sized + Serialize {
+        Err(unsupported_key())
+    }
+
+    fn serialize_unit(self) -> Result<u8> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<u8> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<u8> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _: &'static str, value: &T) -> Result<u8> where T: ?Sized + Serialize {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(self, _: &'static str, _: u32, _: &'static str, _: &T) -> Result<u8> where T: ?Sized + Serialize {
+        Err(unsupported_key())
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeTupleStruct> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_tuple_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeTupleVariant> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_struct_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeStructVariant> {
+        Err(unsupported_key())
+    }
+
+}
@@ -0,0 +1,126 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Shared value
+
+use {
+    alloc::sync::Arc,
+    core::{
+        fmt::{self, Debug, Formatter},
+        ops::Deref,
+    },
+
+    crate::Value,
+};
+
+/// # Shared value
+///
+/// This is a [`Value`][enum:Value] behind an [`Arc`][alloc::sync::Arc]. Cloning it takes _O(1)_ -- no matter how large the value is -- so
+/// a large document can be sent to multiple threads without deep copies.
+///
+/// All methods of [`Value`][enum:Value] which take `&self` are available via [`Deref`][core::ops::Deref]. To modify the value, use
+/// [`make_mut()`][#make_mut()], which only copies the value if it's shared.
+///
+/// ## Examples
+///
+/// ```
+/// use std::thread;
+/// use binn_ir::{SharedValue, Value};
+///
+/// let document = SharedValue::new(Value::List(vec![Value::from("some text"); 10_000]));
+///
+/// let workers = (0..4).map(|_| {
+///     let document = document.clone();
+///     thread::spawn(move || document.size())
+/// }).collect::<Vec<_>>();
+/// for worker in workers {
+///     assert_eq!(worker.join().unwrap()?, document.size()?);
+/// }
+///
+/// // Copy on write
+/// let mut copy = document.clone();
+/// copy.make_mut().push(Value::Null)?;
+/// assert!(!SharedValue::ptr_eq(&document, &copy));
+/// assert_eq!(document.as_list()?.len() + 1, copy.as_list()?.len());
+/// # Ok::<_, binn_ir::Error>(())
+/// ```
+///
+/// [enum:Value]: enum.Value.html
+/// [alloc::sync::Arc]: https://doc.rust-lang.org/alloc/sync/struct.Arc.html
+/// [core::ops::Deref]: https://doc.rust-lang.org/core/ops/trait.Deref.html
+/// [#make_mut()]: #method.make_mut
+#[derive(Clone, PartialEq)]
+pub struct SharedValue {
+    value: Arc<Value>,
+}
+
+impl SharedValue {
+
+    /// # Makes new instance
+    pub fn new(value: Value) -> Self {
+        Self {
+            value: Arc::new(value),
+        }
+    }
+
+    /// # Gets a mutable reference to the value
+    ///
+    /// If the value is shared with other instances, it is cloned first, so the others are not affected.
+    pub fn make_mut(&mut self) -> &mut Value {
+        Arc::make_mut(&mut self.value)
+    }
+
+    /// # Checks if 2 instances share the same value
+    pub fn ptr_eq(first: &Self, second: &Self) -> bool {
+        Arc::ptr_eq(&first.value, &second.value)
+    }
+
+    /// # Takes the value out
+    ///
+    /// If the value is shared with other instances, it is cloned.
+    pub fn into_value(self) -> Value {
+        Arc::try_unwrap(self.value).unwrap_or_else(|value| Value::clone(&value))
+    }
+
+}
+
+impl Debug for SharedValue {
+
+    fn fmt(&self, f: &mut Formatter) -> core::result::Result<(), fmt::Error> {
+        Debug::fmt(&self.value, f)
+    }
+
+}
+
+impl Deref for SharedValue {
+
+    type Target = Value;
+
+    fn deref(&self) -> &Value {
+        &self.value
+    }
+
+}
+
+impl AsRef<Value> for SharedValue {
+
+    fn as_ref(&self) -> &Value {
+        &self.value
+    }
+
+}
+
+impl From<Value> for SharedValue {
+
+    fn from(value: Value) -> Self {
+        Self::new(value)
+    }
+
+}
+
+impl From<SharedValue> for Value {
+
+    fn from(value: SharedValue) -> Self {
+        value.into_value()
+    }
+
+}
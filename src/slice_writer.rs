@@ -0,0 +1,83 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Slice writer
+
+use crate::{
+    Result, Size,
+    value_enum::HeaderBytes,
+};
+
+/// # A writer into a slice
+///
+/// This writes parts of encoded values, without allocation. Sizes of values should be verified before writing; still, if the slice is too
+/// small, an error is returned.
+pub(crate) struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl<'a> SliceWriter<'a> {
+
+    /// # Makes new instance
+    pub(crate) fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    /// # Number of bytes written
+    pub(crate) fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// # Writes some bytes
+    pub(crate) fn put(&mut self, bytes: &[u8]) -> Result<()> {
+        let end = self.pos + bytes.len();
+        match self.buf.get_mut(self.pos..end) {
+            Some(target) => {
+                target.copy_from_slice(bytes);
+                self.pos = end;
+                Ok(())
+            },
+            None => Err(err!("buffer too small: {} byte(s) needed, {} available", end, self.buf.len())),
+        }
+    }
+
+    /// # Writes a type, then a big-endian number
+    pub(crate) fn put_int(&mut self, ty: u8, bytes: &[u8]) -> Result<()> {
+        self.put(&[ty])?;
+        self.put(bytes)
+    }
+
+    /// # Writes a size
+    pub(crate) fn put_size(&mut self, size: Size) -> Result<()> {
+        self.put(HeaderBytes::of_size(size).as_bytes())
+    }
+
+    /// # Writes a string, with its type, its length and its null terminator
+    pub(crate) fn put_str(&mut self, ty: u8, s: &str) -> Result<()> {
+        self.put(&[ty])?;
+        self.put_size(s.len() as Size)?;
+        self.put(s.as_bytes())?;
+        self.put(&[0])
+    }
+
+    /// # Writes a blob, with its type and its length
+    pub(crate) fn put_blob(&mut self, bytes: &[u8]) -> Result<()> {
+        self.put(&[crate::value::BLOB])?;
+        self.put_size(bytes.len() as Size)?;
+        self.put(bytes)
+    }
+
+    /// # Writes a container header: type, size and count
+    pub(crate) fn put_container_header(&mut self, ty: u8, size: Size, count: usize) -> Result<()> {
+        self.put(HeaderBytes::of_container(ty, size, count as Size).as_bytes())
+    }
+
+    /// # Writes an object key, with its length
+    ///
+    /// Its length must have been verified.
+    pub(crate) fn put_object_key(&mut self, key: &str) -> Result<()> {
+        self.put(&[key.len() as u8])?;
+        self.put(key.as_bytes())
+    }
+
+}
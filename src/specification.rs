@@ -311,9 +311,9 @@
 //!
 //! **Json:** (47 bytes)
 //! >[
-//! {"id": 1, "name": "John"},
-//! {"id": 2, "name": "Eric"}
-//! ]
+//! > {"id": 1, "name": "John"},
+//! > {"id": 2, "name": "Eric"}
+//! > ]
 //!
 //! **Binn:** (43 bytes)
 //! ```Text
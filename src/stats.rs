@@ -0,0 +1,165 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Stats
+
+use {
+    alloc::collections::BTreeMap,
+
+    crate::{Path, PathSegment, Size, Value},
+};
+
+/// # Statistics of a value
+///
+/// This is made by [`Value::stats()`][Value::stats()].
+///
+/// [Value::stats()]: enum.Value.html#method.stats
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Stats {
+    counts: BTreeMap<u8, usize>,
+    max_depth: usize,
+    text_bytes: u64,
+    blob_bytes: u64,
+    largest_container: Option<(Path, usize)>,
+}
+
+impl Stats {
+
+    /// # Number of values of a type
+    ///
+    /// `type_byte` is one of [type constants][mod:value], such as [`TEXT`][value::TEXT].
+    ///
+    /// [mod:value]: value/index.html
+    /// [value::TEXT]: value/constant.TEXT.html
+    pub fn count(&self, type_byte: u8) -> usize {
+        self.counts.get(&type_byte).copied().unwrap_or_default()
+    }
+
+    /// # Numbers of values, per type
+    ///
+    /// Keys are type bytes. Types which don't appear are not included.
+    pub fn counts(&self) -> &BTreeMap<u8, usize> {
+        &self.counts
+    }
+
+    /// # Total number of values, including the root value
+    pub fn total_count(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// # Max depth of nested containers
+    ///
+    /// A non-container value has depth zero. A container has depth of its deepest item, plus one.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// # Total bytes of all strings
+    ///
+    /// That includes [`Text`][Value::Text], [`DateTime`][Value::DateTime], [`Date`][Value::Date], [`Time`][Value::Time] and
+    /// [`DecimalStr`][Value::DecimalStr]. Keys of objects are not included.
+    ///
+    /// [Value::Text]: enum.Value.html#variant.Text
+    /// [Value::DateTime]: enum.Value.html#variant.DateTime
+    /// [Value::Date]: enum.Value.html#variant.Date
+    /// [Value::Time]: enum.Value.html#variant.Time
+    /// [Value::DecimalStr]: enum.Value.html#variant.DecimalStr
+    pub fn text_bytes(&self) -> u64 {
+        self.text_bytes
+    }
+
+    /// # Total bytes of all blobs
+    pub fn blob_bytes(&self) -> u64 {
+        self.blob_bytes
+    }
+
+    /// # Largest container, by item count
+    ///
+    /// Result: path to the container, and its item count. If several containers have the same item count, the first one -- in order of
+    /// encoding -- wins.
+    pub fn largest_container(&self) -> Option<(&Path, usize)> {
+        self.largest_container.as_ref().map(|(path, count)| (path, *count))
+    }
+
+    /// # Collects statistics of a value
+    ///
+    /// Result: depth of the value.
+    pub(crate) fn collect(&mut self, value: &Value, path: &mut Path) -> usize {
+        *self.counts.entry(type_byte(value)).or_default() += 1;
+
+        let depth = match value {
+            Value::Text(s) | Value::DateTime(s) | Value::Date(s) | Value::Time(s) | Value::DecimalStr(s) => {
+                self.text_bytes += s.len() as u64;
+                0
+            },
+            Value::Blob(blob) => {
+                self.blob_bytes += blob.len() as u64;
+                0
+            },
+            Value::List(list) => {
+                self.add_container(path, list.len());
+                list.iter().enumerate().fold(0, |depth, (index, item)|
+                    depth.max(self.collect_item(item, PathSegment::Index(index as Size), path))
+                ) + 1
+            },
+            Value::Map(map) => {
+                self.add_container(path, map.len());
+                map.iter().fold(0, |depth, (key, item)| depth.max(self.collect_item(item, PathSegment::MapKey(*key), path))) + 1
+            },
+            Value::Object(object) => {
+                self.add_container(path, object.len());
+                object.iter().fold(0, |depth, (key, item)|
+                    depth.max(self.collect_item(item, PathSegment::ObjectKey(key.clone()), path))
+                ) + 1
+            },
+            _ => 0,
+        };
+        self.max_depth = self.max_depth.max(depth);
+        depth
+    }
+
+    /// # Collects statistics of an item
+    fn collect_item(&mut self, item: &Value, segment: PathSegment, path: &mut Path) -> usize {
+        path.push(segment);
+        let result = self.collect(item, path);
+        path.pop();
+        result
+    }
+
+    /// # Adds a container
+    fn add_container(&mut self, path: &Path, count: usize) {
+        if self.largest_container.as_ref().map(|(_, largest)| count > *largest).unwrap_or(true) {
+            self.largest_container = Some((path.clone(), count));
+        }
+    }
+
+}
+
+/// # Type byte of a value
+fn type_byte(value: &Value) -> u8 {
+    use crate::value::*;
+
+    match value {
+        Value::Null => NULL,
+        Value::True => TRUE,
+        Value::False => FALSE,
+        Value::U8(_) => U8,
+        Value::I8(_) => I8,
+        Value::U16(_) => U16,
+        Value::I16(_) => I16,
+        Value::U32(_) => U32,
+        Value::I32(_) => I32,
+        Value::Float(_) => FLOAT,
+        Value::U64(_) => U64,
+        Value::I64(_) => I64,
+        Value::Double(_) => DOUBLE,
+        Value::Text(_) => TEXT,
+        Value::DateTime(_) => DATE_TIME,
+        Value::Date(_) => DATE,
+        Value::Time(_) => TIME,
+        Value::DecimalStr(_) => DECIMAL_STR,
+        Value::Blob(_) => BLOB,
+        Value::List(_) => LIST,
+        Value::Map(_) => MAP,
+        Value::Object(_) => OBJECT,
+    }
+}
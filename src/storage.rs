@@ -35,3 +35,111 @@ pub const BLOB: u8 = 0b_110;
 /// [`Map`]: ../enum.Value.html#variant.Map
 /// [`Object`]: ../enum.Value.html#variant.Object
 pub const CONTAINER: u8 = 0b_111;
+
+/// # Storage of a value
+///
+/// This is the typed form of the constants in this module. It is the 3 most significant bits of a type byte.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{storage::Storage, value};
+///
+/// assert_eq!(Storage::of_type_byte(value::I16), Storage::Word);
+/// assert_eq!(Storage::of_type_byte(value::OBJECT), Storage::Container);
+/// assert_eq!(Storage::of_type_byte(0xA9).bits(), binn_ir::storage::STRING);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Storage {
+
+    /// # See [`NO_BYTES`][NO_BYTES]
+    ///
+    /// [NO_BYTES]: constant.NO_BYTES.html
+    NoBytes,
+
+    /// # See [`BYTE`][BYTE]
+    ///
+    /// [BYTE]: constant.BYTE.html
+    Byte,
+
+    /// # See [`WORD`][WORD]
+    ///
+    /// [WORD]: constant.WORD.html
+    Word,
+
+    /// # See [`DWORD`][DWORD]
+    ///
+    /// [DWORD]: constant.DWORD.html
+    DWord,
+
+    /// # See [`QWORD`][QWORD]
+    ///
+    /// [QWORD]: constant.QWORD.html
+    QWord,
+
+    /// # See [`STRING`][STRING]
+    ///
+    /// [STRING]: constant.STRING.html
+    String,
+
+    /// # See [`BLOB`][BLOB]
+    ///
+    /// [BLOB]: constant.BLOB.html
+    Blob,
+
+    /// # See [`CONTAINER`][CONTAINER]
+    ///
+    /// [CONTAINER]: constant.CONTAINER.html
+    Container,
+
+}
+
+impl Storage {
+
+    /// # Gets storage of a type byte
+    ///
+    /// Any byte is accepted, including ones of user defined types. For 2-byte types, pass the first byte.
+    pub const fn of_type_byte(type_byte: u8) -> Self {
+        match type_byte >> 5 {
+            NO_BYTES => Storage::NoBytes,
+            BYTE => Storage::Byte,
+            WORD => Storage::Word,
+            DWORD => Storage::DWord,
+            QWORD => Storage::QWord,
+            STRING => Storage::String,
+            BLOB => Storage::Blob,
+            _ => Storage::Container,
+        }
+    }
+
+    /// # Gets storage bits
+    ///
+    /// Result is one of the constants in this module.
+    pub const fn bits(&self) -> u8 {
+        match self {
+            Storage::NoBytes => NO_BYTES,
+            Storage::Byte => BYTE,
+            Storage::Word => WORD,
+            Storage::DWord => DWORD,
+            Storage::QWord => QWORD,
+            Storage::String => STRING,
+            Storage::Blob => BLOB,
+            Storage::Container => CONTAINER,
+        }
+    }
+
+    /// # Gets size of data, if it is fixed
+    ///
+    /// Type byte(s) do _not_ count. For strings, blobs and containers, size is stored within data, so this function returns `None`.
+    pub const fn fixed_data_size(&self) -> Option<u8> {
+        match self {
+            Storage::NoBytes => Some(0),
+            Storage::Byte => Some(1),
+            Storage::Word => Some(2),
+            Storage::DWord => Some(4),
+            Storage::QWord => Some(8),
+            Storage::String | Storage::Blob | Storage::Container => None,
+        }
+    }
+
+}
@@ -0,0 +1,370 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Stream layers
+
+use {
+    alloc::vec::Vec,
+    std::io::{self, ErrorKind, Read, Write},
+
+    crate::{IoResult, framing::crc32c_update},
+};
+
+#[cfg(any(feature="flate2", feature="zstd"))]
+use std::io::BufReader;
+
+/// # Size of a checksum, in bytes
+const CRC_SIZE: usize = 4;
+
+/// # Finishes a wrapped stream
+///
+/// Some layers need to do more work after all data is written (for example, compressors flush their last blocks, checksums write
+/// themselves), or after all data is read (checksums verify themselves). So a layered stream must be finished.
+pub trait Finish {
+
+    /// # Inner stream
+    type Inner;
+
+    /// # Finishes this stream
+    ///
+    /// Result: the inner stream.
+    fn finish(self) -> IoResult<Self::Inner>;
+
+}
+
+/// # Stream layer
+///
+/// A layer wraps streams of an [`Encoder`][trait:Encoder] or a [`Decoder`][trait:Decoder], for example to compress, to verify integrity, to
+/// collect metrics, or to encrypt data. Layers can be stacked via [`Encoder::layer()`][Encoder#layer()] and
+/// [`Decoder::layer()`][Decoder#layer()]. To read data back, stack the same layers in the same order.
+///
+/// Each layered stream must be [finished][trait:Finish], from the outermost layer to the innermost one.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{CrcLayer, Decoder, Encoder, Finish, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// let mut encoder = (&mut buf).layer(CrcLayer::new())?;
+/// encoder.encode_text("first")?;
+/// encoder.encode_text("second")?;
+/// encoder.finish()?;
+///
+/// let mut decoder = buf.as_slice().layer(CrcLayer::new())?;
+/// assert_eq!(decoder.decode_text()?.unwrap(), "first");
+/// assert_eq!(decoder.decode_text()?.unwrap(), "second");
+/// assert!(decoder.decode()?.is_none());
+/// decoder.finish()?;
+///
+/// // Corrupted data
+/// buf[1] ^= 1;
+/// assert!(buf.as_slice().layer(CrcLayer::new())?.finish().is_err());
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [trait:Encoder]: trait.Encoder.html
+/// [trait:Decoder]: trait.Decoder.html
+/// [trait:Finish]: trait.Finish.html
+/// [Encoder#layer()]: trait.Encoder.html#method.layer
+/// [Decoder#layer()]: trait.Decoder.html#method.layer
+pub trait StreamLayer {
+
+    /// # Writer
+    type Writer<W>: Write + Finish<Inner=W> where W: Write;
+
+    /// # Reader
+    type Reader<R>: Read + Finish<Inner=R> where R: Read;
+
+    /// # Wraps a writer
+    fn wrap_writer<W>(self, inner: W) -> IoResult<Self::Writer<W>> where W: Write;
+
+    /// # Wraps a reader
+    fn wrap_reader<R>(self, inner: R) -> IoResult<Self::Reader<R>> where R: Read;
+
+}
+
+/// # Checksum layer
+///
+/// The writer appends CRC-32C (Castagnoli) checksum of all data -- a big-endian `u32` -- when it's finished. The reader verifies it when
+/// reaching end of its inner stream, with an error of [`InvalidData`][io::ErrorKind#InvalidData] if mismatched.
+///
+/// ## Notes
+///
+/// The checksum covers the whole stream, not each value. So the reader must read until end of stream -- via
+/// [`finish()`][Finish#finish()], or until decoding returns `None` -- for the checksum to be verified.
+///
+/// [io::ErrorKind#InvalidData]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+/// [Finish#finish()]: trait.Finish.html#tymethod.finish
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CrcLayer {}
+
+impl CrcLayer {
+
+    /// # Makes new instance
+    pub const fn new() -> Self {
+        Self {}
+    }
+
+}
+
+impl StreamLayer for CrcLayer {
+
+    type Writer<W> = CrcWriter<W> where W: Write;
+    type Reader<R> = CrcReader<R> where R: Read;
+
+    fn wrap_writer<W>(self, inner: W) -> IoResult<Self::Writer<W>> where W: Write {
+        Ok(CrcWriter { inner, crc: 0 })
+    }
+
+    fn wrap_reader<R>(self, inner: R) -> IoResult<Self::Reader<R>> where R: Read {
+        Ok(CrcReader { inner, crc: 0, pending: Vec::new(), eof: false })
+    }
+
+}
+
+/// # Writer of [`CrcLayer`][struct:CrcLayer]
+///
+/// [struct:CrcLayer]: struct.CrcLayer.html
+#[derive(Debug)]
+pub struct CrcWriter<W> {
+    inner: W,
+    crc: u32,
+}
+
+impl<W> Write for CrcWriter<W> where W: Write {
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.crc = crc32c_update(self.crc, &buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+}
+
+impl<W> Finish for CrcWriter<W> where W: Write {
+
+    type Inner = W;
+
+    fn finish(mut self) -> IoResult<W> {
+        self.inner.write_all(&self.crc.to_be_bytes())?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+}
+
+/// # Reader of [`CrcLayer`][struct:CrcLayer]
+///
+/// [struct:CrcLayer]: struct.CrcLayer.html
+#[derive(Debug)]
+pub struct CrcReader<R> {
+    inner: R,
+    crc: u32,
+    /// # Bytes which have been read from inner stream, but not from this one
+    ///
+    /// Last bytes of the stream are the checksum, so there are always at least `CRC_SIZE` bytes here, until end of stream.
+    pending: Vec<u8>,
+    eof: bool,
+}
+
+impl<R> Read for CrcReader<R> where R: Read {
+
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        while !self.eof && self.pending.len() <= CRC_SIZE {
+            let mut chunk = [0; 1024];
+            match self.inner.read(&mut chunk) {
+                Ok(0) => self.eof = true,
+                Ok(read) => self.pending.extend_from_slice(&chunk[..read]),
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+        }
+
+        let available = match self.pending.len().checked_sub(CRC_SIZE) {
+            Some(available) => available,
+            None => return Err(io::Error::new(ErrorKind::UnexpectedEof, __!("missing checksum"))),
+        };
+        if available == 0 {
+            let expected = u32::from_be_bytes([self.pending[0], self.pending[1], self.pending[2], self.pending[3]]);
+            return match self.crc == expected {
+                true => Ok(0),
+                false => Err(io::Error::new(
+                    ErrorKind::InvalidData, __!("checksum mismatches, expected: 0x{:08x}, got: 0x{:08x}", expected, self.crc),
+                )),
+            };
+        }
+
+        let count = buf.len().min(available);
+        buf[..count].copy_from_slice(&self.pending[..count]);
+        self.crc = crc32c_update(self.crc, &buf[..count]);
+        self.pending.drain(..count);
+        Ok(count)
+    }
+
+}
+
+impl<R> Finish for CrcReader<R> where R: Read {
+
+    type Inner = R;
+
+    /// # Reads all remaining data, then verifies the checksum
+    fn finish(mut self) -> IoResult<R> {
+        io::copy(&mut self, &mut io::sink())?;
+        Ok(self.inner)
+    }
+
+}
+
+/// # Deflate layer, via `flate2`
+///
+/// Data is compressed with raw Deflate format, without zlib or gzip headers.
+///
+/// ## Notes
+///
+/// The reader is buffered. On [`finish()`][Finish#finish()], data which has been buffered, but not used, is dropped.
+///
+/// [Finish#finish()]: trait.Finish.html#tymethod.finish
+#[cfg(feature="flate2")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeflateLayer {
+    level: u32,
+}
+
+#[cfg(feature="flate2")]
+impl DeflateLayer {
+
+    /// # Makes new instance, with a compression level, from `0` to `9`
+    pub const fn new(level: u32) -> Self {
+        Self { level }
+    }
+
+}
+
+#[cfg(feature="flate2")]
+impl Default for DeflateLayer {
+
+    fn default() -> Self {
+        Self::new(flate2::Compression::default().level())
+    }
+
+}
+
+#[cfg(feature="flate2")]
+impl StreamLayer for DeflateLayer {
+
+    type Writer<W> = flate2::write::DeflateEncoder<W> where W: Write;
+    type Reader<R> = flate2::bufread::DeflateDecoder<BufReader<R>> where R: Read;
+
+    fn wrap_writer<W>(self, inner: W) -> IoResult<Self::Writer<W>> where W: Write {
+        Ok(flate2::write::DeflateEncoder::new(inner, flate2::Compression::new(self.level)))
+    }
+
+    fn wrap_reader<R>(self, inner: R) -> IoResult<Self::Reader<R>> where R: Read {
+        Ok(flate2::bufread::DeflateDecoder::new(BufReader::new(inner)))
+    }
+
+}
+
+#[cfg(feature="flate2")]
+impl<W> Finish for flate2::write::DeflateEncoder<W> where W: Write {
+
+    type Inner = W;
+
+    fn finish(self) -> IoResult<W> {
+        flate2::write::DeflateEncoder::finish(self)
+    }
+
+}
+
+#[cfg(feature="flate2")]
+impl<R> Finish for flate2::bufread::DeflateDecoder<BufReader<R>> where R: Read {
+
+    type Inner = R;
+
+    fn finish(self) -> IoResult<R> {
+        Ok(self.into_inner().into_inner())
+    }
+
+}
+
+/// # Zstandard layer, via `zstd`
+///
+/// ## Notes
+///
+/// The reader is buffered. On [`finish()`][Finish#finish()], data which has been buffered, but not used, is dropped.
+///
+/// [Finish#finish()]: trait.Finish.html#tymethod.finish
+#[cfg(feature="zstd")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ZstdLayer {
+    level: i32,
+}
+
+#[cfg(feature="zstd")]
+impl ZstdLayer {
+
+    /// # Makes new instance, with a compression level
+    ///
+    /// Level `0` means default level of `zstd`.
+    pub const fn new(level: i32) -> Self {
+        Self { level }
+    }
+
+}
+
+#[cfg(feature="zstd")]
+impl Default for ZstdLayer {
+
+    fn default() -> Self {
+        Self::new(zstd::DEFAULT_COMPRESSION_LEVEL)
+    }
+
+}
+
+#[cfg(feature="zstd")]
+impl StreamLayer for ZstdLayer {
+
+    type Writer<W> = zstd::stream::write::Encoder<'static, W> where W: Write;
+    type Reader<R> = zstd::stream::read::Decoder<'static, BufReader<R>> where R: Read;
+
+    fn wrap_writer<W>(self, inner: W) -> IoResult<Self::Writer<W>> where W: Write {
+        zstd::stream::write::Encoder::new(inner, self.level)
+    }
+
+    fn wrap_reader<R>(self, inner: R) -> IoResult<Self::Reader<R>> where R: Read {
+        zstd::stream::read::Decoder::new(inner)
+    }
+
+}
+
+#[cfg(feature="zstd")]
+impl<W> Finish for zstd::stream::write::Encoder<'static, W> where W: Write {
+
+    type Inner = W;
+
+    fn finish(self) -> IoResult<W> {
+        zstd::stream::write::Encoder::finish(self)
+    }
+
+}
+
+#[cfg(feature="zstd")]
+impl<R> Finish for zstd::stream::read::Decoder<'static, BufReader<R>> where R: Read {
+
+    type Inner = R;
+
+    fn finish(self) -> IoResult<R> {
+        Ok(zstd::stream::read::Decoder::finish(self).into_inner())
+    }
+
+}
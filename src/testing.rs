@@ -0,0 +1,207 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Assertion helpers, for conformance tests
+//!
+//! This module is available with feature `testing`.
+//!
+//! When an assertion fails, the panic message lists all differences between expected and actual values -- each with its
+//! [`Path`][struct:Path] -- instead of dumping both values.
+//!
+//! ## Examples
+//!
+//! ```
+//! use binn_ir::testing;
+//!
+//! let mut document = binn_ir::object();
+//! document.object_insert("name", "moss")?;
+//! document.object_insert("tags", vec![binn_ir::Value::from("green")])?;
+//!
+//! testing::assert_roundtrip(&document);
+//! # Ok::<_, binn_ir::Error>(())
+//! ```
+//!
+//! [struct:Path]: ../struct.Path.html
+
+use {
+    alloc::{
+        format,
+        string::String,
+        vec::Vec,
+    },
+    core::fmt::Write,
+
+    crate::{ObjectKey, Path, PathSegment, Size, Value},
+};
+
+/// # Max differences to show in a panic message
+const MAX_DIFFERENCES: usize = 32;
+
+/// # Asserts that a value survives encoding, then decoding
+///
+/// The value must be encoded into exactly [`size()`][Value#size()] bytes, which must decode back to an equal value, with no trailing
+/// bytes. Floating point numbers are compared by their bits, so `NaN`s are equal.
+///
+/// ## Panics
+///
+/// If any of above conditions fails. The message shows differences between the value and the decoded one.
+///
+/// [Value#size()]: ../enum.Value.html#method.size
+#[track_caller]
+pub fn assert_roundtrip(value: &Value) {
+    let mut buf = Vec::new();
+    let size = match value.encode(&mut buf) {
+        Ok(size) => size,
+        Err(err) => panic!("failed to encode: {}", err),
+    };
+    match value.size() {
+        Ok(expected) => assert!(size == expected && buf.len() == size as usize, "encoded {} byte(s), but size() is {}", buf.len(), expected),
+        Err(err) => panic!("failed to calculate size: {}", err),
+    };
+
+    let decoded = decode_one(&buf);
+    if let Some(differences) = differences(value, &decoded) {
+        panic!("decoded value differs from original one:\n{}", differences);
+    }
+}
+
+/// # Asserts that an encoded value survives decoding, then encoding
+///
+/// The bytes must contain exactly one value. Once decoded, it must be encoded back into the very same bytes.
+///
+/// ## Panics
+///
+/// If any of above conditions fails. If bytes differ, the message shows the first offset where they differ, and differences between the
+/// decoded value and the one decoded from new bytes.
+#[track_caller]
+pub fn assert_bytes_roundtrip(bytes: &[u8]) {
+    let value = decode_one(bytes);
+    let mut buf = Vec::with_capacity(bytes.len());
+    if let Err(err) = value.encode(&mut buf) {
+        panic!("failed to encode decoded value: {}", err);
+    }
+    if buf == bytes {
+        return;
+    }
+
+    let offset = bytes.iter().zip(buf.iter()).position(|(a, b)| a != b).unwrap_or_else(|| bytes.len().min(buf.len()));
+    let mut msg = format!(
+        "encoded bytes differ from original ones, at byte {} (original: {} byte(s), {:02x?}; encoded: {} byte(s), {:02x?})",
+        offset, bytes.len(), context(bytes, offset), buf.len(), context(&buf, offset),
+    );
+    if let Some(differences) = differences(&value, &decode_one(&buf)) {
+        write!(msg, "\nvalues also differ:\n{}", differences).unwrap();
+    }
+    panic!("{}", msg);
+}
+
+/// # Decodes exactly one value
+#[track_caller]
+fn decode_one(mut bytes: &[u8]) -> Value {
+    match crate::decode(&mut bytes) {
+        Ok(Some(value)) => match bytes.is_empty() {
+            true => value,
+            false => panic!("trailing data after decoded value: {} byte(s)", bytes.len()),
+        },
+        Ok(None) => panic!("no value to decode"),
+        Err(err) => panic!("failed to decode: {}", err),
+    }
+}
+
+/// # Some bytes around an offset
+fn context(bytes: &[u8], offset: usize) -> &[u8] {
+    &bytes[offset.saturating_sub(4).min(bytes.len())..offset.saturating_add(5).min(bytes.len())]
+}
+
+/// # Formats differences between 2 values, if any
+fn differences(expected: &Value, actual: &Value) -> Option<String> {
+    let mut differences = Vec::new();
+    diff(&mut Path::new(), expected, actual, &mut differences);
+    if differences.is_empty() {
+        return None;
+    }
+
+    let mut result = String::new();
+    for (path, msg) in differences.iter().take(MAX_DIFFERENCES) {
+        writeln!(result, "  at {}: {}", path, msg).unwrap();
+    }
+    if differences.len() > MAX_DIFFERENCES {
+        writeln!(result, "  ...and {} more", differences.len() - MAX_DIFFERENCES).unwrap();
+    }
+    Some(result)
+}
+
+/// # Collects differences between 2 values
+fn diff(path: &mut Path, expected: &Value, actual: &Value, differences: &mut Vec<(Path, String)>) {
+    match (expected, actual) {
+        (Value::List(expected), Value::List(actual)) => {
+            for (index, (expected, actual)) in expected.iter().zip(actual.iter()).enumerate() {
+                path.push(PathSegment::Index(index as Size));
+                diff(path, expected, actual, differences);
+                path.pop();
+            }
+            if expected.len() != actual.len() {
+                differences.push((path.clone(), format!("expected {} item(s), got {}", expected.len(), actual.len())));
+            }
+        },
+        (Value::Map(expected), Value::Map(actual)) => {
+            for (key, expected) in expected {
+                path.push(PathSegment::MapKey(*key));
+                match actual.get(key) {
+                    Some(actual) => diff(path, expected, actual, differences),
+                    None => differences.push((path.clone(), format!("missing, expected {:?}", expected))),
+                };
+                path.pop();
+            }
+            for (key, actual) in actual.iter().filter(|(key, _)| !expected.contains_key(key)) {
+                path.push(PathSegment::MapKey(*key));
+                differences.push((path.clone(), format!("unexpected {:?}", actual)));
+                path.pop();
+            }
+        },
+        (Value::Object(expected), Value::Object(actual)) => {
+            let mut keys = expected.keys().chain(actual.keys().filter(|key| !expected.contains_key(*key))).collect::<Vec<_>>();
+            keys.sort();
+            for key in keys {
+                path.push(PathSegment::ObjectKey(ObjectKey::clone(key)));
+                match (expected.get(key), actual.get(key)) {
+                    (Some(expected), Some(actual)) => diff(path, expected, actual, differences),
+                    (Some(expected), None) => differences.push((path.clone(), format!("missing, expected {:?}", expected))),
+                    (None, Some(actual)) => differences.push((path.clone(), format!("unexpected {:?}", actual))),
+                    (None, None) => (),
+                };
+                path.pop();
+            }
+        },
+        (Value::Float(a), Value::Float(b)) if a.to_bits() == b.to_bits() => (),
+        (Value::Double(a), Value::Double(b)) if a.to_bits() == b.to_bits() => (),
+        (Value::Float(_), Value::Float(_)) | (Value::Double(_), Value::Double(_)) => {
+            differences.push((path.clone(), format!("expected {:?}, got {:?}", expected, actual)));
+        },
+        _ => if expected != actual {
+            differences.push((path.clone(), format!("expected {:?}, got {:?}", expected, actual)));
+        },
+    };
+}
+
+#[test]
+fn test_differences() {
+    use crate::Map;
+
+    let mut expected = crate::object();
+    expected.object_insert("list", alloc::vec![Value::U8(1), Value::Double(f64::NAN)]).unwrap();
+    expected.object_insert("map", Map::from([(1, Value::True), (2, Value::Null)])).unwrap();
+    assert_eq!(differences(&expected, &expected.clone()), None);
+
+    let mut actual = crate::object();
+    actual.object_insert("list", alloc::vec![Value::I8(1)]).unwrap();
+    actual.object_insert("map", Map::from([(1, Value::False), (3, Value::Null)])).unwrap();
+    actual.object_insert("other", Value::Null).unwrap();
+    assert_eq!(differences(&expected, &actual).unwrap(), concat!(
+        "  at $[\"list\"][0]: expected U8(1), got I8(1)\n",
+        "  at $[\"list\"]: expected 2 item(s), got 1\n",
+        "  at $[\"map\"]{1}: expected True, got False\n",
+        "  at $[\"map\"]{2}: missing, expected Null\n",
+        "  at $[\"map\"]{3}: unexpected Null\n",
+        "  at $[\"other\"]: unexpected Null\n",
+    ));
+}
@@ -0,0 +1,107 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Tracking reader
+
+use std::io::{self, BufRead, Read};
+
+/// # A reader which counts consumed bytes
+///
+/// It implements [`Read`][std::io/Read] (and [`BufRead`][std::io/BufRead] if the inner reader does), so it can be used wherever a reader is
+/// expected -- including [`Decoder`][trait:Decoder]. The count tells exactly how many bytes have been consumed, which is useful for framing
+/// and resynchronization.
+///
+/// Decoding functions never read past the end of a value, so after each [`decode()`][fn:decode] call, the count grows by the size of the
+/// decoded value.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Decoder, Encoder, TrackingReader, Value};
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// buf.encode_u8(9)?;
+/// buf.encode_text("text")?;
+///
+/// let mut reader = TrackingReader::new(&buf[..]);
+/// assert_eq!(reader.decode()?, Some(Value::U8(9)));
+/// assert_eq!(reader.count(), 2);
+/// assert_eq!(reader.decode()?, Some(Value::Text("text".into())));
+/// assert_eq!(reader.count(), buf.len() as u64);
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [std::io/Read]: https://doc.rust-lang.org/std/io/trait.Read.html
+/// [std::io/BufRead]: https://doc.rust-lang.org/std/io/trait.BufRead.html
+/// [trait:Decoder]: trait.Decoder.html
+/// [fn:decode]: trait.Decoder.html#method.decode
+#[derive(Debug)]
+pub struct TrackingReader<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R> TrackingReader<R> {
+
+    /// # Makes new instance
+    pub const fn new(inner: R) -> Self {
+        Self {
+            inner,
+            count: 0,
+        }
+    }
+
+    /// # Number of bytes consumed so far
+    pub const fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// # Resets the count to zero
+    ///
+    /// Result: the count before resetting.
+    pub fn reset_count(&mut self) -> u64 {
+        core::mem::replace(&mut self.count, 0)
+    }
+
+    /// # Gets a reference to the inner reader
+    pub const fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// # Gets a mutable reference to the inner reader
+    ///
+    /// Bytes read directly from the inner reader are not counted.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// # Unwraps the inner reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+}
+
+impl<R> Read for TrackingReader<R> where R: Read {
+
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result = self.inner.read(buf)?;
+        self.count += result as u64;
+        Ok(result)
+    }
+
+}
+
+impl<R> BufRead for TrackingReader<R> where R: BufRead {
+
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.count += amt as u64;
+    }
+
+}
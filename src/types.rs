@@ -27,8 +27,30 @@ pub type Map = BTreeMap<MapKey, Value>;
 /// # Map key
 pub type MapKey = i32;
 
+/// # Entry of a [`Map`][type:Map]
+///
+/// [type:Map]: type.Map.html
+pub type MapEntry<'a> = alloc::collections::btree_map::Entry<'a, MapKey, Value>;
+
 /// # Object
 pub type Object = BTreeMap<ObjectKey, Value>;
 
+/// # An object backed by a [`HashMap`][std::collections::HashMap]
+///
+/// This is faster than [`Object`][type:Object] for random lookups of large objects. It can be converted into a [`Value`][enum:Value], or be
+/// encoded directly via [`encode_hash_object()`][fn:encode_hash_object]. Either way, its items are encoded in order of their keys.
+///
+/// [std::collections::HashMap]: https://doc.rust-lang.org/std/collections/struct.HashMap.html
+/// [type:Object]: type.Object.html
+/// [enum:Value]: enum.Value.html
+/// [fn:encode_hash_object]: fn.encode_hash_object.html
+#[cfg(feature="std")]
+pub type HashObject = std::collections::HashMap<ObjectKey, Value>;
+
 /// # Object key
 pub type ObjectKey = String;
+
+/// # Entry of an [`Object`][type:Object]
+///
+/// [type:Object]: type.Object.html
+pub type ObjectEntry<'a> = alloc::collections::btree_map::Entry<'a, ObjectKey, Value>;
@@ -0,0 +1,318 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Validator
+
+use {
+    alloc::{
+        borrow::Cow,
+        collections::BTreeSet,
+        vec::Vec,
+    },
+    core::{
+        fmt::{self, Display, Formatter},
+        str,
+    },
+
+    crate::{
+        Result,
+        storage::Storage,
+        value::{self, DEFAULT_MAX_DEPTH},
+        value_enum::{SUB_TYPE_SIZE_MASK, read_size_at},
+    },
+};
+
+/// # A violation found by [`validate()`][fn:validate]
+///
+/// [fn:validate]: fn.validate.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Violation {
+    offset: usize,
+    msg: Cow<'static, str>,
+}
+
+impl Violation {
+
+    /// # Byte offset of the violation, from start of the buffer
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// # Message
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+
+}
+
+impl Display for Violation {
+
+    fn fmt(&self, f: &mut Formatter) -> core::result::Result<(), fmt::Error> {
+        write!(f, "at byte {}: {}", self.offset, self.msg)
+    }
+
+}
+
+/// # Report of [`validate()`][fn:validate]
+///
+/// [fn:validate]: fn.validate.html
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Report {
+    violations: Vec<Violation>,
+}
+
+impl Report {
+
+    /// # Checks if there are no violations
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// # Violations, in order of their offsets
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// # Adds a violation
+    fn add<S>(&mut self, offset: usize, msg: S) where S: Into<Cow<'static, str>> {
+        self.violations.push(Violation { offset, msg: msg.into() });
+    }
+
+}
+
+/// # Validates structural integrity of an encoded value
+///
+/// The buffer must contain exactly one value. This function walks through it without building any [`Value`][enum:Value]s, and checks that:
+///
+/// - Sizes are consistent: declared sizes of strings, blobs and containers fit inside the buffer (and their parents), and containers' items
+///   fill their declared sizes exactly.
+/// - Item counts of containers are correct.
+/// - Strings are null-terminated, and are valid UTF-8. So are object keys.
+/// - Keys of maps and objects are unique.
+/// - Types are supported.
+/// - There is no trailing data after the value.
+///
+/// Some violations -- such as a size reaching beyond the end of its parent -- make the rest of the buffer unreadable. In that case,
+/// validation stops there.
+///
+/// ## Errors
+///
+/// An error is returned if containers are nested deeper than [`DEFAULT_MAX_DEPTH`][value::DEFAULT_MAX_DEPTH].
+///
+/// ## Examples
+///
+/// ```
+/// # #[cfg(feature="std")]
+/// # fn test() -> binn_ir::IoResult<()> {
+/// use binn_ir::Encoder;
+///
+/// let mut buf = vec![];
+/// buf.encode(&binn_ir::object_from("name", "moss"))?;
+/// assert!(binn_ir::validate(&buf)?.is_valid());
+///
+/// buf.pop();
+/// let report = binn_ir::validate(&buf)?;
+/// assert_eq!(report.violations()[0].offset(), 1);
+/// # Ok(()) }
+/// # #[cfg(feature="std")]
+/// # test().unwrap();
+/// ```
+///
+/// [enum:Value]: enum.Value.html
+/// [value::DEFAULT_MAX_DEPTH]: value/constant.DEFAULT_MAX_DEPTH.html
+pub fn validate(bytes: &[u8]) -> Result<Report> {
+    let mut report = Report::default();
+    if bytes.is_empty() {
+        report.add(0, "empty buffer");
+        return Ok(report);
+    }
+
+    let mut validator = Validator { bytes, report: &mut report };
+    if let Some(end) = validator.validate_value(0, bytes.len(), 0)? {
+        if end < bytes.len() {
+            validator.report.add(end, alloc::format!("trailing data: {} byte(s)", bytes.len() - end));
+        }
+    }
+
+    Ok(report)
+}
+
+/// # Validator
+struct Validator<'a, 'b> {
+    bytes: &'a [u8],
+    report: &'b mut Report,
+}
+
+impl Validator<'_, '_> {
+
+    /// # Validates a value which starts at `offset`, and must end at or before `limit`
+    ///
+    /// Result: end offset of the value, or `None` if it could not be determined.
+    fn validate_value(&mut self, offset: usize, limit: usize, depth: usize) -> Result<Option<usize>> {
+        let type_byte = match self.bytes[..limit].get(offset) {
+            Some(type_byte) => *type_byte,
+            None => {
+                self.report.add(offset, "missing value");
+                return Ok(None);
+            },
+        };
+        let type_len = match type_byte & SUB_TYPE_SIZE_MASK {
+            0 => 1,
+            _ => 2,
+        };
+        if offset + type_len > limit {
+            self.report.add(offset, "truncated type");
+            return Ok(None);
+        }
+        let data_offset = offset + type_len;
+
+        let storage = Storage::of_type_byte(type_byte);
+        let known = type_len == 1 && match storage {
+            Storage::NoBytes => matches!(type_byte, value::NULL | value::TRUE | value::FALSE),
+            Storage::Byte => matches!(type_byte, value::U8 | value::I8),
+            Storage::Word => matches!(type_byte, value::U16 | value::I16),
+            Storage::DWord => matches!(type_byte, value::U32 | value::I32 | value::FLOAT),
+            Storage::QWord => matches!(type_byte, value::U64 | value::I64 | value::DOUBLE),
+            Storage::String => matches!(type_byte, value::TEXT | value::DATE_TIME | value::DATE | value::TIME | value::DECIMAL_STR),
+            Storage::Blob => type_byte == value::BLOB,
+            Storage::Container => matches!(type_byte, value::LIST | value::MAP | value::OBJECT),
+        };
+        if !known {
+            self.report.add(offset, alloc::format!("unsupported type: 0x{:02x}", type_byte));
+        }
+
+        if let Some(data_size) = storage.fixed_data_size() {
+            let end = data_offset + usize::from(data_size);
+            return Ok(match end <= limit {
+                true => Some(end),
+                false => {
+                    self.report.add(offset, alloc::format!("expected {} byte(s) of data, got: {}", data_size, limit - data_offset));
+                    None
+                },
+            });
+        }
+
+        let (size, size_len) = match self.read_size(data_offset, limit) {
+            Some(size) => size,
+            None => return Ok(None),
+        };
+        match storage {
+            Storage::String => {
+                let str_offset = data_offset + size_len;
+                // 1 byte for null terminator
+                let end = match str_offset.checked_add(size).map(|end| end + 1) {
+                    Some(end) if end <= limit => end,
+                    _ => {
+                        self.report.add(data_offset, alloc::format!("string size is too large: {}", size));
+                        return Ok(None);
+                    },
+                };
+                if let Err(err) = str::from_utf8(&self.bytes[str_offset..end - 1]) {
+                    self.report.add(str_offset + err.valid_up_to(), "invalid UTF-8");
+                }
+                if self.bytes[end - 1] != 0 {
+                    self.report.add(end - 1, alloc::format!("expected null terminator, got: 0x{:02x}", self.bytes[end - 1]));
+                }
+                Ok(Some(end))
+            },
+            Storage::Blob => match data_offset.checked_add(size_len + size) {
+                Some(end) if end <= limit => Ok(Some(end)),
+                _ => {
+                    self.report.add(data_offset, alloc::format!("blob size is too large: {}", size));
+                    Ok(None)
+                },
+            },
+            _ => {
+                // Container's size includes its type and the size itself
+                let end = match offset.checked_add(size) {
+                    Some(end) if end <= limit => end,
+                    _ => {
+                        self.report.add(data_offset, alloc::format!("container size is too large: {}", size));
+                        return Ok(None);
+                    },
+                };
+                if size < type_len + size_len {
+                    self.report.add(data_offset, alloc::format!("container size is too small: {}", size));
+                    return Ok(None);
+                }
+                if known {
+                    self.validate_container_items(type_byte, data_offset + size_len, end, depth)?;
+                }
+                Ok(Some(end))
+            },
+        }
+    }
+
+    /// # Validates items of a container, which start at `offset` (with item count), and must end exactly at `end`
+    fn validate_container_items(&mut self, type_byte: u8, offset: usize, end: usize, depth: usize) -> Result<()> {
+        if depth >= DEFAULT_MAX_DEPTH {
+            return Err(err_code!("containers are nested too deeply, at byte", offset));
+        }
+
+        let (item_count, item_count_len) = match self.read_size(offset, end) {
+            Some(item_count) => item_count,
+            None => return Ok(()),
+        };
+
+        let mut map_keys = BTreeSet::new();
+        let mut object_keys = BTreeSet::new();
+        let mut offset = offset + item_count_len;
+        let mut actual_count: usize = 0;
+        while offset < end {
+            match type_byte {
+                value::MAP => match self.bytes[..end].get(offset..offset + 4) {
+                    Some(key) => {
+                        let key = i32::from_be_bytes([key[0], key[1], key[2], key[3]]);
+                        if !map_keys.insert(key) {
+                            self.report.add(offset, alloc::format!("duplicate key: {}", key));
+                        }
+                        offset += 4;
+                    },
+                    None => {
+                        self.report.add(offset, "truncated map key");
+                        return Ok(());
+                    },
+                },
+                value::OBJECT => {
+                    let key_len = usize::from(self.bytes[offset]);
+                    match self.bytes[..end].get(offset + 1..offset + 1 + key_len) {
+                        Some(key) => match str::from_utf8(key) {
+                            Ok(key) => if !object_keys.insert(key) {
+                                self.report.add(offset, alloc::format!("duplicate key: {:?}", key));
+                            },
+                            Err(err) => self.report.add(offset + 1 + err.valid_up_to(), "invalid UTF-8 in object key"),
+                        },
+                        None => {
+                            self.report.add(offset, "truncated object key");
+                            return Ok(());
+                        },
+                    };
+                    offset += 1 + key_len;
+                },
+                _ => (),
+            };
+
+            offset = match self.validate_value(offset, end, depth + 1)? {
+                Some(value_end) => value_end,
+                None => return Ok(()),
+            };
+            actual_count += 1;
+        }
+
+        if actual_count != item_count {
+            self.report.add(end, alloc::format!("item count is declared: {}, but got: {}", item_count, actual_count));
+        }
+        Ok(())
+    }
+
+    /// # Reads a size (or an item count), which takes 1 or 4 bytes
+    ///
+    /// Result: the size, and its length.
+    fn read_size(&mut self, offset: usize, limit: usize) -> Option<(usize, usize)> {
+        let result = read_size_at(&self.bytes[..limit], offset);
+        if result.is_none() {
+            self.report.add(offset, "truncated size");
+        }
+        result
+    }
+
+}
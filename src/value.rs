@@ -164,4 +164,14 @@ pub const OBJECT: u8 = 0b_1110_0010;
 pub const OBJECT_KEY_MAX_LEN: usize = 255;
 
 /// # Max data size, in bytes
-pub const MAX_DATA_SIZE: Size = i32::max_value() as Size;
+pub const MAX_DATA_SIZE: Size = i32::MAX as Size;
+
+/// # Default max nesting depth of containers
+///
+/// Decoders return an error if containers are nested deeper than this. A top-level container has depth 1.
+///
+/// For [`decode_with_config()`][fn:decode_with_config], this can be changed via [`DecodeConfig::max_depth`][DecodeConfig#max_depth].
+///
+/// [fn:decode_with_config]: ../fn.decode_with_config.html
+/// [DecodeConfig#max_depth]: ../struct.DecodeConfig.html#structfield.max_depth
+pub const DEFAULT_MAX_DEPTH: usize = 128;
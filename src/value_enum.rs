@@ -11,8 +11,9 @@ use {
     },
 
     crate::{
-        Blob, List, Map, Object, Result, Size,
+        Blob, List, Map, Object, ObjectKey, Result, Size,
         cmp::CmpTo,
+        storage::Storage,
         value::{MAX_DATA_SIZE, OBJECT_KEY_MAX_LEN},
     },
 };
@@ -20,19 +21,29 @@ use {
 #[cfg(feature="std")]
 use {
     alloc::vec::Vec,
+    core::str,
     std::io::{self, ErrorKind, Read, Write},
 
-    crate::IoResult,
+    crate::{
+        CompatLevel, DecodeConfig, DepthLimitExceeded, EncodeConfig, IoResult, MapKey, PathSegment, TrackingReader,
+        decode_error::locate,
+    },
 };
 
 mod impls;
+pub(crate) mod temporal;
+pub(crate) mod ordering;
 
-const MAX_I8_AS_USIZE: usize = i8::max_value() as usize;
-const MAX_I8_AS_U32: Size = i8::max_value() as Size;
+const MAX_I8_AS_USIZE: usize = i8::MAX as usize;
+pub(crate) const MAX_I8_AS_U32: Size = i8::MAX as Size;
 
 /// # Size mask
-#[cfg(feature="std")]
-const SIZE_MASK: Size = 0x_8000_0000;
+pub(crate) const SIZE_MASK: Size = 0x_8000_0000;
+
+/// # Sub-type size mask
+///
+/// If this bit is set in the first byte of a type, the type uses 2 bytes.
+pub(crate) const SUB_TYPE_SIZE_MASK: u8 = 0b_0001_0000;
 
 /// # Values
 ///
@@ -355,18 +366,6 @@ macro_rules! read_int_be { ($ty: ty, $source: ident) => {{
     $source.read_exact(&mut buf).map(|()| <$ty>::from_be_bytes(buf))
 }};}
 
-/// # Writes size (u32) into the stream
-///
-/// Result: number of bytes written - `IoResult<Size>`.
-#[cfg(feature="std")]
-macro_rules! write_size { ($size: expr, $stream: ident) => {{
-    let size = $size;
-    match size > MAX_I8_AS_U32 {
-        true => write_int_be!(size | SIZE_MASK, $stream),
-        false => write_int_be!(size as u8, $stream),
-    }
-}};}
-
 /// # Reads size from source
 ///
 /// Result:
@@ -374,23 +373,69 @@ macro_rules! write_size { ($size: expr, $stream: ident) => {{
 /// - First value is size.
 /// - Second value is total bytes read (the 'length' of first value).
 #[cfg(feature="std")]
-fn read_size_and_its_length<R>(source: &mut R) -> IoResult<(Size, Size)> where R: Read {
+pub(crate) fn read_size_and_its_length<R>(source: &mut R) -> IoResult<(Size, Size)> where R: Read {
     let first_byte = read_int_be!(u8, source)?;
     match first_byte & 0b_1000_0000 {
         0b_1000_0000 => {
             let mut buf = [first_byte, 0, 0, 0];
-            source.read_exact(&mut buf[1..]).and_then(|()|
-                Ok((Size::from_be_bytes(buf) & !(SIZE_MASK), mem::size_of::<Size>() as Size))
-            )
+            source.read_exact(&mut buf[1..]).map(|()| (Size::from_be_bytes(buf) & !(SIZE_MASK), mem::size_of::<Size>() as Size))
         },
         _ => Ok((Size::from(first_byte), mem::size_of::<u8>() as Size)),
     }
 }
 
+/// # Reads size (or item count) at an offset of some bytes
+///
+/// Result: the size, and its length (1 or 4 bytes). `None` if the size is truncated.
+pub(crate) fn read_size_at(bytes: &[u8], offset: usize) -> Option<(usize, usize)> {
+    match bytes.get(offset) {
+        Some(b) if Size::from(*b) <= MAX_I8_AS_U32 => Some((usize::from(*b), 1)),
+        Some(_) => bytes.get(offset..offset + 4).map(|b| ((Size::from_be_bytes([b[0], b[1], b[2], b[3]]) & !SIZE_MASK) as usize, 4)),
+        None => None,
+    }
+}
+
+/// # Reads a map key from source
+#[cfg(feature="std")]
+pub(crate) fn read_map_key<R>(source: &mut R) -> IoResult<MapKey> where R: Read {
+    read_int_be!(MapKey, source)
+}
+
+/// # Decodes a value of fixed size, whose header has been read
+///
+/// `storage` should be one of `NoBytes`, `Byte`, `Word`, `DWord` and `QWord`.
+#[cfg(feature="std")]
+pub(crate) fn decode_fixed_value<R>(type_byte: u8, storage: Storage, source: &mut R) -> IoResult<Value> where R: Read {
+    // Fixed size values are decoded as usual, from a small buffer
+    let mut buf = [type_byte, 0, 0, 0, 0, 0, 0, 0, 0];
+    let len = 1 + storage.fixed_data_size().unwrap_or_default() as usize;
+    source.read_exact(&mut buf[1..len])?;
+    crate::decode(&mut &buf[..len])?.ok_or_else(|| io::Error::new(ErrorKind::UnexpectedEof, __!()))
+}
+
+/// # Adds some bytes to total bytes read of a container, and verifies the result against container's declared size
+#[cfg(feature="std")]
+pub(crate) fn add_to_read(read: &mut u64, bytes: u64, size: Size) -> IoResult<()> {
+    *read += bytes;
+    match *read > u64::from(size) {
+        true => Err(io::Error::new(ErrorKind::InvalidData, __!("expected to read at most {} bytes, got: {}", &size, read))),
+        false => Ok(()),
+    }
+}
+
+/// # Verifies total bytes read of a container -- including its header -- against its declared size
+#[cfg(feature="std")]
+pub(crate) fn verify_container_size(read: u64, size: Size) -> IoResult<()> {
+    match read == u64::from(size) {
+        true => Ok(()),
+        false => Err(io::Error::new(ErrorKind::InvalidData, __!("size is declared: {}; but decoded: {}", &size, &read))),
+    }
+}
+
 /// # Reads size from source
 #[cfg(feature="std")]
 fn read_size<R>(source: &mut R) -> IoResult<Size> where R: Read {
-    read_size_and_its_length(source).and_then(|(size, _)| Ok(size))
+    read_size_and_its_length(source).map(|(size, _)| size)
 }
 
 #[test]
@@ -399,7 +444,7 @@ fn test_read_size_and_its_length() {
     use ::std::io::Cursor;
 
     const U32_SIZE: Size = mem::size_of::<Size>() as Size;
-    const MAX_U8: u8 = ::std::u8::MAX;
+    const MAX_U8: u8 = u8::MAX;
 
     assert_eq!(read_size_and_its_length(&mut Cursor::new(alloc::vec![MAX_U8, MAX_U8, MAX_U8, MAX_U8])).unwrap(), (MAX_DATA_SIZE, U32_SIZE));
 
@@ -430,13 +475,13 @@ macro_rules! sum {
                 result = {
                     let b = $b;
                     match b.cmp_to(&MAX_DATA_SIZE) {
-                        Ordering::Greater => Err(err!("too large for: {} + {} (max allowed: {})", &current, &b, MAX_DATA_SIZE)),
+                        Ordering::Greater => Err(err_code!("too large to add (bytes)", b)),
                         _ => match current.checked_add(b as Size) {
                             Some(new) => match new.cmp_to(&MAX_DATA_SIZE) {
-                                Ordering::Greater => Err(err!("too large for: {} + {} (max allowed: {})", &current, &b, MAX_DATA_SIZE)),
+                                Ordering::Greater => Err(err_code!("sum is too large (bytes)", new)),
                                 _ => Ok(new),
                             },
-                            None => Err(err!("can't add {} into {}", &b, &current)),
+                            None => Err(err_code!("sum overflows, after adding", b)),
                         },
                     }
                 };
@@ -455,8 +500,8 @@ macro_rules! new_vec_with_capacity { ($capacity: expr) => {{
     let capacity = $capacity;
     match capacity.cmp_to(&MAX_DATA_SIZE) {
         Ordering::Greater => Err(err!("cannot allocate a vector with capacity: {} (max allowed: {})", &capacity, MAX_DATA_SIZE)),
-        _ => match capacity.cmp_to(&usize::max_value()) {
-            Ordering::Greater => Err(err!("cannot allocate a vector with capacity: {} (max allowed: {})", &capacity, ::std::usize::MAX)),
+        _ => match capacity.cmp_to(&usize::MAX) {
+            Ordering::Greater => Err(err!("cannot allocate a vector with capacity: {} (max allowed: {})", &capacity, usize::MAX)),
             _ => Ok(Vec::with_capacity(capacity as usize)),
         },
     }
@@ -486,15 +531,46 @@ macro_rules! read_into_new_vec { ($len: expr, $source: ident) => {{
 ///
 /// Returns: `IoResult<String>`
 #[cfg(feature="std")]
-macro_rules! read_str { ($source: ident) => {{
+macro_rules! read_str { ($source: ident, $limits: ident) => {{
     // Note that null terminator does NOT count
-    let buf = read_into_new_vec!(read_size_and_its_length($source)?.0, $source)?;
+    let size = read_size(&mut *$source)?;
+    $limits.check_bytes($source.count(), u64::from(size) + 1)?;
+    let buf = read_into_new_vec!(size, $source)?;
     match read_int_be!(u8, $source)? {
         0 => String::from_utf8(buf).map_err(|err| io::Error::new(ErrorKind::InvalidData, __!("failed to decode UTF-8: {}", &err))),
         other => Err(io::Error::new(ErrorKind::InvalidData, __!("expected to read a null terminator ('\\0'), got: {}", &other))),
     }
 }};}
 
+/// # Reads an object key from source
+///
+/// Result:
+///
+/// - First value is the key.
+/// - Second value is total bytes read.
+#[cfg(feature="std")]
+pub(crate) fn read_object_key<R>(source: &mut R) -> IoResult<(ObjectKey, Size)> where R: Read {
+    let mut buf = [0_u8; OBJECT_KEY_MAX_LEN];
+    let (key, bytes_of_key) = read_object_key_into(source, &mut buf)?;
+    Ok((ObjectKey::from(key), bytes_of_key))
+}
+
+/// # Reads an object key from source, into a buffer
+///
+/// Result:
+///
+/// - First value is the key, borrowed from the buffer.
+/// - Second value is total bytes read.
+#[cfg(feature="std")]
+pub(crate) fn read_object_key_into<'a, R>(source: &mut R, buf: &'a mut [u8; OBJECT_KEY_MAX_LEN]) -> IoResult<(&'a str, Size)> where R: Read {
+    // Key length always takes 1 byte, and there's NO null terminator
+    let key_len = read_int_be!(u8, source)?;
+    let buf = &mut buf[..usize::from(key_len)];
+    source.read_exact(buf)?;
+    let key = str::from_utf8(buf).map_err(|err| io::Error::new(ErrorKind::InvalidData, __!("failed to decode UTF-8: {}", &err)))?;
+    Ok((key, sum!(Size::from(key_len), 1)?))
+}
+
 /// # Calculates bytes needed for a length
 ///
 /// Result: `Result<Size>`
@@ -502,7 +578,7 @@ macro_rules! bytes_for_len { ($len: expr) => {{
     let len = $len;
     match len.cmp_to(&MAX_I8_AS_USIZE) {
         Ordering::Greater => match len.cmp_to(&MAX_DATA_SIZE) {
-            Ordering::Greater => Err(err!("too large: {} bytes", &len)),
+            Ordering::Greater => Err(err_code!("too large (bytes)", len)),
             _ => Ok(4_u32),
         },
         _ => Ok(1_u32),
@@ -513,8 +589,11 @@ macro_rules! bytes_for_len { ($len: expr) => {{
 ///
 /// Returns: `IoResult<Option<Value>>`
 #[cfg(feature="std")]
-macro_rules! decode_list { ($source: ident) => {{
+macro_rules! decode_list { ($source: ident, $config: ident, $limits: ident) => {{
+    $limits.enter_container()?;
     let (size, bytes_of_size) = read_size_and_its_length($source)?;
+    // The type byte and the size have been read
+    $limits.check_bytes($source.count(), u64::from(size).saturating_sub(u64::from(bytes_of_size) + 1))?;
     // 1 byte for header; at least 1 byte for size; at least 1 byte for item count
     if size < 3 {
         return Err(io::Error::new(ErrorKind::InvalidData, __!("invalid declared size: {}", &size)));
@@ -525,7 +604,8 @@ macro_rules! decode_list { ($source: ident) => {{
     let mut result = alloc::vec![];
     let mut read: Size = sum!(bytes_of_size, bytes_of_item_count)?;
     for item_index in 0..item_count {
-        let value = match crate::decode($source)? {
+        let value = decode_value_in(None, $source, $config, $limits).map_err(|err| locate(err, $source.count(), Some(PathSegment::Index(item_index))))?;
+        let value = match value {
             Some(value) => value,
             None => return Err(io::Error::new(ErrorKind::InvalidData, __!("missing item #{}/{}", &item_index, &item_count))),
         };
@@ -539,6 +619,8 @@ macro_rules! decode_list { ($source: ident) => {{
         result.push(value);
     }
 
+    $limits.leave_container();
+
     // Verify total read (1 byte for header)
     match read.checked_add(1) {
         Some(v) if v == size => Ok(Some(Value::List(result))),
@@ -550,8 +632,11 @@ macro_rules! decode_list { ($source: ident) => {{
 ///
 /// Returns: `IoResult<Option<Value>>`
 #[cfg(feature="std")]
-macro_rules! decode_map { ($source: ident) => {{
+macro_rules! decode_map { ($source: ident, $config: ident, $limits: ident) => {{
+    $limits.enter_container()?;
     let (size, bytes_of_size) = read_size_and_its_length($source)?;
+    // The type byte and the size have been read
+    $limits.check_bytes($source.count(), u64::from(size).saturating_sub(u64::from(bytes_of_size) + 1))?;
     // 1 byte for header; at least 1 byte for size; at least 1 byte for item count
     if size < 3 {
         return Err(io::Error::new(ErrorKind::InvalidData, __!("invalid declared size: {}", &size)));
@@ -563,7 +648,8 @@ macro_rules! decode_map { ($source: ident) => {{
     let mut read: Size = sum!(bytes_of_size, bytes_of_item_count)?;
     for _ in 0..item_count {
         let key = read_int_be!(i32, $source)?;
-        let value = match crate::decode($source)? {
+        let value = decode_value_in(None, $source, $config, $limits).map_err(|err| locate(err, $source.count(), Some(PathSegment::MapKey(key))))?;
+        let value = match value {
             Some(value) => value,
             None => return Err(io::Error::new(ErrorKind::InvalidData, __!("missing value for key {}", &key))),
         };
@@ -581,6 +667,8 @@ macro_rules! decode_map { ($source: ident) => {{
         }
     }
 
+    $limits.leave_container();
+
     // Verify total read (1 byte for header)
     match read.checked_add(1) {
         Some(v) if v == size => Ok(Some(Value::Map(result))),
@@ -592,8 +680,11 @@ macro_rules! decode_map { ($source: ident) => {{
 ///
 /// Returns: `IoResult<Option<Value>>`
 #[cfg(feature="std")]
-macro_rules! decode_object { ($source: ident) => {{
+macro_rules! decode_object { ($source: ident, $config: ident, $limits: ident) => {{
+    $limits.enter_container()?;
     let (size, bytes_of_size) = read_size_and_its_length($source)?;
+    // The type byte and the size have been read
+    $limits.check_bytes($source.count(), u64::from(size).saturating_sub(u64::from(bytes_of_size) + 1))?;
     // 1 byte for header; at least 1 byte for size; at least 1 byte for item count
     if size < 3 {
         return Err(io::Error::new(ErrorKind::InvalidData, __!("invalid declared size: {}", &size)));
@@ -603,30 +694,28 @@ macro_rules! decode_object { ($source: ident) => {{
 
     let mut result = Object::new();
     let mut read: Size = sum!(bytes_of_size, bytes_of_item_count)?;
+    let mut key_buf = [0_u8; OBJECT_KEY_MAX_LEN];
     for _ in 0..item_count {
-        // Read key (note that there's NO null terminator)
-        let (key_len, bytes_of_key_len) = read_size_and_its_length($source)?;
-        match key_len.cmp_to(&OBJECT_KEY_MAX_LEN) {
-            Ordering::Greater => return Err(io::Error::new(
-                ErrorKind::InvalidData, __!("key length is limited to {} bytes, got: {}", OBJECT_KEY_MAX_LEN, key_len)
-            )),
-            _ => read = match read.checked_add(sum!(bytes_of_key_len, key_len)?) {
-                Some(v) => match size.cmp_to(&v) {
-                    Ordering::Greater => v,
-                    _ => return Err(io::Error::new(ErrorKind::InvalidData, __!("expected to read less than {} bytes, got: {}", &size, &v))),
-                },
-                None => return Err(io::Error::new(
-                    ErrorKind::InvalidData,
-                    __!("invalid object size -> expected: {}, current: {}, new key length: {} + {}", &size, &read, &bytes_of_key_len, &key_len)
-                )),
+        // Read key
+        let (key, bytes_of_key) = read_object_key_into($source, &mut key_buf)?;
+        let key = ObjectKey::from(key);
+        if key.is_empty() && $config.rejects_empty_object_keys() {
+            return Err(io::Error::new(ErrorKind::InvalidData, __!("empty object key")));
+        }
+        read = match read.checked_add(bytes_of_key) {
+            Some(v) => match size.cmp_to(&v) {
+                Ordering::Greater => v,
+                _ => return Err(io::Error::new(ErrorKind::InvalidData, __!("expected to read less than {} bytes, got: {}", &size, &v))),
             },
+            None => return Err(io::Error::new(
+                ErrorKind::InvalidData, __!("invalid object size -> expected: {}, current: {}, new key: {:?}", &size, &read, &key)
+            )),
         };
-        let key = String::from_utf8(read_into_new_vec!(key_len, $source)?).map_err(|err|
-            io::Error::new(ErrorKind::InvalidData, __!("failed to decode UTF-8: {}", &err))
-        )?;
 
         // Read value
-        let value = match crate::decode($source)? {
+        let value = decode_value_in(None, $source, $config, $limits)
+            .map_err(|err| locate(err, $source.count(), Some(PathSegment::ObjectKey(key.clone()))))?;
+        let value = match value {
             Some(value) => value,
             None => return Err(io::Error::new(ErrorKind::InvalidData, __!("missing value for key {:?}", &key))),
         };
@@ -644,6 +733,8 @@ macro_rules! decode_object { ($source: ident) => {{
         }
     }
 
+    $limits.leave_container();
+
     // Verify total read (1 byte for header)
     match read.checked_add(1) {
         Some(v) if v == size => Ok(Some(Value::Object(result))),
@@ -653,6 +744,20 @@ macro_rules! decode_object { ($source: ident) => {{
 
 impl Value {
 
+    /// # Gets storage of this value
+    pub fn storage(&self) -> Storage {
+        match self {
+            Value::Null | Value::True | Value::False => Storage::NoBytes,
+            Value::U8(_) | Value::I8(_) => Storage::Byte,
+            Value::U16(_) | Value::I16(_) => Storage::Word,
+            Value::U32(_) | Value::I32(_) | Value::Float(_) => Storage::DWord,
+            Value::U64(_) | Value::I64(_) | Value::Double(_) => Storage::QWord,
+            Value::Text(_) | Value::DateTime(_) | Value::Date(_) | Value::Time(_) | Value::DecimalStr(_) => Storage::String,
+            Value::Blob(_) => Storage::Blob,
+            Value::List(_) | Value::Map(_) | Value::Object(_) => Storage::Container,
+        }
+    }
+
     /// # Calculates size of this value
     pub fn size(&self) -> Result<Size> {
         match self {
@@ -670,17 +775,9 @@ impl Value {
             Value::I64(_) => Ok(9),
             Value::Double(_) => Ok(9),
             // 1 byte for type, 1 byte for null terminator
-            Value::Text(t) => sum!(bytes_for_len!(t.len())?, 2, t.len()),
-            // 1 byte for type, 1 byte for null terminator
-            Value::DateTime(dt) => sum!(bytes_for_len!(dt.len())?, 2, dt.len()),
-            // 1 byte for type, 1 byte for null terminator
-            Value::Date(d) => sum!(bytes_for_len!(d.len())?, 2, d.len()),
-            // 1 byte for type, 1 byte for null terminator
-            Value::Time(t) => sum!(bytes_for_len!(t.len())?, 2, t.len()),
-            // 1 byte for type, 1 byte for null terminator
-            Value::DecimalStr(ds) => sum!(bytes_for_len!(ds.len())?, 2, ds.len()),
+            Value::Text(s) | Value::DateTime(s) | Value::Date(s) | Value::Time(s) | Value::DecimalStr(s) => size_of_data(s.len(), 2),
             // 1 byte for type
-            Value::Blob(bytes) => sum!(bytes_for_len!(bytes.len())?, 1, bytes.len()),
+            Value::Blob(bytes) => size_of_data(bytes.len(), 1),
             Value::List(list) => size_of_list(list),
             Value::Map(map) => size_of_map(map),
             Value::Object(object) => size_of_object(object),
@@ -718,6 +815,47 @@ impl Value {
         }
     }
 
+    /// # Encodes this value into a stream, with a compatibility level
+    ///
+    /// Returns the number of bytes written.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::{CompatLevel, Value};
+    ///
+    /// # fn test() -> binn_ir::IoResult<()> {
+    /// let value = Value::List(vec![Value::I64(1), Value::U32(300), Value::I32(-1)]);
+    ///
+    /// let mut buf = vec![];
+    /// value.encode_with_compat(&mut buf, CompatLevel::CBinn)?;
+    /// assert_eq!(buf, [0xe0, 0x0a, 0x03, 0x20, 0x01, 0x40, 0x01, 0x2c, 0x21, 0xff]);
+    /// # Ok(()) }
+    /// # test().unwrap();
+    /// ```
+    #[cfg(feature="std")]
+    pub fn encode_with_compat<W>(&self, stream: &mut W, compat: CompatLevel) -> IoResult<Size> where W: Write {
+        match compat {
+            CompatLevel::Spec => self.encode(stream),
+            CompatLevel::CBinn => crate::compat_level::C_BINN.encode(self, stream),
+        }
+    }
+
+    /// # Encodes this value into a stream, with a config
+    ///
+    /// Returns the number of bytes written.
+    ///
+    /// See [`EncodeConfig`][struct:EncodeConfig] for an example.
+    ///
+    /// [struct:EncodeConfig]: struct.EncodeConfig.html
+    #[cfg(feature="std")]
+    pub fn encode_with_config<W>(&self, stream: &mut W, config: &EncodeConfig) -> IoResult<Size> where W: Write {
+        match config.is_default() {
+            true => self.encode(stream),
+            false => config.encode(self, stream),
+        }
+    }
+
 }
 
 /// # Decodes a value from source
@@ -727,6 +865,90 @@ impl Value {
 /// If `filter` is `None`, the function decodes any value from source.
 #[cfg(feature="std")]
 pub(crate) fn decode_value<R>(filter: Option<&[u8]>, source: &mut R) -> IoResult<Option<Value>> where R: Read {
+    decode_value_with_config(filter, source, &DecodeConfig::new())
+}
+
+/// # Decodes a value from source, with a config
+///
+/// See `decode_value()`.
+///
+/// Errors carry a `DecodeError`, which tells where the failure occurred.
+#[cfg(feature="std")]
+pub(crate) fn decode_value_with_config<R>(filter: Option<&[u8]>, source: &mut R, config: &DecodeConfig) -> IoResult<Option<Value>>
+where R: Read {
+    let mut source = TrackingReader::new(source);
+    decode_value_in(filter, &mut source, config, &mut Limits::new(config)).map_err(|err| locate(err, source.count(), None))
+}
+
+/// # Limits of a decode call
+#[cfg(feature="std")]
+struct Limits {
+    max_values: Option<u64>,
+    max_bytes: Option<u64>,
+    max_depth: usize,
+    values: u64,
+    depth: usize,
+}
+
+#[cfg(feature="std")]
+impl Limits {
+
+    /// # Makes new instance
+    const fn new(config: &DecodeConfig) -> Self {
+        Self {
+            max_values: config.max_values,
+            max_bytes: config.max_bytes,
+            max_depth: config.max_depth,
+            values: 0,
+            depth: 0,
+        }
+    }
+
+    /// # Spends one value
+    fn spend_value(&mut self) -> IoResult<()> {
+        self.values += 1;
+        match self.max_values {
+            Some(max_values) if self.values > max_values => Err(io::Error::new(
+                ErrorKind::InvalidData, __!("decode budget exceeded: more than {} value(s)", max_values),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// # Checks if some upcoming bytes fit into the budget, after `consumed` bytes
+    fn check_bytes(&self, consumed: u64, upcoming: u64) -> IoResult<()> {
+        match self.max_bytes {
+            Some(max_bytes) if consumed.saturating_add(upcoming) > max_bytes => Err(io::Error::new(
+                ErrorKind::InvalidData, __!("decode budget exceeded: more than {} byte(s)", max_bytes),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// # Enters a container
+    fn enter_container(&mut self) -> IoResult<()> {
+        match self.depth < self.max_depth {
+            true => {
+                self.depth += 1;
+                Ok(())
+            },
+            false => Err(DepthLimitExceeded::new_io_error(self.max_depth)),
+        }
+    }
+
+    /// # Leaves a container
+    fn leave_container(&mut self) {
+        self.depth -= 1;
+    }
+
+}
+
+/// # Decodes a value from a tracking reader
+///
+/// Errors from containers' items are located (with their paths). Other errors are left for `decode_value_with_config()` to locate.
+#[cfg(feature="std")]
+fn decode_value_in<R>(filter: Option<&[u8]>, source: &mut TrackingReader<R>, config: &DecodeConfig, limits: &mut Limits)
+-> IoResult<Option<Value>> where R: Read {
     let source_value = match read_int_be!(u8, source) {
         Ok(source_value) => source_value,
         Err(err) => return match err.kind() {
@@ -736,11 +958,16 @@ pub(crate) fn decode_value<R>(filter: Option<&[u8]>, source: &mut R) -> IoResult
     };
 
     if let Some(ref expected_values) = filter {
-        if expected_values.contains(&source_value) == false {
+        if !expected_values.contains(&source_value) {
             return Err(io::Error::new(ErrorKind::InvalidData, __!("expected one of: {:?}, got: {}", &expected_values, &source_value)));
         }
     }
 
+    limits.spend_value()?;
+    if let Some(data_size) = Storage::of_type_byte(source_value).fixed_data_size() {
+        limits.check_bytes(source.count(), u64::from(data_size))?;
+    }
+
     match source_value {
         crate::value::NULL => Ok(Some(Value::Null)),
         crate::value::TRUE => Ok(Some(Value::True)),
@@ -755,93 +982,266 @@ pub(crate) fn decode_value<R>(filter: Option<&[u8]>, source: &mut R) -> IoResult
         crate::value::U64 => Ok(Some(Value::U64(read_int_be!(u64, source)?))),
         crate::value::I64 => Ok(Some(Value::I64(read_int_be!(i64, source)?))),
         crate::value::DOUBLE => Ok(Some(Value::Double(f64::from_bits(read_int_be!(u64, source)?)))),
-        crate::value::TEXT => Ok(Some(Value::Text(read_str!(source)?))),
-        crate::value::DATE_TIME => Ok(Some(Value::DateTime(read_str!(source)?))),
-        crate::value::DATE => Ok(Some(Value::Date(read_str!(source)?))),
-        crate::value::TIME => Ok(Some(Value::Time(read_str!(source)?))),
-        crate::value::DECIMAL_STR => Ok(Some(Value::DecimalStr(read_str!(source)?))),
-        crate::value::BLOB => Ok(Some(Value::Blob(read_into_new_vec!(read_size(source)?, source)?))),
-        crate::value::LIST => decode_list!(source),
-        crate::value::MAP => decode_map!(source),
-        crate::value::OBJECT => decode_object!(source),
-        _ => Err(io::Error::new(ErrorKind::InvalidData, __!("data type is either invalid or not supported: {}", &source_value))),
+        crate::value::TEXT => Ok(Some(Value::Text(read_str_in(source, limits)?))),
+        crate::value::DATE_TIME => validate_temporal(Value::DateTime(read_str_in(source, limits)?), config),
+        crate::value::DATE => validate_temporal(Value::Date(read_str_in(source, limits)?), config),
+        crate::value::TIME => validate_temporal(Value::Time(read_str_in(source, limits)?), config),
+        crate::value::DECIMAL_STR => validate_decimal_str(Value::DecimalStr(read_str_in(source, limits)?), config),
+        crate::value::BLOB => Ok(Some(Value::Blob(read_blob_in(source, limits)?))),
+        crate::value::LIST => decode_list_in(source, config, limits),
+        crate::value::MAP => decode_map_in(source, config, limits),
+        crate::value::OBJECT => decode_object_in(source, config, limits),
+        _ => {
+            // Skip the value, so that callers can still continue decoding next values
+            skip_value_of_unknown_type(source_value, source)?;
+            Err(io::Error::new(ErrorKind::InvalidData, __!("data type is either invalid or not supported: {}", &source_value)))
+        },
+    }
+}
+
+/// # Reads a string from a tracking reader
+///
+/// Strings, blobs and containers are decoded in separate functions, to keep stack frames of `decode_value_in()` small. Otherwise deeply
+/// nested containers could overflow the stack, with unoptimized builds.
+#[cfg(feature="std")]
+fn read_str_in<R>(source: &mut TrackingReader<R>, limits: &mut Limits) -> IoResult<String> where R: Read {
+    read_str!(source, limits)
+}
+
+/// # Reads a blob from a tracking reader
+#[cfg(feature="std")]
+fn read_blob_in<R>(source: &mut TrackingReader<R>, limits: &mut Limits) -> IoResult<Blob> where R: Read {
+    let size = read_size(source)?;
+    limits.check_bytes(source.count(), u64::from(size))?;
+    read_into_new_vec!(size, source)
+}
+
+/// # Decodes a list from a tracking reader
+#[cfg(feature="std")]
+fn decode_list_in<R>(source: &mut TrackingReader<R>, config: &DecodeConfig, limits: &mut Limits) -> IoResult<Option<Value>> where R: Read {
+    decode_list!(source, config, limits)
+}
+
+/// # Decodes a map from a tracking reader
+#[cfg(feature="std")]
+fn decode_map_in<R>(source: &mut TrackingReader<R>, config: &DecodeConfig, limits: &mut Limits) -> IoResult<Option<Value>> where R: Read {
+    decode_map!(source, config, limits)
+}
+
+/// # Decodes an object from a tracking reader
+#[cfg(feature="std")]
+fn decode_object_in<R>(source: &mut TrackingReader<R>, config: &DecodeConfig, limits: &mut Limits) -> IoResult<Option<Value>> where R: Read {
+    decode_object!(source, config, limits)
+}
+
+/// # Validates a temporal value, if the config requires so
+#[cfg(feature="std")]
+fn validate_temporal(value: Value, config: &DecodeConfig) -> IoResult<Option<Value>> {
+    if config.validates_temporal() {
+        value.validate_temporal().map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
+    }
+    Ok(Some(value))
+}
+
+/// # Validates a decimal string, if the config requires so
+#[cfg(feature="std")]
+fn validate_decimal_str(value: Value, config: &DecodeConfig) -> IoResult<Option<Value>> {
+    if config.validates_decimal_str() {
+        value.validate_decimal_str().map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
     }
+    Ok(Some(value))
+}
+
+/// # Skips a value of unknown type
+///
+/// `type_byte` is the first byte of value's type, which has been read from source. Data length is computed by value's storage.
+#[cfg(feature="std")]
+fn skip_value_of_unknown_type<R>(type_byte: u8, source: &mut R) -> IoResult<()> where R: Read {
+    let mut bytes_of_type: Size = 1;
+    if type_byte & SUB_TYPE_SIZE_MASK != 0 {
+        read_int_be!(u8, source)?;
+        bytes_of_type += 1;
+    }
+
+    let storage = Storage::of_type_byte(type_byte);
+    let len = match storage.fixed_data_size() {
+        Some(len) => u64::from(len),
+        None => match storage {
+            // Null terminator does NOT count
+            Storage::String => u64::from(read_size(source)?) + 1,
+            Storage::Blob => u64::from(read_size(source)?),
+            // Container's size includes its type and the size itself
+            _ => {
+                let (size, bytes_of_size) = read_size_and_its_length(source)?;
+                match size.checked_sub(sum!(bytes_of_type, bytes_of_size)?) {
+                    Some(len) => u64::from(len),
+                    None => return Err(io::Error::new(ErrorKind::InvalidData, __!("invalid declared size: {}", &size))),
+                }
+            },
+        },
+    };
+
+    skip_bytes(len, source)
+}
+
+/// # Skips some bytes from source
+#[cfg(feature="std")]
+pub(crate) fn skip_bytes<R>(len: u64, source: &mut R) -> IoResult<()> where R: Read {
+    match io::copy(&mut source.take(len), &mut io::sink())? {
+        skipped if skipped == len => Ok(()),
+        skipped => Err(io::Error::new(ErrorKind::UnexpectedEof, __!("expected to skip {} bytes, but: {}", &len, &skipped))),
+    }
+}
+
+/// # Calculates size of a string or a blob
+///
+/// `extra` is the number of bytes for type, and null terminator (if any).
+pub(crate) fn size_of_data(len: usize, extra: Size) -> Result<Size> {
+    sum!(bytes_for_len!(len)?, extra, len)
 }
 
 /// # Calculates list size
 fn size_of_list(list: &[Value]) -> Result<Size> {
-    // Type + count
-    let mut result: Size = sum!(bytes_for_len!(list.len())?, 1)?;
-    // Items
+    let mut items: Size = 0;
     for v in list {
-        result = sum!(result, v.size()?)?;
-    }
-    // The len value itself:
-    // First, assume that it needs just 1 byte
-    result = sum!(result, 1)?;
-    match result > MAX_I8_AS_U32 {
-        // Now we need 3 more bytes
-        true => result = sum!(result, 3)?,
-        false => (),
-    };
-    match result <= MAX_DATA_SIZE {
-        true => Ok(result),
-        false => Err(err!("data too large: {} bytes", result)),
+        items = sum!(items, v.size()?)?;
     }
+    size_of_container(list.len(), items)
 }
 
 /// # Calculates map size
 fn size_of_map(map: &Map) -> Result<Size> {
-    // Type + count
-    let mut result = sum!(bytes_for_len!(map.len())?, 1)?;
-    // Items
+    let mut items: Size = 0;
     for v in map.values() {
-        result = sum!(result, mem::size_of::<i32>(), v.size()?)?;
-    }
-    // The len value itself:
-    // First, assume that it needs just 1 byte
-    result = sum!(result, 1)?;
-    match result > MAX_I8_AS_U32 {
-        // Now we need 3 more bytes
-        true => result = sum!(result, 3)?,
-        false => (),
-    };
-    match result <= MAX_DATA_SIZE {
-        true => Ok(result),
-        false => Err(err!("data too large: {} bytes", result)),
+        items = sum!(items, mem::size_of::<i32>(), v.size()?)?;
     }
+    size_of_container(map.len(), items)
 }
 
 /// # Calculates object size
 fn size_of_object(object: &Object) -> Result<Size> {
-    // Type + count
-    let mut result = sum!(bytes_for_len!(object.len())?, 1)?;
-    // Items
-    for (key, value) in object {
-        // Key has NO null terminator
-        let key_len = key.len();
-        if key_len > OBJECT_KEY_MAX_LEN {
-            return Err(err!("key size is limited to {} bytes; got: {}", OBJECT_KEY_MAX_LEN, &key_len));
-        }
-        result = sum!(result, key_len, value.size()?, 1)?;
+    size_of_object_items(object.len(), object)
+}
+
+/// # Calculates size of an object, from its item count and its items
+pub(crate) fn size_of_object_items<'a, I>(count: usize, items: I) -> Result<Size> where I: IntoIterator<Item=(&'a ObjectKey, &'a Value)> {
+    let mut result: Size = 0;
+    for (key, value) in items {
+        result = sum!(result, size_of_object_key(key)?, value.size()?)?;
+    }
+    size_of_container(count, result)
+}
+
+/// # Calculates size of an object key, including its length
+pub(crate) fn size_of_object_key(key: &str) -> Result<Size> {
+    // Key has NO null terminator
+    let key_len = key.len();
+    if key_len > OBJECT_KEY_MAX_LEN {
+        return Err(err!("key size is limited to {} bytes; got: {}", OBJECT_KEY_MAX_LEN, &key_len));
     }
+    sum!(key_len as Size, 1)
+}
+
+/// # Calculates size of a container, from its item count, and total size of its items (including their keys)
+pub(crate) fn size_of_container(count: usize, items: Size) -> Result<Size> {
+    // Type + count + items
+    let mut result = sum!(bytes_for_len!(count)?, 1, items)?;
     // The len value itself:
     // First, assume that it needs just 1 byte
     result = sum!(result, 1)?;
-    match result > MAX_I8_AS_U32 {
+    if result > MAX_I8_AS_U32 {
         // Now we need 3 more bytes
-        true => result = sum!(result, 3)?,
-        false => (),
-    };
+        result = sum!(result, 3)?;
+    }
     match result <= MAX_DATA_SIZE {
         true => Ok(result),
-        false => Err(err!("data too large: {} bytes", result)),
+        false => Err(err_code!("data too large (bytes)", result)),
+    }
+}
+
+/// # Calculates bytes needed for a size, or an item count
+#[cfg(feature="std")]
+pub(crate) const fn len_of_size(size: Size) -> Size {
+    match size > MAX_I8_AS_U32 {
+        true => 4,
+        false => 1,
     }
 }
 
+/// # Encoded bytes of a size (or an item count), or of a whole container header
+///
+/// Sizes and item counts take 1 byte if they're less than `128`, 4 bytes otherwise. Every encoder uses this, so they all make the same
+/// bytes.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HeaderBytes {
+    buf: [u8; 9],
+    len: usize,
+}
+
+impl HeaderBytes {
+
+    /// # Encodes a size, or an item count
+    pub(crate) fn of_size(size: Size) -> Self {
+        let mut result = Self { buf: [0; 9], len: 0 };
+        result.push_size(size);
+        result
+    }
+
+    /// # Encodes header of a container: its type, its size and its item count
+    pub(crate) fn of_container(type_byte: u8, size: Size, count: Size) -> Self {
+        let mut result = Self { buf: [type_byte, 0, 0, 0, 0, 0, 0, 0, 0], len: 1 };
+        result.push_size(size);
+        result.push_size(count);
+        result
+    }
+
+    /// # Appends a size
+    fn push_size(&mut self, size: Size) {
+        match size > MAX_I8_AS_U32 {
+            true => {
+                self.buf[self.len..self.len + 4].copy_from_slice(&(size | SIZE_MASK).to_be_bytes());
+                self.len += 4;
+            },
+            false => {
+                self.buf[self.len] = size as u8;
+                self.len += 1;
+            },
+        };
+    }
+
+    /// # Gets the bytes
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+}
+
+/// # Writes a size, or an item count, into a stream
+///
+/// Result: number of bytes written.
+#[cfg(feature="std")]
+pub(crate) fn write_size<W>(size: Size, stream: &mut W) -> IoResult<Size> where W: Write {
+    write_header_bytes(HeaderBytes::of_size(size), stream)
+}
+
+/// # Writes header of a container into a stream
+///
+/// Result: number of bytes written.
+#[cfg(feature="std")]
+pub(crate) fn write_container_header<W>(type_byte: u8, size: Size, count: Size, stream: &mut W) -> IoResult<Size> where W: Write {
+    write_header_bytes(HeaderBytes::of_container(type_byte, size, count), stream)
+}
+
+/// # Writes header bytes into a stream
+#[cfg(feature="std")]
+fn write_header_bytes<W>(bytes: HeaderBytes, stream: &mut W) -> IoResult<Size> where W: Write {
+    let bytes = bytes.as_bytes();
+    stream.write_all(bytes).map(|()| bytes.len() as Size)
+}
+
 /// # Encodes a `Value`'s string into the stream
 #[cfg(feature="std")]
-fn encode_value_str<W>(ty: u8, s: &str, stream: &mut W) -> IoResult<Size> where W: Write {
+pub(crate) fn encode_value_str<W>(ty: u8, s: &str, stream: &mut W) -> IoResult<Size> where W: Write {
     let bytes = s.as_bytes();
     let str_len = {
         let tmp = bytes.len();
@@ -854,7 +1254,7 @@ fn encode_value_str<W>(ty: u8, s: &str, stream: &mut W) -> IoResult<Size> where
     let total_size = sum!(
         str_len,
         // 1 for type, 1 for null terminator
-        2 + match str_len > MAX_I8_AS_U32 { true => 4, false => 1 }
+        2 + len_of_size(str_len)
     )?;
 
     // Type
@@ -865,7 +1265,7 @@ fn encode_value_str<W>(ty: u8, s: &str, stream: &mut W) -> IoResult<Size> where
 
     // Size
     // Note that null terminator does NOT count
-    write_size!(str_len, stream)?;
+    write_size(str_len, stream)?;
 
     // Data
     let written = stream.write(bytes)?;
@@ -883,25 +1283,31 @@ fn encode_value_str<W>(ty: u8, s: &str, stream: &mut W) -> IoResult<Size> where
     Ok(total_size)
 }
 
-/// # Encodes `Value`'s blob into the stream
+/// # Encodes header of a blob into the stream
+///
+/// Result: total bytes that have been written.
 #[cfg(feature="std")]
-fn encode_value_blob<W>(bytes: &[u8], stream: &mut W) -> IoResult<Size> where W: Write {
-    let len = {
-        let tmp = bytes.len();
-        match tmp.cmp_to(&MAX_DATA_SIZE) {
-            Ordering::Greater => return Err(io::Error::from(err!("too large: {} byte(s)", tmp))),
-            _ => tmp as Size,
-        }
+pub(crate) fn encode_blob_header<W>(len: u64, stream: &mut W) -> IoResult<Size> where W: Write {
+    let len = match len.cmp(&u64::from(MAX_DATA_SIZE)) {
+        Ordering::Greater => return Err(io::Error::from(err!("too large: {} byte(s)", len))),
+        _ => len as Size,
     };
 
     // Type
-    let mut bytes_written = match stream.write(&[crate::value::BLOB])? {
+    let bytes_written = match stream.write(&[crate::value::BLOB])? {
         1 => 1 as Size,
         other => return Err(io::Error::from(err!("expected to write 1 byte; result: {}", &other))),
     };
 
     // Size
-    bytes_written = sum!(write_size!(len, stream)?, bytes_written)?;
+    Ok(sum!(write_size(len, stream)?, bytes_written)?)
+}
+
+/// # Encodes `Value`'s blob into the stream
+#[cfg(feature="std")]
+fn encode_value_blob<W>(bytes: &[u8], stream: &mut W) -> IoResult<Size> where W: Write {
+    let len = bytes.len();
+    let mut bytes_written = encode_blob_header(len as u64, stream)?;
 
     // Data
     let written = stream.write(bytes)?;
@@ -917,16 +1323,9 @@ fn encode_value_blob<W>(bytes: &[u8], stream: &mut W) -> IoResult<Size> where W:
 /// # Encodes a `Value`'s list into the stream
 #[cfg(feature="std")]
 fn encode_value_list<W>(size: Size, list: &[Value], stream: &mut W) -> IoResult<Size> where W: Write {
-    let mut result = sum!(
-        // Type
-        write_int_be!(crate::value::LIST, stream)?,
-        // Size
-        write_size!(size, stream)?,
-        // Count
-        // We don't have to verify this value. Since at the beginning of Value::encode(), we already called size(), which verified the whole
-        // container's size.
-        write_size!(list.len() as Size, stream)?
-    )?;
+    // We don't have to verify item count. Since at the beginning of Value::encode(), we already called size(), which verified the whole
+    // container's size.
+    let mut result = write_container_header(crate::value::LIST, size, list.len() as Size, stream)?;
 
     // Items
     for v in list {
@@ -939,16 +1338,9 @@ fn encode_value_list<W>(size: Size, list: &[Value], stream: &mut W) -> IoResult<
 /// # Encodes a `Value`'s map into the stream
 #[cfg(feature="std")]
 fn encode_value_map<W>(size: Size, map: &Map, stream: &mut W) -> IoResult<Size> where W: Write {
-    let mut result = sum!(
-        // Type
-        write_int_be!(crate::value::MAP, stream)?,
-        // Size
-        write_size!(size, stream)?,
-        // Count
-        // We don't have to verify this value. Since at the beginning of Value::encode(), we already called size(), which verified the whole
-        // container's size.
-        write_size!(map.len() as Size, stream)?
-    )?;
+    // We don't have to verify item count. Since at the beginning of Value::encode(), we already called size(), which verified the whole
+    // container's size.
+    let mut result = write_container_header(crate::value::MAP, size, map.len() as Size, stream)?;
 
     // Items
     for (key, value) in map {
@@ -965,19 +1357,22 @@ fn encode_value_map<W>(size: Size, map: &Map, stream: &mut W) -> IoResult<Size>
 /// - `size`: should be calculated by `Value::size()`.
 #[cfg(feature="std")]
 fn encode_value_object<W>(size: Size, object: &Object, stream: &mut W) -> IoResult<Size> where W: Write {
-    let mut result = sum!(
-        // Type
-        write_int_be!(crate::value::OBJECT, stream)?,
-        // Size
-        write_size!(size, stream)?,
-        // Count
-        // We don't have to verify this value. Since at the beginning of Value::encode(), we already called size(), which verified the whole
-        // container's size.
-        write_size!(object.len() as Size, stream)?
-    )?;
+    encode_object_items(size, object.len(), object, stream)
+}
+
+/// # Encodes an object's items into the stream, with their header
+///
+/// ## Parameters
+///
+/// - `size`: should be calculated by `size_of_object_items()`.
+#[cfg(feature="std")]
+pub(crate) fn encode_object_items<'a, W, I>(size: Size, count: usize, items: I, stream: &mut W) -> IoResult<Size>
+where W: Write, I: IntoIterator<Item=(&'a ObjectKey, &'a Value)> {
+    // We don't have to verify item count. Since size has been calculated, which verified the whole container's size.
+    let mut result = write_container_header(crate::value::OBJECT, size, count as Size, stream)?;
 
     // Items
-    for (key, value) in object {
+    for (key, value) in items {
         let key_len = key.len();
         result = match key_len <= OBJECT_KEY_MAX_LEN {
             true => sum!(result, write_int_be!(key_len as u8, stream)?)?,
@@ -997,3 +1392,11 @@ fn encode_value_object<W>(size: Size, object: &Object, stream: &mut W) -> IoResu
 
     Ok(result)
 }
+
+/// # Encodes an object into the stream, by reference
+///
+/// Result: total bytes that have been written.
+#[cfg(feature="std")]
+pub(crate) fn encode_object_ref<W>(object: &Object, stream: &mut W) -> IoResult<Size> where W: Write {
+    encode_value_object(size_of_object(object)?, object, stream)
+}
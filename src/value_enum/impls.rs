@@ -4,9 +4,43 @@
 
 mod blob;
 mod r#bool;
+mod collections;
+mod equality;
+mod fixed;
+mod index;
 mod list;
 mod map;
+mod memory;
+mod normalize;
 mod null;
 mod numbers;
 mod object;
+mod select;
+mod stats;
 mod strings;
+mod validation;
+mod walk;
+
+#[cfg(feature="arbitrary")]
+mod arbitrary;
+
+#[cfg(feature="chrono")]
+mod chrono;
+
+#[cfg(feature="digest")]
+mod digest;
+
+#[cfg(feature="rayon")]
+mod rayon;
+
+#[cfg(feature="time")]
+mod time;
+
+#[cfg(feature="uuid")]
+mod uuid;
+
+#[cfg(feature="std")]
+mod net;
+
+#[cfg(feature="std")]
+mod std_time;
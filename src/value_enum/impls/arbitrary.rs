@@ -0,0 +1,154 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Arbitrary values, for fuzzers and property tests
+
+use {
+    alloc::{
+        format,
+        string::String,
+    },
+
+    ::arbitrary::{Arbitrary, Result, Unstructured},
+
+    crate::{Blob, List, Map, MapKey, Object, ObjectKey, Value},
+};
+
+/// # Max depth of containers
+const MAX_DEPTH: usize = 8;
+
+/// # Max items of a container
+const MAX_ITEMS: usize = 16;
+
+/// # Max length of a string or a blob, in bytes
+const MAX_DATA_LEN: usize = 256;
+
+/// # Max length of an object key, in bytes
+const MAX_OBJECT_KEY_LEN: usize = 255;
+
+/// # Count of scalar variants, which come before containers in arbitrary_value()
+const SCALARS: u8 = 17;
+
+/// # Generates structured values
+///
+/// Generated values are bounded:
+///
+/// - Containers are nested at most 8 levels deep, and have at most 16 items each.
+/// - Strings and blobs have at most 256 bytes.
+///
+/// They can always be encoded. [`Date`](#variant.Date), [`Time`](#variant.Time), [`DateTime`](#variant.DateTime) and
+/// [`DecimalStr`](#variant.DecimalStr) values are well formed, so they also pass validations of
+/// [`DecodeConfig`](struct.DecodeConfig.html).
+///
+/// ## Examples
+///
+/// ```
+/// use arbitrary::{Arbitrary, Unstructured};
+/// use binn_ir::Value;
+///
+/// let bytes = (0..1024).map(|i| (i * 7) as u8).collect::<Vec<_>>();
+/// let value = Value::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+/// assert!(value.size().is_ok());
+/// ```
+impl<'a> Arbitrary<'a> for Value {
+
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        arbitrary_value(u, 0)
+    }
+
+}
+
+/// # Generates a value, at some depth
+fn arbitrary_value(u: &mut Unstructured, depth: usize) -> Result<Value> {
+    let variants = match depth < MAX_DEPTH {
+        true => SCALARS + 3,
+        false => SCALARS,
+    };
+    Ok(match u.int_in_range(0..=variants - 1)? {
+        0 => Value::Null,
+        1 => Value::True,
+        2 => Value::False,
+        3 => Value::U8(u.arbitrary()?),
+        4 => Value::I8(u.arbitrary()?),
+        5 => Value::U16(u.arbitrary()?),
+        6 => Value::I16(u.arbitrary()?),
+        7 => Value::U32(u.arbitrary()?),
+        8 => Value::I32(u.arbitrary()?),
+        9 => Value::U64(u.arbitrary()?),
+        10 => Value::I64(u.arbitrary()?),
+        11 => Value::Float(u.arbitrary()?),
+        12 => Value::Double(u.arbitrary()?),
+        13 => Value::Text(arbitrary_string(u, MAX_DATA_LEN)?),
+        14 => arbitrary_temporal(u)?,
+        15 => Value::DecimalStr(arbitrary_decimal_str(u)?),
+        16 => {
+            let len = u.int_in_range(0..=MAX_DATA_LEN)?;
+            Value::Blob(Blob::from(u.bytes(len.min(u.len()))?))
+        },
+        17 => {
+            let mut list = List::new();
+            for _ in 0..u.arbitrary_len::<Value>()?.min(MAX_ITEMS) {
+                list.push(arbitrary_value(u, depth + 1)?);
+            }
+            Value::List(list)
+        },
+        18 => {
+            let mut map = Map::new();
+            for _ in 0..u.arbitrary_len::<(MapKey, Value)>()?.min(MAX_ITEMS) {
+                map.insert(u.arbitrary()?, arbitrary_value(u, depth + 1)?);
+            }
+            Value::Map(map)
+        },
+        _ => {
+            let mut object = Object::new();
+            for _ in 0..u.arbitrary_len::<(String, Value)>()?.min(MAX_ITEMS) {
+                object.insert(ObjectKey::from(arbitrary_string(u, MAX_OBJECT_KEY_LEN)?), arbitrary_value(u, depth + 1)?);
+            }
+            Value::Object(object)
+        },
+    })
+}
+
+/// # Generates a string, which has at most `max_len` bytes
+fn arbitrary_string(u: &mut Unstructured, max_len: usize) -> Result<String> {
+    let mut result = String::arbitrary(u)?;
+    if result.len() > max_len {
+        let mut len = max_len;
+        while !result.is_char_boundary(len) {
+            len -= 1;
+        }
+        result.truncate(len);
+    }
+    Ok(result)
+}
+
+/// # Generates a date, a time, or a date-time
+fn arbitrary_temporal(u: &mut Unstructured) -> Result<Value> {
+    let date = format!("{:04}-{:02}-{:02}", u.int_in_range(0..=9999)?, u.int_in_range(1..=12)?, u.int_in_range(1..=28)?);
+    let mut time = format!("{:02}:{:02}:{:02}", u.int_in_range(0..=23)?, u.int_in_range(0..=59)?, u.int_in_range(0..=59)?);
+    if u.arbitrary()? {
+        time.push_str(&format!(".{}", u.int_in_range(0..=999_999_999_u32)?));
+    }
+    match u.int_in_range(0..=2)? {
+        0 => (),
+        1 => time.push('Z'),
+        _ => time.push_str(&format!("{}{:02}:{:02}", ["+", "-"][u.choose_index(2)?], u.int_in_range(0..=14)?, u.int_in_range(0..=59)?)),
+    };
+
+    Ok(match u.int_in_range(0..=2)? {
+        0 => Value::Date(date),
+        1 => Value::Time(time),
+        _ => Value::DateTime(format!("{}T{}", date, time)),
+    })
+}
+
+/// # Generates a decimal string
+fn arbitrary_decimal_str(u: &mut Unstructured) -> Result<String> {
+    let mut result = format!("{}", i128::arbitrary(u)?);
+    if u.arbitrary()? {
+        result.push_str(&format!(".{}", u64::arbitrary(u)?));
+    }
+    if u.arbitrary()? {
+        result.push_str(&format!("e{}", i16::arbitrary(u)?));
+    }
+    Ok(result)
+}
@@ -0,0 +1,157 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Conversions for `chrono`
+
+use {
+    alloc::string::ToString,
+    core::convert::TryFrom,
+
+    ::chrono::{Datelike, DateTime, FixedOffset, NaiveDate, NaiveTime, SecondsFormat, Utc},
+
+    crate::{Error, Value},
+};
+
+/// # Converts a date-time to a [`DateTime`](#variant.DateTime) of an RFC 3339 string
+///
+/// An error is returned if the year is out of range `0..=9999`.
+impl TryFrom<DateTime<Utc>> for Value {
+
+    type Error = Error;
+
+    fn try_from(date_time: DateTime<Utc>) -> core::result::Result<Self, Self::Error> {
+        check_year(date_time.year())?;
+        Ok(Value::DateTime(date_time.to_rfc3339_opts(SecondsFormat::AutoSi, true)))
+    }
+
+}
+
+/// # Converts a date-time to a [`DateTime`](#variant.DateTime) of an RFC 3339 string
+///
+/// The offset is kept as-is. An error is returned if the year -- in that offset -- is out of range `0..=9999`.
+impl TryFrom<DateTime<FixedOffset>> for Value {
+
+    type Error = Error;
+
+    fn try_from(date_time: DateTime<FixedOffset>) -> core::result::Result<Self, Self::Error> {
+        check_year(date_time.year())?;
+        Ok(Value::DateTime(date_time.to_rfc3339_opts(SecondsFormat::AutoSi, false)))
+    }
+
+}
+
+/// # Parses a [`DateTime`](enum.Value.html#variant.DateTime) of an RFC 3339 string
+impl TryFrom<&Value> for DateTime<FixedOffset> {
+
+    type Error = Error;
+
+    fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+        let s = v.as_date_time()?;
+        DateTime::parse_from_rfc3339(s).map_err(|e| err!("Invalid RFC 3339 date-time: {:?}: {}", s, e))
+    }
+
+}
+
+/// # Parses a [`DateTime`](enum.Value.html#variant.DateTime) of an RFC 3339 string
+impl TryFrom<Value> for DateTime<FixedOffset> {
+
+    type Error = Error;
+
+    fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+        Self::try_from(&v)
+    }
+
+}
+
+/// # Parses a [`DateTime`](enum.Value.html#variant.DateTime) of an RFC 3339 string, and converts it to UTC
+impl TryFrom<&Value> for DateTime<Utc> {
+
+    type Error = Error;
+
+    fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+        DateTime::<FixedOffset>::try_from(v).map(|date_time| date_time.with_timezone(&Utc))
+    }
+
+}
+
+/// # Parses a [`DateTime`](enum.Value.html#variant.DateTime) of an RFC 3339 string, and converts it to UTC
+impl TryFrom<Value> for DateTime<Utc> {
+
+    type Error = Error;
+
+    fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+        Self::try_from(&v)
+    }
+
+}
+
+/// # Converts a date to a [`Date`](#variant.Date), such as `2019-12-31`
+impl From<NaiveDate> for Value {
+
+    fn from(date: NaiveDate) -> Self {
+        Value::Date(date.to_string())
+    }
+
+}
+
+/// # Parses a [`Date`](enum.Value.html#variant.Date), such as `2019-12-31`
+impl TryFrom<&Value> for NaiveDate {
+
+    type Error = Error;
+
+    fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+        let s = v.as_date()?;
+        s.parse().map_err(|e| err!("Invalid date: {:?}: {}", s, e))
+    }
+
+}
+
+/// # Parses a [`Date`](enum.Value.html#variant.Date), such as `2019-12-31`
+impl TryFrom<Value> for NaiveDate {
+
+    type Error = Error;
+
+    fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+        Self::try_from(&v)
+    }
+
+}
+
+/// # Converts a time to a [`Time`](#variant.Time), such as `23:59:59.5`
+impl From<NaiveTime> for Value {
+
+    fn from(time: NaiveTime) -> Self {
+        Value::Time(time.to_string())
+    }
+
+}
+
+/// # Parses a [`Time`](enum.Value.html#variant.Time), such as `23:59:59.5`
+impl TryFrom<&Value> for NaiveTime {
+
+    type Error = Error;
+
+    fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+        let s = v.as_time()?;
+        s.parse().map_err(|e| err!("Invalid time: {:?}: {}", s, e))
+    }
+
+}
+
+/// # Parses a [`Time`](enum.Value.html#variant.Time), such as `23:59:59.5`
+impl TryFrom<Value> for NaiveTime {
+
+    type Error = Error;
+
+    fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+        Self::try_from(&v)
+    }
+
+}
+
+/// # Checks if a year can be written in RFC 3339
+fn check_year(year: i32) -> crate::Result<()> {
+    match (0..=9999).contains(&year) {
+        true => Ok(()),
+        false => Err(err!("Year is out of range: {}", year)),
+    }
+}
@@ -0,0 +1,248 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Conversions between values and Rust collections
+
+use {
+    core::convert::TryFrom,
+    alloc::{
+        collections::BTreeMap,
+        string::String,
+        vec::Vec,
+    },
+
+    crate::{Blob, Error, List, MapKey, Object, ObjectKey, Value},
+};
+
+#[cfg(feature="std")]
+use std::collections::HashMap;
+
+/// # Converts a slice of bytes to a [`Blob`](#variant.Blob)
+impl From<&[u8]> for Value {
+
+    fn from(bytes: &[u8]) -> Self {
+        Value::Blob(Blob::from(bytes))
+    }
+
+}
+
+/// # Converts an array of bytes to a [`Blob`](#variant.Blob)
+impl<const N: usize> From<[u8; N]> for Value {
+
+    fn from(bytes: [u8; N]) -> Self {
+        Value::Blob(Blob::from(bytes))
+    }
+
+}
+
+/// # Converts an array to a [`List`](#variant.List)
+impl<const N: usize> From<[Value; N]> for Value {
+
+    fn from(values: [Value; N]) -> Self {
+        Value::List(List::from(values))
+    }
+
+}
+
+/// # _Clones_ items of a slice, and converts them to a [`List`](#variant.List)
+impl From<&[Value]> for Value {
+
+    fn from(values: &[Value]) -> Self {
+        Value::List(values.to_vec())
+    }
+
+}
+
+/// # Makes an object from a map
+fn object_from_iter<K, T, I>(iter: I) -> Value where K: AsRef<str>, T: Into<Value>, I: IntoIterator<Item=(K, T)> {
+    Value::Object(iter.into_iter().map(|(k, v)| (ObjectKey::from(k.as_ref()), v.into())).collect::<Object>())
+}
+
+/// # Implements conversions from collections of some types
+///
+/// Bytes (`u8`) are not included in here, since `Vec<u8>` is a [`Blob`][type:Blob].
+///
+/// [type:Blob]: ../type.Blob.html
+macro_rules! impl_from_collections_for_value { ($($ty: ty,)+) => {
+    $(
+        /// # Converts items of a vector, to a [`List`](#variant.List)
+        impl From<Vec<$ty>> for Value {
+
+            fn from(values: Vec<$ty>) -> Self {
+                values.into_iter().map(Value::from).collect()
+            }
+
+        }
+
+        /// # _Clones_ items of a slice, and converts them to a [`List`](#variant.List)
+        impl From<&[$ty]> for Value {
+
+            fn from(values: &[$ty]) -> Self {
+                values.iter().cloned().map(Value::from).collect()
+            }
+
+        }
+
+        /// # Converts items of an array, to a [`List`](#variant.List)
+        impl<const N: usize> From<[$ty; N]> for Value {
+
+            fn from(values: [$ty; N]) -> Self {
+                IntoIterator::into_iter(values).map(Value::from).collect()
+            }
+
+        }
+    )+
+    impl_from_maps_for_value!(u8, $($ty,)+);
+}}
+
+/// # Implements conversions from maps of some types
+macro_rules! impl_from_maps_for_value { ($($ty: ty,)+) => {
+    $(
+        /// # Converts a map to an [`Object`](#variant.Object)
+        impl From<BTreeMap<String, $ty>> for Value {
+
+            fn from(map: BTreeMap<String, $ty>) -> Self {
+                object_from_iter(map)
+            }
+
+        }
+
+        /// # Converts a map to an [`Object`](#variant.Object)
+        #[cfg(feature="std")]
+        impl From<HashMap<String, $ty>> for Value {
+
+            fn from(map: HashMap<String, $ty>) -> Self {
+                object_from_iter(map)
+            }
+
+        }
+    )+
+}}
+
+impl_from_collections_for_value! {
+    bool,
+    i8, i16, u16, i32, u32, i64, u64,
+    f32, f64,
+    char, String, &str,
+}
+
+/// # Converts a [`HashObject`][type:HashObject] to an [`Object`](#variant.Object)
+///
+/// [type:HashObject]: ../type.HashObject.html
+#[cfg(feature="std")]
+impl From<HashMap<String, Value>> for Value {
+
+    fn from(map: HashMap<String, Value>) -> Self {
+        object_from_iter(map)
+    }
+
+}
+
+/// # Converts all items of a list
+fn try_from_list<T>(v: Value) -> core::result::Result<Vec<T>, Error> where T: TryFrom<Value, Error=Error> {
+    match v {
+        Value::List(list) => list.into_iter().enumerate().map(|(index, item)|
+            T::try_from(item).map_err(|e| err!("Item #{}: {}", index, e))
+        ).collect(),
+        _ => Err(err!("Value is not a List")),
+    }
+}
+
+/// # Converts all items of an object
+fn try_from_object<T>(v: Value) -> core::result::Result<BTreeMap<String, T>, Error> where T: TryFrom<Value, Error=Error> {
+    match v {
+        Value::Object(object) => object.into_iter().map(|(key, item)| match T::try_from(item) {
+            Ok(item) => Ok((String::from(key.as_str()), item)),
+            Err(e) => Err(err!("Key {:?}: {}", key.as_str(), e)),
+        }).collect(),
+        _ => Err(err!("Value is not an Object")),
+    }
+}
+
+/// # Converts all items of a map
+fn try_from_map<T>(v: Value) -> core::result::Result<BTreeMap<MapKey, T>, Error> where T: TryFrom<Value, Error=Error> {
+    match v {
+        Value::Map(map) => map.into_iter().map(|(key, item)| match T::try_from(item) {
+            Ok(item) => Ok((key, item)),
+            Err(e) => Err(err!("Key {}: {}", key, e)),
+        }).collect(),
+        _ => Err(err!("Value is not a Map")),
+    }
+}
+
+/// # Implements conversions from values to collections of some types
+///
+/// Bytes (`u8`) are not included in vectors, since `Vec<u8>` is a [`Blob`][type:Blob].
+///
+/// [type:Blob]: ../type.Blob.html
+macro_rules! impl_try_from_value_for_collections { ($($ty: ty,)+) => {
+    $(
+        /// # Converts a [`List`](enum.Value.html#variant.List) to a vector
+        ///
+        /// All items must be convertible, otherwise an error is returned.
+        impl TryFrom<Value> for Vec<$ty> {
+
+            type Error = Error;
+
+            fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+                try_from_list(v)
+            }
+
+        }
+    )+
+    impl_try_from_value_for_maps!(u8, $($ty,)+);
+}}
+
+/// # Implements conversions from values to maps of some types
+macro_rules! impl_try_from_value_for_maps { ($($ty: ty,)+) => {
+    $(
+        /// # Converts an [`Object`](enum.Value.html#variant.Object) to a map
+        ///
+        /// All items must be convertible, otherwise an error is returned.
+        impl TryFrom<Value> for BTreeMap<String, $ty> {
+
+            type Error = Error;
+
+            fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+                try_from_object(v)
+            }
+
+        }
+
+        /// # Converts a [`Map`](enum.Value.html#variant.Map) to a map
+        ///
+        /// All items must be convertible, otherwise an error is returned.
+        impl TryFrom<Value> for BTreeMap<MapKey, $ty> {
+
+            type Error = Error;
+
+            fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+                try_from_map(v)
+            }
+
+        }
+    )+
+}}
+
+impl_try_from_value_for_collections! {
+    bool,
+    i8, i16, u16, i32, u32, i64, u64,
+    f32, f64,
+    char, String,
+}
+
+/// # Converts an [`Object`](enum.Value.html#variant.Object) to a [`HashObject`][type:HashObject]
+///
+/// [type:HashObject]: ../type.HashObject.html
+#[cfg(feature="std")]
+impl TryFrom<Value> for HashMap<String, Value> {
+
+    type Error = Error;
+
+    fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+        match v {
+            Value::Object(object) => Ok(object.into_iter().collect()),
+            _ => Err(err!("Value is not an Object")),
+        }
+    }
+
+}
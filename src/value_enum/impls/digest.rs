@@ -0,0 +1,87 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Hashing with `digest`
+
+use {
+    std::io::{self, Write},
+
+    ::digest::{Digest, Output},
+
+    crate::{EncodeConfig, IoResult, Value},
+};
+
+/// # Config of canonical encoding
+const CANONICAL: EncodeConfig = EncodeConfig {
+    canonicalize_floats: true,
+    ..EncodeConfig::new()
+};
+
+/// # Hashing
+impl Value {
+
+    /// # Hashes canonical encoding of this value
+    ///
+    /// Canonical encoding is the one of [`encode()`][#encode()]: sizes and counts take 1 byte if they're less than `128`, 4 bytes otherwise;
+    /// and items of objects are written in order of their keys. In addition, floating point numbers are canonicalized on the fly, as by
+    /// [`canonicalize_floats()`][#canonicalize_floats()]: `-0.0` is hashed as `0.0`, and all NaNs as the same NaN. So equal values always
+    /// give equal hashes, while the buffer is never collected in memory.
+    ///
+    /// ## Notes
+    ///
+    /// Types are part of the encoding, so for example [`U8(1)`][Value#U8] and [`I64(1)`][Value#I64] have different hashes. If integers
+    /// might come from different implementations, consider converting them first, for example with
+    /// [`CompatLevel::CBinn`][CompatLevel#CBinn].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    /// use sha2::Sha256;
+    ///
+    /// # fn test() -> binn_ir::IoResult<()> {
+    /// let mut first = binn_ir::object();
+    /// first.object_insert("id", 1_u8)?;
+    /// first.object_insert("name", "moss")?;
+    ///
+    /// let mut second = binn_ir::object();
+    /// second.object_insert("name", "moss")?;
+    /// second.object_insert("id", 1_u8)?;
+    ///
+    /// assert_eq!(first.canonical_hash::<Sha256>()?, second.canonical_hash::<Sha256>()?);
+    /// assert_ne!(first.canonical_hash::<Sha256>()?, Value::Null.canonical_hash::<Sha256>()?);
+    /// # Ok(()) }
+    /// # test().unwrap();
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Same as [`encode()`][#encode()].
+    ///
+    /// [#encode()]: #method.encode
+    /// [#canonicalize_floats()]: #method.canonicalize_floats
+    /// [Value#U8]: #variant.U8
+    /// [Value#I64]: #variant.I64
+    /// [CompatLevel#CBinn]: enum.CompatLevel.html#variant.CBinn
+    pub fn canonical_hash<D>(&self) -> IoResult<Output<D>> where D: Digest {
+        let mut writer = DigestWriter(D::new());
+        CANONICAL.encode(self, &mut writer)?;
+        Ok(writer.0.finalize())
+    }
+
+}
+
+/// # Writer which feeds a digest
+struct DigestWriter<D>(D);
+
+impl<D> Write for DigestWriter<D> where D: Digest {
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+}
@@ -0,0 +1,81 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Equality
+
+use crate::Value;
+
+/// # Equality
+impl Value {
+
+    /// # Checks if this value means the same thing as another one
+    ///
+    /// [`PartialEq`][core::cmp/PartialEq] is structural: an [`U8`][#U8] of `5` is not equal to an [`I32`][#I32] of `5`. But producers --
+    /// and other implementations -- may pick different types for the same number. This function ignores such differences:
+    ///
+    /// - Integers of any width and sign are equal if their values are equal.
+    /// - [`Float`][#Float] and [`Double`][#Double] are equal if their values are equal. As with `PartialEq`, NaN is not equal to anything.
+    ///
+    /// Integers are never equal to floating point numbers. Containers are compared recursively. Other values are compared with
+    /// `PartialEq`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// assert!(Value::U8(5).semantic_eq(&Value::I32(5)));
+    /// assert!(Value::Float(0.5).semantic_eq(&Value::Double(0.5)));
+    /// assert!(Value::List(vec![Value::U64(5)]).semantic_eq(&Value::List(vec![Value::I8(5)])));
+    ///
+    /// assert!(Value::U8(5).semantic_eq(&Value::Double(5.0)) == false);
+    /// assert!(Value::I8(-1).semantic_eq(&Value::U64(u64::MAX)) == false);
+    /// ```
+    ///
+    /// [core::cmp/PartialEq]: https://doc.rust-lang.org/core/cmp/trait.PartialEq.html
+    /// [#U8]: #variant.U8
+    /// [#I32]: #variant.I32
+    /// [#Float]: #variant.Float
+    /// [#Double]: #variant.Double
+    pub fn semantic_eq(&self, other: &Self) -> bool {
+        if let (Some(a), Some(b)) = (integer(self), integer(other)) {
+            return a == b;
+        }
+        if let (Some(a), Some(b)) = (float(self), float(other)) {
+            return a == b;
+        }
+
+        match (self, other) {
+            (Value::List(a), Value::List(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.semantic_eq(b)),
+            (Value::Map(a), Value::Map(b)) =>
+                a.len() == b.len() && a.iter().all(|(key, a)| b.get(key).map(|b| a.semantic_eq(b)).unwrap_or(false)),
+            (Value::Object(a), Value::Object(b)) =>
+                a.len() == b.len() && a.iter().all(|(key, a)| b.get(key).map(|b| a.semantic_eq(b)).unwrap_or(false)),
+            _ => self == other,
+        }
+    }
+
+}
+
+/// # Gets an integer, of any width
+fn integer(value: &Value) -> Option<i128> {
+    match value {
+        Value::U8(u) => Some(i128::from(*u)),
+        Value::I8(i) => Some(i128::from(*i)),
+        Value::U16(u) => Some(i128::from(*u)),
+        Value::I16(i) => Some(i128::from(*i)),
+        Value::U32(u) => Some(i128::from(*u)),
+        Value::I32(i) => Some(i128::from(*i)),
+        Value::U64(u) => Some(i128::from(*u)),
+        Value::I64(i) => Some(i128::from(*i)),
+        _ => None,
+    }
+}
+
+/// # Gets a floating point number, of any width
+fn float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(f) => Some(f64::from(*f)),
+        Value::Double(d) => Some(*d),
+        _ => None,
+    }
+}
@@ -0,0 +1,96 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Fixed buffers
+
+use {
+    crate::{
+        Result, Value,
+        slice_writer::SliceWriter,
+    },
+};
+
+/// # Fixed buffers
+impl Value {
+
+    /// # Encodes this value into a fixed buffer
+    ///
+    /// This does not allocate, and does not need `std`, so it's suitable for sending values from a static buffer -- such as a DMA or packet
+    /// buffer. Arrays can be passed directly, as `&mut [u8; N]` coerces into a slice.
+    ///
+    /// Size of the value is verified first: if the value does not fit into the buffer, an error is returned, and the buffer is left
+    /// untouched.
+    ///
+    /// Result: number of bytes written, at the start of the buffer. Output is the same as the one of [`encode()`][#encode()].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// # fn test() -> binn_ir::Result<()> {
+    /// let value = Value::List(vec![Value::U8(1), Value::from("two")]);
+    ///
+    /// let mut buf = [0_u8; 16];
+    /// let len = value.encode_into_slice(&mut buf)?;
+    /// assert_eq!(&buf[..len], &[0xe0, 0x0b, 0x02, 0x20, 0x01, 0xa0, 0x03, b't', b'w', b'o', 0x00]);
+    ///
+    /// assert!(value.encode_into_slice(&mut [0_u8; 10]).is_err());
+    /// # Ok(()) }
+    /// # test().unwrap();
+    /// ```
+    ///
+    /// [#encode()]: #method.encode
+    pub fn encode_into_slice(&self, buf: &mut [u8]) -> Result<usize> {
+        let size = self.size()? as usize;
+        if size > buf.len() {
+            return Err(err!("buffer too small: {} byte(s) needed, {} available", size, buf.len()));
+        }
+
+        let mut writer = SliceWriter::new(buf);
+        put_value(&mut writer, self)?;
+        Ok(writer.pos())
+    }
+
+}
+
+/// # Writes a value
+///
+/// Its size must have been verified via `Value::size()`.
+fn put_value(writer: &mut SliceWriter, value: &Value) -> Result<()> {
+    match value {
+        Value::Null => writer.put(&[crate::value::NULL]),
+        Value::True => writer.put(&[crate::value::TRUE]),
+        Value::False => writer.put(&[crate::value::FALSE]),
+        Value::U8(u) => writer.put(&[crate::value::U8, *u]),
+        Value::I8(i) => writer.put(&[crate::value::I8, *i as u8]),
+        Value::U16(u) => writer.put_int(crate::value::U16, &u.to_be_bytes()),
+        Value::I16(i) => writer.put_int(crate::value::I16, &i.to_be_bytes()),
+        Value::U32(u) => writer.put_int(crate::value::U32, &u.to_be_bytes()),
+        Value::I32(i) => writer.put_int(crate::value::I32, &i.to_be_bytes()),
+        Value::U64(u) => writer.put_int(crate::value::U64, &u.to_be_bytes()),
+        Value::I64(i) => writer.put_int(crate::value::I64, &i.to_be_bytes()),
+        Value::Float(f) => writer.put_int(crate::value::FLOAT, &f.to_bits().to_be_bytes()),
+        Value::Double(f) => writer.put_int(crate::value::DOUBLE, &f.to_bits().to_be_bytes()),
+        Value::Text(t) => writer.put_str(crate::value::TEXT, t),
+        Value::DateTime(dt) => writer.put_str(crate::value::DATE_TIME, dt),
+        Value::Date(d) => writer.put_str(crate::value::DATE, d),
+        Value::Time(t) => writer.put_str(crate::value::TIME, t),
+        Value::DecimalStr(ds) => writer.put_str(crate::value::DECIMAL_STR, ds),
+        Value::Blob(bytes) => writer.put_blob(bytes),
+        Value::List(list) => {
+            writer.put_container_header(crate::value::LIST, value.size()?, list.len())?;
+            list.iter().try_for_each(|item| put_value(writer, item))
+        },
+        Value::Map(map) => {
+            writer.put_container_header(crate::value::MAP, value.size()?, map.len())?;
+            map.iter().try_for_each(|(key, item)| writer.put(&key.to_be_bytes()).and_then(|()| put_value(writer, item)))
+        },
+        Value::Object(object) => {
+            writer.put_container_header(crate::value::OBJECT, value.size()?, object.len())?;
+            object.iter().try_for_each(|(key, item)| {
+                writer.put_object_key(key)?;
+                put_value(writer, item)
+            })
+        },
+    }
+}
@@ -0,0 +1,177 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Index operators
+
+use {
+    core::{
+        convert::TryFrom,
+        ops::{Index, IndexMut},
+    },
+
+    crate::{List, Map, MapKey, Object, Value},
+};
+
+/// # Null, returned by index operators for missing items
+static NULL: Value = Value::Null;
+
+/// # Indexes an object
+///
+/// If the value is not an object, or the key does not exist, [`Null`](enum.Value.html#variant.Null) is returned.
+///
+/// ## Examples
+///
+/// ```
+/// let mut doc = binn_ir::object();
+/// doc["user"]["name"] = "moss".into();
+///
+/// assert_eq!(doc["user"]["name"].as_text()?, "moss");
+/// assert!(doc["user"]["id"].is_null());
+/// assert!(doc["user"]["name"]["first"].is_null());
+///
+/// # Ok::<_, binn_ir::Error>(())
+/// ```
+impl Index<&str> for Value {
+
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        match self {
+            Value::Object(object) => object.get(key).unwrap_or(&NULL),
+            _ => &NULL,
+        }
+    }
+
+}
+
+/// # Indexes an object mutably
+///
+/// - If the value is [`Null`](enum.Value.html#variant.Null), it becomes an empty object first.
+/// - If the key does not exist, a `Null` is inserted.
+///
+/// ## Panics
+///
+/// Panics if the value is neither an object nor `Null`.
+impl IndexMut<&str> for Value {
+
+    fn index_mut(&mut self, key: &str) -> &mut Self::Output {
+        if self.is_null() {
+            *self = Value::Object(Object::new());
+        }
+        match self {
+            Value::Object(object) => object.entry(key.into()).or_insert(Value::Null),
+            _ => panic!("Value is not an Object"),
+        }
+    }
+
+}
+
+/// # Indexes a list
+///
+/// If the value is not a list, or the index is out of bounds, [`Null`](enum.Value.html#variant.Null) is returned.
+impl Index<usize> for Value {
+
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self {
+            Value::List(list) => list.get(index).unwrap_or(&NULL),
+            _ => &NULL,
+        }
+    }
+
+}
+
+/// # Indexes a list mutably
+///
+/// ## Panics
+///
+/// Panics if the value is not a list, or the index is out of bounds.
+impl IndexMut<usize> for Value {
+
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match self {
+            Value::List(list) => index_list_mut(list, index),
+            _ => panic!("Value is not a List"),
+        }
+    }
+
+}
+
+/// # Indexes a map, or a list
+///
+/// Since integer literals are `i32` by default, `value[0]` uses this operator. So for convenience, lists can also be indexed via
+/// non-negative numbers.
+///
+/// If the value is neither a map nor a list, or the item does not exist, [`Null`](enum.Value.html#variant.Null) is returned.
+///
+/// ## Notes
+///
+/// When indexing further into an item, or calling a method on it, the compiler can't infer type of the index. A suffix is needed, such as
+/// `0_i32` or `0_usize`.
+///
+/// ## Examples
+///
+/// ```
+/// let mut map = binn_ir::map();
+/// map[-1] = binn_ir::list();
+/// map[-1_i32].push("first")?;
+///
+/// assert_eq!(map[-1_i32][0_usize].as_text()?, "first");
+/// assert!(map[-1_i32][1_usize].is_null());
+/// assert!(map[1_i32].is_null());
+///
+/// # Ok::<_, binn_ir::Error>(())
+/// ```
+impl Index<MapKey> for Value {
+
+    type Output = Value;
+
+    fn index(&self, key: MapKey) -> &Self::Output {
+        match self {
+            Value::Map(map) => map.get(&key).unwrap_or(&NULL),
+            Value::List(list) => usize::try_from(key).ok().and_then(|index| list.get(index)).unwrap_or(&NULL),
+            _ => &NULL,
+        }
+    }
+
+}
+
+/// # Indexes a map, or a list, mutably
+///
+/// For maps:
+///
+/// - If the value is [`Null`](enum.Value.html#variant.Null), it becomes an empty map first.
+/// - If the key does not exist, a `Null` is inserted.
+///
+/// ## Panics
+///
+/// Panics if:
+///
+/// - The value is neither a map, a list, nor `Null`.
+/// - The value is a list, and the index is negative or out of bounds.
+impl IndexMut<MapKey> for Value {
+
+    fn index_mut(&mut self, key: MapKey) -> &mut Self::Output {
+        if self.is_null() {
+            *self = Value::Map(Map::new());
+        }
+        match self {
+            Value::Map(map) => map.entry(key).or_insert(Value::Null),
+            Value::List(list) => match usize::try_from(key) {
+                Ok(index) => index_list_mut(list, index),
+                Err(_) => panic!("Index {} is out of bounds (list length: {})", key, list.len()),
+            },
+            _ => panic!("Value is not a Map"),
+        }
+    }
+
+}
+
+/// # Indexes a list mutably
+fn index_list_mut(list: &mut List, index: usize) -> &mut Value {
+    let len = list.len();
+    match list.get_mut(index) {
+        Some(item) => item,
+        None => panic!("Index {} is out of bounds (list length: {})", index, len),
+    }
+}
@@ -3,12 +3,18 @@
 //! # Shortcuts for `Value::List`
 
 use {
+    alloc::vec::Vec,
     core::{
+        cmp::Ordering,
         convert::TryFrom,
-        iter::FromIterator,
+        iter::{Extend, FromIterator},
+        mem,
     },
 
-    crate::{Error, List, Result, Value},
+    crate::{
+        Error, List, Result, Value,
+        value_enum::ordering::{Number, number_of},
+    },
 };
 
 /// # Helper macro for Value::at()/mut_at()
@@ -41,7 +47,10 @@ impl Value {
     /// Returns an error if the value is not a list.
     pub fn push<T>(&mut self, value: T) -> Result<()> where T: Into<Self> {
         match self {
-            Value::List(list) => Ok(crate::push(list, value)),
+            Value::List(list) => {
+                crate::push(list, value);
+                Ok(())
+            },
             _ => Err(err!("Value is not a list")),
         }
     }
@@ -153,6 +162,105 @@ impl Value {
         }
     }
 
+    /// # If the value is a list of objects, sorts it by an item of the objects
+    ///
+    /// Sort keys are compared this way:
+    ///
+    /// - Objects without the key, or with a [`Null`][#Null], come first.
+    /// - Numbers -- integers and floating point numbers -- are compared by their values.
+    /// - Strings -- [`Text`][#Text], [`DateTime`][#DateTime], [`Date`][#Date], [`Time`][#Time] and [`DecimalStr`][#DecimalStr] -- are
+    ///   compared by their bytes.
+    /// - `false` comes before `true`.
+    ///
+    /// Sorting is stable: objects with equal keys keep their order.
+    ///
+    /// Returns an error if the value is not a list, an item is not an object, or keys can't be compared -- such as a number and a string.
+    /// In which case, the list is left unchanged.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mut value = binn_ir::Value::List(vec![
+    ///     binn_ir::object_from("timestamp", 9_u64),
+    ///     binn_ir::object_from("timestamp", 1_u8),
+    ///     binn_ir::object(),
+    /// ]);
+    /// value.sort_list_by_key("timestamp")?;
+    /// assert_eq!(value, binn_ir::Value::List(vec![
+    ///     binn_ir::object(),
+    ///     binn_ir::object_from("timestamp", 1_u8),
+    ///     binn_ir::object_from("timestamp", 9_u64),
+    /// ]));
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    ///
+    /// [#Null]: #variant.Null
+    /// [#Text]: #variant.Text
+    /// [#DateTime]: #variant.DateTime
+    /// [#Date]: #variant.Date
+    /// [#Time]: #variant.Time
+    /// [#DecimalStr]: #variant.DecimalStr
+    pub fn sort_list_by_key(&mut self, key: &str) -> Result<()> {
+        let list = self.as_mut_list()?;
+
+        let order = {
+            let keys = list.iter().map(|item| SortKey::of(item, key)).collect::<Result<Vec<_>>>()?;
+            let kinds = keys.iter().filter_map(SortKey::kind).collect::<Vec<_>>();
+            if kinds.windows(2).any(|kinds| kinds[0] != kinds[1]) {
+                return Err(err!("Cannot compare items of different types at key {:?}", key));
+            }
+
+            let mut order = (0..list.len()).collect::<Vec<_>>();
+            order.sort_by(|a, b| keys[*a].cmp(&keys[*b]));
+            order
+        };
+
+        let mut items = mem::take(list).into_iter().map(Some).collect::<Vec<_>>();
+        list.extend(order.into_iter().filter_map(|index| items[index].take()));
+        Ok(())
+    }
+
+    /// # If the value is a list of objects, removes consecutive objects having the same item
+    ///
+    /// Like [`Vec::dedup_by_key()`][alloc::vec/Vec#dedup_by_key()], only consecutive objects are compared. To remove all duplicates, sort
+    /// the list first (see [`sort_list_by_key()`][#sort_list_by_key()]). The first object of each run is kept.
+    ///
+    /// Items are compared with [`semantic_eq()`][#semantic_eq()]. Objects without the key are always kept.
+    ///
+    /// Returns an error if the value is not a list, or an item is not an object. In which case, the list is left unchanged.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mut value = binn_ir::Value::List(vec![
+    ///     binn_ir::object_from("id", 1_u8),
+    ///     binn_ir::object_from("id", 1_i64),
+    ///     binn_ir::object_from("id", 2_u8),
+    /// ]);
+    /// value.dedup_list_by_key("id")?;
+    /// assert_eq!(value, binn_ir::Value::List(vec![binn_ir::object_from("id", 1_u8), binn_ir::object_from("id", 2_u8)]));
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    ///
+    /// [alloc::vec/Vec#dedup_by_key()]: https://doc.rust-lang.org/alloc/vec/struct.Vec.html#method.dedup_by_key
+    /// [#sort_list_by_key()]: #method.sort_list_by_key
+    /// [#semantic_eq()]: #method.semantic_eq
+    pub fn dedup_list_by_key(&mut self, key: &str) -> Result<()> {
+        let list = self.as_mut_list()?;
+        if list.iter().any(|item| item.as_object().is_err()) {
+            return Err(err!("Value is not a list of objects"));
+        }
+
+        fn item_of<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+            value.as_object().ok()?.get(key)
+        }
+        list.dedup_by(|item, previous| match (item_of(item, key), item_of(previous, key)) {
+            (Some(item), Some(previous)) => item.semantic_eq(previous),
+            _ => false,
+        });
+        Ok(())
+    }
+
 }
 
 impl From<List> for Value {
@@ -171,6 +279,38 @@ impl FromIterator<Value> for Value {
 
 }
 
+/// # Extends a list
+///
+/// If the value is [`Null`](enum.Value.html#variant.Null), it becomes an empty list first.
+///
+/// ## Panics
+///
+/// Panics if the value is neither a list nor `Null`. Use [`as_mut_list()`](enum.Value.html#method.as_mut_list) to handle that case
+/// without panicking.
+///
+/// ## Examples
+///
+/// ```
+/// let mut list = binn_ir::Value::Null;
+/// list.extend((0..3).map(binn_ir::Value::from));
+/// assert_eq!(list.as_list()?.len(), 3);
+///
+/// # Ok::<_, binn_ir::Error>(())
+/// ```
+impl Extend<Value> for Value {
+
+    fn extend<T>(&mut self, iter: T) where T: IntoIterator<Item=Self> {
+        if self.is_null() {
+            *self = Value::List(List::new());
+        }
+        match self {
+            Value::List(list) => list.extend(iter),
+            _ => panic!("Value is not a List"),
+        }
+    }
+
+}
+
 impl TryFrom<Value> for List {
 
     type Error = Error;
@@ -183,3 +323,58 @@ impl TryFrom<Value> for List {
     }
 
 }
+
+/// # A sort key of `Value::sort_list_by_key()`
+enum SortKey<'a> {
+    Missing,
+    Bool(bool),
+    Number(Number),
+    Str(&'a str),
+}
+
+impl<'a> SortKey<'a> {
+
+    /// # Gets sort key of an object
+    fn of(item: &'a Value, key: &str) -> Result<Self> {
+        let value = match item.as_object()?.get(key) {
+            Some(value) => value,
+            None => return Ok(SortKey::Missing),
+        };
+        Ok(match value {
+            Value::Null => SortKey::Missing,
+            Value::True => SortKey::Bool(true),
+            Value::False => SortKey::Bool(false),
+            Value::Text(s) | Value::DateTime(s) | Value::Date(s) | Value::Time(s) | Value::DecimalStr(s) => SortKey::Str(s),
+            _ => match number_of(value) {
+                Some(number) => SortKey::Number(number),
+                None => return Err(err!("Cannot sort by value at key {:?}", key)),
+            },
+        })
+    }
+
+    /// # Kind of this key
+    ///
+    /// Keys of the same kind can be compared. Missing keys can be compared with any kind.
+    fn kind(&self) -> Option<u8> {
+        match self {
+            SortKey::Missing => None,
+            SortKey::Bool(_) => Some(0),
+            SortKey::Number(_) => Some(1),
+            SortKey::Str(_) => Some(2),
+        }
+    }
+
+    /// # Compares to another key of the same kind
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (SortKey::Missing, SortKey::Missing) => Ordering::Equal,
+            (SortKey::Missing, _) => Ordering::Less,
+            (_, SortKey::Missing) => Ordering::Greater,
+            (SortKey::Bool(a), SortKey::Bool(b)) => a.cmp(b),
+            (SortKey::Number(a), SortKey::Number(b)) => a.cmp(b),
+            (SortKey::Str(a), SortKey::Str(b)) => a.cmp(b),
+            _ => Ordering::Equal,
+        }
+    }
+
+}
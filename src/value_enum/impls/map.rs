@@ -5,10 +5,10 @@
 use {
     core::{
         convert::TryFrom,
-        iter::FromIterator,
+        iter::{Extend, FromIterator},
     },
 
-    crate::{Error, Map, MapKey, Result, Value},
+    crate::{Error, Map, MapEntry, MapKey, Result, Value},
 };
 
 /// # Helper macro for Value::*_maybe_by()/*_maybe_mut_by()
@@ -67,6 +67,27 @@ impl Value {
         }
     }
 
+    /// # If the value is a map, gets an entry of it
+    ///
+    /// Returns an error if the value is not a map.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mut map = binn_ir::map();
+    /// map.map_entry(0)?.or_insert_with(binn_ir::list).push("first")?;
+    /// map.map_entry(0)?.or_insert_with(binn_ir::list).push("second")?;
+    /// assert_eq!(map.map_by(&[0])?.at(&[1])?.as_text()?, "second");
+    ///
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    pub fn map_entry<K>(&mut self, key: K) -> Result<MapEntry<'_>> where K: Into<MapKey> {
+        match self {
+            Value::Map(map) => Ok(map.entry(key.into())),
+            _ => Err(err!("Value is not a map")),
+        }
+    }
+
     /// # Gets an immutable item from this map and its sub maps
     ///
     /// The function returns an error on one of these conditions:
@@ -215,6 +236,28 @@ impl FromIterator<(MapKey, Value)> for Value {
 
 }
 
+/// # Extends a map
+///
+/// If the value is [`Null`](enum.Value.html#variant.Null), it becomes an empty map first. Existing keys are overwritten.
+///
+/// ## Panics
+///
+/// Panics if the value is neither a map nor `Null`. Use [`as_mut_map()`](enum.Value.html#method.as_mut_map) to handle that case
+/// without panicking.
+impl Extend<(MapKey, Value)> for Value {
+
+    fn extend<T>(&mut self, iter: T) where T: IntoIterator<Item=(MapKey, Self)> {
+        if self.is_null() {
+            *self = Value::Map(Map::new());
+        }
+        match self {
+            Value::Map(map) => map.extend(iter),
+            _ => panic!("Value is not a Map"),
+        }
+    }
+
+}
+
 impl TryFrom<Value> for Map {
 
     type Error = Error;
@@ -0,0 +1,98 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Memory
+
+use {
+    core::mem,
+
+    crate::{MapKey, ObjectKey, Value},
+};
+
+/// # Memory
+impl Value {
+
+    /// # Estimates heap memory used by this value, in bytes
+    ///
+    /// This walks the whole tree, and sums up capacities of all strings, blobs and lists. For maps and objects, each item counts as one key
+    /// plus one value; internal nodes of [`BTreeMap`][alloc::collections::BTreeMap] are _not_ counted exactly.
+    ///
+    /// The value itself (`mem::size_of::<Value>()`) is not included. Note that this is unrelated to [`size()`][fn:size], which is the
+    /// encoded size.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// assert_eq!(Value::U8(0).heap_size_estimate(), 0);
+    /// assert!(Value::Blob(Vec::with_capacity(1024)).heap_size_estimate() >= 1024);
+    /// ```
+    ///
+    /// [alloc::collections::BTreeMap]: https://doc.rust-lang.org/alloc/collections/struct.BTreeMap.html
+    /// [fn:size]: #method.size
+    pub fn heap_size_estimate(&self) -> usize {
+        match self {
+            Value::Null | Value::True | Value::False | Value::U8(_) | Value::I8(_) | Value::U16(_) | Value::I16(_) | Value::U32(_) |
+            Value::I32(_) | Value::Float(_) | Value::U64(_) | Value::I64(_) | Value::Double(_) => 0,
+            Value::Text(s) | Value::DateTime(s) | Value::Date(s) | Value::Time(s) | Value::DecimalStr(s) => s.capacity(),
+            Value::Blob(blob) => blob.capacity(),
+            Value::List(list) => list.iter().fold(list.capacity().saturating_mul(mem::size_of::<Value>()), |result, v|
+                result.saturating_add(v.heap_size_estimate())
+            ),
+            Value::Map(map) => map.values().fold(map.len().saturating_mul(mem::size_of::<(MapKey, Value)>()), |result, v|
+                result.saturating_add(v.heap_size_estimate())
+            ),
+            Value::Object(object) => object.iter().fold(object.len().saturating_mul(mem::size_of::<(ObjectKey, Value)>()), |result, (k, v)|
+                result.saturating_add(k.capacity()).saturating_add(v.heap_size_estimate())
+            ),
+        }
+    }
+
+    /// # Shrinks capacities of all strings, blobs and lists in this value, recursively
+    ///
+    /// This is useful after building a document incrementally, before keeping it for a long time.
+    ///
+    /// Keys of objects are shrunk too. Since keys of a map can't be modified in place, an object is rebuilt if -- and only if -- some of its
+    /// keys have spare capacity.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// let mut list = Vec::with_capacity(1024);
+    /// list.push(Value::Blob(Vec::with_capacity(1024)));
+    ///
+    /// let mut value = Value::List(list);
+    /// value.shrink_to_fit();
+    /// assert_eq!(value.heap_size_estimate(), core::mem::size_of::<Value>());
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        match self {
+            Value::Null | Value::True | Value::False | Value::U8(_) | Value::I8(_) | Value::U16(_) | Value::I16(_) | Value::U32(_) |
+            Value::I32(_) | Value::Float(_) | Value::U64(_) | Value::I64(_) | Value::Double(_) => (),
+            Value::Text(s) | Value::DateTime(s) | Value::Date(s) | Value::Time(s) | Value::DecimalStr(s) => s.shrink_to_fit(),
+            Value::Blob(blob) => blob.shrink_to_fit(),
+            Value::List(list) => {
+                list.shrink_to_fit();
+                list.iter_mut().for_each(Value::shrink_to_fit);
+            },
+            Value::Map(map) => map.values_mut().for_each(Value::shrink_to_fit),
+            Value::Object(object) => {
+                if object.keys().any(key_has_spare_capacity) {
+                    *object = mem::take(object).into_iter().map(|(mut key, value)| {
+                        key.shrink_to_fit();
+                        (key, value)
+                    }).collect();
+                }
+                object.values_mut().for_each(Value::shrink_to_fit);
+            },
+        }
+    }
+
+}
+
+/// # Checks if an object key has spare capacity
+fn key_has_spare_capacity(key: &ObjectKey) -> bool {
+    key.capacity() > key.len()
+}
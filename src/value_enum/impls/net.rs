@@ -0,0 +1,120 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Conversions for `std::net`
+//!
+//! Addresses are converted to [`Text`][Value::Text]s, in their standard formats, such as `127.0.0.1`, `::1`, `127.0.0.1:80` and
+//! `[::1]:80`. IP addresses can also be converted from [`Blob`][Value::Blob]s of 4 or 16 bytes, in network byte order.
+//!
+//! [Value::Blob]: ../../enum.Value.html#variant.Blob
+//! [Value::Text]: ../../enum.Value.html#variant.Text
+
+use {
+    alloc::string::ToString,
+    core::{convert::TryFrom, fmt::Display, str::FromStr},
+    std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+
+    crate::{Error, Value},
+};
+
+macro_rules! impl_from_and_try_from_value_for_addrs { ($($ty: ty,)+) => {
+    $(
+        /// # Converts an address to a [`Text`](#variant.Text)
+        impl From<$ty> for Value {
+
+            fn from(addr: $ty) -> Self {
+                Value::Text(addr.to_string())
+            }
+
+        }
+
+        impl TryFrom<Value> for $ty {
+
+            type Error = Error;
+
+            fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+                Self::try_from(&v)
+            }
+
+        }
+    )+
+}}
+
+impl_from_and_try_from_value_for_addrs! {
+    IpAddr, Ipv4Addr, Ipv6Addr,
+    SocketAddr, SocketAddrV4, SocketAddrV6,
+}
+
+macro_rules! impl_try_from_text_for_socket_addrs { ($($ty: ty, $name: literal,)+) => {
+    $(
+        /// # Parses a [`Text`](enum.Value.html#variant.Text)
+        impl TryFrom<&Value> for $ty {
+
+            type Error = Error;
+
+            fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+                parse_text(v, $name)
+            }
+
+        }
+    )+
+}}
+
+impl_try_from_text_for_socket_addrs! {
+    SocketAddr, "socket address",
+    SocketAddrV4, "IPv4 socket address",
+    SocketAddrV6, "IPv6 socket address",
+}
+
+/// # Parses a [`Text`](enum.Value.html#variant.Text), or converts a [`Blob`](enum.Value.html#variant.Blob) of 4 or 16 bytes
+impl TryFrom<&Value> for IpAddr {
+
+    type Error = Error;
+
+    fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+        match v {
+            Value::Blob(blob) => match blob.len() {
+                4 => Ipv4Addr::try_from(v).map(IpAddr::V4),
+                16 => Ipv6Addr::try_from(v).map(IpAddr::V6),
+                other => Err(err!("Expected a Blob of 4 or 16 bytes, got: {}", other)),
+            },
+            _ => parse_text(v, "IP address"),
+        }
+    }
+
+}
+
+/// # Parses a [`Text`](enum.Value.html#variant.Text), or converts a [`Blob`](enum.Value.html#variant.Blob) of 4 bytes
+impl TryFrom<&Value> for Ipv4Addr {
+
+    type Error = Error;
+
+    fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+        match v {
+            Value::Blob(blob) => <[u8; 4]>::try_from(&blob[..]).map(Self::from)
+                .map_err(|_| err!("Expected a Blob of 4 bytes, got: {}", blob.len())),
+            _ => parse_text(v, "IPv4 address"),
+        }
+    }
+
+}
+
+/// # Parses a [`Text`](enum.Value.html#variant.Text), or converts a [`Blob`](enum.Value.html#variant.Blob) of 16 bytes
+impl TryFrom<&Value> for Ipv6Addr {
+
+    type Error = Error;
+
+    fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+        match v {
+            Value::Blob(blob) => <[u8; 16]>::try_from(&blob[..]).map(Self::from)
+                .map_err(|_| err!("Expected a Blob of 16 bytes, got: {}", blob.len())),
+            _ => parse_text(v, "IPv6 address"),
+        }
+    }
+
+}
+
+/// # Parses a [`Text`](enum.Value.html#variant.Text)
+fn parse_text<T>(v: &Value, name: &str) -> Result<T, Error> where T: FromStr, T::Err: Display {
+    let s = v.as_text()?;
+    s.parse().map_err(|e| err!("Invalid {}: {:?}: {}", name, s, e))
+}
@@ -0,0 +1,148 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Normalization
+
+use {
+    alloc::string::String,
+
+    crate::{
+        Value,
+        compat_level::{compress_signed, compress_unsigned},
+        value_enum::temporal,
+    },
+
+    super::validation::is_decimal_str,
+};
+
+/// # Normalization
+impl Value {
+
+    /// # Rewrites this value in place, into its minimal representation
+    ///
+    /// After normalization, values which mean the same thing are structurally equal -- so they compare, hash and encode the same:
+    ///
+    /// - Integers are stored in the smallest type which holds them. Non-negative integers become unsigned: `u8`, `u16`, `u32` or `u64`;
+    ///   negative ones become `i8`, `i16`, `i32` or `i64`. Unlike [`CompatLevel::CBinn`][CompatLevel#CBinn], this also applies to
+    ///   integers of 1 byte, so an [`I8`][#I8] of `1` becomes an [`U8`][#U8].
+    /// - Floating point numbers are canonicalized by [`canonicalize_floats()`][#canonicalize_floats()]. Then a [`Double`][#Double] becomes
+    ///   a [`Float`][#Float], if that does not lose precision.
+    /// - A valid [`DateTime`][#DateTime] uses `T` to separate its date and time.
+    /// - A valid [`DecimalStr`][#DecimalStr] has no leading `+`, and uses `e` for its exponent.
+    ///
+    /// Containers are normalized recursively. Other values are left as-is.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// let mut value = Value::List(vec![
+    ///     Value::U64(300), Value::I8(1), Value::I32(-1), Value::Double(0.5),
+    ///     Value::DateTime("2019-12-31 23:59:59".into()), Value::DecimalStr("+1.5E3".into()),
+    /// ]);
+    /// value.normalize();
+    /// assert_eq!(value, Value::List(vec![
+    ///     Value::U16(300), Value::U8(1), Value::I8(-1), Value::Float(0.5),
+    ///     Value::DateTime("2019-12-31T23:59:59".into()), Value::DecimalStr("1.5e3".into()),
+    /// ]));
+    /// ```
+    ///
+    /// [CompatLevel#CBinn]: enum.CompatLevel.html#variant.CBinn
+    /// [#canonicalize_floats()]: #method.canonicalize_floats
+    /// [#I8]: #variant.I8
+    /// [#U8]: #variant.U8
+    /// [#Double]: #variant.Double
+    /// [#Float]: #variant.Float
+    /// [#DateTime]: #variant.DateTime
+    /// [#DecimalStr]: #variant.DecimalStr
+    pub fn normalize(&mut self) {
+        match self {
+            Value::U8(u) => *self = compress_unsigned(u64::from(*u)),
+            Value::U16(u) => *self = compress_unsigned(u64::from(*u)),
+            Value::U32(u) => *self = compress_unsigned(u64::from(*u)),
+            Value::U64(u) => *self = compress_unsigned(*u),
+            Value::I8(i) => *self = compress_signed(i64::from(*i)),
+            Value::I16(i) => *self = compress_signed(i64::from(*i)),
+            Value::I32(i) => *self = compress_signed(i64::from(*i)),
+            Value::I64(i) => *self = compress_signed(*i),
+            Value::Float(f) => *f = canonical_f32(*f),
+            Value::Double(d) => {
+                let f = *d as f32;
+                if d.is_nan() || f64::from(f) == *d {
+                    *self = Value::Float(canonical_f32(f));
+                }
+            },
+            Value::DateTime(s) if temporal::parse_date_time(s).is_some() => s.replace_range(10..11, "T"),
+            Value::DecimalStr(s) if is_decimal_str(s) => *s = normalize_decimal_str(s),
+            Value::List(list) => list.iter_mut().for_each(Value::normalize),
+            Value::Map(map) => map.values_mut().for_each(Value::normalize),
+            Value::Object(object) => object.values_mut().for_each(Value::normalize),
+            _ => (),
+        };
+    }
+
+    /// # Canonicalizes floating point numbers in place
+    ///
+    /// Producers and platforms may write different bytes for the same number:
+    ///
+    /// - All NaNs become the canonical quiet NaN: [`f32::NAN`][f32::NAN] and [`f64::NAN`][f64::NAN].
+    /// - Negative zero becomes positive zero.
+    ///
+    /// This applies to [`Float`][#Float] and [`Double`][#Double]. Their types are kept. Containers are canonicalized recursively. Other
+    /// values are left as-is.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// let mut value = Value::List(vec![Value::Float(-0.0), Value::Double(f64::from_bits(0xfff8_0000_0000_0001))]);
+    /// value.canonicalize_floats();
+    /// assert_eq!(value.as_list()?[0].as_f64_lossy()?.to_bits(), 0);
+    /// assert_eq!(value.as_list()?[1].as_f64_lossy()?.to_bits(), f64::NAN.to_bits());
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    ///
+    /// [f32::NAN]: https://doc.rust-lang.org/core/primitive.f32.html#associatedconstant.NAN
+    /// [f64::NAN]: https://doc.rust-lang.org/core/primitive.f64.html#associatedconstant.NAN
+    /// [#Float]: #variant.Float
+    /// [#Double]: #variant.Double
+    pub fn canonicalize_floats(&mut self) {
+        match self {
+            Value::Float(f) => *f = canonical_f32(*f),
+            Value::Double(d) => *d = canonical_f64(*d),
+            Value::List(list) => list.iter_mut().for_each(Value::canonicalize_floats),
+            Value::Map(map) => map.values_mut().for_each(Value::canonicalize_floats),
+            Value::Object(object) => object.values_mut().for_each(Value::canonicalize_floats),
+            _ => (),
+        };
+    }
+
+}
+
+/// # Canonicalizes an `f32`
+fn canonical_f32(f: f32) -> f32 {
+    if f.is_nan() {
+        f32::NAN
+    } else if f == 0.0 {
+        0.0
+    } else {
+        f
+    }
+}
+
+/// # Canonicalizes an `f64`
+fn canonical_f64(d: f64) -> f64 {
+    if d.is_nan() {
+        f64::NAN
+    } else if d == 0.0 {
+        0.0
+    } else {
+        d
+    }
+}
+
+/// # Normalizes a valid decimal string
+fn normalize_decimal_str(s: &str) -> String {
+    s.strip_prefix('+').unwrap_or(s).replace('E', "e")
+}
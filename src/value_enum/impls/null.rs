@@ -15,10 +15,7 @@ impl Value {
     ///
     /// [#Null]: #variant.Null
     pub fn is_null(&self) -> bool {
-        match self {
-            Value::Null => true,
-            _ => false,
-        }
+        matches!(self, Value::Null)
     }
 
     /// # Tries to convert this value into something
@@ -89,4 +86,39 @@ impl Value {
         }
     }
 
+    /// # Tries to convert this value into an option
+    ///
+    /// If this is [`Null`][#Null], returns `None`. Otherwise, converts this value and returns it as `Some`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// assert_eq!(Value::Null.try_into_option::<u8>()?, None);
+    /// assert_eq!(Value::U16(9).try_into_option::<u8>()?, Some(9));
+    /// assert!(Value::from("nine").try_into_option::<u8>().is_err());
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    ///
+    /// [#Null]: #variant.Null
+    pub fn try_into_option<T>(self) -> Result<Option<T>> where T: TryFrom<Self, Error=Error> {
+        match self {
+            Value::Null => Ok(None),
+            _ => T::try_from(self).map(Some),
+        }
+    }
+
+    /// # Tries to convert a reference of this value into an option
+    ///
+    /// If this is [`Null`][#Null], returns `None`. Otherwise, converts this value and returns it as `Some`.
+    ///
+    /// [#Null]: #variant.Null
+    pub fn try_ref_into_option<'a, T>(&'a self) -> Result<Option<T>> where T: TryFrom<&'a Self, Error=Error> {
+        match self {
+            Value::Null => Ok(None),
+            _ => T::try_from(self).map(Some),
+        }
+    }
+
 }
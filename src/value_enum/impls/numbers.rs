@@ -3,11 +3,95 @@
 //! # Numbers
 
 use {
-    core::convert::TryFrom,
+    alloc::string::ToString,
+    core::{
+        convert::TryFrom,
+        num::{NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8},
+    },
 
-    crate::{Error, Value},
+    crate::{Error, Result, Value},
 };
 
+/// # Shortcuts for numbers
+impl Value {
+
+    /// # Gets an integer as `i64`
+    ///
+    /// Integers of any width and sign are accepted, as long as their values fit into `i64`. So it does not matter whether a producer encoded
+    /// `5` as [`U8`][#U8] or [`I32`][#I32].
+    ///
+    /// Returns an error if the value is not an integer, or is out of range.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// assert_eq!(Value::U8(5).as_i64_lossless()?, 5);
+    /// assert_eq!(Value::I32(-5).as_i64_lossless()?, -5);
+    /// assert!(Value::U64(u64::MAX).as_i64_lossless().is_err());
+    /// assert!(Value::Double(5.0).as_i64_lossless().is_err());
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    ///
+    /// [#U8]: #variant.U8
+    /// [#I32]: #variant.I32
+    pub fn as_i64_lossless(&self) -> Result<i64> {
+        i64::try_from(self)
+    }
+
+    /// # Gets an integer as `u64`
+    ///
+    /// Integers of any width and sign are accepted, as long as their values fit into `u64`. See
+    /// [`as_i64_lossless()`][#as_i64_lossless()].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// assert_eq!(Value::I16(300).as_u64_lossless()?, 300);
+    /// assert!(Value::I8(-1).as_u64_lossless().is_err());
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    ///
+    /// [#as_i64_lossless()]: #method.as_i64_lossless
+    pub fn as_u64_lossless(&self) -> Result<u64> {
+        u64::try_from(self)
+    }
+
+    /// # Gets a number as `f64`
+    ///
+    /// All numeric variants are accepted. Unlike `f64::try_from()`, this also accepts [`U64`][#U64] and
+    /// [`I64`][#I64]: their values are rounded to the nearest `f64`, which loses precision above 2<sup>53</sup>. This mirrors what
+    /// dynamic-language consumers of Binn do.
+    ///
+    /// Returns an error if the value is not a number.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// assert_eq!(Value::U8(1).as_f64_lossy()?, 1.0);
+    /// assert_eq!(Value::Float(0.5).as_f64_lossy()?, 0.5);
+    /// assert_eq!(Value::U64(u64::MAX).as_f64_lossy()?, 18_446_744_073_709_551_615.0);
+    /// assert!(Value::from("1").as_f64_lossy().is_err());
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    ///
+    /// [#U64]: #variant.U64
+    /// [#I64]: #variant.I64
+    pub fn as_f64_lossy(&self) -> Result<f64> {
+        match self {
+            Value::U64(u) => Ok(*u as f64),
+            Value::I64(i) => Ok(*i as f64),
+            _ => f64::try_from(self),
+        }
+    }
+
+}
+
 macro_rules! impl_from_numbers_for_value { ($($number: ty, $variant: tt,)+) => {
     $(
         impl From<&$number> for Value {
@@ -42,14 +126,14 @@ macro_rules! impl_try_from_value_for_integers { ($($ty: ty,)+) => {
 
             fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
                 match v {
-                    Value::I8(i) => Self::try_from(*i).map_err(|e| err!("{}", e)),
-                    Value::U8(u) => Self::try_from(*u).map_err(|e| err!("{}", e)),
-                    Value::I16(i) => Self::try_from(*i).map_err(|e| err!("{}", e)),
-                    Value::U16(u) => Self::try_from(*u).map_err(|e| err!("{}", e)),
-                    Value::I32(i) => Self::try_from(*i).map_err(|e| err!("{}", e)),
-                    Value::U32(u) => Self::try_from(*u).map_err(|e| err!("{}", e)),
-                    Value::I64(i) => Self::try_from(*i).map_err(|e| err!("{}", e)),
-                    Value::U64(u) => Self::try_from(*u).map_err(|e| err!("{}", e)),
+                    Value::I8(i) => Self::try_from(*i).map_err(|_| err_code!("Integer is out of range", *i)),
+                    Value::U8(u) => Self::try_from(*u).map_err(|_| err_code!("Integer is out of range", *u)),
+                    Value::I16(i) => Self::try_from(*i).map_err(|_| err_code!("Integer is out of range", *i)),
+                    Value::U16(u) => Self::try_from(*u).map_err(|_| err_code!("Integer is out of range", *u)),
+                    Value::I32(i) => Self::try_from(*i).map_err(|_| err_code!("Integer is out of range", *i)),
+                    Value::U32(u) => Self::try_from(*u).map_err(|_| err_code!("Integer is out of range", *u)),
+                    Value::I64(i) => Self::try_from(*i).map_err(|_| err_code!("Integer is out of range", *i)),
+                    Value::U64(u) => Self::try_from(*u).map_err(|_| err_code!("Integer is out of range", *u)),
                     _ => Err(err!("Value is not an integer")),
                 }
             }
@@ -129,3 +213,94 @@ impl TryFrom<Value> for f64 {
     }
 
 }
+
+macro_rules! impl_from_and_try_from_value_for_128_bit_integers { ($($ty: ty, $small_ty: ty,)+) => {
+    $(
+        /// # Converts a 128-bit integer to a 64-bit integer, or a [`DecimalStr`](#variant.DecimalStr) if it's out of range
+        impl From<$ty> for Value {
+
+            fn from(n: $ty) -> Self {
+                match <$small_ty>::try_from(n) {
+                    Ok(n) => Self::from(n),
+                    Err(_) => Value::DecimalStr(n.to_string()),
+                }
+            }
+
+        }
+
+        /// # Converts an integer, or a [`DecimalStr`](enum.Value.html#variant.DecimalStr) of an integer, to a 128-bit integer
+        impl TryFrom<&Value> for $ty {
+
+            type Error = Error;
+
+            fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+                match v {
+                    Value::I8(i) => Self::try_from(*i).map_err(|_| err_code!("Integer is out of range", *i)),
+                    Value::U8(u) => Self::try_from(*u).map_err(|_| err_code!("Integer is out of range", *u)),
+                    Value::I16(i) => Self::try_from(*i).map_err(|_| err_code!("Integer is out of range", *i)),
+                    Value::U16(u) => Self::try_from(*u).map_err(|_| err_code!("Integer is out of range", *u)),
+                    Value::I32(i) => Self::try_from(*i).map_err(|_| err_code!("Integer is out of range", *i)),
+                    Value::U32(u) => Self::try_from(*u).map_err(|_| err_code!("Integer is out of range", *u)),
+                    Value::I64(i) => Self::try_from(*i).map_err(|_| err_code!("Integer is out of range", *i)),
+                    Value::U64(u) => Self::try_from(*u).map_err(|_| err_code!("Integer is out of range", *u)),
+                    Value::DecimalStr(s) => s.parse().map_err(|e| err!("{}", e)),
+                    _ => Err(err!("Value is neither an integer nor a DecimalStr")),
+                }
+            }
+
+        }
+
+        /// # Converts an integer, or a [`DecimalStr`](enum.Value.html#variant.DecimalStr) of an integer, to a 128-bit integer
+        impl TryFrom<Value> for $ty {
+
+            type Error = Error;
+
+            fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+                Self::try_from(&v)
+            }
+
+        }
+    )+
+}}
+
+impl_from_and_try_from_value_for_128_bit_integers! {
+    i128, i64,
+    u128, u64,
+}
+
+macro_rules! impl_from_and_try_from_value_for_non_zero_integers { ($($ty: ty, $int: ty,)+) => {
+    $(
+        impl From<$ty> for Value {
+
+            fn from(n: $ty) -> Self {
+                Self::from(n.get())
+            }
+
+        }
+
+        impl TryFrom<&Value> for $ty {
+
+            type Error = Error;
+
+            fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+                Self::new(<$int>::try_from(v)?).ok_or_else(|| err!("Value is zero"))
+            }
+
+        }
+
+        impl TryFrom<Value> for $ty {
+
+            type Error = Error;
+
+            fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+                Self::try_from(&v)
+            }
+
+        }
+    )+
+}}
+
+impl_from_and_try_from_value_for_non_zero_integers! {
+    NonZeroI8, i8, NonZeroI16, i16, NonZeroI32, i32, NonZeroI64, i64,
+    NonZeroU8, u8, NonZeroU16, u16, NonZeroU32, u32, NonZeroU64, u64,
+}
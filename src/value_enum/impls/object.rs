@@ -5,10 +5,10 @@
 use {
     core::{
         convert::TryFrom,
-        iter::FromIterator,
+        iter::{Extend, FromIterator},
     },
 
-    crate::{Error, Object, ObjectKey, Result, Value},
+    crate::{Error, Object, ObjectEntry, ObjectKey, Result, Value},
 };
 
 /// # Helper macro for Value::*_maybe_by()/*_maybe_mut_by()
@@ -67,6 +67,59 @@ impl Value {
         }
     }
 
+    /// # If the value is an object, inserts new item into it, after validating its key
+    ///
+    /// See [`object_key()`][fn:object_key].
+    ///
+    /// On success, returns previous value (if it existed).
+    ///
+    /// Returns an error if the value is not an object, or the key is too long. In which case, the object is left unchanged.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mut value = binn_ir::object();
+    /// assert_eq!(value.object_insert_checked("name", "moss")?, None);
+    /// assert!(value.object_insert_checked("k".repeat(256), 0).is_err());
+    /// assert_eq!(value.as_object()?.len(), 1);
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    ///
+    /// [fn:object_key]: fn.object_key.html
+    pub fn object_insert_checked<K, V>(&mut self, key: K, value: V) -> Result<Option<Value>> where K: Into<ObjectKey>, V: Into<Self> {
+        match self {
+            Value::Object(object) => crate::object_insert_checked(object, key, value),
+            _ => Err(err!("Value is not an object")),
+        }
+    }
+
+    /// # If the value is an object, gets an entry of it
+    ///
+    /// Returns an error if the value is not an object.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use core::convert::TryFrom;
+    ///
+    /// let mut object = binn_ir::object();
+    /// for _ in 0..3 {
+    ///     object.object_entry("count")?.and_modify(|count| *count = (u8::try_from(&*count).unwrap() + 1).into()).or_insert(1.into());
+    /// }
+    /// object.object_entry("user")?.or_insert_with(binn_ir::object).object_insert("name", "moss")?;
+    ///
+    /// assert_eq!(u8::try_from(object.object_by(&["count"])?)?, 3);
+    /// assert_eq!(object.object_by(&["user", "name"])?.as_text()?, "moss");
+    ///
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    pub fn object_entry<K>(&mut self, key: K) -> Result<ObjectEntry<'_>> where K: Into<ObjectKey> {
+        match self {
+            Value::Object(object) => Ok(object.entry(key.into())),
+            _ => Err(err!("Value is not an object")),
+        }
+    }
+
     /// # Gets an immutable item from this object and its sub objects
     ///
     /// The function returns an error on one of these conditions:
@@ -177,6 +230,90 @@ impl Value {
         maybe_take_by!(self, Object, keys)
     }
 
+    /// # Gets an item of this object, and converts it
+    ///
+    /// If the item does not exist, or is [`Null`][#Null], returns `default`. This is handy for reading configuration-style objects.
+    ///
+    /// The function returns an error on one of these conditions:
+    ///
+    /// - The value is not an object.
+    /// - The item can't be converted.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mut config = binn_ir::object();
+    /// config.object_insert("name", "moss")?;
+    /// config.object_insert("port", 8080_u16)?;
+    /// config.object_insert("verbose", binn_ir::Value::Null)?;
+    ///
+    /// assert_eq!(config.get_str_or("name", "unknown")?, "moss");
+    /// assert_eq!(config.get_str_or("host", "localhost")?, "localhost");
+    /// assert_eq!(config.get_u64_or("port", 80)?, 8080);
+    /// assert_eq!(config.get_bool_or("verbose", true)?, true);
+    /// assert_eq!(config.get_or::<u8>("retries", 3)?, 3);
+    ///
+    /// assert!(config.get_u64_or("name", 0).is_err());
+    /// assert!(config.get_bool_or("port", false).is_err());
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    ///
+    /// [#Null]: #variant.Null
+    pub fn get_or<'a, T>(&'a self, key: &str, default: T) -> Result<T> where T: TryFrom<&'a Self, Error=Error> {
+        match self.as_object()?.get(key) {
+            Some(value) => value.try_ref_into_or(default),
+            None => Ok(default),
+        }
+    }
+
+    /// # Gets a text of this object
+    ///
+    /// See [`get_or()`][#get_or()] for details.
+    ///
+    /// [#get_or()]: #method.get_or
+    pub fn get_str_or<'a>(&'a self, key: &str, default: &'a str) -> Result<&'a str> {
+        match self.as_object()?.get(key) {
+            Some(Value::Null) | None => Ok(default),
+            Some(value) => value.as_text(),
+        }
+    }
+
+    /// # Gets an unsigned integer of this object
+    ///
+    /// See [`get_or()`][#get_or()] for details. Integers of any width are accepted, as long as they fit into `u64`.
+    ///
+    /// [#get_or()]: #method.get_or
+    pub fn get_u64_or(&self, key: &str, default: u64) -> Result<u64> {
+        self.get_or(key, default)
+    }
+
+    /// # Gets a signed integer of this object
+    ///
+    /// See [`get_or()`][#get_or()] for details. Integers of any width are accepted, as long as they fit into `i64`.
+    ///
+    /// [#get_or()]: #method.get_or
+    pub fn get_i64_or(&self, key: &str, default: i64) -> Result<i64> {
+        self.get_or(key, default)
+    }
+
+    /// # Gets a floating point number of this object
+    ///
+    /// See [`get_or()`][#get_or()] for details.
+    ///
+    /// [#get_or()]: #method.get_or
+    pub fn get_f64_or(&self, key: &str, default: f64) -> Result<f64> {
+        self.get_or(key, default)
+    }
+
+    /// # Gets a boolean of this object
+    ///
+    /// See [`get_or()`][#get_or()] for details.
+    ///
+    /// [#get_or()]: #method.get_or
+    pub fn get_bool_or(&self, key: &str, default: bool) -> Result<bool> {
+        self.get_or(key, default)
+    }
+
     /// # If the value is an object, returns an immutable reference of it
     ///
     /// Returns an error if the value is not an object.
@@ -215,6 +352,28 @@ impl FromIterator<(ObjectKey, Value)> for Value {
 
 }
 
+/// # Extends an object
+///
+/// If the value is [`Null`](enum.Value.html#variant.Null), it becomes an empty object first. Existing keys are overwritten.
+///
+/// ## Panics
+///
+/// Panics if the value is neither an object nor `Null`. Use [`as_mut_object()`](enum.Value.html#method.as_mut_object) to handle that
+/// case without panicking.
+impl Extend<(ObjectKey, Value)> for Value {
+
+    fn extend<T>(&mut self, iter: T) where T: IntoIterator<Item=(ObjectKey, Self)> {
+        if self.is_null() {
+            *self = Value::Object(Object::new());
+        }
+        match self {
+            Value::Object(object) => object.extend(iter),
+            _ => panic!("Value is not an Object"),
+        }
+    }
+
+}
+
 impl TryFrom<Value> for Object {
 
     type Error = Error;
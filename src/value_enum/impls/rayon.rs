@@ -0,0 +1,60 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Parallel computation with `rayon`
+
+use {
+    core::mem,
+
+    ::rayon::prelude::*,
+
+    crate::{
+        Result, Size, Value,
+        value::MAX_DATA_SIZE,
+        value_enum::{size_of_container, size_of_object_key},
+    },
+};
+
+/// # Parallel computation
+impl Value {
+
+    /// # Calculates size of this value, in parallel
+    ///
+    /// Result is the same as [`size()`][#size()]. Items of containers are walked via `rayon`'s thread pool, so this is useful for very large
+    /// trees. For small values, [`size()`][#size()] is faster.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// let value = Value::List(vec![Value::List(vec![Value::from("text"); 1_000]); 100]);
+    /// assert_eq!(value.size_parallel()?, value.size()?);
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    ///
+    /// [#size()]: #method.size
+    pub fn size_parallel(&self) -> Result<Size> {
+        match self {
+            Value::List(list) => size_of_container(list.len(), sum(list.par_iter().map(|v| v.size_parallel()))?),
+            Value::Map(map) => size_of_container(
+                map.len(), sum(map.par_iter().map(|(_, v)| v.size_parallel().map(|size| size + mem::size_of::<i32>() as Size)))?,
+            ),
+            Value::Object(object) => size_of_container(
+                object.len(), sum(object.par_iter().map(|(k, v)| Ok(size_of_object_key(k)? + v.size_parallel()?)))?,
+            ),
+            _ => self.size(),
+        }
+    }
+
+}
+
+/// # Sums up sizes
+///
+/// Each size is at most `MAX_DATA_SIZE`, so partial sums are kept in `u64` without overflowing.
+fn sum<I>(sizes: I) -> Result<Size> where I: ParallelIterator<Item=Result<Size>> {
+    let sum = sizes.map(|size| size.map(u64::from)).try_reduce(|| 0, |a, b| match a.saturating_add(b) {
+        sum if sum <= u64::from(MAX_DATA_SIZE) => Ok(sum),
+        sum => Err(err_code!("sum is too large (bytes)", sum)),
+    })?;
+    Ok(sum as Size)
+}
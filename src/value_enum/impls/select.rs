@@ -0,0 +1,153 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Selection
+
+use {
+    alloc::{
+        string::String,
+        vec::Vec,
+    },
+
+    crate::{MapKey, Path, PathSegment, Result, Size, Value},
+};
+
+/// # A step of a selector
+#[derive(Debug)]
+enum Step {
+    Key(String),
+    Index(Size),
+    MapKey(MapKey),
+    Wildcard,
+}
+
+/// # Selection
+impl Value {
+
+    /// # Selects values by a selector
+    ///
+    /// This is a lightweight analog of JSONPath. A selector is made of steps, optionally starting with `$` (the root value):
+    ///
+    /// | Step | Matches |
+    /// |---|---|
+    /// | `.key`, `["key"]` | An item of an object. The leading `.` may be omitted for the first step. |
+    /// | `[0]` | An item of a list. |
+    /// | `{-1}` | An item of a map. |
+    /// | `.*`, `[*]` | All items of a list, a map or an object. |
+    ///
+    /// Keys after `.` end at next `.`, `[` or `{`. Other keys must be quoted, and must not contain `"]`. Values which do not match -- such
+    /// as missing keys, or values of other types -- are skipped.
+    ///
+    /// Result: matched values with their paths. Items of objects come in order of their keys.
+    ///
+    /// ## Errors
+    ///
+    /// An error is returned if the selector has invalid syntax.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// let value = binn_ir::object_from("users", vec![
+    ///     binn_ir::object_from("address", binn_ir::object_from("city", "Hanoi")),
+    ///     binn_ir::object_from("name", "moss"),
+    ///     binn_ir::object_from("address", binn_ir::object_from("city", "Hue")),
+    /// ]);
+    ///
+    /// let cities = value.select("users[*].address.city")?;
+    /// assert_eq!(cities.iter().map(|(path, _)| path.to_string()).collect::<Vec<_>>(), [
+    ///     r#"$["users"][0]["address"]["city"]"#,
+    ///     r#"$["users"][2]["address"]["city"]"#,
+    /// ]);
+    /// assert_eq!(cities[1].1.as_text()?, "Hue");
+    ///
+    /// assert_eq!(value.select(r#"$["users"][1].name"#)?[0].1.as_text()?, "moss");
+    /// assert!(value.select("users[").is_err());
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    pub fn select(&self, selector: &str) -> Result<Vec<(Path, &Self)>> {
+        let mut result = alloc::vec![(Path::new(), self)];
+        for step in parse_selector(selector)? {
+            let mut next = Vec::with_capacity(result.len());
+            for (path, value) in result {
+                select_step(&step, path, value, &mut next);
+            }
+            result = next;
+        }
+        Ok(result)
+    }
+
+}
+
+/// # Selects items of a value by a step
+fn select_step<'a>(step: &Step, path: Path, value: &'a Value, result: &mut Vec<(Path, &'a Value)>) {
+    let mut add = |segment: PathSegment, item: &'a Value| {
+        let mut path = path.clone();
+        path.push(segment);
+        result.push((path, item));
+    };
+    match (step, value) {
+        (Step::Key(key), Value::Object(object)) => if let Some((key, item)) = object.get_key_value(key.as_str()) {
+            add(PathSegment::ObjectKey(key.clone()), item);
+        },
+        (Step::Index(index), Value::List(list)) => if let Some(item) = list.get(*index as usize) {
+            add(PathSegment::Index(*index), item);
+        },
+        (Step::MapKey(key), Value::Map(map)) => if let Some(item) = map.get(key) {
+            add(PathSegment::MapKey(*key), item);
+        },
+        (Step::Wildcard, Value::List(list)) => list.iter().enumerate().for_each(|(index, item)|
+            add(PathSegment::Index(index as Size), item)
+        ),
+        (Step::Wildcard, Value::Map(map)) => map.iter().for_each(|(key, item)| add(PathSegment::MapKey(*key), item)),
+        (Step::Wildcard, Value::Object(object)) => object.iter().for_each(|(key, item)|
+            add(PathSegment::ObjectKey(key.clone()), item)
+        ),
+        _ => (),
+    };
+}
+
+/// # Parses a selector
+fn parse_selector(selector: &str) -> Result<Vec<Step>> {
+    let mut result = Vec::new();
+    let mut rest = selector.strip_prefix('$').unwrap_or(selector);
+    let mut first = rest.len() == selector.len();
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '[' if rest.starts_with("[\"") => {
+                // Quoted keys may contain `]`
+                let end = rest[2..].find("\"]").ok_or_else(|| err!("Invalid selector {:?}: missing closing quote", selector))? + 2;
+                result.push(Step::Key(rest[2..end].into()));
+                rest = &rest[end + 2..];
+            },
+            '[' | '{' => {
+                let close = if c == '[' { ']' } else { '}' };
+                let end = rest.find(close).ok_or_else(|| err!("Invalid selector {:?}: missing {:?}", selector, close))?;
+                let inner = &rest[1..end];
+                result.push(match (c, inner) {
+                    (_, "*") => Step::Wildcard,
+                    ('[', _) => Step::Index(inner.parse().map_err(|_| err!("Invalid selector {:?}: invalid index {:?}", selector, inner))?),
+                    _ => Step::MapKey(inner.parse().map_err(|_| err!("Invalid selector {:?}: invalid map key {:?}", selector, inner))?),
+                });
+                rest = &rest[end + 1..];
+            },
+            _ => {
+                let key = match (c, first) {
+                    ('.', _) => &rest[1..],
+                    (_, true) => rest,
+                    (_, false) => return Err(err!("Invalid selector {:?}: unexpected {:?}", selector, c)),
+                };
+                let end = key.find(['.', '[', '{']).unwrap_or(key.len());
+                result.push(match &key[..end] {
+                    "" => return Err(err!("Invalid selector {:?}: empty key", selector)),
+                    "*" => Step::Wildcard,
+                    k => Step::Key(k.into()),
+                });
+                rest = &key[end..];
+            },
+        };
+        first = false;
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,87 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Stats
+
+use crate::{Path, Stats, Value};
+
+/// # Stats
+impl Value {
+
+    /// # Collects statistics of this value
+    ///
+    /// This walks the whole tree once, and counts values per type, max depth, total bytes of strings and blobs, and finds the largest
+    /// container.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// let mut value = binn_ir::object_from("name", "moss");
+    /// value.object_insert("tags", vec![Value::from("green"), Value::Blob(vec![0; 10])])?;
+    ///
+    /// let stats = value.stats();
+    /// assert_eq!(stats.count(binn_ir::value::TEXT), 2);
+    /// assert_eq!(stats.total_count(), 5);
+    /// assert_eq!(stats.max_depth(), 2);
+    /// assert_eq!(stats.text_bytes(), 9);
+    /// assert_eq!(stats.blob_bytes(), 10);
+    /// assert_eq!(stats.largest_container().map(|(path, count)| (path.to_string(), count)), Some((String::from("$"), 2)));
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    pub fn stats(&self) -> Stats {
+        let mut result = Stats::default();
+        result.collect(self, &mut Path::new());
+        result
+    }
+
+    /// # Counts all values in this tree, including this value itself
+    ///
+    /// This is the number that decoders count against [`DecodeConfig::max_values`][DecodeConfig#max_values].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// assert_eq!(Value::Null.deep_len(), 1);
+    /// assert_eq!(Value::List(vec![Value::Null, binn_ir::object_from("key", 0)]).deep_len(), 4);
+    /// ```
+    ///
+    /// [DecodeConfig#max_values]: struct.DecodeConfig.html#structfield.max_values
+    pub fn deep_len(&self) -> usize {
+        1 + match self {
+            Value::List(list) => list.iter().map(Value::deep_len).sum(),
+            Value::Map(map) => map.values().map(Value::deep_len).sum(),
+            Value::Object(object) => object.values().map(Value::deep_len).sum(),
+            _ => 0,
+        }
+    }
+
+    /// # Calculates max depth of nested containers
+    ///
+    /// A non-container value has depth zero. A container has depth of its deepest item, plus one. This is the number that decoders check
+    /// against [`DecodeConfig::max_depth`][DecodeConfig#max_depth].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// assert_eq!(Value::Null.max_depth(), 0);
+    /// assert_eq!(binn_ir::list().max_depth(), 1);
+    /// assert_eq!(Value::List(vec![Value::Null, binn_ir::object_from("key", binn_ir::map())]).max_depth(), 3);
+    /// ```
+    ///
+    /// [DecodeConfig#max_depth]: struct.DecodeConfig.html#structfield.max_depth
+    pub fn max_depth(&self) -> usize {
+        let items = match self {
+            Value::List(list) => list.iter().map(Value::max_depth).max(),
+            Value::Map(map) => map.values().map(Value::max_depth).max(),
+            Value::Object(object) => object.values().map(Value::max_depth).max(),
+            _ => return 0,
+        };
+        items.unwrap_or_default() + 1
+    }
+
+}
@@ -0,0 +1,143 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Conversions for `std::time`
+
+use {
+    alloc::format,
+    core::convert::TryFrom,
+    std::time::{Duration, SystemTime, UNIX_EPOCH},
+
+    crate::{
+        Error, Object, Value,
+        value_enum::temporal::{self, SECS_PER_DAY},
+    },
+};
+
+/// # Key of seconds, in an object of a duration
+const KEY_SECS: &str = "secs";
+
+/// # Key of nanoseconds, in an object of a duration
+const KEY_NANOS: &str = "nanos";
+
+/// # Nanoseconds per second
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+/// # Converts a duration to an [`Object`](#variant.Object)
+///
+/// The object has 2 keys: `secs` ([`U64`](#variant.U64)) and `nanos` ([`U32`](#variant.U32)).
+impl From<Duration> for Value {
+
+    fn from(duration: Duration) -> Self {
+        let mut object = Object::new();
+        object.insert(KEY_SECS.into(), Value::U64(duration.as_secs()));
+        object.insert(KEY_NANOS.into(), Value::U32(duration.subsec_nanos()));
+        Value::Object(object)
+    }
+
+}
+
+/// # Converts an [`Object`](enum.Value.html#variant.Object) of `secs` and `nanos` to a duration
+impl TryFrom<&Value> for Duration {
+
+    type Error = Error;
+
+    fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+        let (secs, nanos) = (u64::try_from(v.object_by(&[KEY_SECS])?)?, u32::try_from(v.object_by(&[KEY_NANOS])?)?);
+        match nanos < NANOS_PER_SEC {
+            true => Ok(Duration::new(secs, nanos)),
+            false => Err(err!("Invalid nanoseconds: {}", nanos)),
+        }
+    }
+
+}
+
+/// # Converts an [`Object`](enum.Value.html#variant.Object) of `secs` and `nanos` to a duration
+impl TryFrom<Value> for Duration {
+
+    type Error = Error;
+
+    fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+        Self::try_from(&v)
+    }
+
+}
+
+/// # Converts a system time to a [`DateTime`](#variant.DateTime)
+///
+/// The result is an RFC 3339 string in UTC, such as `2019-12-31T23:59:59.5Z`. An error is returned if the year is out of range
+/// `0..=9999`.
+impl TryFrom<SystemTime> for Value {
+
+    type Error = Error;
+
+    fn try_from(time: SystemTime) -> core::result::Result<Self, Self::Error> {
+        let (secs, nanos) = match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => (i64::try_from(duration.as_secs()).map_err(|e| err!("{}", e))?, duration.subsec_nanos()),
+            Err(err) => {
+                let duration = err.duration();
+                let secs = i64::try_from(duration.as_secs()).map_err(|e| err!("{}", e))?;
+                match duration.subsec_nanos() {
+                    0 => (-secs, 0),
+                    nanos => (-secs - 1, NANOS_PER_SEC - nanos),
+                }
+            },
+        };
+
+        let (year, month, day) = temporal::civil_from_days(secs.div_euclid(SECS_PER_DAY));
+        if !(0..=9999).contains(&year) {
+            return Err(err!("Year is out of range: {}", year));
+        }
+        let secs_of_day = secs.rem_euclid(SECS_PER_DAY);
+        let mut result = format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}", year, month, day, secs_of_day / 3600, secs_of_day % 3600 / 60, secs_of_day % 60,
+        );
+        if nanos > 0 {
+            result.push_str(format!(".{:09}", nanos).trim_end_matches('0'));
+        }
+        result.push('Z');
+
+        Ok(Value::DateTime(result))
+    }
+
+}
+
+/// # Converts a [`DateTime`](enum.Value.html#variant.DateTime) of an RFC 3339 string to a system time
+///
+/// Time zone offsets are supported. Fractions of a second beyond nanoseconds are truncated.
+impl TryFrom<&Value> for SystemTime {
+
+    type Error = Error;
+
+    fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+        let s = v.as_date_time()?;
+        let (secs, nanos) = parse_rfc3339(s).ok_or_else(|| err!("Invalid RFC 3339 date-time: {:?}", s))?;
+        let time = match u64::try_from(secs) {
+            Ok(secs) => UNIX_EPOCH.checked_add(Duration::from_secs(secs)),
+            Err(_) => UNIX_EPOCH.checked_sub(Duration::from_secs(secs.unsigned_abs())),
+        };
+        time.and_then(|time| time.checked_add(Duration::from_nanos(u64::from(nanos))))
+            .ok_or_else(|| err!("Date-time is out of range of system time: {:?}", s))
+    }
+
+}
+
+/// # Converts a [`DateTime`](enum.Value.html#variant.DateTime) of an RFC 3339 string to a system time
+impl TryFrom<Value> for SystemTime {
+
+    type Error = Error;
+
+    fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+        Self::try_from(&v)
+    }
+
+}
+
+/// # Parses an RFC 3339 string
+///
+/// Result: seconds since Unix epoch, and nanoseconds.
+fn parse_rfc3339(s: &str) -> Option<(i64, u32)> {
+    let (date, time) = temporal::parse_date_time(s)?;
+    // Leap seconds roll over to next minute
+    let secs = date.days_since_unix_epoch() * SECS_PER_DAY + time.secs_of_day() - time.offset?;
+    Some((secs, time.nanos))
+}
@@ -105,3 +105,38 @@ impl TryFrom<Value> for String {
     }
 
 }
+
+/// # Converts a character to a single-character [`Text`](#variant.Text)
+impl From<char> for Value {
+
+    fn from(c: char) -> Self {
+        Self::from(c.to_string())
+    }
+
+}
+
+/// # Converts a single-character [`Text`](enum.Value.html#variant.Text) to a character
+impl TryFrom<&Value> for char {
+
+    type Error = Error;
+
+    fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+        let mut chars = v.as_text()?.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(err!("Text is not a single character")),
+        }
+    }
+
+}
+
+/// # Converts a single-character [`Text`](enum.Value.html#variant.Text) to a character
+impl TryFrom<Value> for char {
+
+    type Error = Error;
+
+    fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+        Self::try_from(&v)
+    }
+
+}
@@ -0,0 +1,117 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Conversions for `time`
+
+use {
+    alloc::{format, string::ToString},
+    core::convert::TryFrom,
+
+    ::time::{Date, OffsetDateTime, Time, format_description::well_known::Rfc3339, macros::format_description},
+
+    crate::{Error, Value},
+};
+
+/// # Converts a date-time to a [`DateTime`](#variant.DateTime) of an RFC 3339 string
+///
+/// The offset is kept as-is. An error is returned if the year is out of range `0..=9999`.
+impl TryFrom<OffsetDateTime> for Value {
+
+    type Error = Error;
+
+    fn try_from(date_time: OffsetDateTime) -> core::result::Result<Self, Self::Error> {
+        date_time.format(&Rfc3339).map(Value::DateTime).map_err(|e| err!("{}", e))
+    }
+
+}
+
+/// # Parses a [`DateTime`](enum.Value.html#variant.DateTime) of an RFC 3339 string
+impl TryFrom<&Value> for OffsetDateTime {
+
+    type Error = Error;
+
+    fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+        let s = v.as_date_time()?;
+        OffsetDateTime::parse(s, &Rfc3339).map_err(|e| err!("Invalid RFC 3339 date-time: {:?}: {}", s, e))
+    }
+
+}
+
+/// # Parses a [`DateTime`](enum.Value.html#variant.DateTime) of an RFC 3339 string
+impl TryFrom<Value> for OffsetDateTime {
+
+    type Error = Error;
+
+    fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+        Self::try_from(&v)
+    }
+
+}
+
+/// # Converts a date to a [`Date`](#variant.Date), such as `2019-12-31`
+impl From<Date> for Value {
+
+    fn from(date: Date) -> Self {
+        Value::Date(date.to_string())
+    }
+
+}
+
+/// # Parses a [`Date`](enum.Value.html#variant.Date), such as `2019-12-31`
+impl TryFrom<&Value> for Date {
+
+    type Error = Error;
+
+    fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+        let s = v.as_date()?;
+        Date::parse(s, format_description!("[year]-[month]-[day]")).map_err(|e| err!("Invalid date: {:?}: {}", s, e))
+    }
+
+}
+
+/// # Parses a [`Date`](enum.Value.html#variant.Date), such as `2019-12-31`
+impl TryFrom<Value> for Date {
+
+    type Error = Error;
+
+    fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+        Self::try_from(&v)
+    }
+
+}
+
+/// # Converts a time to a [`Time`](#variant.Time), such as `23:59:59.5`
+impl From<Time> for Value {
+
+    fn from(time: Time) -> Self {
+        let mut result = format!("{:02}:{:02}:{:02}", time.hour(), time.minute(), time.second());
+        if time.nanosecond() > 0 {
+            result.push_str(format!(".{:09}", time.nanosecond()).trim_end_matches('0'));
+        }
+        Value::Time(result)
+    }
+
+}
+
+/// # Parses a [`Time`](enum.Value.html#variant.Time), such as `23:59:59.5`
+impl TryFrom<&Value> for Time {
+
+    type Error = Error;
+
+    fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+        let s = v.as_time()?;
+        Time::parse(s, format_description!("[hour]:[minute]:[second][optional [.[subsecond]]]"))
+            .map_err(|e| err!("Invalid time: {:?}: {}", s, e))
+    }
+
+}
+
+/// # Parses a [`Time`](enum.Value.html#variant.Time), such as `23:59:59.5`
+impl TryFrom<Value> for Time {
+
+    type Error = Error;
+
+    fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+        Self::try_from(&v)
+    }
+
+}
@@ -0,0 +1,76 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Conversions for `uuid`
+
+use {
+    alloc::string::ToString,
+    core::convert::TryFrom,
+
+    ::uuid::{Uuid, fmt::Hyphenated},
+
+    crate::{Error, Value},
+};
+
+/// # Converts a UUID to a 16-byte [`Blob`](#variant.Blob)
+///
+/// To get a hyphenated [`Text`](#variant.Text) instead, convert [`Uuid::hyphenated()`][uuid::Uuid#hyphenated()].
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::Value;
+/// use uuid::Uuid;
+///
+/// let uuid = Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+/// assert_eq!(Value::from(uuid).as_blob()?.len(), 16);
+/// assert_eq!(Value::from(uuid.hyphenated()).as_text()?, "67e55044-10b1-426f-9247-bb680e5fe0c8");
+/// # Ok::<_, binn_ir::Error>(())
+/// ```
+///
+/// [uuid::Uuid#hyphenated()]: https://docs.rs/uuid/1/uuid/struct.Uuid.html#method.hyphenated
+impl From<Uuid> for Value {
+
+    fn from(uuid: Uuid) -> Self {
+        Value::Blob(uuid.as_bytes().to_vec())
+    }
+
+}
+
+/// # Converts a UUID to a hyphenated [`Text`](#variant.Text), such as `67e55044-10b1-426f-9247-bb680e5fe0c8`
+impl From<Hyphenated> for Value {
+
+    fn from(uuid: Hyphenated) -> Self {
+        Value::Text(uuid.to_string())
+    }
+
+}
+
+/// # Converts a 16-byte [`Blob`](enum.Value.html#variant.Blob), or a [`Text`](enum.Value.html#variant.Text), to a UUID
+///
+/// Texts can be in any format that [`Uuid::parse_str()`][uuid::Uuid#parse_str()] supports.
+///
+/// [uuid::Uuid#parse_str()]: https://docs.rs/uuid/1/uuid/struct.Uuid.html#method.parse_str
+impl TryFrom<&Value> for Uuid {
+
+    type Error = Error;
+
+    fn try_from(v: &Value) -> core::result::Result<Self, Self::Error> {
+        match v {
+            Value::Blob(blob) => Uuid::from_slice(blob).map_err(|e| err!("{}", e)),
+            Value::Text(s) => Uuid::parse_str(s).map_err(|e| err!("Invalid UUID: {:?}: {}", s, e)),
+            _ => Err(err!("Value is neither a Blob nor a Text")),
+        }
+    }
+
+}
+
+/// # Converts a 16-byte [`Blob`](enum.Value.html#variant.Blob), or a [`Text`](enum.Value.html#variant.Text), to a UUID
+impl TryFrom<Value> for Uuid {
+
+    type Error = Error;
+
+    fn try_from(v: Value) -> core::result::Result<Self, Self::Error> {
+        Self::try_from(&v)
+    }
+
+}
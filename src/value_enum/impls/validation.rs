@@ -0,0 +1,147 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Validation
+
+use {
+    alloc::string::String,
+
+    crate::{
+        Result, Value,
+        value_enum::temporal,
+    },
+};
+
+/// # Validation
+impl Value {
+
+    /// # Validates formats of temporal values
+    ///
+    /// [`Date`][#Date], [`Time`][#Time] and [`DateTime`][#DateTime] must follow these formats -- a subset of ISO 8601:
+    ///
+    /// - Date: `YYYY-MM-DD`.
+    /// - Time: `hh:mm:ss`, with optional fraction of a second (`.s...`), and optional offset (`Z`, or `+hh:mm`/`-hh:mm`).
+    /// - Date-time: a date and a time, separated by `T` (or a space).
+    ///
+    /// Containers are validated recursively. Other values are ignored.
+    ///
+    /// ## Errors
+    ///
+    /// The error message contains the offending value.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// assert!(Value::Date("2019-12-31".into()).validate_temporal().is_ok());
+    /// assert!(Value::Time("23:59:59.5+07:00".into()).validate_temporal().is_ok());
+    /// assert!(Value::List(vec![Value::DateTime("2019-12-31 24:00:00".into())]).validate_temporal().is_err());
+    /// ```
+    ///
+    /// [#Date]: #variant.Date
+    /// [#Time]: #variant.Time
+    /// [#DateTime]: #variant.DateTime
+    pub fn validate_temporal(&self) -> Result<()> {
+        match self {
+            Value::DateTime(s) => match temporal::parse_date_time(s) {
+                Some(_) => Ok(()),
+                None => Err(err!("Invalid DateTime: {:?}", s)),
+            },
+            Value::Date(s) => match temporal::parse_date(s) {
+                Some(_) => Ok(()),
+                None => Err(err!("Invalid Date: {:?}", s)),
+            },
+            Value::Time(s) => match temporal::parse_time(s) {
+                Some(_) => Ok(()),
+                None => Err(err!("Invalid Time: {:?}", s)),
+            },
+            Value::List(list) => list.iter().try_for_each(Value::validate_temporal),
+            Value::Map(map) => map.values().try_for_each(Value::validate_temporal),
+            Value::Object(object) => object.values().try_for_each(Value::validate_temporal),
+            _ => Ok(()),
+        }
+    }
+
+    /// # Makes a [`DecimalStr`][#DecimalStr], after validating its syntax
+    ///
+    /// See [`validate_decimal_str()`][#validate_decimal_str()] for the syntax.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// assert_eq!(Value::checked_decimal_str("-0.5")?, Value::DecimalStr("-0.5".into()));
+    /// assert!(Value::checked_decimal_str("one half").is_err());
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    ///
+    /// [#DecimalStr]: #variant.DecimalStr
+    /// [#validate_decimal_str()]: #method.validate_decimal_str
+    pub fn checked_decimal_str<S>(s: S) -> Result<Self> where S: Into<String> {
+        let result = Value::DecimalStr(s.into());
+        result.validate_decimal_str()?;
+        Ok(result)
+    }
+
+    /// # Validates syntax of decimal strings
+    ///
+    /// A [`DecimalStr`][#DecimalStr] must be a signed decimal number, such as `1`, `-0.5`, `+.5` or `1.5e-10`:
+    ///
+    /// - An optional sign (`+` or `-`).
+    /// - Digits, with an optional fraction. Either the integer part or the fraction must have at least one digit.
+    /// - An optional exponent: `e` or `E`, an optional sign, then at least one digit.
+    ///
+    /// Containers are validated recursively. Other values are ignored.
+    ///
+    /// ## Errors
+    ///
+    /// The error message contains the offending value.
+    ///
+    /// [#DecimalStr]: #variant.DecimalStr
+    pub fn validate_decimal_str(&self) -> Result<()> {
+        match self {
+            Value::DecimalStr(s) => match is_decimal_str(s) {
+                true => Ok(()),
+                false => Err(err!("Invalid DecimalStr: {:?}", s)),
+            },
+            Value::List(list) => list.iter().try_for_each(Value::validate_decimal_str),
+            Value::Map(map) => map.values().try_for_each(Value::validate_decimal_str),
+            Value::Object(object) => object.values().try_for_each(Value::validate_decimal_str),
+            _ => Ok(()),
+        }
+    }
+
+}
+
+/// # Checks if a string is a signed decimal number
+pub(super) fn is_decimal_str(s: &str) -> bool {
+    fn skip_digits(bytes: &[u8]) -> (usize, &[u8]) {
+        let count = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+        (count, &bytes[count..])
+    }
+    fn skip_sign(bytes: &[u8]) -> &[u8] {
+        match bytes.first() {
+            Some(b'+' | b'-') => &bytes[1..],
+            _ => bytes,
+        }
+    }
+
+    let (integer_digits, rest) = skip_digits(skip_sign(s.as_bytes()));
+    let (fraction_digits, rest) = match rest.first() {
+        Some(b'.') => skip_digits(&rest[1..]),
+        _ => (0, rest),
+    };
+    if integer_digits == 0 && fraction_digits == 0 {
+        return false;
+    }
+
+    match rest.first() {
+        None => true,
+        Some(b'e' | b'E') => match skip_digits(skip_sign(&rest[1..])) {
+            (1.., rest) => rest.is_empty(),
+            _ => false,
+        },
+        Some(_) => false,
+    }
+}
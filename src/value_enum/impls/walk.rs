@@ -0,0 +1,276 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Walk
+
+use {
+    alloc::vec::Vec,
+
+    crate::{Path, PathSegment, Size, Value, Walk},
+};
+
+/// # Walk
+impl Value {
+
+    /// # Walks this value, and all values inside it
+    ///
+    /// `f` is called on every value, with its path -- the root value first, then items of containers, depth-first. Items of objects are
+    /// visited in order of their keys. Result of `f` controls what comes next (see [`Walk`][enum:Walk]).
+    ///
+    /// Result: `false` if walking was stopped by `f`, `true` otherwise.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::{Value, Walk};
+    ///
+    /// let mut value = binn_ir::object_from("name", "moss");
+    /// value.object_insert("tags", vec![Value::from("green"), Value::from("soft")])?;
+    ///
+    /// let mut texts = vec![];
+    /// value.walk(|path, value| {
+    ///     if let Value::Text(s) = value {
+    ///         texts.push(format!("{}: {}", path, s));
+    ///     }
+    ///     Walk::Continue
+    /// });
+    /// assert_eq!(texts, [r#"$["name"]: moss"#, r#"$["tags"][0]: green"#, r#"$["tags"][1]: soft"#]);
+    ///
+    /// // Finds first text
+    /// let mut first = None;
+    /// assert!(value.walk(|path, value| match value {
+    ///     Value::Text(_) => {
+    ///         first = Some(path.clone());
+    ///         Walk::Stop
+    ///     },
+    ///     _ => Walk::Continue,
+    /// }) == false);
+    /// assert_eq!(first.unwrap().to_string(), r#"$["name"]"#);
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    ///
+    /// [enum:Walk]: enum.Walk.html
+    pub fn walk<F>(&self, mut f: F) -> bool where F: FnMut(&Path, &Self) -> Walk {
+        self.walk_in(&mut Path::new(), &mut f)
+    }
+
+    /// # Transforms this value, and all values inside it, in place
+    ///
+    /// `f` is called on every value, with its path -- the root value first, then items of containers, depth-first. Items of objects are
+    /// visited in order of their keys.
+    ///
+    /// A container is passed to `f` before its items. So `f` can rewrite the container itself -- such as renaming or removing its keys --
+    /// and then items of the rewritten container are visited.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// let mut value = binn_ir::object_from("user", binn_ir::object_from("password", "secret"));
+    /// value.object_insert("id", 9_u64)?;
+    ///
+    /// value.transform(|path, value| {
+    ///     match path.segments().last().map(|segment| segment.to_string()) {
+    ///         Some(key) if key == r#"["password"]"# => *value = Value::from("***"),
+    ///         _ => if let Value::U64(u) = value {
+    ///             *value = Value::Text(u.to_string());
+    ///         },
+    ///     };
+    /// });
+    /// assert_eq!(value.object_by(&["user", "password"])?.as_text()?, "***");
+    /// assert_eq!(value.object_by(&["id"])?.as_text()?, "9");
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    pub fn transform<F>(&mut self, mut f: F) where F: FnMut(&Path, &mut Self) {
+        self.transform_in(&mut Path::new(), &mut f);
+    }
+
+    /// # Transforms this value, at a path
+    fn transform_in<F>(&mut self, path: &mut Path, f: &mut F) where F: FnMut(&Path, &mut Self) {
+        f(path, self);
+
+        match self {
+            Value::List(list) => list.iter_mut().enumerate().for_each(|(index, item)|
+                item.transform_item(PathSegment::Index(index as Size), path, f)
+            ),
+            Value::Map(map) => map.iter_mut().for_each(|(key, item)| item.transform_item(PathSegment::MapKey(*key), path, f)),
+            Value::Object(object) => object.iter_mut().for_each(|(key, item)|
+                item.transform_item(PathSegment::ObjectKey(key.clone()), path, f)
+            ),
+            _ => (),
+        };
+    }
+
+    /// # Transforms an item of a container
+    fn transform_item<F>(&mut self, segment: PathSegment, path: &mut Path, f: &mut F) where F: FnMut(&Path, &mut Self) {
+        path.push(segment);
+        self.transform_in(path, f);
+        path.pop();
+    }
+
+    /// # Removes items of containers which don't match a predicate, recursively
+    ///
+    /// `pred` is called on every item of lists, maps and objects, with its path. Items for which it returns `false` are removed, along
+    /// with everything inside them. Items which are kept are then filtered the same way. The root value itself is never removed.
+    ///
+    /// Paths hold original positions of list items, before any removal.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// let mut value = binn_ir::object_from("name", "moss");
+    /// value.object_insert("avatar", Value::Blob(vec![0; 1024]))?;
+    /// value.object_insert("tags", vec![Value::from("green"), Value::Null])?;
+    ///
+    /// value.retain(|_, value| match value {
+    ///     Value::Null | Value::Blob(_) => false,
+    ///     _ => true,
+    /// });
+    ///
+    /// let mut expected = binn_ir::object_from("name", "moss");
+    /// expected.object_insert("tags", vec![Value::from("green")])?;
+    /// assert_eq!(value, expected);
+    /// # Ok::<_, binn_ir::Error>(())
+    /// ```
+    pub fn retain<F>(&mut self, mut pred: F) where F: FnMut(&Path, &Self) -> bool {
+        self.retain_in(&mut Path::new(), &mut pred);
+    }
+
+    /// # Removes items of this value which don't match a predicate, recursively
+    fn retain_in<F>(&mut self, path: &mut Path, pred: &mut F) where F: FnMut(&Path, &Self) -> bool {
+        match self {
+            Value::List(list) => {
+                let mut index: Size = 0;
+                list.retain_mut(|item| {
+                    let result = item.retain_item(PathSegment::Index(index), path, pred);
+                    index += 1;
+                    result
+                });
+            },
+            Value::Map(map) => map.retain(|key, item| item.retain_item(PathSegment::MapKey(*key), path, pred)),
+            Value::Object(object) => object.retain(|key, item| item.retain_item(PathSegment::ObjectKey(key.clone()), path, pred)),
+            _ => (),
+        };
+    }
+
+    /// # Checks if an item of a container should be kept, and if so, filters its own items
+    fn retain_item<F>(&mut self, segment: PathSegment, path: &mut Path, pred: &mut F) -> bool where F: FnMut(&Path, &Self) -> bool {
+        path.push(segment);
+        let result = pred(path, self);
+        if result {
+            self.retain_in(path, pred);
+        }
+        path.pop();
+        result
+    }
+
+    /// # Finds first value matching a predicate
+    ///
+    /// Values are visited in the same order as [`walk()`][#walk()], including this value itself.
+    ///
+    /// Result: path to the value, and the value.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// let value = binn_ir::object_from("files", vec![Value::Blob(vec![0; 8]), Value::Blob(vec![0; 1024])]);
+    ///
+    /// let (path, _) = value.find_first(|value| matches!(value, Value::Blob(blob) if blob.len() > 512)).unwrap();
+    /// assert_eq!(path.to_string(), r#"$["files"][1]"#);
+    /// assert!(value.find_first(|value| value == &Value::Null).is_none());
+    /// ```
+    ///
+    /// [#walk()]: #method.walk
+    pub fn find_first<F>(&self, mut pred: F) -> Option<(Path, &Self)> where F: FnMut(&Self) -> bool {
+        let mut result = Vec::with_capacity(1);
+        self.find_in(&mut Path::new(), &mut pred, &mut result, true);
+        result.pop()
+    }
+
+    /// # Finds all values matching a predicate
+    ///
+    /// Values are visited in the same order as [`walk()`][#walk()], including this value itself. Values inside matching containers are
+    /// also checked.
+    ///
+    /// Result: paths to the values, and the values.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use binn_ir::Value;
+    ///
+    /// let value = binn_ir::object_from("files", vec![Value::Blob(vec![0; 8]), Value::Blob(vec![0; 1024])]);
+    ///
+    /// let paths = value.find_all(|value| matches!(value, Value::Blob(_)))
+    ///     .into_iter().map(|(path, _)| path.to_string()).collect::<Vec<_>>();
+    /// assert_eq!(paths, [r#"$["files"][0]"#, r#"$["files"][1]"#]);
+    /// ```
+    ///
+    /// [#walk()]: #method.walk
+    pub fn find_all<F>(&self, mut pred: F) -> Vec<(Path, &Self)> where F: FnMut(&Self) -> bool {
+        let mut result = Vec::new();
+        self.find_in(&mut Path::new(), &mut pred, &mut result, false);
+        result
+    }
+
+    /// # Finds values matching a predicate, at a path
+    ///
+    /// Result: `false` if searching should stop.
+    fn find_in<'a, F>(&'a self, path: &mut Path, pred: &mut F, result: &mut Vec<(Path, &'a Self)>, first_only: bool) -> bool
+    where F: FnMut(&Self) -> bool {
+        if pred(self) {
+            result.push((path.clone(), self));
+            if first_only {
+                return false;
+            }
+        }
+
+        let mut find_item = |item: &'a Self, segment: PathSegment| {
+            path.push(segment);
+            let result = item.find_in(path, pred, result, first_only);
+            path.pop();
+            result
+        };
+        match self {
+            Value::List(list) => list.iter().enumerate().all(|(index, item)| find_item(item, PathSegment::Index(index as Size))),
+            Value::Map(map) => map.iter().all(|(key, item)| find_item(item, PathSegment::MapKey(*key))),
+            Value::Object(object) => object.iter().all(|(key, item)|
+                find_item(item, PathSegment::ObjectKey(key.clone()))
+            ),
+            _ => true,
+        }
+    }
+
+    /// # Walks this value, at a path
+    ///
+    /// Result: `false` if walking was stopped.
+    fn walk_in<F>(&self, path: &mut Path, f: &mut F) -> bool where F: FnMut(&Path, &Self) -> Walk {
+        match f(path, self) {
+            Walk::Continue => (),
+            Walk::SkipChildren => return true,
+            Walk::Stop => return false,
+        };
+
+        match self {
+            Value::List(list) => list.iter().enumerate().all(|(index, item)| item.walk_item(PathSegment::Index(index as Size), path, f)),
+            Value::Map(map) => map.iter().all(|(key, item)| item.walk_item(PathSegment::MapKey(*key), path, f)),
+            Value::Object(object) => object.iter().all(|(key, item)|
+                item.walk_item(PathSegment::ObjectKey(key.clone()), path, f)
+            ),
+            _ => true,
+        }
+    }
+
+    /// # Walks an item of a container
+    fn walk_item<F>(&self, segment: PathSegment, path: &mut Path, f: &mut F) -> bool where F: FnMut(&Path, &Self) -> Walk {
+        path.push(segment);
+        let result = self.walk_in(path, f);
+        path.pop();
+        result
+    }
+
+}
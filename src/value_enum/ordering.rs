@@ -0,0 +1,168 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Ordering of values
+
+use {
+    core::cmp::Ordering,
+
+    crate::Value,
+};
+
+/// # Compares 2 values
+///
+/// This gives a total order over [`Value`][enum:Value], so it can be used for sorting:
+///
+/// - Values of different kinds are ordered by kind: null, booleans, numbers, strings, blobs, lists, maps, objects.
+/// - Numbers are compared by their numeric values, regardless of their types.
+/// - Strings are compared by their contents, then by their types.
+/// - Blobs are compared by their bytes.
+/// - Containers are compared item by item.
+///
+/// [enum:Value]: ../../enum.Value.html
+#[cfg(feature="std")]
+pub(crate) fn cmp_values(first: &Value, second: &Value) -> Ordering {
+    match (first, second) {
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::True, Value::True) | (Value::False, Value::False) => Ordering::Equal,
+        (Value::False, Value::True) => Ordering::Less,
+        (Value::True, Value::False) => Ordering::Greater,
+        (Value::Blob(first), Value::Blob(second)) => first.cmp(second),
+        (Value::List(first), Value::List(second)) => cmp_iters(first.iter(), second.iter(), cmp_values),
+        (Value::Map(first), Value::Map(second)) => cmp_iters(first.iter(), second.iter(), |(first_key, first_value), (second_key, second_value)|
+            first_key.cmp(second_key).then_with(|| cmp_values(first_value, second_value))
+        ),
+        (Value::Object(first), Value::Object(second)) => cmp_iters(first.iter(), second.iter(), |(first_key, first_value), (second_key, second_value)|
+            first_key.cmp(second_key).then_with(|| cmp_values(first_value, second_value))
+        ),
+        _ => match (number_of(first), number_of(second)) {
+            (Some(first), Some(second)) => first.cmp(&second),
+            _ => match (str_of(first), str_of(second)) {
+                (Some((first_ty, first)), Some((second_ty, second))) => first.cmp(second).then(first_ty.cmp(&second_ty)),
+                _ => rank_of(first).cmp(&rank_of(second)),
+            },
+        },
+    }
+}
+
+/// # Compares 2 iterators, item by item
+#[cfg(feature="std")]
+fn cmp_iters<I, F>(first: I, second: I, f: F) -> Ordering where I: Iterator, F: Fn(I::Item, I::Item) -> Ordering {
+    let mut first = first;
+    let mut second = second;
+    loop {
+        match (first.next(), second.next()) {
+            (Some(a), Some(b)) => match f(a, b) {
+                Ordering::Equal => continue,
+                other => return other,
+            },
+            (Some(_), None) => return Ordering::Greater,
+            (None, Some(_)) => return Ordering::Less,
+            (None, None) => return Ordering::Equal,
+        };
+    }
+}
+
+/// # Numbers, used for comparing number values of different types
+#[derive(Clone, Copy)]
+pub(crate) enum Number {
+    Integer(i128),
+    Float(f64),
+}
+
+impl Number {
+
+    /// # Compares to another number
+    ///
+    /// Integers and floats are compared exactly, so this is a total order.
+    pub(crate) fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Number::Integer(first), Number::Integer(second)) => first.cmp(second),
+            (Number::Float(first), Number::Float(second)) => first.total_cmp(second),
+            (Number::Integer(first), Number::Float(second)) => cmp_integer_to_float(*first, *second),
+            (Number::Float(first), Number::Integer(second)) => cmp_integer_to_float(*second, *first).reverse(),
+        }
+    }
+
+}
+
+/// # Compares an integer to a float, exactly
+///
+/// This agrees with [`f64::total_cmp()`][f64#total_cmp()]: NaNs with negative sign are the smallest, other NaNs are the largest. Integer
+/// `0` equals `0.0`, and is larger than `-0.0`.
+///
+/// [f64#total_cmp()]: https://doc.rust-lang.org/std/primitive.f64.html#method.total_cmp
+fn cmp_integer_to_float(integer: i128, float: f64) -> Ordering {
+    /// # 2^127, the smallest float which is larger than all `i128` values
+    const I128_END: f64 = 170_141_183_460_469_231_731_687_303_715_884_105_728.0;
+
+    if float.is_nan() {
+        return match float.is_sign_negative() {
+            true => Ordering::Greater,
+            false => Ordering::Less,
+        };
+    }
+
+    if float >= I128_END {
+        return Ordering::Less;
+    }
+    if float < -I128_END {
+        return Ordering::Greater;
+    }
+
+    // Now the float is in range of i128: this cast truncates it exactly, and so does the subtraction
+    let truncated = float as i128;
+    integer.cmp(&truncated).then_with(|| match float - truncated as f64 {
+        fraction if fraction > 0.0 => Ordering::Less,
+        fraction if fraction < 0.0 => Ordering::Greater,
+        _ => match float == 0.0 && float.is_sign_negative() {
+            true => Ordering::Greater,
+            false => Ordering::Equal,
+        },
+    })
+}
+
+/// # Gets number from a value
+pub(crate) fn number_of(value: &Value) -> Option<Number> {
+    match value {
+        Value::U8(u) => Some(Number::Integer(i128::from(*u))),
+        Value::I8(i) => Some(Number::Integer(i128::from(*i))),
+        Value::U16(u) => Some(Number::Integer(i128::from(*u))),
+        Value::I16(i) => Some(Number::Integer(i128::from(*i))),
+        Value::U32(u) => Some(Number::Integer(i128::from(*u))),
+        Value::I32(i) => Some(Number::Integer(i128::from(*i))),
+        Value::U64(u) => Some(Number::Integer(i128::from(*u))),
+        Value::I64(i) => Some(Number::Integer(i128::from(*i))),
+        Value::Float(f) => Some(Number::Float(f64::from(*f))),
+        Value::Double(d) => Some(Number::Float(*d)),
+        _ => None,
+    }
+}
+
+/// # Gets string and its type from a value
+#[cfg(feature="std")]
+fn str_of(value: &Value) -> Option<(u8, &str)> {
+    match value {
+        Value::Text(s) => Some((crate::value::TEXT, s)),
+        Value::DateTime(s) => Some((crate::value::DATE_TIME, s)),
+        Value::Date(s) => Some((crate::value::DATE, s)),
+        Value::Time(s) => Some((crate::value::TIME, s)),
+        Value::DecimalStr(s) => Some((crate::value::DECIMAL_STR, s)),
+        _ => None,
+    }
+}
+
+/// # Gets rank of a value's kind
+#[cfg(feature="std")]
+fn rank_of(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::True | Value::False => 1,
+        Value::U8(_) | Value::I8(_) | Value::U16(_) | Value::I16(_) | Value::U32(_) | Value::I32(_) | Value::U64(_) | Value::I64(_)
+            | Value::Float(_) | Value::Double(_) => 2,
+        Value::Text(_) | Value::DateTime(_) | Value::Date(_) | Value::Time(_) | Value::DecimalStr(_) => 3,
+        Value::Blob(_) => 4,
+        Value::List(_) => 5,
+        Value::Map(_) => 6,
+        Value::Object(_) => 7,
+    }
+}
@@ -0,0 +1,183 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Temporal formats
+//!
+//! These parsers accept a subset of ISO 8601:
+//!
+//! - Date: `YYYY-MM-DD`.
+//! - Time: `hh:mm:ss`, with optional fraction of a second (`.s...`), and optional offset (`Z`, or `+hh:mm`/`-hh:mm`).
+//! - Date-time: a date and a time, separated by `T` (or a space).
+//!
+//! Leap seconds (`60`) are accepted.
+
+/// # Seconds per day
+#[cfg(feature="std")]
+pub(crate) const SECS_PER_DAY: i64 = 86_400;
+
+/// # A date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Date {
+    pub(crate) year: u32,
+    pub(crate) month: u32,
+    pub(crate) day: u32,
+}
+
+impl Date {
+
+    /// # Days since Unix epoch
+    #[cfg(feature="std")]
+    pub(crate) fn days_since_unix_epoch(&self) -> i64 {
+        days_from_civil(i64::from(self.year), self.month, self.day)
+    }
+
+}
+
+/// # A time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Time {
+    pub(crate) hour: u32,
+    pub(crate) minute: u32,
+    pub(crate) second: u32,
+    pub(crate) nanos: u32,
+    /// # Offset from UTC, in seconds
+    pub(crate) offset: Option<i64>,
+}
+
+impl Time {
+
+    /// # Seconds since midnight, ignoring offset
+    #[cfg(feature="std")]
+    pub(crate) fn secs_of_day(&self) -> i64 {
+        i64::from(self.hour * 3600 + self.minute * 60 + self.second)
+    }
+
+}
+
+/// # Parses a date
+pub(crate) fn parse_date(s: &str) -> Option<Date> {
+    let bytes = s.as_bytes();
+    if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+
+    let date = Date { year: parse_digits(&bytes[0..4])?, month: parse_digits(&bytes[5..7])?, day: parse_digits(&bytes[8..10])? };
+    match (1..=12).contains(&date.month) && date.day >= 1 && date.day <= days_in_month(i64::from(date.year), date.month) {
+        true => Some(date),
+        false => None,
+    }
+}
+
+/// # Parses a time
+pub(crate) fn parse_time(s: &str) -> Option<Time> {
+    let bytes = s.as_bytes();
+    if bytes.len() < 8 || bytes[2] != b':' || bytes[5] != b':' {
+        return None;
+    }
+
+    let (hour, minute, second) = (parse_digits(&bytes[0..2])?, parse_digits(&bytes[3..5])?, parse_digits(&bytes[6..8])?);
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let mut rest = &bytes[8..];
+    let mut nanos = 0;
+    if rest.first() == Some(&b'.') {
+        let digit_count = rest[1..].iter().take_while(|b| b.is_ascii_digit()).count();
+        if digit_count == 0 {
+            return None;
+        }
+        // Fractions beyond nanoseconds are truncated
+        for (index, digit) in rest[1..=digit_count].iter().take(9).enumerate() {
+            nanos += u32::from(digit - b'0') * 10_u32.pow(8 - index as u32);
+        }
+        rest = &rest[1 + digit_count..];
+    }
+
+    let offset = match rest {
+        [] => None,
+        [b'Z'] | [b'z'] => Some(0),
+        [sign @ (b'+' | b'-'), h1, h2, b':', m1, m2] => {
+            let (hours, minutes) = (parse_digits(&[*h1, *h2])?, parse_digits(&[*m1, *m2])?);
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            let offset = i64::from(hours * 3600 + minutes * 60);
+            match sign {
+                b'+' => Some(offset),
+                _ => Some(-offset),
+            }
+        },
+        _ => return None,
+    };
+
+    Some(Time { hour, minute, second, nanos, offset })
+}
+
+/// # Parses a date-time
+pub(crate) fn parse_date_time(s: &str) -> Option<(Date, Time)> {
+    match (s.get(..10), s.as_bytes().get(10), s.get(11..)) {
+        (Some(date), Some(b'T' | b't' | b' '), Some(time)) => Some((parse_date(date)?, parse_time(time)?)),
+        _ => None,
+    }
+}
+
+/// # Parses ASCII digits
+fn parse_digits(bytes: &[u8]) -> Option<u32> {
+    bytes.iter().try_fold(0_u32, |result, b| match b.is_ascii_digit() {
+        true => Some(result * 10 + u32::from(b - b'0')),
+        false => None,
+    })
+}
+
+/// # Days in a month of the proleptic Gregorian calendar
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 => match (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 {
+            true => 29,
+            false => 28,
+        },
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// # Converts a date to days since Unix epoch
+///
+/// Algorithm: <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+#[cfg(feature="std")]
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = match month <= 2 {
+        true => year - 1,
+        false => year,
+    };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = i64::from((153 * ((month + 9) % 12) + 2) / 5 + day - 1);
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// # Converts days since Unix epoch to a date
+///
+/// Result: `(year, month, day)`.
+///
+/// Algorithm: <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+#[cfg(feature="std")]
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = match mp < 10 {
+        true => mp + 3,
+        false => mp - 9,
+    } as u32;
+    let year = year_of_era + era * 400;
+    match month <= 2 {
+        true => (year + 1, month, day),
+        false => (year, month, day),
+    }
+}
@@ -0,0 +1,427 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Value references
+
+use {
+    core::convert::TryFrom,
+
+    crate::{
+        List, Map, MapKey, Object, ObjectKey, Result, Size, Value,
+        slice_writer::SliceWriter,
+        value_enum::{size_of_container, size_of_data, size_of_object_key},
+    },
+};
+
+/// # A value, which borrows its data
+///
+/// This is a parallel of [`Value`][enum:Value], for firmware which does not allocate: strings, blobs and containers are borrowed slices,
+/// so a whole document can be built on the stack, or from [`heapless`][crate:heapless] containers, then encoded into a fixed buffer via
+/// [`encode_into_slice()`][#encode_into_slice()] or [`encode_into_heapless()`][#encode_into_heapless()].
+///
+/// Items of maps and objects are encoded in given order. Their keys must be unique: this is verified by [`size()`][#size()], which is
+/// called before encoding.
+///
+/// This is available with feature `heapless`.
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::{Value, ValueRef};
+///
+/// # fn test() -> binn_ir::Result<()> {
+/// let mut name = heapless::String::<16>::new();
+/// name.push_str("sensor-1").unwrap();
+/// let readings = [ValueRef::I16(-40), ValueRef::I16(125)];
+/// let items = [("name", ValueRef::from(&name)), ("readings", ValueRef::List(&readings))];
+/// let document = ValueRef::Object(&items);
+///
+/// let mut packet = heapless::Vec::<u8, 64>::new();
+/// let len = document.encode_into_heapless(&mut packet)?;
+/// assert_eq!(len, packet.len());
+///
+/// # #[cfg(feature="std")] {
+/// let mut expected = binn_ir::object_from("name", "sensor-1");
+/// expected.object_insert("readings", vec![Value::I16(-40), Value::I16(125)])?;
+/// assert_eq!(binn_ir::decode(&mut &packet[..]).unwrap(), Some(expected));
+/// # }
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [enum:Value]: enum.Value.html
+/// [crate:heapless]: https://docs.rs/heapless
+/// [#encode_into_slice()]: #method.encode_into_slice
+/// [#encode_into_heapless()]: #method.encode_into_heapless
+/// [#size()]: #method.size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueRef<'a> {
+
+    /// # Null
+    Null,
+
+    /// # True
+    True,
+
+    /// # False
+    False,
+
+    /// # 8-bit unsigned integer
+    U8(u8),
+
+    /// # 8-bit signed integer
+    I8(i8),
+
+    /// # 16-bit unsigned integer
+    U16(u16),
+
+    /// # 16-bit signed integer
+    I16(i16),
+
+    /// # 32-bit unsigned integer
+    U32(u32),
+
+    /// # 32-bit signed integer
+    I32(i32),
+
+    /// # Float
+    Float(f32),
+
+    /// # 64-bit unsigned integer
+    U64(u64),
+
+    /// # 64-bit signed integer
+    I64(i64),
+
+    /// # Double
+    Double(f64),
+
+    /// # Text
+    Text(&'a str),
+
+    /// # Date time
+    DateTime(&'a str),
+
+    /// # Date
+    Date(&'a str),
+
+    /// # Time
+    Time(&'a str),
+
+    /// # Decimal string
+    DecimalStr(&'a str),
+
+    /// # Blob
+    Blob(&'a [u8]),
+
+    /// # List
+    List(&'a [ValueRef<'a>]),
+
+    /// # Map
+    Map(&'a [(MapKey, ValueRef<'a>)]),
+
+    /// # Object
+    Object(&'a [(&'a str, ValueRef<'a>)]),
+
+}
+
+impl ValueRef<'_> {
+
+    /// # Calculates size of this value, when encoded
+    ///
+    /// This also verifies that keys of maps and objects are unique.
+    pub fn size(&self) -> Result<Size> {
+        match self {
+            ValueRef::Null | ValueRef::True | ValueRef::False => Ok(1),
+            ValueRef::U8(_) | ValueRef::I8(_) => Ok(2),
+            ValueRef::U16(_) | ValueRef::I16(_) => Ok(3),
+            ValueRef::U32(_) | ValueRef::I32(_) | ValueRef::Float(_) => Ok(5),
+            ValueRef::U64(_) | ValueRef::I64(_) | ValueRef::Double(_) => Ok(9),
+            // 1 byte for type, 1 byte for null terminator
+            ValueRef::Text(s) | ValueRef::DateTime(s) | ValueRef::Date(s) | ValueRef::Time(s) | ValueRef::DecimalStr(s) =>
+                size_of_data(s.len(), 2),
+            // 1 byte for type
+            ValueRef::Blob(bytes) => size_of_data(bytes.len(), 1),
+            ValueRef::List(list) => {
+                let mut items: u64 = 0;
+                for v in list.iter() {
+                    items += u64::from(v.size()?);
+                }
+                size_of_items(list.len(), items)
+            },
+            ValueRef::Map(map) => {
+                let mut items: u64 = 0;
+                for (i, (key, v)) in map.iter().enumerate() {
+                    if map[..i].iter().any(|(k, _)| k == key) {
+                        return Err(err!("duplicate map key: {}", key));
+                    }
+                    items += core::mem::size_of::<MapKey>() as u64 + u64::from(v.size()?);
+                }
+                size_of_items(map.len(), items)
+            },
+            ValueRef::Object(object) => {
+                let mut items: u64 = 0;
+                for (i, (key, v)) in object.iter().enumerate() {
+                    if object[..i].iter().any(|(k, _)| k == key) {
+                        return Err(err!("duplicate object key: {:?}", key));
+                    }
+                    items += u64::from(size_of_object_key(key)?) + u64::from(v.size()?);
+                }
+                size_of_items(object.len(), items)
+            },
+        }
+    }
+
+    /// # Encodes this value into a fixed buffer
+    ///
+    /// This does not allocate. If the value does not fit into the buffer, an error is returned, and the buffer is left untouched.
+    ///
+    /// Result: number of bytes written, at the start of the buffer.
+    pub fn encode_into_slice(&self, buf: &mut [u8]) -> Result<usize> {
+        let size = self.size()? as usize;
+        if size > buf.len() {
+            return Err(err!("buffer too small: {} byte(s) needed, {} available", size, buf.len()));
+        }
+
+        let mut writer = SliceWriter::new(buf);
+        self.put(&mut writer)?;
+        Ok(writer.pos())
+    }
+
+    /// # Encodes this value, appending it to a [`heapless::Vec`][heapless::Vec]
+    ///
+    /// If the value does not fit into the vector's free capacity, an error is returned, and the vector is left untouched.
+    ///
+    /// Result: number of bytes written.
+    ///
+    /// [heapless::Vec]: https://docs.rs/heapless/0.8/heapless/struct.Vec.html
+    pub fn encode_into_heapless<const N: usize>(&self, out: &mut heapless::Vec<u8, N>) -> Result<usize> {
+        let old_len = out.len();
+        let size = self.size()? as usize;
+        if size > N - old_len {
+            return Err(err!("buffer too small: {} byte(s) needed, {} available", size, N - old_len));
+        }
+
+        out.resize(old_len + size, 0).map_err(|()| err!())?;
+        let mut writer = SliceWriter::new(&mut out[old_len..]);
+        self.put(&mut writer)?;
+        Ok(writer.pos())
+    }
+
+    /// # Writes this value
+    ///
+    /// Its size must have been verified via `size()`.
+    fn put(&self, writer: &mut SliceWriter) -> Result<()> {
+        match self {
+            ValueRef::Null => writer.put(&[crate::value::NULL]),
+            ValueRef::True => writer.put(&[crate::value::TRUE]),
+            ValueRef::False => writer.put(&[crate::value::FALSE]),
+            ValueRef::U8(u) => writer.put(&[crate::value::U8, *u]),
+            ValueRef::I8(i) => writer.put(&[crate::value::I8, *i as u8]),
+            ValueRef::U16(u) => writer.put_int(crate::value::U16, &u.to_be_bytes()),
+            ValueRef::I16(i) => writer.put_int(crate::value::I16, &i.to_be_bytes()),
+            ValueRef::U32(u) => writer.put_int(crate::value::U32, &u.to_be_bytes()),
+            ValueRef::I32(i) => writer.put_int(crate::value::I32, &i.to_be_bytes()),
+            ValueRef::U64(u) => writer.put_int(crate::value::U64, &u.to_be_bytes()),
+            ValueRef::I64(i) => writer.put_int(crate::value::I64, &i.to_be_bytes()),
+            ValueRef::Float(f) => writer.put_int(crate::value::FLOAT, &f.to_bits().to_be_bytes()),
+            ValueRef::Double(f) => writer.put_int(crate::value::DOUBLE, &f.to_bits().to_be_bytes()),
+            ValueRef::Text(t) => writer.put_str(crate::value::TEXT, t),
+            ValueRef::DateTime(dt) => writer.put_str(crate::value::DATE_TIME, dt),
+            ValueRef::Date(d) => writer.put_str(crate::value::DATE, d),
+            ValueRef::Time(t) => writer.put_str(crate::value::TIME, t),
+            ValueRef::DecimalStr(ds) => writer.put_str(crate::value::DECIMAL_STR, ds),
+            ValueRef::Blob(bytes) => writer.put_blob(bytes),
+            ValueRef::List(list) => {
+                writer.put_container_header(crate::value::LIST, self.size()?, list.len())?;
+                list.iter().try_for_each(|item| item.put(writer))
+            },
+            ValueRef::Map(map) => {
+                writer.put_container_header(crate::value::MAP, self.size()?, map.len())?;
+                map.iter().try_for_each(|(key, item)| writer.put(&key.to_be_bytes()).and_then(|()| item.put(writer)))
+            },
+            ValueRef::Object(object) => {
+                writer.put_container_header(crate::value::OBJECT, self.size()?, object.len())?;
+                object.iter().try_for_each(|(key, item)| writer.put_object_key(key).and_then(|()| item.put(writer)))
+            },
+        }
+    }
+
+    /// # Makes an owned value
+    ///
+    /// If keys of a map or an object are duplicate, the last item wins.
+    pub fn to_value(&self) -> Value {
+        match *self {
+            ValueRef::Null => Value::Null,
+            ValueRef::True => Value::True,
+            ValueRef::False => Value::False,
+            ValueRef::U8(u) => Value::U8(u),
+            ValueRef::I8(i) => Value::I8(i),
+            ValueRef::U16(u) => Value::U16(u),
+            ValueRef::I16(i) => Value::I16(i),
+            ValueRef::U32(u) => Value::U32(u),
+            ValueRef::I32(i) => Value::I32(i),
+            ValueRef::Float(f) => Value::Float(f),
+            ValueRef::U64(u) => Value::U64(u),
+            ValueRef::I64(i) => Value::I64(i),
+            ValueRef::Double(f) => Value::Double(f),
+            ValueRef::Text(t) => Value::Text(t.into()),
+            ValueRef::DateTime(dt) => Value::DateTime(dt.into()),
+            ValueRef::Date(d) => Value::Date(d.into()),
+            ValueRef::Time(t) => Value::Time(t.into()),
+            ValueRef::DecimalStr(ds) => Value::DecimalStr(ds.into()),
+            ValueRef::Blob(bytes) => Value::Blob(bytes.into()),
+            ValueRef::List(list) => Value::List(list.iter().map(ValueRef::to_value).collect::<List>()),
+            ValueRef::Map(map) => Value::Map(map.iter().map(|(key, item)| (*key, item.to_value())).collect::<Map>()),
+            ValueRef::Object(object) => Value::Object(
+                object.iter().map(|(key, item)| (ObjectKey::from(*key), item.to_value())).collect::<Object>()
+            ),
+        }
+    }
+
+}
+
+/// # Calculates size of a container, from its item count, and total size of its items (including their keys)
+fn size_of_items(count: usize, items: u64) -> Result<Size> {
+    size_of_container(count, Size::try_from(items).map_err(|_| err_code!("data too large (bytes)", items))?)
+}
+
+impl From<&ValueRef<'_>> for Value {
+
+    fn from(value: &ValueRef) -> Self {
+        value.to_value()
+    }
+
+}
+
+impl From<ValueRef<'_>> for Value {
+
+    fn from(value: ValueRef) -> Self {
+        value.to_value()
+    }
+
+}
+
+impl From<bool> for ValueRef<'_> {
+
+    fn from(b: bool) -> Self {
+        match b {
+            true => ValueRef::True,
+            false => ValueRef::False,
+        }
+    }
+
+}
+
+macro_rules! impl_from_numbers_for_value_ref { ($($ty: ty, $variant: ident,)+) => {
+    $(
+        impl From<$ty> for ValueRef<'_> {
+
+            fn from(n: $ty) -> Self {
+                ValueRef::$variant(n)
+            }
+
+        }
+    )+
+}}
+
+impl_from_numbers_for_value_ref!(
+    u8, U8, i8, I8, u16, U16, i16, I16, u32, U32, i32, I32, f32, Float, u64, U64, i64, I64, f64, Double,
+);
+
+impl<'a> From<&'a str> for ValueRef<'a> {
+
+    fn from(s: &'a str) -> Self {
+        ValueRef::Text(s)
+    }
+
+}
+
+impl<'a, const N: usize> From<&'a heapless::String<N>> for ValueRef<'a> {
+
+    fn from(s: &'a heapless::String<N>) -> Self {
+        ValueRef::Text(s)
+    }
+
+}
+
+impl<'a, const N: usize> From<&'a heapless::Vec<u8, N>> for ValueRef<'a> {
+
+    fn from(bytes: &'a heapless::Vec<u8, N>) -> Self {
+        ValueRef::Blob(bytes)
+    }
+
+}
+
+impl<'a, const N: usize> From<&'a heapless::Vec<ValueRef<'a>, N>> for ValueRef<'a> {
+
+    fn from(list: &'a heapless::Vec<ValueRef<'a>, N>) -> Self {
+        ValueRef::List(list)
+    }
+
+}
+
+impl<'a, const N: usize> From<&'a heapless::Vec<(MapKey, ValueRef<'a>), N>> for ValueRef<'a> {
+
+    fn from(map: &'a heapless::Vec<(MapKey, ValueRef<'a>), N>) -> Self {
+        ValueRef::Map(map)
+    }
+
+}
+
+impl<'a, const N: usize> From<&'a heapless::Vec<(&'a str, ValueRef<'a>), N>> for ValueRef<'a> {
+
+    fn from(object: &'a heapless::Vec<(&'a str, ValueRef<'a>), N>) -> Self {
+        ValueRef::Object(object)
+    }
+
+}
+
+impl<const N: usize> From<heapless::String<N>> for Value {
+
+    fn from(s: heapless::String<N>) -> Self {
+        Value::Text(s.as_str().into())
+    }
+
+}
+
+impl<const N: usize> From<heapless::Vec<u8, N>> for Value {
+
+    fn from(bytes: heapless::Vec<u8, N>) -> Self {
+        Value::Blob(bytes.as_slice().into())
+    }
+
+}
+
+impl<'a> TryFrom<&'a Value> for ValueRef<'a> {
+
+    type Error = crate::Error;
+
+    /// # Borrows a value
+    ///
+    /// Only values which are not containers can be borrowed; otherwise an error is returned.
+    fn try_from(value: &'a Value) -> core::result::Result<Self, Self::Error> {
+        match value {
+            Value::Null => Ok(ValueRef::Null),
+            Value::True => Ok(ValueRef::True),
+            Value::False => Ok(ValueRef::False),
+            Value::U8(u) => Ok(ValueRef::U8(*u)),
+            Value::I8(i) => Ok(ValueRef::I8(*i)),
+            Value::U16(u) => Ok(ValueRef::U16(*u)),
+            Value::I16(i) => Ok(ValueRef::I16(*i)),
+            Value::U32(u) => Ok(ValueRef::U32(*u)),
+            Value::I32(i) => Ok(ValueRef::I32(*i)),
+            Value::Float(f) => Ok(ValueRef::Float(*f)),
+            Value::U64(u) => Ok(ValueRef::U64(*u)),
+            Value::I64(i) => Ok(ValueRef::I64(*i)),
+            Value::Double(f) => Ok(ValueRef::Double(*f)),
+            Value::Text(t) => Ok(ValueRef::Text(t)),
+            Value::DateTime(dt) => Ok(ValueRef::DateTime(dt)),
+            Value::Date(d) => Ok(ValueRef::Date(d)),
+            Value::Time(t) => Ok(ValueRef::Time(t)),
+            Value::DecimalStr(ds) => Ok(ValueRef::DecimalStr(ds)),
+            Value::Blob(bytes) => Ok(ValueRef::Blob(bytes)),
+            Value::List(_) | Value::Map(_) | Value::Object(_) => Err(err!("containers can't be borrowed")),
+        }
+    }
+
+}
@@ -0,0 +1,99 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Values
+
+use {
+    std::io::Read,
+
+    crate::{IoResult, Value},
+};
+
+/// # Makes an iterator over top-level values of a source
+///
+/// See [`Values`][struct:Values].
+///
+/// ## Examples
+///
+/// ```
+/// use binn_ir::Value;
+///
+/// # fn test() -> binn_ir::IoResult<()> {
+/// let mut buf = vec![];
+/// Value::from("first").encode(&mut buf)?;
+/// Value::U8(2).encode(&mut buf)?;
+///
+/// let mut count = 0;
+/// for value in binn_ir::values(&buf[..]) {
+///     value?;
+///     count += 1;
+/// }
+/// assert_eq!(count, 2);
+/// # Ok(()) }
+/// # test().unwrap();
+/// ```
+///
+/// [struct:Values]: struct.Values.html
+pub fn values<R>(source: R) -> Values<R> where R: Read {
+    Values::new(source)
+}
+
+/// # An iterator over top-level values of a source
+///
+/// Values are decoded via [`decode()`][fn:decode]. The iterator ends at a clean end of source: when there's no more data before a value. If
+/// a value is truncated, or invalid, an error is returned. Once an error is returned, the iterator stops.
+///
+/// You can make one via [`values()`][fn:values], or [`Decoder::iter()`][trait:Decoder#iter()].
+///
+/// [fn:decode]: fn.decode.html
+/// [fn:values]: fn.values.html
+/// [trait:Decoder#iter()]: trait.Decoder.html#method.iter
+#[derive(Debug)]
+pub struct Values<R> {
+    source: R,
+    done: bool,
+}
+
+impl<R> Values<R> {
+
+    /// # Makes new instance
+    pub const fn new(source: R) -> Self {
+        Self {
+            source,
+            done: false,
+        }
+    }
+
+    /// # Gets a reference to the source
+    pub const fn get_ref(&self) -> &R {
+        &self.source
+    }
+
+    /// # Gets a mutable reference to the source
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.source
+    }
+
+    /// # Unwraps the source
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+
+}
+
+impl<R> Iterator for Values<R> where R: Read {
+
+    type Item = IoResult<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let result = crate::decode(&mut self.source).transpose();
+        self.done = !matches!(result, Some(Ok(_)));
+        result
+    }
+
+}
+
+impl<R> core::iter::FusedIterator for Values<R> where R: Read {}
@@ -0,0 +1,24 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Walk
+
+/// # Control flow of [`Value::walk()`][Value::walk()]
+///
+/// [Value::walk()]: enum.Value.html#method.walk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Walk {
+
+    /// # Continues walking, including items of current value
+    Continue,
+
+    /// # Continues walking, but skips items of current value
+    ///
+    /// For non-container values, this is the same as [`Continue`][#Continue].
+    ///
+    /// [#Continue]: #variant.Continue
+    SkipChildren,
+
+    /// # Stops walking
+    Stop,
+
+}
@@ -0,0 +1,75 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Appending to encoded containers
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use {
+    std::io::ErrorKind,
+
+    binn_ir::{Encoder, IoResult, Value},
+};
+
+#[test]
+fn append_to_list() -> IoResult<()> {
+    let mut buf = vec![];
+    buf.encode_list(vec![])?;
+
+    let mut expected = vec![];
+    for i in 0..200_u8 {
+        let size = binn_ir::append_to_list(&mut buf, i)?;
+        expected.push(Value::U8(i));
+        assert_eq!(size as usize, buf.len());
+        assert_eq!(Value::List(expected.clone()).size()?, size);
+    }
+    assert_eq!(binn_ir::decode(&mut &buf[..])?, Some(Value::List(expected)));
+
+    // Wrong types
+    let mut map = vec![];
+    map.encode_map(binn_ir::Map::new())?;
+    assert_eq!(binn_ir::append_to_list(&mut map, 0_u8).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // Trailing data
+    buf.push(0);
+    let before = buf.clone();
+    assert_eq!(binn_ir::append_to_list(&mut buf, 0_u8).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(buf, before);
+
+    Ok(())
+}
+
+#[test]
+fn append_to_map() -> IoResult<()> {
+    let mut buf = vec![];
+    buf.encode(&binn_ir::map_from(-1, "first"))?;
+    binn_ir::append_to_map(&mut buf, 9, Value::Blob(vec![0; 200]))?;
+
+    let before = buf.clone();
+    assert_eq!(binn_ir::append_to_map(&mut buf, 9, Value::Null).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(buf, before);
+
+    let mut expected = binn_ir::map_from(-1, "first");
+    expected.map_insert(9, Value::Blob(vec![0; 200]))?;
+    assert_eq!(binn_ir::decode(&mut &buf[..])?, Some(expected));
+
+    Ok(())
+}
+
+#[test]
+fn append_to_object() -> IoResult<()> {
+    let mut buf = vec![];
+    buf.encode(&binn_ir::object_from("z", 0_u8))?;
+    let size = binn_ir::append_to_object(&mut buf, "a", binn_ir::object_from("nested", true))?;
+    assert_eq!(size as usize, buf.len());
+
+    assert_eq!(binn_ir::append_to_object(&mut buf, "z", Value::Null).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert!(binn_ir::append_to_object(&mut buf, "k".repeat(256), Value::Null).is_err());
+
+    let mut expected = binn_ir::object_from("z", 0_u8);
+    expected.object_insert("a", binn_ir::object_from("nested", true))?;
+    assert_eq!(binn_ir::decode(&mut &buf[..])?, Some(expected));
+
+    Ok(())
+}
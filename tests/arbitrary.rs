@@ -0,0 +1,60 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Arbitrary values
+
+#![cfg(all(feature="arbitrary", feature="std"))]
+
+extern crate binn_ir;
+
+use {
+    arbitrary::{Arbitrary, Unstructured},
+
+    binn_ir::{DecodeConfig, IoResult, Value},
+};
+
+/// # Depth of a value
+fn depth(value: &Value) -> usize {
+    match value {
+        Value::List(list) => 1 + list.iter().map(depth).max().unwrap_or(0),
+        Value::Map(map) => 1 + map.values().map(depth).max().unwrap_or(0),
+        Value::Object(object) => 1 + object.values().map(depth).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+#[test]
+fn arbitrary_values() -> IoResult<()> {
+    let mut config = DecodeConfig::new();
+    config.validate_temporal = true;
+    config.validate_decimal_str = true;
+
+    let mut seed = 0x9e37_79b9_7f4a_7c15_u64;
+    let mut containers = 0;
+    for len in (0..2_000).map(|i| i * 7 % 4_096) {
+        let bytes = (0..len).map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed as u8
+        }).collect::<Vec<_>>();
+
+        let value = Value::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        assert!(depth(&value) <= 8);
+        if depth(&value) > 0 {
+            containers += 1;
+        }
+
+        let mut buf = vec![];
+        assert_eq!(value.encode(&mut buf)?, value.size()?);
+        let decoded = binn_ir::decode_with_config(&mut &buf[..], &config)?.unwrap();
+        let mut decoded_buf = vec![];
+        decoded.encode(&mut decoded_buf)?;
+        assert_eq!(buf, decoded_buf);
+    }
+    assert!(containers > 0);
+
+    // Empty input still makes a value
+    assert!(Value::arbitrary(&mut Unstructured::new(&[])).is_ok());
+
+    Ok(())
+}
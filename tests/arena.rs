@@ -0,0 +1,128 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Arena decoding
+
+#![cfg(feature="bumpalo")]
+
+extern crate binn_ir;
+
+use {
+    std::io::{self, ErrorKind},
+
+    binn_ir::{ArenaValue, Encoder, IoResult, KeyInterner, Value},
+    bumpalo::Bump,
+};
+
+#[test]
+fn decode_in_arena() -> IoResult<()> {
+    let mut object = binn_ir::object();
+    object.object_insert("z", Value::Null)?;
+    object.object_insert("numbers", Value::List(vec![
+        Value::True, Value::False, Value::U8(1), Value::I8(-1), Value::U16(2), Value::I16(-2), Value::U32(3), Value::I32(-3), Value::Float(4.0),
+        Value::U64(5), Value::I64(-5), Value::Double(6.0),
+    ]))?;
+    object.object_insert("strings", Value::List(vec![
+        Value::Text("text".repeat(100)), Value::DateTime("dt".into()), Value::Date("d".into()), Value::Time("t".into()),
+        Value::DecimalStr("0.1".into()),
+    ]))?;
+    object.object_insert("blob", Value::Blob(vec![9; 300]))?;
+    let mut map = binn_ir::map_from(9, "nine");
+    map.map_insert(-1, binn_ir::list())?;
+    object.object_insert("map", map)?;
+
+    let mut buf = vec![];
+    buf.encode(&object)?;
+    buf.encode_u8(9)?;
+
+    let arena = Bump::new();
+    let mut source = &buf[..];
+    let decoded = binn_ir::decode_in(&mut source, &arena)?.unwrap();
+    assert_eq!(Value::from(&decoded), object);
+    assert_eq!(decoded.object_get("z"), Some(&ArenaValue::Null));
+    assert_eq!(decoded.object_get("map").and_then(|m| m.map_get(9)), Some(&ArenaValue::Text("nine")));
+    assert_eq!(decoded.object_get("blob"), Some(&ArenaValue::Blob(&[9; 300])));
+    assert_eq!(decoded.object_get("missing"), None);
+    assert_eq!(decoded.map_get(9), None);
+
+    assert_eq!(binn_ir::decode_in(&mut source, &arena)?, Some(ArenaValue::U8(9)));
+    assert_eq!(binn_ir::decode_in(&mut source, &arena)?, None);
+
+    Ok(())
+}
+
+#[test]
+fn decode_invalid_data_in_arena() -> IoResult<()> {
+    let arena = Bump::new();
+
+    let mut buf = vec![];
+    buf.encode(&binn_ir::object_from("id", 1))?;
+    assert_eq!(binn_ir::decode_in(&mut &buf[..buf.len() - 1], &arena).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    // Wrong declared size
+    buf[1] += 1;
+    buf.push(0);
+    assert_eq!(binn_ir::decode_in(&mut &buf[..], &arena).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // Duplicate keys: {"a": 0, "a": 0}
+    let buf = [binn_ir::value::OBJECT, 11, 2, 1, b'a', 0x20, 0, 1, b'a', 0x20, 0];
+    assert_eq!(binn_ir::decode_in(&mut &buf[..], &arena).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // Missing null terminator
+    let buf = [binn_ir::value::TEXT, 1, b'a', 1];
+    assert_eq!(binn_ir::decode_in(&mut &buf[..], &arena).unwrap_err().kind(), ErrorKind::InvalidData);
+    // Invalid UTF-8
+    let buf = [binn_ir::value::TEXT, 1, 0xFF, 0];
+    assert_eq!(binn_ir::decode_in(&mut &buf[..], &arena).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    assert_eq!(binn_ir::decode_in(&mut io::empty(), &arena)?, None);
+
+    // Containers nested too deeply
+    let value = (0..binn_ir::value::DEFAULT_MAX_DEPTH).fold(Value::List(vec![]), |value, _| Value::List(vec![value]));
+    let mut buf = vec![];
+    value.encode(&mut buf)?;
+    assert!(binn_ir::DepthLimitExceeded::find(&binn_ir::decode_in(&mut &buf[..], &arena).unwrap_err()).is_some());
+    let mut interner = KeyInterner::new();
+    let err = binn_ir::decode_in_with_max_depth(&mut &buf[..], &arena, Some(&mut interner), 9).unwrap_err();
+    assert_eq!(binn_ir::DepthLimitExceeded::find(&err).map(|e| e.max_depth()), Some(9));
+    let max_depth = binn_ir::value::DEFAULT_MAX_DEPTH + 1;
+    assert!(binn_ir::decode_in_with_max_depth(&mut &buf[..], &arena, Some(&mut interner), max_depth)?.is_some());
+
+    Ok(())
+}
+
+#[test]
+fn decode_in_arena_with_interner() -> IoResult<()> {
+    let mut list = binn_ir::list();
+    for id in 0..9 {
+        let mut object = binn_ir::object_from("id", id);
+        object.object_insert("name", id.to_string())?;
+        list.push(object)?;
+    }
+    let mut buf = vec![];
+    buf.encode(&list)?;
+    buf.encode(&binn_ir::object_from("other", Value::Null))?;
+
+    let arena = Bump::new();
+    let mut interner = KeyInterner::new();
+    assert!(interner.is_empty());
+
+    let mut source = &buf[..];
+    let decoded = binn_ir::decode_in_with_interner(&mut source, &arena, &mut interner)?.unwrap();
+    assert_eq!(Value::from(decoded), list);
+    assert_eq!(interner.len(), 2);
+
+    // Keys are shared
+    let keys = match decoded {
+        ArenaValue::List(list) => list.iter().map(|object| match object {
+            ArenaValue::Object(object) => object[0].0.as_ptr(),
+            _ => panic!("not an object"),
+        }).collect::<Vec<_>>(),
+        _ => panic!("not a list"),
+    };
+    assert!(keys.windows(2).all(|pair| pair[0] == pair[1]));
+
+    assert_eq!(binn_ir::decode_in_with_interner(&mut source, &arena, &mut interner)?.unwrap().object_get("other"), Some(&ArenaValue::Null));
+    assert_eq!(interner.len(), 3);
+
+    Ok(())
+}
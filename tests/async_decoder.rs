@@ -0,0 +1,114 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Async decoder
+
+#![cfg(feature="futures-io")]
+
+extern crate binn_ir;
+
+use {
+    core::{
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll, Waker},
+    },
+    std::io::{self, ErrorKind},
+
+    binn_ir::{AsyncDecoder, IoResult, Value},
+    futures_io::AsyncRead,
+};
+
+/// # An async source, which returns one byte at a time, and is pending every other poll
+struct Trickle<'a> {
+    bytes: &'a [u8],
+    pending: bool,
+}
+
+impl AsyncRead for Trickle<'_> {
+
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        self.pending = !self.pending;
+        if self.pending {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        match self.bytes.split_first() {
+            Some((b, rest)) => {
+                buf[0] = *b;
+                self.bytes = rest;
+                Poll::Ready(Ok(1))
+            },
+            None => Poll::Ready(Ok(0)),
+        }
+    }
+
+}
+
+/// # Polls a future until it's ready
+fn block_on<F>(future: F) -> F::Output where F: Future {
+    let mut future = Box::pin(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(result) = future.as_mut().poll(&mut cx) {
+            return result;
+        }
+    }
+}
+
+fn values() -> Vec<Value> {
+    vec![
+        Value::Null, Value::Text("text".into()), Value::Blob(vec![0; 300]),
+        binn_ir::object_from("list", Value::List(vec![Value::True, binn_ir::map_from(-1, Value::Double(1.5))])),
+    ]
+}
+
+#[test]
+fn async_decoder() -> IoResult<()> {
+    let values = values();
+    let mut buf = vec![];
+    for value in &values {
+        value.encode(&mut buf)?;
+    }
+
+    let mut decoder = AsyncDecoder::new(Trickle { bytes: &buf, pending: false });
+    for value in &values {
+        assert_eq!(block_on(decoder.decode())?.as_ref(), Some(value));
+    }
+    assert_eq!(block_on(decoder.decode())?, None);
+
+    // Truncated source
+    let mut decoder = AsyncDecoder::new(Trickle { bytes: &buf[..buf.len() - 1], pending: false });
+    for value in &values[..values.len() - 1] {
+        assert_eq!(block_on(decoder.decode())?.as_ref(), Some(value));
+    }
+    assert_eq!(block_on(decoder.decode()).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    Ok(())
+}
+
+#[test]
+fn cancellation_safety() -> IoResult<()> {
+    let values = values();
+    let mut buf = vec![];
+    for value in &values {
+        value.encode(&mut buf)?;
+    }
+
+    let mut decoder = AsyncDecoder::new(Trickle { bytes: &buf, pending: false });
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut decoded = vec![];
+    while decoded.len() < values.len() {
+        // Poll each future a few times, then drop it
+        let mut future = decoder.decode();
+        for _ in 0..3 {
+            if let Poll::Ready(value) = Pin::new(&mut future).poll(&mut cx) {
+                decoded.push(value?.unwrap());
+                break;
+            }
+        }
+    }
+    assert_eq!(decoded, values);
+    assert!(decoder.buffered().is_empty());
+
+    Ok(())
+}
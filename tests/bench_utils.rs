@@ -0,0 +1,46 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Bench utils
+
+#![cfg(feature="bench-utils")]
+
+extern crate binn_ir;
+
+use {
+    binn_ir::{IoResult, Value, bench_utils, value},
+};
+
+#[test]
+fn generators() -> IoResult<()> {
+    let values = bench_utils::small_scalars(100)
+        .into_iter()
+        .chain(vec![
+            bench_utils::large_list(1_000),
+            bench_utils::deep_nesting(value::DEFAULT_MAX_DEPTH - 1),
+            bench_utils::big_blob(100_000),
+            bench_utils::object_heavy(10, 8),
+        ])
+        .collect::<Vec<_>>();
+
+    for value in values {
+        let mut buf = vec![];
+        assert_eq!(value.encode(&mut buf)?, value.size()?);
+        assert_eq!(buf.len(), value.size()? as usize);
+        assert_eq!(binn_ir::decode(&mut &buf[..])?, Some(value));
+    }
+
+    // Deterministic
+    assert_eq!(bench_utils::object_heavy(3, 3), bench_utils::object_heavy(3, 3));
+    assert_eq!(bench_utils::small_scalars(7), bench_utils::small_scalars(7));
+
+    match bench_utils::large_list(5) {
+        Value::List(list) => assert_eq!(list.len(), 5),
+        other => panic!("{:?}", other),
+    };
+    match bench_utils::big_blob(300) {
+        Value::Blob(blob) => assert_eq!((blob.len(), blob[0], blob[256]), (300, 0, 0)),
+        other => panic!("{:?}", other),
+    };
+
+    Ok(())
+}
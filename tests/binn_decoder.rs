@@ -0,0 +1,132 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Binn decoder
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use {
+    std::io::{self, ErrorKind},
+
+    binn_ir::{BinnDecoder, Encoder, IoResult, Value},
+};
+
+#[test]
+fn decode_into_values() -> IoResult<()> {
+    let mut values = vec![Value::Null, Value::U8(9), Value::Double(-1.5), Value::from("moss"), Value::Blob(vec![9; 300]), binn_ir::list()];
+    for id in 0..3_u8 {
+        let mut object = binn_ir::object_from("id", id);
+        object.object_insert("name", "x".repeat(usize::from(id) * 100))?;
+        object.object_insert("tags", Value::List((0..id).map(Value::from).collect()))?;
+        object.object_insert("map", binn_ir::map_from(i32::from(id), Value::Date(id.to_string())))?;
+        values.push(object);
+    }
+    values.push(Value::Text(String::new()));
+
+    let mut buf = vec![];
+    for value in &values {
+        buf.encode(value)?;
+    }
+
+    // Reusing one value
+    let mut decoder = BinnDecoder::new(&buf[..]);
+    let mut value = Value::Null;
+    for expected in &values {
+        assert!(decoder.decode_into(&mut value)?);
+        assert_eq!(&value, expected);
+    }
+    assert!(!decoder.decode_into(&mut value)?);
+    assert_eq!(value, Value::Text(String::new()));
+
+    // Decoding new values
+    let mut decoder = BinnDecoder::new(io::Cursor::new(&buf));
+    for expected in &values {
+        assert_eq!(decoder.decode()?.as_ref(), Some(expected));
+    }
+    assert_eq!(decoder.decode()?, None);
+    assert_eq!(decoder.into_inner().position(), buf.len() as u64);
+
+    Ok(())
+}
+
+#[test]
+fn decode_invalid_data_into_values() -> IoResult<()> {
+    let mut value = Value::Null;
+
+    let mut buf = vec![];
+    buf.encode(&binn_ir::object_from("id", 1))?;
+    assert_eq!(BinnDecoder::new(&buf[..buf.len() - 1]).decode_into(&mut value).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    // Wrong declared size
+    buf[1] += 1;
+    buf.push(0);
+    assert_eq!(BinnDecoder::new(&buf[..]).decode_into(&mut value).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // Duplicate keys: {"a": 0, "a": 0}
+    let buf = [binn_ir::value::OBJECT, 11, 2, 1, b'a', 0x20, 0, 1, b'a', 0x20, 0];
+    assert_eq!(BinnDecoder::new(&buf[..]).decode_into(&mut value).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // Missing null terminator, then invalid UTF-8
+    for buf in &[[binn_ir::value::TEXT, 1, b'a', 1], [binn_ir::value::TEXT, 1, 0xFF, 0]] {
+        let mut value = Value::from("old");
+        assert_eq!(BinnDecoder::new(&buf[..]).decode_into(&mut value).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    // Unknown types are skipped
+    let buf = [0b_1010_1111, 1, b'a', 0, binn_ir::value::TRUE];
+    let mut decoder = BinnDecoder::new(&buf[..]);
+    assert_eq!(decoder.decode_into(&mut value).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(decoder.decode()?, Some(Value::True));
+
+    // Containers nested too deeply
+    let mut buf = vec![];
+    buf.encode(&Value::List(vec![Value::List(vec![Value::List(vec![])])]))?;
+    let mut decoder = BinnDecoder::new(&buf[..]);
+    decoder.set_max_depth(2);
+    assert_eq!(decoder.max_depth(), 2);
+    let err = decoder.decode_into(&mut value).unwrap_err();
+    assert_eq!(binn_ir::DepthLimitExceeded::find(&err).map(|e| e.max_depth()), Some(2));
+    let mut decoder = BinnDecoder::new(&buf[..]);
+    decoder.set_max_depth(3);
+    assert!(decoder.decode_into(&mut value)?);
+
+    Ok(())
+}
+
+#[test]
+fn decode_containers_in_place() -> IoResult<()> {
+    let first = {
+        let mut object = binn_ir::object_from("name", "x".repeat(100));
+        object.object_insert("old", 0)?;
+        object.object_insert("inner", binn_ir::object_from("a", binn_ir::object_from("b", 0)))?;
+        object.object_insert("map", Value::Map(vec![(1, Value::from("one")), (2, Value::Null)].into_iter().collect()))?;
+        object
+    };
+    let second = {
+        let mut object = binn_ir::object_from("name", "y".repeat(90));
+        object.object_insert("map", binn_ir::map_from(1, "uno"))?;
+        object.object_insert("new", 1)?;
+        object.object_insert("inner", binn_ir::object_from("b", binn_ir::object_from("a", 1)))?;
+        object
+    };
+
+    let mut buf = vec![];
+    buf.encode(&first)?;
+    buf.encode(&second)?;
+    let mut decoder = BinnDecoder::new(&buf[..]);
+    let mut value = Value::Null;
+    assert!(decoder.decode_into(&mut value)?);
+    assert_eq!(value, first);
+
+    let addresses = |value: &Value| -> IoResult<_> {
+        let (key, name) = value.as_object()?.iter().find(|(key, _)| &***key == "name").unwrap();
+        Ok((key.as_ptr(), name.as_text()?.as_ptr()))
+    };
+    let old_addresses = addresses(&value)?;
+    assert!(decoder.decode_into(&mut value)?);
+    assert_eq!(value, second);
+    assert_eq!(addresses(&value)?, old_addresses);
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Encoding/decoding via `bytes::Buf` and `bytes::BufMut`
+
+#![cfg(feature="bytes")]
+
+extern crate binn_ir;
+
+use {
+    binn_ir::{IoResult, Value},
+    bytes::{Buf, BytesMut},
+};
+
+#[test]
+fn encode_and_decode_via_bufs() -> IoResult<()> {
+    let values = [
+        Value::Null, Value::U8(9), Value::from("binn"), Value::Blob(vec![9; 300]), binn_ir::object_from("id", 1), binn_ir::list(),
+    ];
+
+    let mut buf = BytesMut::new();
+    for value in &values {
+        assert_eq!(binn_ir::encode_into_buf(&mut buf, value)?, value.size()?);
+    }
+
+    // Chained buffers
+    let mut first = buf.freeze();
+    let second = first.split_off(7);
+    let mut source = first.chain(second);
+    for value in &values {
+        assert_eq!(binn_ir::decode_from_buf(&mut source)?.as_ref(), Some(value));
+    }
+    assert_eq!(binn_ir::decode_from_buf(&mut source)?, None);
+
+    // Buffer is too small
+    let mut small = [0_u8; 3];
+    assert!(binn_ir::encode_into_buf(&mut &mut small[..], &Value::from("binn")).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Blobs backed by `bytes::Bytes`
+
+#![cfg(feature="bytes")]
+
+extern crate binn_ir;
+
+use {
+    std::io::ErrorKind,
+
+    binn_ir::{BytesValue, Encoder, IoResult, Value},
+    bytes::Bytes,
+};
+
+#[test]
+fn decode_blobs_from_bytes() -> IoResult<()> {
+    let blob: Vec<u8> = (0..1000_u32).map(|i| i as u8).collect();
+
+    let mut buf = vec![];
+    buf.encode_blob(blob.clone())?;
+    buf.encode_blob(vec![])?;
+    buf.encode_null()?;
+
+    let mut source = Bytes::from(buf);
+    let decoded = binn_ir::decode_blob_from_bytes(&mut source)?.unwrap();
+    assert_eq!(decoded, blob);
+    assert_eq!(binn_ir::decode_blob_from_bytes(&mut source)?, Some(Bytes::new()));
+
+    // Errors leave source untouched
+    assert_eq!(binn_ir::decode_blob_from_bytes(&mut source).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(source, &[binn_ir::value::NULL][..]);
+    source.clear();
+    assert_eq!(binn_ir::decode_blob_from_bytes(&mut source)?, None);
+
+    let mut buf = vec![];
+    buf.encode_blob(blob.clone())?;
+    let mut source = Bytes::from(buf).slice(..100);
+    assert_eq!(binn_ir::decode_blob_from_bytes(&mut source).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(source.len(), 100);
+
+    assert_eq!(Value::from(Bytes::from(blob.clone())), Value::Blob(blob));
+
+    Ok(())
+}
+
+#[test]
+fn decode_nested_blobs_from_bytes() -> IoResult<()> {
+    let mut value = binn_ir::object();
+    value.object_insert("blob", Value::Blob(vec![1; 300]))?;
+    value.object_insert("list", Value::List(vec![Value::Blob(vec![2; 10]), Value::U8(3), Value::Text("text".into())]))?;
+    value.object_insert("map", Value::Map(vec![(-1, Value::Blob(vec![4; 200]))].into_iter().collect()))?;
+
+    let mut buf = vec![];
+    buf.encode(&value)?;
+    buf.encode_null()?;
+    let source = Bytes::from(buf);
+    let range = source.as_ptr_range();
+
+    let mut cursor = source.clone();
+    let decoded = binn_ir::decode_from_bytes(&mut cursor)?.unwrap();
+    assert_eq!(binn_ir::decode_from_bytes(&mut cursor)?, Some(BytesValue::Other(Value::Null)));
+    assert_eq!(binn_ir::decode_from_bytes(&mut cursor)?, None);
+
+    // Nested blobs share memory with source
+    let blobs = match &decoded {
+        BytesValue::Object(object) => match (&object["blob"], &object["list"], &object["map"]) {
+            (BytesValue::Blob(a), BytesValue::List(list), BytesValue::Map(map)) => match (&list[0], &map[&-1]) {
+                (BytesValue::Blob(b), BytesValue::Blob(c)) => vec![a.clone(), b.clone(), c.clone()],
+                other => panic!("{:?}", other),
+            },
+            other => panic!("{:?}", other),
+        },
+        other => panic!("{:?}", other),
+    };
+    for blob in blobs {
+        assert!(range.contains(&blob.as_ptr()));
+    }
+    assert_eq!(Value::from(decoded), value);
+
+    // Errors leave source untouched
+    let len = source.len() - 2;
+    let mut source = source.slice(..len);
+    assert_eq!(binn_ir::decode_from_bytes(&mut source).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(source.len(), len);
+
+    // Declared sizes of containers are verified
+    for bytes in &[&[binn_ir::value::LIST, 5, 1, binn_ir::value::NULL, binn_ir::value::NULL][..], &[binn_ir::value::LIST, 4, 2, binn_ir::value::NULL]] {
+        assert_eq!(binn_ir::decode_from_bytes(&mut Bytes::copy_from_slice(bytes)).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    // Depth is limited
+    let mut buf = vec![];
+    let mut value = Value::Null;
+    for _ in 0..binn_ir::value::DEFAULT_MAX_DEPTH + 1 {
+        value = Value::List(vec![value]);
+    }
+    buf.encode(&value)?;
+    assert!(binn_ir::DepthLimitExceeded::find(&binn_ir::decode_from_bytes(&mut Bytes::from(buf)).unwrap_err()).is_some());
+
+    Ok(())
+}
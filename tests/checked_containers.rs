@@ -0,0 +1,75 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Checked containers
+
+extern crate binn_ir;
+
+use {
+    core::convert::TryFrom,
+
+    binn_ir::{CheckedList, CheckedMap, CheckedObject, List, MapExt, Object, ObjectExt, Result, Value, value::OBJECT_KEY_MAX_LEN},
+};
+
+#[test]
+fn checked_list() -> Result<()> {
+    let mut list = CheckedList::new();
+    assert_eq!(list.size()?, binn_ir::list().size()?);
+
+    for i in 0..200_u32 {
+        list.push(i)?;
+        list.push(Value::Text(i.to_string()))?;
+        assert_eq!(list.size()?, Value::from(list.clone()).size()?);
+    }
+    assert_eq!(list.pop(), Some(Value::Text(String::from("199"))));
+    assert_eq!(list.size()?, Value::List(list.clone().into_inner()).size()?);
+    assert_eq!(list.len(), 399);
+    assert_eq!(list[0], Value::U32(0));
+
+    let inner: List = vec![Value::Null, Value::from("text")];
+    let list = CheckedList::try_from(inner.clone())?;
+    assert_eq!(list.into_inner(), inner);
+
+    Ok(())
+}
+
+#[test]
+fn checked_map() -> Result<()> {
+    let mut map = CheckedMap::new();
+    assert_eq!(map.insert(-1, "some")?, None);
+    assert_eq!(map.insert(-1, "text")?, Some(Value::from("some")));
+    assert_eq!(map.insert(0, 99_u8)?, None);
+    assert_eq!(map.size()?, Value::from(map.clone()).size()?);
+    assert_eq!(map.get_i64(0)?, Some(99));
+
+    assert_eq!(map.remove(-1), Some(Value::from("text")));
+    assert_eq!(map.remove(-1), None);
+    assert_eq!(map.size()?, Value::from(map.clone()).size()?);
+
+    let map = CheckedMap::try_from(map.into_inner())?;
+    assert_eq!(map.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn checked_object() -> Result<()> {
+    let mut object = CheckedObject::new();
+    assert_eq!(object.insert("text", "some")?, None);
+    assert_eq!(object.insert("text", "longer text")?, Some(Value::from("some")));
+    object.insert("list", vec![Value::Null])?;
+    object.insert("k".repeat(OBJECT_KEY_MAX_LEN), Value::Null)?;
+    object.insert("k".repeat(OBJECT_KEY_MAX_LEN + 1), Value::Null).unwrap_err();
+    assert_eq!(object.len(), 3);
+    assert_eq!(object.size()?, Value::from(object.clone()).size()?);
+    assert_eq!(object.get_text("text")?, Some("longer text"));
+
+    assert_eq!(object.remove("list"), Some(Value::List(vec![Value::Null])));
+    assert_eq!(object.remove("list"), None);
+    assert_eq!(object.size()?, Value::from(object.clone()).size()?);
+
+    let mut inner = Object::new();
+    binn_ir::object_insert(&mut inner, "k".repeat(OBJECT_KEY_MAX_LEN + 1), Value::Null);
+    CheckedObject::try_from(inner).unwrap_err();
+
+    Ok(())
+}
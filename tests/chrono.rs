@@ -0,0 +1,61 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Conversions for `chrono`
+
+#![cfg(feature="chrono")]
+
+extern crate binn_ir;
+
+use {
+    core::convert::TryFrom,
+
+    binn_ir::{Result, Value},
+    chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc},
+};
+
+#[test]
+fn date_times() -> Result<()> {
+    let date_time = Utc.with_ymd_and_hms(2019, 12, 31, 23, 59, 59).unwrap();
+    let value = Value::try_from(date_time)?;
+    assert_eq!(value.as_date_time()?, "2019-12-31T23:59:59Z");
+    assert_eq!(DateTime::<Utc>::try_from(&value)?, date_time);
+
+    let date_time = FixedOffset::east_opt(3600).unwrap().with_ymd_and_hms(2020, 1, 1, 0, 59, 59).unwrap();
+    let value = Value::try_from(date_time)?;
+    assert_eq!(value.as_date_time()?, "2020-01-01T00:59:59+01:00");
+    assert_eq!(DateTime::<FixedOffset>::try_from(&value)?, date_time);
+    assert_eq!(DateTime::<Utc>::try_from(value)?, Utc.with_ymd_and_hms(2019, 12, 31, 23, 59, 59).unwrap());
+
+    for s in &["2019-02-29T00:00:00Z", "2019-12-31T23:59:59", "yesterday"] {
+        assert!(DateTime::<Utc>::try_from(Value::DateTime(String::from(*s))).is_err());
+    }
+    assert!(DateTime::<Utc>::try_from(Value::from("2019-12-31T23:59:59Z")).is_err());
+
+    // Years which RFC 3339 can't hold
+    assert!(Value::try_from(Utc.with_ymd_and_hms(10000, 1, 1, 0, 0, 0).unwrap()).is_err());
+    assert!(Value::try_from(Utc.with_ymd_and_hms(-1, 12, 31, 23, 59, 59).unwrap()).is_err());
+    assert!(Value::try_from(FixedOffset::east_opt(3600).unwrap().with_ymd_and_hms(10000, 1, 1, 0, 59, 59).unwrap()).is_err());
+    let value = Value::try_from(Utc.with_ymd_and_hms(9999, 12, 31, 23, 59, 59).unwrap())?;
+    value.validate_temporal()?;
+    assert_eq!(DateTime::<Utc>::try_from(value)?, Utc.with_ymd_and_hms(9999, 12, 31, 23, 59, 59).unwrap());
+
+    Ok(())
+}
+
+#[test]
+fn dates_and_times() -> Result<()> {
+    let date = NaiveDate::from_ymd_opt(2019, 12, 31).unwrap();
+    let value = Value::from(date);
+    assert_eq!(value, Value::Date("2019-12-31".into()));
+    assert_eq!(NaiveDate::try_from(value)?, date);
+    assert!(NaiveDate::try_from(Value::Date("2019-13-01".into())).is_err());
+
+    let time = NaiveTime::from_hms_milli_opt(23, 59, 59, 500).unwrap();
+    let value = Value::from(time);
+    assert_eq!(value, Value::Time("23:59:59.500".into()));
+    assert_eq!(NaiveTime::try_from(value)?, time);
+    assert!(NaiveTime::try_from(Value::Time("24:00:01".into())).is_err());
+    assert!(NaiveTime::try_from(Value::Date("23:59:59".into())).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Chunked blob
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use {
+    std::io::{self, ErrorKind},
+
+    binn_ir::{ChunkedBlob, Encoder, IoResult},
+};
+
+#[test]
+fn chunked_blobs() -> IoResult<()> {
+    let data: Vec<u8> = (0..10_000_u32).map(|i| i as u8).collect();
+
+    for (len, chunk_size, chunk_count) in [(0, 1, 0), (1, 1, 1), (10_000, 1000, 10), (10_000, 999, 11), (9_999, 10_000, 1)] {
+        let chunked_blob = ChunkedBlob::new(len, chunk_size)?;
+        assert_eq!(chunked_blob.chunk_count(), chunk_count);
+
+        let mut buf = vec![];
+        let size = chunked_blob.encode(&mut buf, &mut &data[..])?;
+        assert_eq!(size, buf.len() as u64);
+        buf.encode_null()?;
+
+        let mut source = &buf[..];
+        let decoded = ChunkedBlob::decode(&mut source)?.unwrap();
+        assert_eq!(decoded, chunked_blob);
+        let mut sink = vec![];
+        assert_eq!(decoded.decode_into(&mut source, &mut sink)?, len);
+        assert_eq!(sink, &data[..len as usize]);
+        assert_eq!(binn_ir::decode(&mut source)?, Some(binn_ir::Value::Null));
+    }
+
+    assert_eq!(ChunkedBlob::decode(&mut io::empty())?, None);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_chunked_blobs() -> IoResult<()> {
+    assert_eq!(ChunkedBlob::new(9, 0).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(ChunkedBlob::new(9, binn_ir::value::MAX_DATA_SIZE + 1).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    // Short reader
+    let data = [0_u8; 9];
+    assert_eq!(ChunkedBlob::new(10, 3)?.encode(&mut vec![], &mut &data[..]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    // Missing chunks
+    let chunked_blob = ChunkedBlob::new(9, 3)?;
+    let mut buf = vec![];
+    chunked_blob.encode(&mut buf, &mut &data[..])?;
+    let mut source = &buf[..buf.len() - 5];
+    let chunked_blob = ChunkedBlob::decode(&mut source)?.unwrap();
+    assert_eq!(chunked_blob.decode_into(&mut source, &mut io::sink()).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    // Wrong chunk
+    let mut buf = vec![];
+    chunked_blob.encode(&mut buf, &mut &data[..])?;
+    buf.truncate(buf.len() - 10);
+    buf.encode_blob(vec![0; 4])?;
+    buf.encode_blob(vec![0; 2])?;
+    let mut source = &buf[..];
+    let chunked_blob = ChunkedBlob::decode(&mut source)?.unwrap();
+    assert_eq!(chunked_blob.decode_into(&mut source, &mut io::sink()).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // Invalid manifest
+    let mut buf = vec![];
+    buf.encode(&binn_ir::object_from("len", 9_u64))?;
+    assert!(ChunkedBlob::decode(&mut &buf[..]).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Command-line tool
+
+#![cfg(feature="cli")]
+
+extern crate binn_ir;
+
+use {
+    std::{
+        io::Write,
+        process::{Command, Output, Stdio},
+    },
+
+    binn_ir::{Encoder, IoResult, Value},
+};
+
+/// # Runs the tool
+fn run(args: &[&str], stdin: &[u8]) -> IoResult<Output> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_binn")).args(args)
+        .stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    child.stdin.take().unwrap().write_all(stdin)?;
+    child.wait_with_output()
+}
+
+#[test]
+fn commands() -> IoResult<()> {
+    let mut object = binn_ir::object();
+    object.object_insert("name", "moss")?;
+    object.object_insert("items", vec![Value::U8(1), binn_ir::map_from(-1, Value::Blob(vec![0xab])), Value::Double(0.5)])?;
+    let mut buf = vec![];
+    buf.encode(&object)?;
+    buf.encode_null()?;
+
+    // inspect
+    let output = run(&["inspect"], &buf)?;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), concat!(
+        "Object (2 item(s), 45 byte(s))\n",
+        "  [\"items\"] List (3 item(s), 24 byte(s))\n",
+        "    [0] U8(1)\n",
+        "    [1] Map (1 item(s), 10 byte(s))\n",
+        "      {-1} Blob (1 byte(s)): [ab]\n",
+        "    [2] Double(0.5)\n",
+        "  [\"name\"] Text(\"moss\")\n",
+        "Null\n",
+    ));
+
+    // to-json, then from-json
+    let output = run(&["to-json", "-"], &buf)?;
+    assert!(output.status.success());
+    let json = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(json, "{\"items\":[1,{\"-1\":[171]},0.5],\"name\":\"moss\"}\nnull\n");
+
+    let output = run(&["from-json"], json.as_bytes())?;
+    assert!(output.status.success());
+    let mut source = &output.stdout[..];
+    let mut expected = binn_ir::object();
+    expected.object_insert("name", "moss")?;
+    expected.object_insert("items", vec![Value::U8(1), binn_ir::map_from(-1, vec![Value::U8(0xab)]), Value::Double(0.5)])?;
+    assert_eq!(binn_ir::decode(&mut source)?, Some(expected));
+    assert_eq!(binn_ir::decode(&mut source)?, Some(Value::Null));
+    assert_eq!(binn_ir::decode(&mut source)?, None);
+
+    // validate
+    let output = run(&["validate"], &buf[..buf.len() - 1])?;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "valid (45 byte(s))\n");
+    let output = run(&["validate"], &buf)?;
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "at byte 45: trailing data: 1 byte(s)\n");
+
+    Ok(())
+}
+
+#[test]
+fn errors() -> IoResult<()> {
+    assert_eq!(run(&[], &[])?.status.code(), Some(2));
+    assert_eq!(run(&["unknown"], &[])?.status.code(), Some(2));
+    assert_eq!(run(&["inspect", "a", "b"], &[])?.status.code(), Some(2));
+    assert!(run(&["help"], &[])?.status.success());
+
+    let output = run(&["inspect", "/non-existent"], &[])?;
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr).unwrap().starts_with("failed to open \"/non-existent\": "));
+
+    let output = run(&["to-json"], &[binn_ir::value::LIST, 3])?;
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!output.stderr.is_empty());
+
+    assert_eq!(run(&["from-json"], b"{")?.status.code(), Some(1));
+
+    Ok(())
+}
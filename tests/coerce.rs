@@ -0,0 +1,99 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Type coercion
+
+extern crate binn_ir;
+
+use binn_ir::{Result, Value, coerce::{self, Rules}};
+
+#[test]
+fn strict_rules() -> Result<()> {
+    let rules = Rules::new();
+    assert_eq!(rules, Rules::default());
+
+    assert_eq!(coerce::to_u64(&Value::I8(5), rules)?, 5);
+    assert_eq!(coerce::to_i64(&Value::U64(5), rules)?, 5);
+    assert_eq!(coerce::to_f64(&Value::U16(5), rules)?, 5.0);
+    assert_eq!(coerce::to_f64(&Value::Float(0.5), rules)?, 0.5);
+    assert!(coerce::to_bool(&Value::True, rules)?);
+    coerce::to_u64(&Value::I8(-1), rules).unwrap_err();
+    coerce::to_i64(&Value::U64(u64::MAX), rules).unwrap_err();
+
+    for value in &[Value::from("5"), Value::Double(5.0), Value::True, Value::DecimalStr("5".into()), Value::Null] {
+        coerce::to_u64(value, rules).unwrap_err();
+        coerce::to_i64(value, rules).unwrap_err();
+    }
+    coerce::to_f64(&Value::from("0.5"), rules).unwrap_err();
+    coerce::to_bool(&Value::from("true"), rules).unwrap_err();
+    coerce::to_bool(&Value::U8(1), rules).unwrap_err();
+
+    Ok(())
+}
+
+#[test]
+fn numeric_strings() -> Result<()> {
+    let mut rules = Rules::new();
+    rules.parse_numeric_strings = true;
+
+    assert_eq!(coerce::to_u64(&Value::from(" 42\n"), rules)?, 42);
+    assert_eq!(coerce::to_i64(&Value::from("-42"), rules)?, -42);
+    assert_eq!(coerce::to_i64(&Value::DecimalStr("7".into()), rules)?, 7);
+    assert_eq!(coerce::to_f64(&Value::from("1.5e3"), rules)?, 1500.0);
+    coerce::to_u64(&Value::from("-42"), rules).unwrap_err();
+    coerce::to_u64(&Value::from("5.0"), rules).unwrap_err();
+    coerce::to_u64(&Value::from("five"), rules).unwrap_err();
+
+    rules.integral_floats = true;
+    assert_eq!(coerce::to_u64(&Value::from("5.0"), rules)?, 5);
+    assert_eq!(coerce::to_i64(&Value::from("-1e3"), rules)?, -1000);
+    coerce::to_u64(&Value::from("5.5"), rules).unwrap_err();
+
+    Ok(())
+}
+
+#[test]
+fn integral_floats() -> Result<()> {
+    let mut rules = Rules::new();
+    rules.integral_floats = true;
+
+    assert_eq!(coerce::to_u64(&Value::Double(5.0), rules)?, 5);
+    assert_eq!(coerce::to_i64(&Value::Float(-5.0), rules)?, -5);
+    assert_eq!(coerce::to_i64(&Value::Double(-9_223_372_036_854_775_808.0), rules)?, i64::MIN);
+    coerce::to_u64(&Value::Double(-5.0), rules).unwrap_err();
+    coerce::to_u64(&Value::Double(5.5), rules).unwrap_err();
+    coerce::to_u64(&Value::Double(18_446_744_073_709_551_616.0), rules).unwrap_err();
+    coerce::to_i64(&Value::Double(9_223_372_036_854_775_808.0), rules).unwrap_err();
+    coerce::to_i64(&Value::Double(f64::NAN), rules).unwrap_err();
+    coerce::to_i64(&Value::Double(f64::INFINITY), rules).unwrap_err();
+
+    Ok(())
+}
+
+#[test]
+fn bools() -> Result<()> {
+    let mut rules = Rules::new();
+    rules.truthy_strings = true;
+
+    for s in &["true", " Yes", "ON", "1"] {
+        assert!(coerce::to_bool(&Value::from(*s), rules)?);
+    }
+    for s in &["False", "no ", "off", "0"] {
+        assert!(!coerce::to_bool(&Value::from(*s), rules)?);
+    }
+    coerce::to_bool(&Value::from("maybe"), rules).unwrap_err();
+    coerce::to_bool(&Value::U8(1), rules).unwrap_err();
+
+    rules.bools_as_numbers = true;
+    assert!(coerce::to_bool(&Value::I32(1), rules)?);
+    assert!(!coerce::to_bool(&Value::U64(0), rules)?);
+    coerce::to_bool(&Value::U8(2), rules).unwrap_err();
+    assert_eq!(coerce::to_u64(&Value::True, rules)?, 1);
+    assert_eq!(coerce::to_i64(&Value::False, rules)?, 0);
+    assert_eq!(coerce::to_f64(&Value::True, rules)?, 1.0);
+
+    rules.parse_numeric_strings = true;
+    rules.integral_floats = true;
+    assert_eq!(rules, Rules::all());
+
+    Ok(())
+}
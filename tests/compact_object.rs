@@ -0,0 +1,40 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Objects whose keys are compact strings
+
+#![cfg(feature="compact_str")]
+
+extern crate binn_ir;
+
+use {
+    std::io::ErrorKind,
+
+    binn_ir::{Encoder, IoResult, Value, value},
+};
+
+#[test]
+fn decode_compact_objects() -> IoResult<()> {
+    let mut object = binn_ir::object_from("id", 1);
+    object.object_insert("k".repeat(value::OBJECT_KEY_MAX_LEN), "long")?;
+    object.object_insert("nested", binn_ir::object_from("name", "moon"))?;
+
+    let mut buf = vec![];
+    buf.encode(&object)?;
+    buf.encode_null()?;
+
+    let mut cursor = &buf[..];
+    let compact = binn_ir::decode_compact_object(&mut cursor)?.unwrap();
+    assert_eq!(compact.keys().filter(|key| key.is_heap_allocated()).count(), 1);
+    assert_eq!(Value::from(compact), object);
+
+    // Other values
+    assert_eq!(binn_ir::decode_compact_object(&mut cursor).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert!(binn_ir::decode_compact_object(&mut cursor)?.is_none());
+
+    // Declared sizes are verified
+    for bytes in &[&[value::OBJECT, 4, 1, 0, value::NULL][..], &[value::OBJECT, 6, 1, 0, value::NULL, value::NULL]] {
+        assert_eq!(binn_ir::decode_compact_object(&mut &bytes[..]).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Compression
+
+#![cfg(any(feature="flate2", feature="zstd"))]
+
+extern crate binn_ir;
+
+use {
+    std::io::ErrorKind,
+
+    binn_ir::{Codec, DecodeConfig, IoResult, Value},
+};
+
+/// # All enabled codecs
+fn codecs() -> Vec<Codec> {
+    vec![
+        #[cfg(feature="flate2")]
+        Codec::Deflate,
+        #[cfg(feature="zstd")]
+        Codec::Zstd,
+    ]
+}
+
+#[test]
+fn compression() -> IoResult<()> {
+    let mut document = binn_ir::object();
+    document.object_insert("text", "lorem ipsum dolor sit amet ".repeat(1000))?;
+    document.object_insert("list", vec![Value::U64(u64::MAX); 100])?;
+
+    for codec in codecs() {
+        let mut buf = vec![];
+        let size = binn_ir::encode_compressed(&mut buf, &document, codec)?;
+        assert_eq!(size as usize, buf.len());
+        assert!(buf.len() < document.size()? as usize / 10, "{:?}: {} byte(s)", codec, buf.len());
+
+        // Several documents in a stream
+        binn_ir::encode_compressed(&mut buf, &Value::Null, codec)?;
+        let mut source = buf.as_slice();
+        assert_eq!(binn_ir::decode_compressed(&mut source)?.as_ref(), Some(&document));
+        assert_eq!(binn_ir::decode_compressed(&mut source)?, Some(Value::Null));
+        assert!(binn_ir::decode_compressed(&mut source)?.is_none());
+
+        // Truncated data
+        assert_eq!(binn_ir::decode_compressed(&mut &buf[..size as usize - 1]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+        // Limits
+        let mut config = DecodeConfig::new();
+        config.max_bytes = Some(u64::from(document.size()?));
+        assert_eq!(binn_ir::decode_compressed_with_config(&mut &buf[..], &config)?.as_ref(), Some(&document));
+        config.max_bytes = Some(u64::from(document.size()?) - 1);
+        assert_eq!(binn_ir::decode_compressed_with_config(&mut &buf[..], &config).unwrap_err().kind(), ErrorKind::InvalidData);
+        // Compressed data counts too
+        let mut null = vec![];
+        binn_ir::encode_compressed(&mut null, &Value::Null, codec)?;
+        config.max_bytes = Some(1);
+        assert_eq!(binn_ir::decode_compressed_with_config(&mut &null[..], &config).unwrap_err().kind(), ErrorKind::InvalidData);
+        config.max_bytes = Some(null.len() as u64);
+        assert_eq!(binn_ir::decode_compressed_with_config(&mut &null[..], &config)?, Some(Value::Null));
+        config.max_bytes = None;
+        config.max_depth = 0;
+        assert!(binn_ir::DepthLimitExceeded::find(&binn_ir::decode_compressed_with_config(&mut &buf[..], &config).unwrap_err()).is_some());
+
+        // Blobs
+        let blob = Value::compressed_blob(&mut &[0_u8; 10_000][..], codec)?;
+        assert!(blob.as_blob()?.len() < 1000);
+    }
+
+    // Unknown codec
+    assert_eq!(binn_ir::decode_compressed(&mut &[0xff, 0, 0, 0, 0][..]).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    Ok(())
+}
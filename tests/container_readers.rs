@@ -0,0 +1,111 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Container readers
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use binn_ir::{IoResult, ListReader, MapReader, ObjectReader, Value};
+
+#[test]
+fn list_reader() -> IoResult<()> {
+    let items = (0..1000_u32).map(|i| Value::List(vec![Value::U32(i)])).collect::<Vec<_>>();
+    let mut buf = vec![];
+    Value::List(items.clone()).encode(&mut buf)?;
+    Value::Null.encode(&mut buf)?;
+
+    let mut source = &buf[..];
+    let mut reader = ListReader::new(&mut source)?;
+    assert_eq!((reader.len(), reader.remaining(), reader.is_empty()), (1000, 1000, false));
+    assert!(reader.skip_item()?);
+    assert_eq!(reader.next_item()?.as_ref(), items.get(1));
+    assert_eq!(reader.remaining(), 998);
+    assert_eq!(reader.size_hint(), (0, Some(998)));
+    assert_eq!(reader.by_ref().collect::<IoResult<Vec<_>>>()?, items[2..]);
+    assert!(!reader.skip_item()?);
+    assert_eq!(binn_ir::decode(&mut source)?, Some(Value::Null));
+
+    // Empty lists
+    buf.clear();
+    Value::List(vec![]).encode(&mut buf)?;
+    assert!(ListReader::new(&buf[..])?.is_empty());
+    assert!(ListReader::new(&buf[..])?.next().is_none());
+
+    // Not lists
+    ListReader::new(&[][..]).unwrap_err();
+    buf.clear();
+    binn_ir::object().encode(&mut buf)?;
+    ListReader::new(&buf[..]).unwrap_err();
+
+    // Truncated lists
+    buf.clear();
+    Value::List(vec![Value::True, Value::U8(1)]).encode(&mut buf)?;
+    let mut reader = ListReader::new(&buf[..buf.len() - 1])?;
+    assert_eq!(reader.next().unwrap()?, Value::True);
+    reader.next().unwrap().unwrap_err();
+    assert!(reader.next().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn object_reader() -> IoResult<()> {
+    let mut object = binn_ir::object_from("a", vec![Value::Null; 100]);
+    object.object_insert("b", "bee")?;
+    object.object_insert("c", Value::U8(3))?;
+    let mut buf = vec![];
+    object.encode(&mut buf)?;
+    Value::Null.encode(&mut buf)?;
+
+    let mut source = &buf[..];
+    let mut reader = ObjectReader::new(&mut source)?;
+    assert_eq!((reader.len(), reader.is_empty()), (3, false));
+    reader.read_value().unwrap_err();
+    assert_eq!(reader.next_key()?.as_deref(), Some("a"));
+    reader.skip_value()?;
+    reader.skip_value()?;
+    reader.read_value().unwrap_err();
+    assert_eq!(reader.next_key()?.as_deref(), Some("b"));
+    assert_eq!(reader.remaining(), 1);
+    // Value of "b" is skipped
+    assert_eq!(reader.by_ref().collect::<IoResult<Vec<_>>>()?, vec![("c".into(), Value::U8(3))]);
+    assert_eq!(reader.next_key()?, None);
+    assert_eq!(binn_ir::decode(&mut source)?, Some(Value::Null));
+
+    // Pending values are skipped at the end too
+    let mut source = &buf[..];
+    let mut reader = ObjectReader::new(&mut source)?;
+    assert_eq!(reader.nth(1).transpose()?, Some(("b".into(), Value::from("bee"))));
+    assert!(reader.next_key()?.is_some());
+    assert_eq!(reader.next_entry()?, None);
+    assert_eq!(binn_ir::decode(&mut source)?, Some(Value::Null));
+
+    ObjectReader::new(&[][..]).unwrap_err();
+    ObjectReader::new(&buf[buf.len() - 1..]).unwrap_err();
+
+    Ok(())
+}
+
+#[test]
+fn map_reader() -> IoResult<()> {
+    let mut map = binn_ir::map_from(-1, "minus");
+    map.map_insert(1, vec![Value::True])?;
+    let mut buf = vec![];
+    map.encode(&mut buf)?;
+
+    let mut reader = MapReader::new(&buf[..])?;
+    assert_eq!(reader.len(), 2);
+    assert_eq!(reader.next_key()?, Some(-1));
+    assert_eq!(reader.read_value()?, Value::from("minus"));
+    assert_eq!(reader.next_entry()?, Some((1, Value::from(vec![Value::True]))));
+    assert!(reader.next().is_none());
+
+    // Truncated maps
+    let mut reader = MapReader::new(&buf[..buf.len() - 1])?;
+    reader.next().unwrap()?;
+    reader.next().unwrap().unwrap_err();
+    assert!(reader.next().is_none());
+
+    Ok(())
+}
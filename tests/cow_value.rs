@@ -0,0 +1,87 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Copy-on-write value
+
+extern crate binn_ir;
+
+use binn_ir::{CowValue, Path, PathSegment, Result, SharedValue, Value};
+
+fn path(segments: Vec<PathSegment>) -> Path {
+    Path::from(segments)
+}
+
+#[test]
+fn cow_values() -> Result<()> {
+    let mut base = binn_ir::object();
+    base.object_insert("list", Value::List(vec![Value::Null, Value::True]))?;
+    base.object_insert("map", binn_ir::map())?;
+    base.object_insert("text", "base")?;
+    let base = SharedValue::new(base);
+
+    let mut cow = CowValue::new(base.clone());
+    assert!(!cow.is_modified());
+    assert_eq!(cow.size()?, base.size()?);
+    assert_eq!(cow.to_value(), *base);
+
+    let list = PathSegment::ObjectKey("list".into());
+    let map = PathSegment::ObjectKey("map".into());
+    let text = PathSegment::ObjectKey("text".into());
+
+    cow.set(&path(vec![list.clone(), PathSegment::Index(1)]), false)?;
+    cow.set(&path(vec![map.clone(), PathSegment::MapKey(9)]), binn_ir::list())?;
+    cow.set(&path(vec![map.clone(), PathSegment::MapKey(9), PathSegment::Index(0)]), 1_u8).unwrap_err();
+    cow.remove(&path(vec![text.clone()]))?;
+    assert!(cow.is_modified());
+
+    // Errors
+    cow.set(&path(vec![list.clone(), PathSegment::Index(2)]), 0_u8).unwrap_err();
+    cow.set(&path(vec![text.clone(), PathSegment::Index(0)]), 0_u8).unwrap_err();
+    cow.set(&path(vec![list.clone(), PathSegment::MapKey(0)]), 0_u8).unwrap_err();
+    cow.remove(&path(vec![list.clone(), PathSegment::Index(0)])).unwrap_err();
+    cow.remove(&path(vec![text.clone()])).unwrap_err();
+    cow.remove(&Path::default()).unwrap_err();
+
+    let mut expected = (*base).clone();
+    expected.object_mut_by(&["list"])?.as_mut_list()?[1] = Value::False;
+    expected.object_mut_by(&["map"])?.map_insert(9, binn_ir::list())?;
+    expected.object_take_by(&["text"])?;
+    let value = cow.to_value();
+    assert_eq!(value, expected);
+    assert_eq!(cow.size()?, expected.size()?);
+
+    // Base is unchanged
+    assert_eq!(base.object_by(&["text"])?, &Value::from("base"));
+
+    // Modifying a newly set branch
+    cow.set(&path(vec![map.clone(), PathSegment::MapKey(9)]), Value::List(vec![Value::Null]))?;
+    cow.set(&path(vec![map.clone(), PathSegment::MapKey(9), PathSegment::Index(0)]), 1_u8)?;
+    cow.set(&path(vec![text.clone()]), "again")?;
+    cow.remove(&path(vec![list]))?;
+    let value = cow.to_value();
+    assert_eq!(value.object_by(&["map"])?.map_by(&[9])?, &Value::List(vec![Value::U8(1)]));
+    assert_eq!(value.object_by(&["text"])?, &Value::from("again"));
+    assert!(value.object_maybe_by(&["list"])?.is_none());
+    assert_eq!(cow.size()?, value.size()?);
+
+    // Replacing everything
+    cow.set(&Path::default(), Value::Null)?;
+    assert_eq!(cow.to_value(), Value::Null);
+    assert_eq!(cow.size()?, 1);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn encode() -> binn_ir::IoResult<()> {
+    let base = SharedValue::new(Value::List((0..200_u8).map(Value::U8).collect()));
+    let mut cow = CowValue::from(base.clone());
+    cow.set(&path(vec![PathSegment::Index(100)]), Value::Text("x".repeat(300)))?;
+
+    let mut buf = vec![];
+    assert_eq!(cow.encode(&mut buf)? as usize, buf.len());
+    assert_eq!(binn_ir::decode(&mut &buf[..])?, Some(cow.to_value()));
+    assert_eq!(cow.size()? as usize, buf.len());
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Datagrams
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use {
+    std::io::ErrorKind,
+
+    binn_ir::{DatagramTooLarge, IoResult, Value},
+};
+
+#[test]
+fn datagrams() -> IoResult<()> {
+    let value = binn_ir::object_from("id", Value::List(vec![Value::U8(1), Value::from("two")]));
+    let size = value.size()? as usize;
+
+    let datagram = binn_ir::encode_datagram(&value, size)?;
+    assert_eq!(datagram.len(), size);
+    assert_eq!(binn_ir::decode_datagram(&datagram)?, value);
+
+    // Too large
+    let err = binn_ir::encode_datagram(&value, size - 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let too_large = DatagramTooLarge::find(&err).unwrap();
+    assert_eq!((too_large.size(), too_large.max_len()), (size, size - 1));
+
+    // Empty, truncated, and trailing data
+    assert_eq!(binn_ir::decode_datagram(&[]).unwrap_err().kind(), ErrorKind::InvalidData);
+    binn_ir::decode_datagram(&datagram[..size - 1]).unwrap_err();
+    let mut datagram = datagram;
+    datagram.push(binn_ir::value::NULL);
+    assert_eq!(binn_ir::decode_datagram(&datagram).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    Ok(())
+}
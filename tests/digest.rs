@@ -0,0 +1,50 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Hashing with `digest`
+
+#![cfg(feature="digest")]
+
+extern crate binn_ir;
+
+use {
+    binn_ir::{IoResult, Map, Value},
+    sha2::{Digest, Sha256, Sha512},
+};
+
+#[test]
+fn canonical_hash() -> IoResult<()> {
+    let mut object = binn_ir::object();
+    object.object_insert("list", vec![Value::U8(1), Value::from("two"), Value::Double(3.0)])?;
+    object.object_insert("map", Map::from([(-1, Value::Null), (1, Value::Blob(vec![0; 300]))]))?;
+
+    // Hashes are the same as ones of encoded bytes
+    let mut buf = vec![];
+    object.encode(&mut buf)?;
+    assert_eq!(object.canonical_hash::<Sha256>()?, Sha256::digest(&buf));
+    assert_eq!(object.canonical_hash::<Sha512>()?, Sha512::digest(&buf));
+
+    // Order of insertion doesn't matter
+    let mut other = binn_ir::object();
+    for key in ["zzz", "a", "m", "b"] {
+        other.object_insert(key, key)?;
+    }
+    let mut another = binn_ir::object();
+    for key in ["b", "m", "a", "zzz"] {
+        another.object_insert(key, key)?;
+    }
+    assert_eq!(other.canonical_hash::<Sha256>()?, another.canonical_hash::<Sha256>()?);
+
+    // Types matter
+    assert_ne!(Value::U8(1).canonical_hash::<Sha256>()?, Value::I64(1).canonical_hash::<Sha256>()?);
+    assert_ne!(Value::from("1").canonical_hash::<Sha256>()?, Value::DecimalStr("1".into()).canonical_hash::<Sha256>()?);
+
+    // Floats are canonicalized
+    assert_eq!(Value::Double(0.0), Value::Double(-0.0));
+    assert_eq!(Value::Double(0.0).canonical_hash::<Sha256>()?, Value::Double(-0.0).canonical_hash::<Sha256>()?);
+    assert_eq!(Value::Float(0.0).canonical_hash::<Sha256>()?, Value::Float(-0.0).canonical_hash::<Sha256>()?);
+    assert_ne!(Value::Float(0.0).canonical_hash::<Sha256>()?, Value::Double(0.0).canonical_hash::<Sha256>()?);
+    let nans = Value::List(vec![Value::Double(f64::from_bits(0xfff8_0000_0000_0001)), Value::Float(-f32::NAN)]);
+    assert_eq!(nans.canonical_hash::<Sha256>()?, Value::List(vec![Value::Double(f64::NAN), Value::Float(f32::NAN)]).canonical_hash::<Sha256>()?);
+
+    Ok(())
+}
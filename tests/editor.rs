@@ -0,0 +1,68 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Editor
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use {
+    std::io::ErrorKind,
+
+    binn_ir::{Editor, Encoder, IoResult, Path, PathSegment, Value},
+};
+
+#[test]
+fn editor() -> IoResult<()> {
+    let mut value = binn_ir::object_from("list", Value::List(vec![Value::U8(0), Value::Text("short".into()), Value::Null]));
+    value.object_insert("map", binn_ir::map_from(-1, Value::Blob(vec![0; 10])))?;
+    value.object_insert("z", true)?;
+
+    let mut buf = vec![];
+    buf.encode(&value)?;
+    // Trailing data
+    buf.push(binn_ir::value::NULL);
+
+    let list_item = Path::from(vec![PathSegment::ObjectKey("list".into()), PathSegment::Index(1)]);
+    let map_item = Path::from(vec![PathSegment::ObjectKey("map".into()), PathSegment::MapKey(-1)]);
+    let z = Path::from(vec![PathSegment::ObjectKey("z".into())]);
+
+    let mut editor = Editor::new(&mut buf);
+    assert_eq!(editor.get(&list_item)?, Value::from("short"));
+
+    // Growing, so sizes change from 1 byte to 4 bytes
+    editor.set(&list_item, "x".repeat(500))?;
+    value.object_mut_by(&["list"])?.as_mut_list()?[1] = Value::Text("x".repeat(500));
+    editor.set(&map_item, Value::U64(1))?;
+    value.object_mut_by(&["map"])?.map_insert(-1, Value::U64(1))?;
+    editor.set(&z, false)?;
+    value.object_insert("z", false)?;
+    assert_eq!(editor.get(&Path::new())?, value);
+
+    // Shrinking
+    editor.set(&list_item, "")?;
+    value.object_mut_by(&["list"])?.as_mut_list()?[1] = Value::from("");
+
+    // Errors
+    editor.set(&Path::from(vec![PathSegment::ObjectKey("list".into()), PathSegment::Index(3)]), 0_u8).unwrap_err();
+    editor.set(&Path::from(vec![PathSegment::ObjectKey("unknown".into())]), 0_u8).unwrap_err();
+    editor.set(&Path::from(vec![PathSegment::MapKey(0)]), 0_u8).unwrap_err();
+    editor.get(&Path::from(vec![PathSegment::ObjectKey("z".into()), PathSegment::Index(0)])).unwrap_err();
+
+    assert_eq!(value.size()? as usize, buf.len() - 1);
+    let mut source = &buf[..];
+    assert_eq!(binn_ir::decode(&mut source)?, Some(value));
+    assert_eq!(source, [binn_ir::value::NULL]);
+
+    // Root
+    let mut editor = Editor::new(&mut buf);
+    editor.set(&Path::new(), 1_u8)?;
+    assert_eq!(buf, [binn_ir::value::U8, 1, binn_ir::value::NULL]);
+
+    // Invalid data
+    let mut buf = vec![binn_ir::value::LIST, 5, 1, binn_ir::value::TEXT];
+    let err = Editor::new(&mut buf).set(&Path::from(vec![PathSegment::Index(0)]), 0_u8).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    Ok(())
+}
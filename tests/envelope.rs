@@ -0,0 +1,49 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Envelope
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use {
+    std::io::ErrorKind,
+
+    binn_ir::{Envelope, IoResult, Value},
+};
+
+const MAGIC_NUMBER: u64 = 0x_B177_0000;
+
+#[test]
+fn envelopes() -> IoResult<()> {
+    let mut first = Envelope::new(MAGIC_NUMBER, 1, binn_ir::list());
+    binn_ir::object_insert(first.metadata_mut(), "hash", 0_u64);
+    first.payload_mut().push("item")?;
+    let second = Envelope::new(MAGIC_NUMBER, 3, Value::Null);
+
+    let mut file = vec![];
+    let size = first.write_to(&mut file)?;
+    assert_eq!(size as usize, file.len());
+    second.write_to(&mut file)?;
+
+    let mut source = &file[..];
+    let read = Envelope::read_from(&mut source, MAGIC_NUMBER, 1..=3)?.unwrap();
+    assert_eq!(read.magic(), MAGIC_NUMBER);
+    assert_eq!(read.version(), 1);
+    assert_eq!(read.metadata(), first.metadata());
+    assert_eq!(read.into_payload(), Value::List(vec![Value::from("item")]));
+    assert_eq!(Envelope::read_from(&mut source, MAGIC_NUMBER, 1..=3)?.as_ref(), Some(&second));
+    assert!(Envelope::read_from(&mut source, MAGIC_NUMBER, 1..=3)?.is_none());
+
+    // Wrong magic number, unsupported versions
+    for (magic, versions) in [(0, 1..=3), (MAGIC_NUMBER, 2..=3)] {
+        let err = Envelope::read_from(&mut &file[..], magic, versions).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    // Truncated
+    let err = Envelope::read_from(&mut &file[..size as usize - 1], MAGIC_NUMBER, 1..=3).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+    Ok(())
+}
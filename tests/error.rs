@@ -0,0 +1,21 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Error
+
+#![cfg(feature="backtrace")]
+
+extern crate binn_ir;
+
+use {
+    std::backtrace::Backtrace,
+
+    binn_ir::Value,
+};
+
+#[test]
+fn backtraces() {
+    let err = Value::Null.as_text().unwrap_err();
+    // Whether backtraces are collected depends on environment variables
+    assert_eq!(err.backtrace().status(), Backtrace::capture().status());
+    assert!(format!("{:?}", err).contains("backtrace"));
+}
@@ -0,0 +1,119 @@
+// License: see LICENSE file at root directory of `master` branch
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use {
+    core::convert::TryFrom,
+    std::{
+        env,
+        fs,
+        io::ErrorKind,
+    },
+
+    binn_ir::{Encoder, IoResult, Value},
+};
+
+/// # Makes a new temporary directory
+fn make_temp_dir(name: &str) -> IoResult<std::path::PathBuf> {
+    let result = env::temp_dir().join(format!("{}-{}-{}", binn_ir::CODE_NAME, name, std::process::id()));
+    fs::create_dir_all(&result)?;
+    Ok(result)
+}
+
+#[test]
+fn ext_sort() -> IoResult<()> {
+    let ids: Vec<i32> = (0..500).map(|i| (i * 7919) % 500).collect();
+
+    let mut buf = vec![];
+    for (order, id) in ids.iter().enumerate() {
+        let mut object = binn_ir::object();
+        object.object_insert("meta", binn_ir::object_from("id", match id % 2 { 0 => Value::U16(*id as u16), _ => Value::I64(i64::from(*id)) }))?;
+        object.object_insert("order", order as u64)?;
+        buf.encode(&object)?;
+    }
+    // Missing keys come first, and keep their order
+    buf.encode(&binn_ir::object_from("order", 1_000_u64))?;
+    buf.encode(&binn_ir::object_from("order", 1_001_u64))?;
+
+    let temp_dir = make_temp_dir("ext-sort")?;
+    let sorted = binn_ir::ext_sort(&mut &buf[..], &["meta", "id"], &temp_dir, 1024)?;
+    assert!(sorted.spill_file_count() > 1);
+    assert_eq!(fs::read_dir(&temp_dir)?.count(), sorted.spill_file_count());
+
+    let sorted = sorted.collect::<IoResult<Vec<_>>>()?;
+    assert_eq!(sorted.len(), ids.len() + 2);
+    assert_eq!(sorted[0].object_by(&["order"])?, &Value::U64(1_000));
+    assert_eq!(sorted[1].object_by(&["order"])?, &Value::U64(1_001));
+    for (i, object) in sorted[2..].iter().enumerate() {
+        assert_eq!(i64::try_from(object.object_by(&["meta", "id"])?)?, i as i64);
+    }
+
+    // Spill files are removed
+    assert_eq!(fs::read_dir(&temp_dir)?.count(), 0);
+    fs::remove_dir(&temp_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn ext_sort_in_memory() -> IoResult<()> {
+    let mut buf = vec![];
+    for (order, id) in [2_u8, 1, 2, 0, 1].iter().enumerate() {
+        let mut object = binn_ir::object_from("id", *id);
+        object.object_insert("order", order as u8)?;
+        buf.encode(&object)?;
+    }
+
+    let temp_dir = make_temp_dir("ext-sort-in-memory")?;
+    let sorted = binn_ir::ext_sort(&mut &buf[..], &["id"], &temp_dir, usize::MAX)?;
+    assert_eq!(sorted.spill_file_count(), 0);
+
+    // Sort is stable
+    let orders = sorted.map(|object| Ok(u8::try_from(object?.object_by(&["order"])?)?)).collect::<IoResult<Vec<_>>>()?;
+    assert_eq!(orders, [3, 1, 4, 0, 2]);
+
+    fs::remove_dir(&temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn ext_sort_invalid_inputs() -> IoResult<()> {
+    let temp_dir = env::temp_dir();
+
+    assert_eq!(binn_ir::ext_sort(&mut &[][..], &[], &temp_dir, 0).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    let mut buf = vec![];
+    buf.encode(&binn_ir::object_from("id", 0))?;
+    buf.encode_u8(0)?;
+    assert_eq!(binn_ir::ext_sort(&mut &buf[..], &["id"], &temp_dir, 0).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    Ok(())
+}
+
+#[test]
+fn ext_sort_mixed_numbers() -> IoResult<()> {
+    const TWO_POW_53: u64 = 1 << 53;
+
+    let keys = [
+        Value::I64(TWO_POW_53 as i64 + 1), Value::Double(f64::NAN), Value::U64(TWO_POW_53), Value::Double(-0.5), Value::Double(TWO_POW_53 as f64),
+        Value::U8(0), Value::Double(f64::INFINITY), Value::Double(-0.0), Value::I8(-1), Value::Double(0.0), Value::U64(u64::MAX),
+        Value::Float(1e30), Value::Double(-f64::NAN), Value::Double(0.5), Value::Double(-1.0),
+    ];
+    let mut buf = vec![];
+    for (order, key) in keys.iter().enumerate() {
+        let mut object = binn_ir::object_from("key", key.clone());
+        object.object_insert("order", order as u8)?;
+        buf.encode(&object)?;
+    }
+
+    let temp_dir = make_temp_dir("ext-sort-mixed-numbers")?;
+    let sorted = binn_ir::ext_sort(&mut &buf[..], &["key"], &temp_dir, usize::MAX)?;
+    let orders = sorted.map(|object| Ok(u8::try_from(object?.object_by(&["order"])?)?)).collect::<IoResult<Vec<_>>>()?;
+    // Equal keys keep their original order: I8(-1) and Double(-1.0), U8(0) and Double(0.0), U64(2^53) and Double(2^53)
+    assert_eq!(orders, [12, 8, 14, 3, 7, 5, 9, 13, 2, 4, 0, 10, 11, 6, 1]);
+
+    fs::remove_dir(&temp_dir)?;
+    Ok(())
+}
@@ -0,0 +1,111 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Framing
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use {
+    std::io::ErrorKind,
+
+    binn_ir::{DecodeConfig, FRAME_HEADER_SIZE, IoResult, MESSAGE_PREFIX_SIZE, Value, value},
+};
+
+#[test]
+fn framing() -> IoResult<()> {
+    let values = [Value::Null, Value::from("text"), Value::Blob(vec![9; 1000]), Value::List(vec![Value::U8(1), Value::I64(-1)])];
+
+    let mut buf = vec![];
+    for value in &values {
+        let size = binn_ir::write_framed(&mut buf, value)?;
+        assert_eq!(size, value.size()? + FRAME_HEADER_SIZE as u32);
+    }
+    assert_eq!(&buf[..FRAME_HEADER_SIZE], [0, 0, 0, 1, 0x52, 0x7d, 0x53, 0x51]);
+
+    let mut source = buf.as_slice();
+    for value in &values {
+        assert_eq!(&binn_ir::read_framed(&mut source)?.unwrap(), value);
+    }
+    assert!(binn_ir::read_framed(&mut source)?.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn corrupted_frames() -> IoResult<()> {
+    let mut buf = vec![];
+    binn_ir::write_framed(&mut buf, &Value::from("some text"))?;
+
+    // Any corrupted byte is detected
+    for i in 0..buf.len() {
+        let mut buf = buf.clone();
+        buf[i] ^= 0x10;
+        let kind = binn_ir::read_framed(&mut buf.as_slice()).unwrap_err().kind();
+        assert!(kind == ErrorKind::InvalidData || kind == ErrorKind::UnexpectedEof, "{}: {:?}", i, kind);
+    }
+
+    // Truncated frames
+    for len in 1..buf.len() {
+        assert_eq!(binn_ir::read_framed(&mut &buf[..len]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    // Length which is too large
+    assert_eq!(binn_ir::read_framed(&mut &[0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0][..]).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // Payload with trailing data, and empty payload
+    assert_eq!(binn_ir::read_framed(&mut &[0, 0, 0, 2, 0xf1, 0x61, 0x77, 0xd2, 0, 0][..]).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(binn_ir::read_framed(&mut &[0, 0, 0, 0, 0, 0, 0, 0][..]).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    Ok(())
+}
+
+#[test]
+fn messages() -> IoResult<()> {
+    let values = [Value::Null, Value::from("text"), Value::Blob(vec![9; 1000]), Value::List(vec![Value::U8(1), Value::I64(-1)])];
+
+    let mut buf = vec![];
+    for value in &values {
+        let size = binn_ir::write_message(&mut buf, value)?;
+        assert_eq!(size, value.size()? + MESSAGE_PREFIX_SIZE as u32);
+    }
+
+    let mut source = buf.as_slice();
+    for value in &values {
+        assert_eq!(&binn_ir::read_message(&mut source)?.unwrap(), value);
+    }
+    assert!(binn_ir::read_message(&mut source)?.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn resynchronizing_messages() -> IoResult<()> {
+    let mut buf = vec![];
+    // Invalid value, trailing data, empty message, message which is too large
+    buf.extend(&[0, 0, 0, 2, value::TEXT, 9]);
+    buf.extend(&[0, 0, 0, 2, value::NULL, value::NULL]);
+    buf.extend(&[0, 0, 0, 0]);
+    binn_ir::write_message(&mut buf, &Value::Blob(vec![0; 100]))?;
+    binn_ir::write_message(&mut buf, &Value::U8(1))?;
+
+    let mut config = DecodeConfig::new();
+    config.max_bytes = Some(99);
+
+    let mut source = buf.as_slice();
+    for _ in 0..4 {
+        assert!(binn_ir::read_message_with_config(&mut source, &config).is_err());
+    }
+    assert_eq!(binn_ir::read_message_with_config(&mut source, &config)?, Some(Value::U8(1)));
+    assert!(binn_ir::read_message_with_config(&mut source, &config)?.is_none());
+
+    // Truncated messages, and length which is too large
+    for len in 1..5 {
+        assert_eq!(binn_ir::read_message(&mut &[0, 0, 0, 1, value::NULL][..len]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+    assert_eq!(binn_ir::read_message(&mut &[0xff, 0xff, 0xff, 0xff][..]).unwrap_err().kind(), ErrorKind::InvalidData);
+    // Large lengths are not trusted before their bytes arrive
+    assert_eq!(binn_ir::read_message(&mut &[0x7f, 0xff, 0xff, 0xff, value::NULL][..]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    Ok(())
+}
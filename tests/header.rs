@@ -0,0 +1,178 @@
+// License: see LICENSE file at root directory of `master` branch
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use {
+    std::io::{BufReader, Cursor, ErrorKind, Read},
+
+    binn_ir::{Decoder, Encoder, IoResult, Value, storage::Storage, value},
+};
+
+#[test]
+fn decode_headers() -> IoResult<()> {
+    let values = [
+        Value::Null,
+        Value::I16(-1),
+        Value::Double(0.0),
+        Value::from("hello"),
+        Value::Blob(vec![0; 200]),
+        Value::List(vec![Value::True, Value::from("world")]),
+        binn_ir::object_from("x".repeat(200), Value::Null),
+    ];
+
+    let mut buf = vec![];
+    for v in values.iter() {
+        buf.encode(v)?;
+    }
+
+    let mut cursor = Cursor::new(&buf);
+    for v in values.iter() {
+        let position = cursor.position();
+        let header = cursor.decode_header()?.unwrap();
+        assert_eq!(header.storage(), v.storage());
+        assert_eq!(header.value_size(), u64::from(v.size()?));
+        assert_eq!(cursor.position() - position, u64::from(header.size()));
+        cursor.set_position(position + header.value_size());
+    }
+    assert_eq!(cursor.decode_header()?, None);
+
+    let header = binn_ir::decode_header(&mut &buf[buf.len() - values.last().unwrap().size()? as usize..])?.unwrap();
+    assert_eq!(header.type_byte(), value::OBJECT);
+    assert_eq!(header.second_type_byte(), None);
+    assert_eq!(header.item_count(), Some(1));
+    assert_eq!(header.size(), 6);
+
+    // User defined type
+    let header = binn_ir::decode_header(&mut &[0xB0, 0x15, 3, b'a', b'b', b'c', 0][..])?.unwrap();
+    assert_eq!(header.storage(), Storage::String);
+    assert_eq!(header.second_type_byte(), Some(0x15));
+    assert_eq!(header.declared_size(), Some(3));
+    assert_eq!(header.value_size(), 7);
+
+    // Invalid sources
+    assert_eq!(binn_ir::decode_header(&mut &[value::LIST, 2, 0][..]).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(binn_ir::decode_header(&mut &[value::TEXT][..]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    Ok(())
+}
+
+#[test]
+fn peek_headers() -> IoResult<()> {
+    let list = Value::List(vec![Value::U8(1); 200]);
+    let mut buf = vec![];
+    buf.encode(&list)?;
+
+    let mut source = BufReader::new(&buf[..]);
+    let header = binn_ir::peek_header(&mut source)?.unwrap();
+    assert_eq!(header.declared_size(), Some(list.size()?));
+    assert_eq!(header.item_count(), Some(200));
+    assert_eq!(header.size(), 9);
+
+    // Nothing is consumed
+    assert_eq!(binn_ir::peek_header(&mut source)?, Some(header));
+    assert_eq!(source.decode()?, Some(list));
+    assert_eq!(binn_ir::peek_header(&mut source)?, None);
+
+    // Headers which are split across buffers
+    let mut source = BufReader::new((&buf[..3]).chain(&buf[3..]));
+    assert_eq!(binn_ir::peek_header(&mut source).unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert_eq!(source.decode()?, Some(Value::List(vec![Value::U8(1); 200])));
+
+    Ok(())
+}
+
+#[test]
+fn skip_values() -> IoResult<()> {
+    let mut buf = vec![];
+    for i in 0..10_u8 {
+        let mut object = binn_ir::object_from("id", i);
+        object.object_insert("data", vec![i; usize::from(i) * 50])?;
+        buf.encode(&object)?;
+        buf.encode_text(i.to_string())?;
+    }
+    buf.encode(&Value::U64(99))?;
+
+    let mut cursor = Cursor::new(&buf);
+    for i in 0..10_u8 {
+        match i % 2 {
+            0 => {
+                let size = cursor.clone().decode()?.unwrap().size()?;
+                assert_eq!(cursor.skip_value()?, Some(u64::from(size)));
+            },
+            _ => assert_eq!(cursor.decode()?.unwrap().object_by(&["id"])?, &Value::U8(i)),
+        };
+        assert_eq!(cursor.decode_text()?.unwrap(), i.to_string());
+    }
+    assert_eq!(cursor.skip_n(2)?, 1);
+    assert_eq!(cursor.skip_value()?, None);
+
+    // Missing data
+    assert_eq!(binn_ir::skip_value(&mut &[value::BLOB, 5, 0, 1][..]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    Ok(())
+}
+
+#[test]
+fn decode_container_headers() -> IoResult<()> {
+    let mut map = binn_ir::map_from(-1, "minus one");
+    map.map_insert(2, Value::List(vec![Value::Null]))?;
+    let mut object = binn_ir::object_from("k".repeat(200), 0_u8);
+    object.object_insert("second", Value::False)?;
+
+    let mut buf = vec![];
+    buf.encode(&map)?;
+    buf.encode(&object)?;
+    buf.encode_null()?;
+
+    let mut cursor = Cursor::new(&buf);
+    assert_eq!(cursor.decode_map_header()?, Some((map.size()?, 2)));
+    assert_eq!(cursor.decode_map_key()?, -1);
+    assert_eq!(cursor.decode()?, Some(Value::from("minus one")));
+    assert_eq!(cursor.decode_map_key()?, 2);
+    assert_eq!(cursor.decode_list_header()?, Some((4, 1)));
+    assert_eq!(cursor.decode_null()?, Some(()));
+
+    assert_eq!(cursor.decode_object_header()?, Some((object.size()?, 2)));
+    assert_eq!(cursor.decode_object_key()?, "k".repeat(200));
+    assert_eq!(cursor.decode_u8()?, Some(0));
+    assert_eq!(cursor.decode_object_key()?, "second");
+    assert_eq!(cursor.decode_bool()?, Some(false));
+
+    assert_eq!(cursor.clone().decode_list_header().unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(cursor.decode_null()?, Some(()));
+    assert_eq!(cursor.decode_object_header()?, None);
+
+    Ok(())
+}
+
+#[test]
+fn decode_objects_with_keys() -> IoResult<()> {
+    let mut object = binn_ir::object();
+    for i in 0..500_u16 {
+        object.object_insert(format!("field-{}", i), Value::List(vec![Value::U16(i); 10]))?;
+    }
+    object.object_insert("id", 9_u8)?;
+
+    let mut buf = vec![];
+    buf.encode(&object)?;
+    // Declared size is stored in 4 bytes, even though it's small
+    buf.extend(&[value::OBJECT, 0x80, 0, 0, 9, 1, 1, b'x', value::TRUE]);
+
+    let mut cursor = Cursor::new(&buf);
+    let projected = cursor.decode_object_with_keys(&["id", "field-99", "field-999"])?.unwrap();
+    assert_eq!(projected.len(), 2);
+    assert_eq!(projected["id"], Value::U8(9));
+    assert_eq!(&projected["field-99"], object.object_by(&["field-99"])?);
+
+    let projected = cursor.decode_object_with_keys(&["x"])?.unwrap();
+    assert_eq!(projected["x"], Value::True);
+    assert_eq!(cursor.decode_object_with_keys(&[])?, None);
+
+    // Invalid size
+    let projected = binn_ir::decode_object_with_keys(&mut &[value::OBJECT, 6, 1, 1, b'x', value::U8, 0][..], &["x"]);
+    assert_eq!(projected.unwrap_err().kind(), ErrorKind::InvalidData);
+
+    Ok(())
+}
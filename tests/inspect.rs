@@ -0,0 +1,140 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Inspect
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use binn_ir::{IoResult, Path, PathSegment, Value, inspect, value};
+
+#[test]
+fn explain() -> IoResult<()> {
+    let mut object = binn_ir::object();
+    object.object_insert("id", 1_u8)?;
+    object.object_insert("tags", vec![Value::from("x"), Value::I16(-2)])?;
+    object.object_insert("map", binn_ir::Map::from([(1, Value::Blob(vec![0; 9]))]))?;
+    let mut bytes = vec![];
+    object.encode(&mut bytes)?;
+    Value::Double(0.5).encode(&mut bytes)?;
+
+    assert_eq!(inspect::explain(&bytes), concat!(
+        "00000000  e2                       object\n",
+        "00000001  2d                         size: 45\n",
+        "00000002  03                         count: 3\n",
+        "00000003  02 69 64                   key: \"id\"\n",
+        "00000006  20                         [\"id\"] u8\n",
+        "00000007  01                           data: 1\n",
+        "00000008  03 6d 61 70                key: \"map\"\n",
+        "0000000c  e1                         [\"map\"] map\n",
+        "0000000d  12                           size: 18\n",
+        "0000000e  01                           count: 1\n",
+        "0000000f  00 00 00 01                  key: 1\n",
+        "00000013  c0                           {1} blob\n",
+        "00000014  09                             size: 9\n",
+        "00000015  00 00 00 00 00 00 00 ..        data: 9 byte(s)\n",
+        "0000001e  04 74 61 67 73             key: \"tags\"\n",
+        "00000023  e0                         [\"tags\"] list\n",
+        "00000024  0a                           size: 10\n",
+        "00000025  02                           count: 2\n",
+        "00000026  a0                           [0] text\n",
+        "00000027  01                             size: 1\n",
+        "00000028  78                             data: \"x\"\n",
+        "00000029  00                             null terminator\n",
+        "0000002a  41                           [1] i16\n",
+        "0000002b  ff fe                          data: -2\n",
+        "0000002d  82                       double\n",
+        "0000002e  3f e0 00 00 00 00 00 00    data: 0.5\n",
+    ));
+    Ok(())
+}
+
+#[test]
+fn invalid_data() {
+    fn last_line(bytes: &[u8]) -> String {
+        String::from(inspect::explain(bytes).lines().last().unwrap().trim())
+    }
+
+    assert_eq!(inspect::explain(&[]), "");
+    assert_eq!(last_line(&[value::U32, 0, 0]), "!! expected 4 byte(s) of data, got: 2");
+    assert_eq!(last_line(&[value::TEXT, 0x80, 0]), "!! truncated size");
+    assert_eq!(last_line(&[value::TEXT, 3, b'a']), "!! string size is too large: 3");
+    assert_eq!(last_line(&[value::TEXT, 1, b'a', b'b']), "00000003  62                         !! expected null terminator, got: 0x62");
+    assert_eq!(last_line(&[value::BLOB, 2, 0]), "!! blob size is too large: 2");
+    assert_eq!(last_line(&[value::LIST, 9, 1, value::NULL]), "!! container size is too large: 9");
+    assert_eq!(last_line(&[value::LIST, 1, 0]), "!! invalid container size: 1");
+    assert_eq!(last_line(&[value::LIST, 4, 2, value::NULL]), "!! item count is declared: 2, but got: 1");
+    assert_eq!(last_line(&[value::LIST, 5, 1, value::U16, 0]), "!! expected 2 byte(s) of data, got: 1");
+    assert_eq!(last_line(&[value::MAP, 5, 1, 0, 0]), "!! truncated map key");
+    assert_eq!(last_line(&[value::OBJECT, 5, 1, 3, b'a']), "!! truncated object key");
+
+    // Unknown types are explained via their storage
+    assert_eq!(last_line(&[0x23, 9]), "00000001  09                         data: 0x09");
+    assert_eq!(last_line(&[0xb0, 0x01, 0, 0]), "00000003  00                         null terminator");
+}
+
+#[test]
+fn deep_nesting() {
+    let mut bytes = vec![value::LIST, 3, 0];
+    for _ in 0..200 {
+        let size = (bytes.len() + 6) as u32 | 0x8000_0000;
+        bytes.splice(0..0, [value::LIST].iter().copied().chain(size.to_be_bytes().iter().copied()).chain(Some(1)));
+    }
+    assert_eq!(inspect::explain(&bytes).lines().last().unwrap().trim(), "!! containers are nested too deeply");
+}
+
+#[test]
+fn offset_map() -> IoResult<()> {
+    let mut object = binn_ir::object();
+    object.object_insert("id", 1_u8)?;
+    object.object_insert("map", binn_ir::Map::from([(-1, Value::from("abc"))]))?;
+    object.object_insert("tags", vec![Value::Null, Value::U32(9)])?;
+    let mut bytes = vec![];
+    object.encode(&mut bytes)?;
+
+    let map = inspect::offset_map(&bytes).unwrap();
+    assert_eq!(
+        map.iter().map(|(path, offset, len, type_byte)| (path.to_string(), *offset, *len, *type_byte)).collect::<Vec<_>>(),
+        [
+            (String::from("$"), 0, bytes.len(), value::OBJECT),
+            (String::from("$[\"id\"]"), 6, 2, value::U8),
+            (String::from("$[\"map\"]"), 12, 13, value::MAP),
+            (String::from("$[\"map\"]{-1}"), 19, 6, value::TEXT),
+            (String::from("$[\"tags\"]"), 30, 9, value::LIST),
+            (String::from("$[\"tags\"][0]"), 33, 1, value::NULL),
+            (String::from("$[\"tags\"][1]"), 34, 5, value::U32),
+        ],
+    );
+
+    // Patch a field in place
+    let (path, offset, len, _) = &map[6];
+    assert_eq!(path, &Path::from(vec![PathSegment::ObjectKey("tags".into()), PathSegment::Index(1)]));
+    bytes[offset + 1..offset + len].copy_from_slice(&10_u32.to_be_bytes());
+    assert_eq!(binn_ir::decode(&mut &bytes[..])?.unwrap().object_by(&["tags"]).unwrap().at(&[1]).unwrap(), &Value::U32(10));
+
+    // Scalars, and empty containers
+    assert_eq!(inspect::offset_map(&[value::NULL]).unwrap(), [(Path::new(), 0, 1, value::NULL)]);
+    assert_eq!(inspect::offset_map(&[value::LIST, 3, 0]).unwrap(), [(Path::new(), 0, 3, value::LIST)]);
+    assert_eq!(inspect::offset_map(&[0xb0, 0x01, 0, 0]).unwrap(), [(Path::new(), 0, 4, 0xb0)]);
+
+    Ok(())
+}
+
+#[test]
+fn offset_map_of_invalid_data() {
+    for (bytes, offset) in &[
+        (&[][..], 0),
+        (&[value::U32, 0, 0], 1),
+        (&[value::TEXT, 3, b'a'], 1),
+        (&[value::BLOB, 0x80, 0], 1),
+        (&[value::LIST, 9, 1, value::NULL], 1),
+        (&[value::LIST, 1, 0], 1),
+        (&[value::LIST, 4, 2, value::NULL], 4),
+        (&[value::MAP, 5, 1, 0, 0], 3),
+        (&[value::OBJECT, 5, 1, 3, b'a'], 3),
+        (&[value::OBJECT, 6, 1, 1, 0xff, value::NULL], 3),
+        (&[value::NULL, value::NULL], 1),
+    ] {
+        assert_eq!(inspect::offset_map(bytes).unwrap_err().context(), Some(*offset as i128), "{:02x?}", bytes);
+    }
+}
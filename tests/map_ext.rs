@@ -0,0 +1,47 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Map extension
+
+extern crate binn_ir;
+
+use binn_ir::{Map, MapExt, Result, Value};
+
+#[test]
+fn map_ext() -> Result<()> {
+    let mut map = Map::new();
+    binn_ir::map_insert(&mut map, -1, u64::MAX);
+    binn_ir::map_insert(&mut map, 0, -9_i8);
+    binn_ir::map_insert(&mut map, 1, "text");
+    binn_ir::map_insert(&mut map, 2, Value::Blob(vec![1, 2]));
+    binn_ir::map_insert(&mut map, 3, binn_ir::list());
+    binn_ir::map_insert(&mut map, 4, binn_ir::map());
+    binn_ir::map_insert(&mut map, 5, binn_ir::object_from("key", Value::Null));
+
+    assert_eq!(map.get_i64(0)?, Some(-9));
+    map.get_i64(-1).unwrap_err();
+    assert_eq!(map.get_text(1)?, Some("text"));
+    assert_eq!(map.get_blob(2)?, Some(&[1, 2][..]));
+    assert_eq!(map.get_list(3)?.map(Vec::len), Some(0));
+    assert_eq!(map.get_map(4)?.map(|map| map.len()), Some(0));
+    assert_eq!(map.get_object(5)?.map(|object| object.len()), Some(1));
+
+    for key in 0..=5 {
+        assert_eq!(map.get_i64(key).is_ok(), key == 0);
+        assert_eq!(map.get_text(key).is_ok(), key == 1);
+        assert_eq!(map.get_blob(key).is_ok(), key == 2);
+        assert_eq!(map.get_list(key).is_ok(), key == 3);
+        assert_eq!(map.get_map(key).is_ok(), key == 4);
+        assert_eq!(map.get_object(key).is_ok(), key == 5);
+    }
+    assert_eq!(map.get_i64(9)?, None);
+    assert_eq!(map.get_text(9)?, None);
+
+    // Items of other types are left in place
+    map.take_object(1).unwrap_err();
+    assert_eq!(map.get_text(1)?, Some("text"));
+    assert_eq!(map.take_object(5)?, Some(binn_ir::object_from("key", Value::Null).as_object()?.clone()));
+    assert_eq!(map.take_object(5)?, None);
+    assert_eq!(map.len(), 6);
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Object extension
+
+extern crate binn_ir;
+
+use binn_ir::{Object, ObjectExt, Result, Value, value::OBJECT_KEY_MAX_LEN};
+
+#[test]
+fn object_ext() -> Result<()> {
+    let mut object = Object::new();
+    assert_eq!(object.insert_checked("text", "some")?, None);
+    assert_eq!(object.insert_checked("text", "text")?, Some(Value::from("some")));
+    object.insert_checked("blob", Value::Blob(vec![1, 2]))?;
+    object.insert_checked("list", binn_ir::list())?;
+    object.insert_checked("map", binn_ir::map_from(0, Value::Null))?;
+    object.insert_checked("object", binn_ir::object())?;
+    object.insert_checked("k".repeat(OBJECT_KEY_MAX_LEN), Value::Null)?;
+    object.insert_checked("k".repeat(OBJECT_KEY_MAX_LEN + 1), Value::Null).unwrap_err();
+    assert_eq!(object.len(), 6);
+
+    assert_eq!(object.get_text("text")?, Some("text"));
+    assert_eq!(object.get_blob("blob")?, Some(&[1, 2][..]));
+    assert_eq!(object.get_list("list")?.map(Vec::len), Some(0));
+    assert_eq!(object.get_map("map")?.map(|map| map.len()), Some(1));
+    assert_eq!(object.get_object("object")?.map(|object| object.len()), Some(0));
+
+    for key in &["text", "blob", "list", "map", "object"] {
+        assert_eq!(object.get_text(key).is_ok(), *key == "text");
+        assert_eq!(object.get_blob(key).is_ok(), *key == "blob");
+        assert_eq!(object.get_list(key).is_ok(), *key == "list");
+        assert_eq!(object.get_map(key).is_ok(), *key == "map");
+        assert_eq!(object.get_object(key).is_ok(), *key == "object");
+    }
+
+    assert_eq!(object.get_text("missing")?, None);
+    assert_eq!(object.get_object("missing")?, None);
+
+    Ok(())
+}
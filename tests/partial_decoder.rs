@@ -0,0 +1,158 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Partial decoder
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use {
+    std::io::{self, ErrorKind, Read},
+
+    binn_ir::{DecodeConfig, IoResult, PartialDecoder, Value},
+};
+
+/// # A non-blocking source, which returns one byte at a time, and would block every other read
+struct NonBlocking<'a> {
+    bytes: &'a [u8],
+    blocked: bool,
+}
+
+impl Read for NonBlocking<'_> {
+
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.blocked = !self.blocked;
+        match self.blocked {
+            true => Err(io::Error::from(ErrorKind::WouldBlock)),
+            false => self.bytes.read(&mut buf[..1]),
+        }
+    }
+
+}
+
+fn values() -> Vec<Value> {
+    vec![
+        Value::Null, Value::U16(9), Value::Text("text".into()), Value::Blob(vec![0; 300]),
+        binn_ir::object_from("list", Value::List(vec![Value::True, binn_ir::map_from(-1, Value::Double(1.5))])),
+    ]
+}
+
+#[test]
+fn feeding() -> IoResult<()> {
+    let values = values();
+    let mut buf = vec![];
+    for value in &values {
+        value.encode(&mut buf)?;
+    }
+
+    let mut decoder = PartialDecoder::new();
+    let mut decoded = vec![];
+    for b in &buf {
+        decoder.feed(&[*b]);
+        while let Some(value) = decoder.decode()? {
+            decoded.push(value);
+        }
+    }
+    assert_eq!(decoded, values);
+    assert!(decoder.buffered().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn reading_from_non_blocking_sources() -> IoResult<()> {
+    let values = values();
+    let mut buf = vec![];
+    for value in &values {
+        value.encode(&mut buf)?;
+    }
+
+    let mut source = NonBlocking { bytes: &buf, blocked: false };
+    let mut decoder = PartialDecoder::new();
+    let mut decoded = vec![];
+    let mut would_block_count = 0;
+    loop {
+        let eof = match decoder.read_from(&mut source)? {
+            Some(_) => true,
+            None => {
+                would_block_count += 1;
+                false
+            },
+        };
+        while let Some(value) = decoder.decode()? {
+            decoded.push(value);
+        }
+        if eof {
+            break;
+        }
+    }
+    assert_eq!(decoded, values);
+    assert!(would_block_count > buf.len());
+
+    Ok(())
+}
+
+#[test]
+fn errors() -> IoResult<()> {
+    let mut buf = vec![];
+    Value::Date("2019-02-29".into()).encode(&mut buf)?;
+    Value::U8(9).encode(&mut buf)?;
+
+    // Invalid values are dropped
+    let mut decoder = PartialDecoder::with_config(DecodeConfig::strict());
+    decoder.feed(&buf);
+    assert_eq!(decoder.decode().unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(decoder.decode()?, Some(Value::U8(9)));
+
+    // Invalid headers are kept
+    let mut decoder = PartialDecoder::new();
+    decoder.feed(&[binn_ir::value::LIST, 2, 0]);
+    assert_eq!(decoder.decode().unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(decoder.buffered().len(), 3);
+
+    // Values exceeding max bytes are rejected by their headers
+    let mut config = DecodeConfig::new();
+    config.max_bytes = Some(9);
+    let mut decoder = PartialDecoder::with_config(config);
+    let mut buf = vec![];
+    Value::U8(1).encode(&mut buf)?;
+    Value::Blob(vec![0; 300]).encode(&mut buf)?;
+    decoder.feed(&buf[..9]);
+    assert_eq!(decoder.decode()?, Some(Value::U8(1)));
+    assert_eq!(decoder.decode().unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(decoder.buffered(), &buf[2..9]);
+
+    Ok(())
+}
+
+#[test]
+fn limiting_buffered_bytes() -> IoResult<()> {
+    let values = vec![Value::U32(9); 1000];
+    let mut buf = vec![];
+    for value in &values {
+        value.encode(&mut buf)?;
+    }
+
+    let mut config = DecodeConfig::new();
+    config.max_bytes = Some(16);
+    let mut decoder = PartialDecoder::with_config(config);
+    let mut source = &buf[..];
+    let mut decoded = vec![];
+    let mut read_count = 0;
+    loop {
+        let eof = decoder.read_from(&mut source)?.is_some();
+        read_count += 1;
+        assert!(decoder.buffered().len() <= 16 + 8 * 1024);
+        while let Some(value) = decoder.decode()? {
+            decoded.push(value);
+        }
+        if eof {
+            break;
+        }
+    }
+    assert_eq!(decoded, values);
+    assert_eq!(read_count, 2);
+    assert!(decoder.buffered().is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Random access
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use {
+    std::io::{Cursor, ErrorKind},
+
+    binn_ir::{Encoder, IoResult, Value},
+};
+
+#[test]
+fn random_access() -> IoResult<()> {
+    let mut map = binn_ir::map_from(-1, Value::Blob(vec![0; 300]));
+    map.map_insert(9, "nine")?;
+    let mut object = binn_ir::object_from("big", Value::Text("x".repeat(200)));
+    object.object_insert("map", map)?;
+    object.object_insert("small", 1_u8)?;
+
+    // Leading data
+    let mut buf = vec![binn_ir::value::NULL];
+    buf.encode(&object)?;
+    let mut file = Cursor::new(buf);
+
+    assert_eq!(binn_ir::decode_at(&mut file, 1)?.as_ref(), Some(&object));
+    assert_eq!(binn_ir::decode_at(&mut file, 0)?, Some(Value::Null));
+    assert_eq!(binn_ir::decode_at(&mut file, 1_000_000)?, None);
+
+    let map = binn_ir::seek_to_object_key(&mut file, 1, "map")?.unwrap();
+    assert_eq!(binn_ir::seek_to_object_key(&mut file, 1, "none")?, None);
+    let nine = binn_ir::seek_to_map_key(&mut file, map, 9)?.unwrap();
+    assert_eq!(binn_ir::decode_at(&mut file, nine)?, Some(Value::from("nine")));
+    assert_eq!(binn_ir::seek_to_map_key(&mut file, map, 0)?, None);
+
+    // Entries of maps and objects, by index
+    let small = binn_ir::seek_to_item(&mut file, 1, 2)?.unwrap();
+    assert_eq!(binn_ir::decode(&mut file)?, Some(Value::U8(1)));
+    assert_eq!(binn_ir::decode_at(&mut file, small)?, Some(Value::U8(1)));
+    assert_eq!(binn_ir::seek_to_item(&mut file, map, 1)?, Some(nine));
+    assert_eq!(binn_ir::seek_to_item(&mut file, map, 2)?, None);
+
+    // Not containers, or wrong types
+    assert_eq!(binn_ir::seek_to_item(&mut file, 0, 0).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(binn_ir::seek_to_map_key(&mut file, 1, 0).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(binn_ir::seek_to_object_key(&mut file, map, "").unwrap_err().kind(), ErrorKind::InvalidData);
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Parallel computation with `rayon`
+
+#![cfg(feature="rayon")]
+
+extern crate binn_ir;
+
+use binn_ir::{Map, Result, Value};
+
+#[test]
+fn size_parallel() -> Result<()> {
+    let mut object = binn_ir::object();
+    object.object_insert("blob", Value::Blob(vec![0; 200]))?;
+    object.object_insert("text", "some text")?;
+    object.object_insert("empty", binn_ir::list())?;
+
+    let map = (0..500).map(|i| (i, Value::from(vec![Value::I32(i); i as usize % 7]))).collect::<Map>();
+    let value = Value::List(vec![object.clone(), Value::from(map), Value::Null, Value::List(vec![object; 300])]);
+
+    for value in &[Value::Null, Value::Double(1.0), Value::from("text"), binn_ir::map(), value] {
+        assert_eq!(value.size_parallel()?, value.size()?);
+    }
+
+    // Invalid key
+    let mut object = binn_ir::object();
+    object.object_insert("k".repeat(256), Value::Null)?;
+    assert!(Value::List(vec![object]).size_parallel().is_err());
+
+    Ok(())
+}
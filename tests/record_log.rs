@@ -0,0 +1,149 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Record log
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use {
+    std::{
+        env,
+        fs::{self, File},
+        io::ErrorKind,
+    },
+
+    binn_ir::{FRAME_HEADER_SIZE, FlushPolicy, IoResult, LogReader, LogWriter, Value},
+};
+
+/// # Makes a log of some records
+///
+/// Result: the log, and offsets of its records.
+fn log(values: &[Value]) -> IoResult<(Vec<u8>, Vec<u64>)> {
+    let mut log = vec![];
+    let mut offsets = vec![];
+    for value in values {
+        offsets.push(log.len() as u64);
+        binn_ir::write_framed(&mut log, value)?;
+    }
+    Ok((log, offsets))
+}
+
+#[test]
+fn log_reader() -> IoResult<()> {
+    let values = vec![Value::Null, Value::Blob(vec![1; 100_000]), binn_ir::object_from("id", 1)];
+    let (log, offsets) = log(&values)?;
+
+    let mut reader = LogReader::new(&log[..]);
+    assert_eq!(reader.by_ref().collect::<IoResult<Vec<_>>>()?, offsets.iter().copied().zip(values).collect::<Vec<_>>());
+    assert_eq!(reader.offset(), log.len() as u64);
+    assert_eq!(reader.skipped_bytes(), 0);
+    assert!(reader.next_record()?.is_none());
+
+    assert!(LogReader::new(&[][..]).next_record()?.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn corrupt_records() -> IoResult<()> {
+    let values = (0..5_u8).map(|i| Value::List(vec![Value::U8(i); usize::from(i)])).collect::<Vec<_>>();
+    let (mut log, offsets) = log(&values)?;
+
+    // Corrupt length of record 1, and payload of record 3
+    log[offsets[1] as usize] = 0x7f;
+    log[offsets[3] as usize + FRAME_HEADER_SIZE + 1] ^= 1;
+    let len = log.len() as u64;
+    // Garbage at the start, and a truncated record at the end
+    let log = [&[0xff_u8; 3][..], &log, &log[offsets[4] as usize..log.len() - 1]].concat();
+
+    let mut reader = LogReader::new(&log[..]);
+    assert_eq!(reader.next().unwrap().unwrap_err().kind(), ErrorKind::InvalidData);
+    assert!(reader.next().is_none());
+
+    let mut reader = LogReader::with_recovery(&log[..]);
+    let records = reader.by_ref().collect::<IoResult<Vec<_>>>()?;
+    assert_eq!(records, vec![(3 + offsets[0], values[0].clone()), (3 + offsets[2], values[2].clone()), (3 + offsets[4], values[4].clone())]);
+    let kept = (offsets[1] - offsets[0]) + (offsets[3] - offsets[2]) + (len - offsets[4]);
+    assert_eq!(reader.skipped_bytes(), log.len() as u64 - kept);
+    assert_eq!(reader.offset(), log.len() as u64);
+
+    // Truncated records are errors, without recovery
+    let mut reader = LogReader::new(&log[3 + offsets[4] as usize..]);
+    reader.next_record()?.unwrap();
+    assert_eq!(reader.next_record().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    Ok(())
+}
+
+#[test]
+fn recovery_from_garbage() -> IoResult<()> {
+    let values = vec![Value::Blob(vec![1; 8 * 1024 * 1024]), Value::from("last")];
+    let (log, offsets) = log(&values)?;
+
+    // Pseudo-random garbage, whose lengths are mostly large
+    let mut state = 0x2545_f491_u32;
+    let garbage = (0..4000).map(|_| {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state as u8
+    }).collect::<Vec<_>>();
+    let log = [&garbage[..], &log].concat();
+
+    let mut reader = LogReader::with_recovery(&log[..]);
+    let records = reader.by_ref().map(|record| record.map(|(offset, _)| offset)).collect::<IoResult<Vec<_>>>()?;
+    assert_eq!(records, offsets.iter().map(|offset| garbage.len() as u64 + offset).collect::<Vec<_>>());
+    assert_eq!(reader.skipped_bytes(), garbage.len() as u64);
+
+    // Max record size
+    let values = vec![Value::Blob(vec![1; 4096]), Value::from("last")];
+    let (log, offsets) = self::log(&values)?;
+    let log = &log[..];
+    let mut reader = LogReader::with_recovery(log);
+    assert_eq!(reader.max_record_size(), binn_ir::value::MAX_DATA_SIZE);
+    reader.set_max_record_size(1024);
+    assert_eq!(reader.next_record()?, Some((offsets[1], values[1].clone())));
+    assert_eq!(reader.skipped_bytes(), offsets[1]);
+
+    let mut reader = LogReader::new(log);
+    reader.set_max_record_size(1024);
+    assert_eq!(reader.next_record().unwrap_err().kind(), ErrorKind::InvalidData);
+
+    Ok(())
+}
+
+#[test]
+fn log_writer() -> IoResult<()> {
+    let path = env::temp_dir().join(format!("{}-log-writer-{}", binn_ir::CODE_NAME, std::process::id()));
+    let values = (0..100_u32).map(|i| binn_ir::object_from("id", i)).collect::<Vec<_>>();
+
+    let mut offsets = vec![];
+    for (i, policy) in [FlushPolicy::Buffered, FlushPolicy::Flush, FlushPolicy::Sync].iter().enumerate() {
+        let mut writer = LogWriter::open(&path, *policy)?;
+        assert_eq!(writer.policy(), *policy);
+        for value in &values[i * 10..(i + 1) * 10] {
+            offsets.push(writer.append(value)?);
+        }
+        if *policy == FlushPolicy::Buffered {
+            assert_eq!(fs::metadata(&path)?.len(), 0);
+            writer.flush()?;
+        }
+        assert_eq!(fs::metadata(&path)?.len(), writer.offset());
+    }
+
+    // Buffered records are written when writers are dropped
+    let mut writer = LogWriter::open(&path, FlushPolicy::Buffered)?;
+    for value in &values[30..] {
+        offsets.push(writer.append(value)?);
+    }
+    let len = writer.offset();
+    drop(writer);
+    assert_eq!(fs::metadata(&path)?.len(), len);
+
+    let records = LogReader::new(File::open(&path)?).collect::<IoResult<Vec<_>>>()?;
+    assert_eq!(records, offsets.into_iter().zip(values).collect::<Vec<_>>());
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
@@ -0,0 +1,98 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Binn-RPC
+
+extern crate binn_ir;
+
+use {
+    core::convert::TryFrom,
+
+    binn_ir::{Result, Value, rpc::{Fault, Request, Response}},
+};
+
+#[test]
+fn requests() -> Result<()> {
+    let request = Request::new(u64::MAX, "sum", vec![Value::U8(1)]);
+    let value = Value::from(request.clone());
+    assert_eq!(value.object_by(&["id"])?, &Value::U64(u64::MAX));
+    assert_eq!(value.object_by(&["method"])?.as_text()?, "sum");
+    assert_eq!(Request::try_from(value)?, request);
+
+    // IDs of any width
+    let mut value = binn_ir::object_from("id", 9_u8);
+    value.object_insert("method", "ping")?;
+    value.object_insert("params", Value::Null)?;
+    assert_eq!(Request::try_from(value.clone())?, Request::new(9, "ping", Value::Null));
+
+    value.object_insert("id", -1)?;
+    Request::try_from(value.clone()).unwrap_err();
+    value.object_insert("id", 0_u8)?;
+    value.object_insert("method", 0_u8)?;
+    Request::try_from(value).unwrap_err();
+    Request::try_from(binn_ir::list()).unwrap_err();
+
+    Ok(())
+}
+
+#[test]
+fn responses() -> Result<()> {
+    let ok = Response::ok(1, "done");
+    assert_eq!(Response::try_from(Value::from(ok.clone()))?, ok);
+
+    let mut fault = Fault::new(-32601, "method not found");
+    assert_eq!(Response::try_from(Value::from(Response::fault(2, fault.clone())))?, Response::fault(2, fault.clone()));
+    fault.data = Some(Value::from("sum"));
+    let value = Value::from(Response::fault(3, fault.clone()));
+    assert_eq!(value.object_by(&["error", "code"])?, &Value::I32(-32601));
+    assert_eq!(Response::try_from(value)?.result, Err(fault));
+
+    // Both, or none
+    let mut value = Value::from(ok);
+    value.object_insert("error", Fault::new(0, ""))?;
+    Response::try_from(value.clone()).unwrap_err();
+    value.object_take_by(&["result"])?;
+    value.object_take_by(&["error"])?;
+    Response::try_from(value).unwrap_err();
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn encode_and_decode() -> binn_ir::IoResult<()> {
+    use std::io::ErrorKind;
+
+    let mut socket = vec![];
+    let size = binn_ir::rpc::encode_request(&mut socket, &Request::new(1, "a", Value::Null))? as usize;
+    binn_ir::rpc::encode_response(&mut socket, &Response::ok(1, Value::Null))?;
+
+    let mut source = &socket[..];
+    assert_eq!(binn_ir::rpc::decode_request(&mut source)?, Some(Request::new(1, "a", Value::Null)));
+    assert_eq!(binn_ir::rpc::decode_response(&mut source)?, Some(Response::ok(1, Value::Null)));
+    assert_eq!(binn_ir::rpc::decode_response(&mut source)?, None);
+
+    // A response is not a request
+    assert_eq!(binn_ir::rpc::decode_request(&mut &socket[size..]).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // Same encoding as values
+    let mut fault = Fault::new(-1, "fault");
+    let mut messages = vec![
+        Value::from(Request::new(u64::MAX, "sum", vec![Value::U8(1); 200])), Value::from(Response::ok(2, "x".repeat(200))),
+        Value::from(Response::fault(3, fault.clone())),
+    ];
+    fault.data = Some(binn_ir::object_from("data", Value::Null));
+    messages.push(Value::from(Response::fault(4, fault)));
+    for message in messages {
+        let mut buf = vec![];
+        let size = match message.object_by(&["method"]) {
+            Ok(_) => binn_ir::rpc::encode_request(&mut buf, &Request::try_from(message.clone())?)?,
+            Err(_) => binn_ir::rpc::encode_response(&mut buf, &Response::try_from(message.clone())?)?,
+        };
+        assert_eq!(size as usize, buf.len());
+        let mut expected = vec![];
+        message.encode(&mut expected)?;
+        assert_eq!(buf, expected);
+    }
+
+    Ok(())
+}
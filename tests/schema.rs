@@ -0,0 +1,77 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Schema
+
+extern crate binn_ir;
+
+use binn_ir::{Field, Result, Schema, SchemaFields, Value};
+
+/// # Makes a schema of server configs
+fn server_schema() -> Schema {
+    let mut tls = SchemaFields::new();
+    tls.insert("enabled".into(), Field::with_default(Schema::Any, false));
+    tls.insert("cert".into(), Field::new(Schema::Any));
+
+    let mut route = SchemaFields::new();
+    route.insert("path".into(), Field::new(Schema::Any));
+    route.insert("methods".into(), Field::with_default(Schema::List(Box::new(Schema::Any)), vec![Value::from("GET")]));
+
+    let mut fields = SchemaFields::new();
+    fields.insert("port".into(), Field::with_default(Schema::Any, 8080_u16));
+    fields.insert("tls".into(), Field::with_default(Schema::Object(tls), binn_ir::object()));
+    fields.insert("routes".into(), Field::new(Schema::List(Box::new(Schema::Object(route)))));
+    fields.insert("limits".into(), Field::new(Schema::Map(Box::new(Schema::Any))));
+    Schema::Object(fields)
+}
+
+#[test]
+fn apply_defaults() -> Result<()> {
+    let schema = server_schema();
+
+    let mut value = binn_ir::object();
+    schema.apply_defaults(&mut value)?;
+    assert_eq!(value.object_by(&["port"])?, &Value::U16(8080));
+    // Defaults are also filled inside default values
+    assert_eq!(value.object_by(&["tls", "enabled"])?, &Value::False);
+    assert!(value.object_maybe_by(&["tls", "cert"])?.is_none());
+    assert!(value.object_maybe_by(&["routes"])?.is_none());
+
+    let mut value = binn_ir::object_from("port", 80_u16);
+    value.object_insert("routes", vec![binn_ir::object_from("path", "/"), binn_ir::object_from("methods", binn_ir::list())])?;
+    schema.apply_defaults(&mut value)?;
+    assert_eq!(value.object_by(&["port"])?, &Value::U16(80));
+    let routes = value.object_by(&["routes"])?.as_list()?;
+    assert_eq!(routes[0].object_by(&["methods"])?, &Value::List(vec![Value::from("GET")]));
+    assert_eq!(routes[1].object_by(&["methods"])?, &binn_ir::list());
+
+    // Wrong types
+    let err = schema.apply_defaults(&mut binn_ir::object_from("routes", vec![Value::Null])).unwrap_err();
+    assert!(err.msg().unwrap().contains("is not an object"));
+    schema.apply_defaults(&mut binn_ir::object_from("limits", binn_ir::list())).unwrap_err();
+    schema.apply_defaults(&mut Value::Null).unwrap_err();
+    Schema::Any.apply_defaults(&mut Value::Null)?;
+
+    Ok(())
+}
+
+#[test]
+fn prune_unknown() -> Result<()> {
+    let schema = server_schema();
+
+    let mut value = binn_ir::object_from("port", 80_u16);
+    value.object_insert("debug", true)?;
+    value.object_insert("tls", binn_ir::object_from("key", "secret"))?;
+    value.object_insert("routes", vec![binn_ir::object_from("path", "/"), binn_ir::object_from("handler", "index")])?;
+    value.object_insert("limits", binn_ir::map_from(0, binn_ir::object_from("any", "thing")))?;
+    schema.prune_unknown(&mut value)?;
+
+    let mut expected = binn_ir::object_from("port", 80_u16);
+    expected.object_insert("tls", binn_ir::object())?;
+    expected.object_insert("routes", vec![binn_ir::object_from("path", "/"), binn_ir::object()])?;
+    expected.object_insert("limits", binn_ir::map_from(0, binn_ir::object_from("any", "thing")))?;
+    assert_eq!(value, expected);
+
+    schema.prune_unknown(&mut binn_ir::object_from("tls", "none")).unwrap_err();
+
+    Ok(())
+}
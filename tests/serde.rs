@@ -0,0 +1,234 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Serde support
+
+#![cfg(feature="serde")]
+
+extern crate binn_ir;
+
+use {
+    std::collections::BTreeMap,
+
+    serde::{Deserialize, Serialize},
+
+    binn_ir::{IoResult, Value},
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Unit;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Meters(f32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Point,
+    Circle(Meters),
+    Line(i8, i8),
+    Rect { width: u32, height: u32 },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Everything {
+    flag: bool,
+    small: i8,
+    big: u64,
+    ratio: f64,
+    letter: char,
+    name: String,
+    nickname: Option<String>,
+    missing: Option<u8>,
+    unit: Unit,
+    pair: (u16, String),
+    shapes: Vec<Shape>,
+    by_name: BTreeMap<String, i64>,
+    by_id: BTreeMap<i32, bool>,
+    data: Vec<u8>,
+}
+
+/// # Encodes a value via serde
+fn to_vec<T>(value: &T) -> IoResult<Vec<u8>> where T: Serialize {
+    let mut buf = vec![];
+    let size = binn_ir::to_writer(&mut buf, value)?;
+    assert_eq!(size as usize, buf.len());
+    Ok(buf)
+}
+
+#[test]
+fn round_trip() -> IoResult<()> {
+    let everything = Everything {
+        flag: true,
+        small: -1,
+        big: u64::MAX,
+        ratio: 0.25,
+        letter: 'ư',
+        name: "moss".into(),
+        nickname: Some("m".into()),
+        missing: None,
+        unit: Unit,
+        pair: (9, "nine".into()),
+        shapes: vec![
+            Shape::Point, Shape::Circle(Meters(1.5)), Shape::Line(-1, 1), Shape::Rect { width: 2, height: 3 },
+        ],
+        by_name: vec![("a".to_string(), -1), ("b".to_string(), i64::MAX)].into_iter().collect(),
+        by_id: vec![(-1, true), (1, false)].into_iter().collect(),
+        data: vec![0, 1, 255],
+    };
+
+    let buf = to_vec(&everything)?;
+    assert_eq!(binn_ir::from_reader::<Everything>(&mut &buf[..])?, everything);
+
+    // Encoded data is also a valid value
+    let value = binn_ir::decode(&mut &buf[..])?.unwrap();
+    assert_eq!(value.size()? as usize, buf.len());
+    assert_eq!(value["big"], Value::U64(u64::MAX));
+    assert_eq!(value["nickname"], Value::from("m"));
+    assert_eq!(value["missing"], Value::Null);
+    assert_eq!(value["unit"], Value::Null);
+    assert_eq!(value["pair"], Value::from(vec![Value::U16(9), Value::from("nine")]));
+    assert_eq!(value["shapes"][0_usize], Value::from("Point"));
+    assert_eq!(value["shapes"][1_usize], binn_ir::object_from("Circle", Value::Float(1.5)));
+    assert_eq!(value["shapes"][2_usize]["Line"], Value::from(vec![Value::I8(-1), Value::I8(1)]));
+    assert_eq!(value["shapes"][3_usize]["Rect"]["height"], Value::U32(3));
+    assert_eq!(value["by_id"][-1_i32], Value::True);
+    assert!(matches!(value["by_name"], Value::Object(_)));
+
+    // Large and deeply nested data, whose headers take 4 bytes
+    let nested = (0..9).fold(vec![vec!["x".repeat(200)]], |inner, _| vec![inner.concat(); 2]);
+    let buf = to_vec(&nested)?;
+    let mut expected = vec![];
+    binn_ir::decode(&mut &buf[..])?.unwrap().encode(&mut expected)?;
+    assert_eq!(buf, expected);
+    assert_eq!(binn_ir::from_reader::<Vec<Vec<String>>>(&mut &buf[..])?, nested);
+
+    Ok(())
+}
+
+#[test]
+fn values_into_structs() -> IoResult<()> {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Item {
+        id: u64,
+        score: f64,
+        when: String,
+        data: Vec<u8>,
+    }
+
+    let mut value = binn_ir::object_from("id", Value::I8(7));
+    value.object_insert("score", Value::U8(2))?;
+    value.object_insert("when", Value::Date("2021-03-14".into()))?;
+    value.object_insert("data", Value::Blob(vec![1, 2]))?;
+    let mut buf = vec![];
+    value.encode(&mut buf)?;
+
+    assert_eq!(binn_ir::from_reader::<Item>(&mut &buf[..])?, Item { id: 7, score: 2.0, when: "2021-03-14".into(), data: vec![1, 2] });
+
+    Ok(())
+}
+
+#[test]
+fn unknown_fields_are_skipped() -> IoResult<()> {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Id {
+        id: u8,
+    }
+
+    let mut value = binn_ir::object_from("id", 1_u8);
+    value.object_insert("list", vec![binn_ir::object_from("blob", vec![0_u8; 1024]), Value::from("junk")])?;
+    value.object_insert("text", "\u{1}")?;
+    let mut buf = vec![];
+    value.encode(&mut buf)?;
+    // Make the text invalid UTF-8, which is not verified when skipped
+    let len = buf.len();
+    buf[len - 2] = 0xff;
+    binn_ir::decode(&mut &buf[..]).unwrap_err();
+
+    buf.extend_from_slice(&to_vec(&Some(2_u8))?);
+    let mut source = &buf[..];
+    assert_eq!(binn_ir::from_reader::<Id>(&mut source)?, Id { id: 1 });
+    assert_eq!(binn_ir::from_reader::<Option<u8>>(&mut source)?, Some(2));
+    assert!(source.is_empty());
+
+    // Skipping inside options
+    assert!(binn_ir::from_reader::<Option<serde::de::IgnoredAny>>(&mut &to_vec(&vec!["a"; 9])?[..])?.is_some());
+
+    Ok(())
+}
+
+#[test]
+fn errors() -> IoResult<()> {
+    let mut map = BTreeMap::new();
+    map.insert("k".repeat(256), 0);
+    binn_ir::to_writer(&mut vec![], &map).unwrap_err();
+    let mut map = BTreeMap::new();
+    map.insert(i64::MAX, 0);
+    binn_ir::to_writer(&mut vec![], &map).unwrap_err();
+
+    // No data
+    binn_ir::from_reader::<u8>(&mut &[][..]).unwrap_err();
+    // Out of range
+    binn_ir::from_reader::<u8>(&mut &to_vec(&-1_i8)?[..]).unwrap_err();
+    // Too many items
+    binn_ir::from_reader::<(u8, u8)>(&mut &to_vec(&(1, 2, 3))?[..]).unwrap_err();
+    // Missing fields
+    let mut buf = vec![];
+    binn_ir::object_from("Rect", binn_ir::object()).encode(&mut buf)?;
+    binn_ir::from_reader::<Shape>(&mut &buf[..]).unwrap_err();
+
+    // Wrong declared sizes: containers, then enums
+    for value in &[binn_ir::object_from("id", 1), binn_ir::object_from("Circle", 1.5_f32)] {
+        let mut buf = vec![];
+        value.encode(&mut buf)?;
+        for size in &[buf[1] - 1, buf[1] + 1] {
+            let mut buf = buf.clone();
+            buf[1] = *size;
+            buf.push(0);
+            assert!(binn_ir::decode(&mut &buf[..]).is_err());
+            let err = match value.object_by(&["id"]) {
+                Ok(_) => binn_ir::from_reader::<BTreeMap<String, u8>>(&mut &buf[..]).unwrap_err(),
+                Err(_) => binn_ir::from_reader::<Shape>(&mut &buf[..]).unwrap_err(),
+            };
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        }
+    }
+    // Declared size is smaller than header
+    binn_ir::from_reader::<Vec<u8>>(&mut &[binn_ir::value::LIST, 2, 0][..]).unwrap_err();
+
+    // Values which are serialized differently each time
+    struct Unstable(std::cell::Cell<usize>);
+    impl Serialize for Unstable {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+            self.0.set(self.0.get() + 1);
+            vec![0_u8; self.0.get()].serialize(serializer)
+        }
+    }
+    let err = binn_ir::to_writer(&mut vec![], &Unstable(Default::default())).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    Ok(())
+}
+
+#[test]
+fn nested_enums() -> IoResult<()> {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Nested {
+        A(Box<Nested>),
+        B,
+    }
+
+    fn nested(depth: usize) -> Nested {
+        (0..depth).fold(Nested::B, |inner, _| Nested::A(Box::new(inner)))
+    }
+
+    let value = nested(binn_ir::value::DEFAULT_MAX_DEPTH);
+    assert_eq!(binn_ir::from_reader::<Nested>(&mut &to_vec(&value)?[..])?, value);
+
+    let err = binn_ir::from_reader::<Nested>(&mut &to_vec(&nested(binn_ir::value::DEFAULT_MAX_DEPTH + 1))?[..]).unwrap_err();
+    assert!(binn_ir::DepthLimitExceeded::find(&err).is_some());
+
+    // Too deep to be decoded recursively: each level is an object of 1 item, `{"A": ...}`
+    let buf = [binn_ir::value::OBJECT, 5, 1, 1, b'A'].repeat(1_000_000);
+    binn_ir::from_reader::<Nested>(&mut &buf[..]).unwrap_err();
+
+    Ok(())
+}
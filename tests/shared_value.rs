@@ -0,0 +1,41 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Shared value
+
+extern crate binn_ir;
+
+use binn_ir::{Result, SharedValue, Value};
+
+#[test]
+fn shared_values() -> Result<()> {
+    let value = Value::List(vec![Value::Blob(vec![0; 1000]); 100]);
+    let shared = SharedValue::from(value.clone());
+    assert_eq!(*shared, value);
+    assert_eq!(format!("{:?}", shared), format!("{:?}", value));
+
+    // Clones share the same value
+    let clone = shared.clone();
+    assert!(SharedValue::ptr_eq(&shared, &clone));
+    assert_eq!(clone.size()?, value.size()?);
+
+    // Copy on write
+    let mut copy = shared.clone();
+    copy.make_mut().push(Value::Null)?;
+    assert!(!SharedValue::ptr_eq(&shared, &copy));
+    assert_eq!(*shared, value);
+    assert_eq!(copy.as_list()?.len(), 101);
+
+    // Unique instances are not copied
+    let ptr = copy.as_list()?.as_ptr();
+    copy.make_mut().push(Value::Null)?;
+    let copy = copy.into_value();
+    assert_eq!(copy.as_list()?.as_ptr(), ptr);
+
+    // Shared instances are cloned
+    drop(clone);
+    let other = shared.clone();
+    assert_eq!(Value::from(shared), value);
+    assert_eq!(other.into_value(), value);
+
+    Ok(())
+}
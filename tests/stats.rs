@@ -0,0 +1,71 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Stats
+
+extern crate binn_ir;
+
+use binn_ir::{Path, PathSegment, Result, Value, value};
+
+#[test]
+fn stats() -> Result<()> {
+    let stats = Value::Null.stats();
+    assert_eq!(stats.total_count(), 1);
+    assert_eq!(stats.count(value::NULL), 1);
+    assert_eq!(stats.max_depth(), 0);
+    assert!(stats.largest_container().is_none());
+
+    let mut value = binn_ir::object_from("a", Value::List(vec![Value::Date("2020-01-01".into()), binn_ir::list()]));
+    value.object_insert("b", binn_ir::map_from(1, Value::List(vec![Value::U8(0), Value::U8(1), Value::U8(2), Value::Blob(vec![0; 3])])))?;
+    value.object_insert("c", Value::List(vec![Value::Null; 4]))?;
+
+    let stats = value.stats();
+    assert_eq!(stats.count(value::LIST), 4);
+    assert_eq!(stats.count(value::U8), 3);
+    assert_eq!(stats.count(value::TEXT), 0);
+    assert_eq!(stats.counts().len(), 7);
+    assert_eq!(stats.total_count(), 15);
+    assert_eq!(stats.max_depth(), 3);
+    assert_eq!(stats.text_bytes(), 10);
+    assert_eq!(stats.blob_bytes(), 3);
+
+    // First one wins
+    let path = Path::from(vec![PathSegment::ObjectKey("b".into()), PathSegment::MapKey(1)]);
+    assert_eq!(stats.largest_container(), Some((&path, 4)));
+
+    Ok(())
+}
+
+#[test]
+fn deep_len_and_max_depth() -> Result<()> {
+    for value in [Value::Null, binn_ir::list(), Value::List(vec![binn_ir::map(), binn_ir::object_from("", vec![Value::Null])])] {
+        let stats = value.stats();
+        assert_eq!(value.deep_len(), stats.total_count());
+        assert_eq!(value.max_depth(), stats.max_depth());
+    }
+
+    let value = Value::List(vec![Value::Null, binn_ir::map_from(0, binn_ir::object_from("", vec![Value::Null]))]);
+    assert_eq!(value.deep_len(), 6);
+    assert_eq!(value.max_depth(), 4);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn check_limits() -> binn_ir::IoResult<()> {
+    use binn_ir::DecodeConfig;
+
+    let value = Value::List(vec![Value::Blob(vec![0; 100]), binn_ir::list()]);
+    let mut buf = vec![];
+    value.encode(&mut buf)?;
+
+    for (max_values, max_bytes, max_depth) in [(Some(3), None, 2), (None, Some(buf.len() as u64), 2), (Some(2), None, 2), (None, Some(100), 2), (None, None, 1)] {
+        let mut config = DecodeConfig::new();
+        config.max_values = max_values;
+        config.max_bytes = max_bytes;
+        config.max_depth = max_depth;
+        assert_eq!(config.check_limits(&value).is_ok(), binn_ir::decode_with_config(&mut &buf[..], &config).is_ok());
+    }
+
+    Ok(())
+}
@@ -15,3 +15,38 @@ fn constants() {
     assert_eq!(storage::BLOB,       0b_110);
     assert_eq!(storage::CONTAINER,  0b_111);
 }
+
+#[test]
+fn of_type_byte() {
+    use binn_ir::{storage::Storage, value};
+
+    for (type_byte, storage) in &[
+        (value::NULL, Storage::NoBytes), (value::FALSE, Storage::NoBytes),
+        (value::U8, Storage::Byte), (value::I16, Storage::Word), (value::FLOAT, Storage::DWord), (value::DOUBLE, Storage::QWord),
+        (value::DECIMAL_STR, Storage::String), (value::BLOB, Storage::Blob), (value::OBJECT, Storage::Container),
+        // User defined types
+        (0x85, Storage::QWord), (0xA9, Storage::String), (0xB0, Storage::String),
+    ] {
+        assert_eq!(Storage::of_type_byte(*type_byte), *storage);
+        assert_eq!(type_byte >> 5, storage.bits());
+    }
+}
+
+#[test]
+fn of_values() {
+    use binn_ir::{storage::Storage, Value};
+
+    assert_eq!(Value::Null.storage(), Storage::NoBytes);
+    assert_eq!(Value::I8(0).storage(), Storage::Byte);
+    assert_eq!(Value::U16(0).storage(), Storage::Word);
+    assert_eq!(Value::Float(0.0).storage(), Storage::DWord);
+    assert_eq!(Value::I64(0).storage(), Storage::QWord);
+    assert_eq!(Value::Date(String::new()).storage(), Storage::String);
+    assert_eq!(binn_ir::blob().storage(), Storage::Blob);
+    assert_eq!(binn_ir::map().storage(), Storage::Container);
+
+    for value in &[Value::Null, Value::U8(0), Value::I16(0), Value::U32(0), Value::Double(0.0)] {
+        assert_eq!(value.storage().fixed_data_size().map(|size| u32::from(size) + 1), value.size().ok());
+    }
+    assert_eq!(Value::Text(String::new()).storage().fixed_data_size(), None);
+}
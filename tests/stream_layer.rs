@@ -0,0 +1,172 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Stream layers
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use {
+    std::{
+        cell::Cell,
+        io::{self, ErrorKind, Read, Write},
+        rc::Rc,
+    },
+
+    binn_ir::{CrcLayer, Decoder, Encoder, Finish, IoResult, StreamLayer, Value},
+};
+
+/// # Values to test
+fn values() -> Vec<Value> {
+    vec![Value::Null, Value::from("text ".repeat(500)), Value::Blob(vec![7; 3000]), Value::List(vec![Value::U8(1); 200])]
+}
+
+/// # Encodes values via an encoder, then finishes it
+fn encode_all<E>(mut encoder: E) -> IoResult<E::Inner> where E: Encoder + Finish {
+    for value in values() {
+        encoder.encode(&value)?;
+    }
+    encoder.finish()
+}
+
+/// # Decodes all values via a decoder, then finishes it
+fn decode_all<D>(mut decoder: D) -> IoResult<Vec<Value>> where D: Decoder + Finish {
+    let mut result = vec![];
+    while let Some(value) = decoder.decode()? {
+        result.push(value);
+    }
+    decoder.finish()?;
+    Ok(result)
+}
+
+#[test]
+fn crc_layer() -> IoResult<()> {
+    let mut buf = vec![];
+    encode_all((&mut buf).layer(CrcLayer::new())?)?;
+    assert_eq!(decode_all(buf.as_slice().layer(CrcLayer::new())?)?, values());
+
+    // Corrupted data
+    for i in (0..buf.len()).step_by(97).chain(Some(buf.len() - 1)) {
+        let mut buf = buf.clone();
+        buf[i] ^= 0x01;
+        assert!(decode_all(buf.as_slice().layer(CrcLayer::new())?).is_err(), "{}", i);
+    }
+
+    // Missing checksum
+    let err = buf[..2].layer(CrcLayer::new())?.finish().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+    // Empty stream
+    let mut buf = vec![];
+    (&mut buf).layer(CrcLayer::new())?.finish()?;
+    assert_eq!(buf, [0, 0, 0, 0]);
+    assert!(decode_all(buf.as_slice().layer(CrcLayer::new())?)?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="flate2")]
+fn deflate_layer() -> IoResult<()> {
+    use binn_ir::DeflateLayer;
+
+    let mut buf = vec![];
+    encode_all((&mut buf).layer(CrcLayer::new())?.layer(DeflateLayer::default())?)?.finish()?;
+    assert!(buf.len() < 1000);
+    assert_eq!(decode_all(buf.as_slice().layer(CrcLayer::new())?.layer(DeflateLayer::default())?)?, values());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="zstd")]
+fn zstd_layer() -> IoResult<()> {
+    use binn_ir::ZstdLayer;
+
+    let mut buf = vec![];
+    encode_all((&mut buf).layer(CrcLayer::new())?.layer(ZstdLayer::default())?)?.finish()?;
+    assert!(buf.len() < 1000);
+    assert_eq!(decode_all(buf.as_slice().layer(CrcLayer::new())?.layer(ZstdLayer::new(19))?)?, values());
+
+    Ok(())
+}
+
+/// # A layer which counts bytes
+#[derive(Default)]
+struct MetricsLayer {
+    count: Rc<Cell<usize>>,
+}
+
+struct Counter<S> {
+    inner: S,
+    count: Rc<Cell<usize>>,
+}
+
+impl<S> Finish for Counter<S> {
+
+    type Inner = S;
+
+    fn finish(self) -> IoResult<S> {
+        Ok(self.inner)
+    }
+
+}
+
+impl<W> Write for Counter<W> where W: Write {
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count.set(self.count.get() + written);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+}
+
+impl<R> Read for Counter<R> where R: Read {
+
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count.set(self.count.get() + read);
+        Ok(read)
+    }
+
+}
+
+impl StreamLayer for MetricsLayer {
+
+    type Writer<W> = Counter<W> where W: Write;
+    type Reader<R> = Counter<R> where R: Read;
+
+    fn wrap_writer<W>(self, inner: W) -> IoResult<Self::Writer<W>> where W: Write {
+        Ok(Counter { inner, count: self.count })
+    }
+
+    fn wrap_reader<R>(self, inner: R) -> IoResult<Self::Reader<R>> where R: Read {
+        Ok(Counter { inner, count: self.count })
+    }
+
+}
+
+#[test]
+fn user_layer() -> IoResult<()> {
+    let (outer, inner) = (MetricsLayer::default(), MetricsLayer::default());
+    let (outer_count, inner_count) = (outer.count.clone(), inner.count.clone());
+
+    let mut buf = vec![];
+    encode_all((&mut buf).layer(inner)?.layer(CrcLayer::new())?.layer(outer)?)?.finish()?.finish()?;
+    let size = values().iter().try_fold(0, |sum, value| value.size().map(|size| sum + size as usize))?;
+    assert_eq!(outer_count.get(), size);
+    assert_eq!(inner_count.get(), size + 4);
+    assert_eq!(buf.len(), size + 4);
+
+    let reader = MetricsLayer::default();
+    let count = reader.count.clone();
+    assert_eq!(decode_all(buf.as_slice().layer(reader)?.layer(CrcLayer::new())?)?, values());
+    assert_eq!(count.get(), buf.len());
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Testing
+
+#![cfg(feature="testing")]
+
+extern crate binn_ir;
+
+use {
+    std::panic,
+
+    binn_ir::{Encoder, IoResult, Value, testing},
+};
+
+/// # Runs a function, and returns its panic message
+fn panic_msg<F>(f: F) -> String where F: FnOnce() + panic::UnwindSafe {
+    let err = panic::catch_unwind(f).unwrap_err();
+    match err.downcast::<String>() {
+        Ok(msg) => *msg,
+        Err(err) => String::from(*err.downcast::<&str>().unwrap()),
+    }
+}
+
+#[test]
+fn roundtrips() -> IoResult<()> {
+    let mut value = binn_ir::object();
+    value.object_insert("name", "moss")?;
+    value.object_insert("ratio", f64::NAN)?;
+    value.object_insert("items", vec![Value::U8(1), binn_ir::map_from(-1, "-1"), binn_ir::blob()])?;
+    testing::assert_roundtrip(&value);
+
+    let mut buf = vec![];
+    buf.encode(&value)?;
+    testing::assert_bytes_roundtrip(&buf);
+
+    Ok(())
+}
+
+#[test]
+fn failures() -> IoResult<()> {
+    // Object key is too long
+    let value = binn_ir::object_from("-".repeat(256), Value::Null);
+    assert!(panic_msg(move || testing::assert_roundtrip(&value)).starts_with("failed to encode: "));
+
+    // Size is encoded in 4 bytes, although it fits in 1 byte
+    let buf = [binn_ir::value::LIST, 0x80, 0, 0, 9, 1, binn_ir::value::TEXT, 0, 0];
+    let msg = panic_msg(move || testing::assert_bytes_roundtrip(&buf));
+    assert!(msg.starts_with("encoded bytes differ from original ones, at byte 1 (original: 9 byte(s), "), "{}", msg);
+    assert!(!msg.contains("values also differ"));
+
+    // Trailing data, and empty source
+    let msg = panic_msg(|| testing::assert_bytes_roundtrip(&[binn_ir::value::NULL, binn_ir::value::NULL]));
+    assert_eq!(msg, "trailing data after decoded value: 1 byte(s)");
+    assert_eq!(panic_msg(|| testing::assert_bytes_roundtrip(&[])), "no value to decode");
+
+    Ok(())
+}
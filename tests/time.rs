@@ -0,0 +1,59 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Conversions for `time`
+
+#![cfg(feature="time")]
+
+extern crate binn_ir;
+
+use {
+    core::convert::TryFrom,
+
+    binn_ir::{Result, Value},
+    time::{Date, Month, OffsetDateTime, Time, UtcOffset},
+};
+
+#[test]
+fn date_times() -> Result<()> {
+    let date = Date::from_calendar_date(2019, Month::December, 31).unwrap();
+    let date_time = date.with_hms(23, 59, 59).unwrap().assume_utc();
+    let value = Value::try_from(date_time)?;
+    assert_eq!(value.as_date_time()?, "2019-12-31T23:59:59Z");
+    assert_eq!(OffsetDateTime::try_from(value)?, date_time);
+
+    let date_time = date_time.to_offset(UtcOffset::from_hms(1, 0, 0).unwrap());
+    let value = Value::try_from(date_time)?;
+    assert_eq!(value.as_date_time()?, "2020-01-01T00:59:59+01:00");
+    let decoded = OffsetDateTime::try_from(value)?;
+    assert_eq!(decoded, date_time);
+    assert_eq!(decoded.offset(), date_time.offset());
+
+    for s in &["2019-02-29T00:00:00Z", "2019-12-31T23:59:59", "yesterday"] {
+        assert!(OffsetDateTime::try_from(Value::DateTime(String::from(*s))).is_err());
+    }
+    assert!(OffsetDateTime::try_from(Value::from("2019-12-31T23:59:59Z")).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn dates_and_times() -> Result<()> {
+    let date = Date::from_calendar_date(2019, Month::December, 31).unwrap();
+    let value = Value::from(date);
+    assert_eq!(value, Value::Date("2019-12-31".into()));
+    assert_eq!(Date::try_from(value)?, date);
+    assert!(Date::try_from(Value::Date("2019-13-01".into())).is_err());
+
+    for (time, s) in &[
+        (Time::from_hms(1, 2, 3).unwrap(), "01:02:03"),
+        (Time::from_hms_milli(23, 59, 59, 500).unwrap(), "23:59:59.5"),
+    ] {
+        let value = Value::from(*time);
+        assert_eq!(value.as_time()?, *s);
+        assert_eq!(Time::try_from(value)?, *time);
+    }
+    assert!(Time::try_from(Value::Time("24:00:01".into())).is_err());
+    assert!(Time::try_from(Value::Date("23:59:59".into())).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Tracking reader
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use {
+    std::io::{BufRead, BufReader, Read},
+
+    binn_ir::{Decoder, IoResult, TrackingReader, Value},
+};
+
+#[test]
+fn tracking_reader() -> IoResult<()> {
+    let values = [
+        Value::Null, Value::U16(9), Value::Text("text".into()), Value::Blob(vec![0; 300]),
+        binn_ir::object_from("list", Value::List(vec![Value::True, binn_ir::map_from(-1, Value::Double(1.5))])),
+    ];
+    let mut buf = vec![];
+    for value in &values {
+        value.encode(&mut buf)?;
+    }
+
+    // Unbuffered
+    let mut reader = TrackingReader::new(&buf[..]);
+    let mut expected_count = 0;
+    for value in &values {
+        assert_eq!(reader.decode()?.as_ref(), Some(value));
+        expected_count += u64::from(value.size()?);
+        assert_eq!(reader.count(), expected_count);
+    }
+    assert_eq!(reader.decode()?, None);
+    assert_eq!(reader.reset_count(), buf.len() as u64);
+    assert_eq!(reader.count(), 0);
+    assert!(reader.into_inner().is_empty());
+
+    // Buffered
+    let mut reader = TrackingReader::new(BufReader::with_capacity(7, &buf[..]));
+    assert_eq!(reader.decode()?, Some(Value::Null));
+    assert_eq!(reader.fill_buf()?, &buf[1..7]);
+    assert_eq!(reader.count(), 1);
+    reader.consume(3);
+    assert_eq!(reader.count(), 4);
+    let mut rest = vec![];
+    reader.read_to_end(&mut rest)?;
+    assert_eq!(rest, &buf[4..]);
+    assert_eq!(reader.count(), buf.len() as u64);
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Conversions for `uuid`
+
+#![cfg(feature="uuid")]
+
+extern crate binn_ir;
+
+use {
+    core::convert::TryFrom,
+
+    binn_ir::{Result, Value},
+    uuid::Uuid,
+};
+
+#[test]
+fn uuids() -> Result<()> {
+    let uuid = Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+
+    let value = Value::from(uuid);
+    assert_eq!(value.as_blob()?, uuid.as_bytes());
+    assert_eq!(Uuid::try_from(&value)?, uuid);
+
+    let value = Value::from(uuid.hyphenated());
+    assert_eq!(value.as_text()?, "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    assert_eq!(Uuid::try_from(value)?, uuid);
+    assert_eq!(Uuid::try_from(Value::from("67e5504410b1426f9247bb680e5fe0c8"))?, uuid);
+
+    assert!(Uuid::try_from(Value::Blob(vec![0; 15])).is_err());
+    assert!(Uuid::try_from(Value::from("67e55044-10b1-426f-9247")).is_err());
+    assert!(Uuid::try_from(Value::U64(0)).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Validator
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use binn_ir::{Encoder, IoResult, Value, value};
+
+/// # Validates a buffer, and returns offsets of its violations
+fn violation_offsets(bytes: &[u8]) -> Vec<usize> {
+    binn_ir::validate(bytes).unwrap().violations().iter().map(|v| v.offset()).collect()
+}
+
+#[test]
+fn valid_values() -> IoResult<()> {
+    let mut object = binn_ir::object_from("list", Value::List(vec![Value::Null, Value::from("text"), Value::Blob(vec![1, 2, 3])]));
+    object.object_insert("map", binn_ir::map_from(-1, Value::Double(1.5)))?;
+    object.object_insert("big", Value::Blob(vec![0; 300]))?;
+    object.object_insert("", Value::U64(u64::MAX))?;
+
+    for value in &[Value::Null, Value::from(""), binn_ir::list(), binn_ir::map(), binn_ir::object(), object] {
+        let mut buf = vec![];
+        buf.encode(value)?;
+        let report = binn_ir::validate(&buf).unwrap();
+        assert!(report.is_valid(), "{:?}", report);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn invalid_values() -> IoResult<()> {
+    // Empty buffer, trailing data, truncated data
+    assert_eq!(violation_offsets(&[]), [0]);
+    assert_eq!(violation_offsets(&[value::NULL, value::NULL]), [1]);
+    assert_eq!(violation_offsets(&[value::U16, 0]), [0]);
+    assert_eq!(violation_offsets(&[value::TEXT]), [1]);
+    assert_eq!(violation_offsets(&[value::TEXT, 0x80, 0]), [1]);
+
+    // Strings
+    assert_eq!(violation_offsets(&[value::TEXT, 2, b'a', b'b', 0]), []);
+    assert_eq!(violation_offsets(&[value::TEXT, 2, b'a', b'b', b'c']), [4]);
+    assert_eq!(violation_offsets(&[value::TEXT, 2, b'a', 0xff, 0]), [3]);
+    assert_eq!(violation_offsets(&[value::TEXT, 3, b'a', b'b', 0]), [1]);
+
+    // Blobs
+    assert_eq!(violation_offsets(&[value::BLOB, 0x80, 0, 0, 2, 1, 2]), []);
+    assert_eq!(violation_offsets(&[value::BLOB, 3, 1, 2]), [1]);
+
+    // Containers
+    assert_eq!(violation_offsets(&[value::LIST, 4, 1, value::NULL]), []);
+    assert_eq!(violation_offsets(&[value::LIST, 4, 2, value::NULL]), [4]);
+    assert_eq!(violation_offsets(&[value::LIST, 5, 1, value::NULL]), [1]);
+    assert_eq!(violation_offsets(&[value::LIST, 4, 1, value::U8]), [3]);
+    assert_eq!(violation_offsets(&[value::LIST, 1, 0]), [1]);
+    assert_eq!(violation_offsets(&[value::MAP, 13, 2, 0, 0, 0, 1, value::NULL, 0, 0, 0, 1, value::TRUE]), [8]);
+    assert_eq!(violation_offsets(&[value::MAP, 6, 1, 0, 0, 0]), [3]);
+    assert_eq!(violation_offsets(&[value::OBJECT, 9, 2, 1, b'a', value::NULL, 1, b'a', value::TRUE]), [6]);
+    assert_eq!(violation_offsets(&[value::OBJECT, 6, 1, 1, 0xff, value::NULL]), [4]);
+    assert_eq!(violation_offsets(&[value::OBJECT, 5, 1, 2, b'a']), [3]);
+
+    // Unknown types are reported, but skipped
+    assert_eq!(violation_offsets(&[value::LIST, 7, 3, 0x03, 0x23, 9, value::NULL]), [3, 4]);
+    assert_eq!(violation_offsets(&[value::LIST, 7, 2, 0x30, 0x01, 9, value::NULL]), [3]);
+
+    // Multiple violations
+    assert_eq!(violation_offsets(&[value::LIST, 10, 3, value::TEXT, 1, 0xff, 1, value::TEXT, 0, 0]), [5, 6, 10]);
+
+    Ok(())
+}
+
+#[test]
+fn deep_nesting() {
+    /// # Makes nested lists
+    fn nested_lists(depth: usize) -> Vec<u8> {
+        let mut result = vec![value::LIST, 3, 0];
+        for _ in 0..depth {
+            let size = (result.len() + 6) as u32 | 0x8000_0000;
+            result.splice(0..0, [value::LIST].iter().copied().chain(size.to_be_bytes().iter().copied()).chain(Some(1)));
+        }
+        result
+    }
+
+    assert!(binn_ir::validate(&nested_lists(100)).unwrap().is_valid());
+    assert!(binn_ir::validate(&nested_lists(1000)).is_err());
+}
@@ -12,7 +12,7 @@ use {
     },
 
     binn_ir::{
-        Result, Value,
+        ObjectKey, Result, Value,
         value,
     },
 };
@@ -62,6 +62,7 @@ impl CmpTo<usize> for u64 {
 }
 
 #[test]
+#[allow(clippy::identity_op)]
 fn constants() {
     assert_eq!(value::NULL,         0b_0000_0000 | 0x00 | 0);
     assert_eq!(value::TRUE,         0b_0000_0001 | 0x01 | 1);
@@ -93,11 +94,11 @@ fn constants() {
     assert_eq!(value::MAP,          0b_1110_0001 | 0xE1 | 225);
     assert_eq!(value::OBJECT,       0b_1110_0010 | 0xE2 | 226);
 
-    assert_eq!(value::MAX_DATA_SIZE.cmp_to(&i32::max_value()), Ordering::Equal);
+    assert_eq!(value::MAX_DATA_SIZE.cmp_to(&i32::MAX), Ordering::Equal);
     // There are some castings from data's length to u64, so run this test
-    assert_ne!(value::MAX_DATA_SIZE.cmp_to(&u64::max_value()), Ordering::Greater);
+    assert_ne!(value::MAX_DATA_SIZE.cmp_to(&u64::MAX), Ordering::Greater);
 
-    assert_eq!(value::OBJECT_KEY_MAX_LEN, u8::max_value() as usize);
+    assert_eq!(value::OBJECT_KEY_MAX_LEN, u8::MAX as usize);
 }
 
 #[test]
@@ -176,13 +177,13 @@ fn basic_types() -> IoResult<()> {
     buf.encode_time(String::from("harry"))?;
     buf.encode_decimal_str("ginny\t\0\n")?;
 
-    let blob_strings = vec![
+    let blob_strings = [
         "roy eats moss' orange".repeat(20),
         "moss kisses jen".repeat(30),
         "richmond is a ghost".repeat(40),
     ];
     for s in blob_strings.iter() {
-        assert!(s.len() > i8::max_value() as usize);
+        assert!(s.len() > i8::MAX as usize);
         buf.encode_blob(s.as_bytes())?;
     }
 
@@ -290,7 +291,7 @@ fn lists() -> IoResult<()> {
     let list = Value::List(vec![
         Value::from(123_u8), Value::I16(-456), Value::U16(789), Value::Float(-123_f32), Value::Double(-789_f64),
         Value::from(String::from("Draco Malfoy")), Value::from("Slytherin"),
-        Value::Time(String::from(std::u128::MAX.to_string().repeat(100))),
+        Value::Time(u128::MAX.to_string().repeat(100)),
         Value::from(vec![Value::Date(String::from("July 12th, 2018")), Value::DecimalStr(String::from("1234567890"))]),
         Value::from({
             let mut map_data = Map::new();
@@ -303,7 +304,7 @@ fn lists() -> IoResult<()> {
         }),
     ]);
     let list_size = list.size()?;
-    assert!(list_size > i8::max_value() as Size);
+    assert!(list_size > i8::MAX as Size);
 
     let mut buf = vec![];
     list.encode(&mut buf)?;
@@ -521,3 +522,1247 @@ fn benchmarks() -> IoResult<()> {
 
     Ok(())
 }
+
+#[test]
+#[cfg(feature="std")]
+fn decode_unknown_types() -> IoResult<()> {
+    let buf = vec![
+        // User defined QWORD
+        0x85, 0, 1, 2, 3, 4, 5, 6, 7,
+        value::TRUE,
+        // User defined STRING, with 2-byte type
+        0xB0, 0x15, 3, b'a', b'b', b'c', 0,
+        value::U8, 9,
+        // User defined container
+        0xE5, 5, 1, value::NULL, value::NULL,
+        value::FALSE,
+        // User defined BLOB, with missing data
+        0xC1, 3, 0,
+    ];
+
+    let mut cursor = Cursor::new(&buf);
+    assert_eq!(cursor.decode().unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(cursor.decode()?, Some(Value::True));
+    assert_eq!(cursor.decode().unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(cursor.decode()?, Some(Value::U8(9)));
+    assert_eq!(cursor.decode().unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(cursor.decode()?, Some(Value::False));
+    assert_eq!(cursor.decode().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(cursor.decode()?, None);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn objects_with_long_keys() -> IoResult<()> {
+    let mut object = Object::new();
+    for len in &[value::OBJECT_KEY_MAX_LEN, 128, 127] {
+        object.insert("k".repeat(*len), Value::U16(*len as u16));
+    }
+    let object = Value::from(object);
+
+    let mut buf = vec![];
+    object.encode(&mut buf)?;
+    assert_eq!(object.size()?.cmp_to(&buf.len()), Ordering::Equal);
+    assert_eq!(Cursor::new(&buf).decode()?, Some(object));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn decode_blobs_into_sinks() -> IoResult<()> {
+    let blob: Vec<u8> = (0..100_000_u32).map(|i| i as u8).collect();
+    let mut buf = vec![];
+    buf.encode_blob(blob.clone())?;
+    buf.encode_blob(vec![])?;
+    buf.encode_null()?;
+
+    let mut cursor = Cursor::new(&buf);
+    let mut sink = vec![];
+    assert_eq!(cursor.decode_blob_into(&mut sink)?, Some(blob.len() as u64));
+    assert_eq!(sink, blob);
+    assert_eq!(cursor.decode_blob_into(&mut io::sink())?, Some(0));
+    assert_eq!(cursor.decode_blob_into(&mut io::sink()).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(cursor.decode_blob_into(&mut io::sink())?, None);
+
+    // Missing data
+    let mut sink = vec![];
+    assert_eq!(Cursor::new(&buf[..100]).decode_blob_into(&mut sink).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(sink, &blob[..95]);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn encode_blobs_from_readers() -> IoResult<()> {
+    let blob: Vec<u8> = (0..100_000_u32).map(|i| i as u8).collect();
+
+    let mut buf = vec![];
+    assert_eq!(buf.encode_blob_from_reader(&mut &blob[..], blob.len() as u64)?, Value::Blob(blob.clone()).size()?);
+    assert_eq!(buf.encode_blob_from_reader(&mut &blob[..], 9)?, 11);
+    assert_eq!(buf.encode_blob_from_reader(&mut io::empty(), 0)?, 2);
+
+    let mut cursor = Cursor::new(&buf);
+    assert_eq!(cursor.decode_blob()?, Some(blob.clone()));
+    assert_eq!(cursor.decode_blob()?.as_deref(), Some(&blob[..9]));
+    assert_eq!(cursor.decode_blob()?, Some(vec![]));
+    assert_eq!(cursor.decode_blob()?, None);
+
+    // Short reader
+    assert_eq!(vec![].encode_blob_from_reader(&mut &blob[..9], 10).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    // Too large
+    assert!(vec![].encode_blob_from_reader(&mut io::empty(), u64::from(binn_ir::value::MAX_DATA_SIZE) + 1).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn encode_borrowed_strings() -> IoResult<()> {
+    let long = "binn".repeat(100);
+    for s in &["", "moss", long.as_str()] {
+        let mut buf = vec![];
+        assert_eq!(buf.encode_text(s)?, Value::Text(s.to_string()).size()?);
+        buf.encode_date_time(Cow::Borrowed(*s))?;
+        buf.encode_date(*s)?;
+        buf.encode_time(String::from(*s))?;
+        buf.encode_decimal_str(Box::<str>::from(*s))?;
+
+        let mut expected = vec![];
+        for value in &[
+            Value::Text(s.to_string()), Value::DateTime(s.to_string()), Value::Date(s.to_string()), Value::Time(s.to_string()),
+            Value::DecimalStr(s.to_string()),
+        ] {
+            value.encode(&mut expected)?;
+        }
+        assert_eq!(buf, expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn heap_size_estimates() -> Result<()> {
+    assert_eq!(Value::Null.heap_size_estimate(), 0);
+    assert_eq!(Value::Double(0.0).heap_size_estimate(), 0);
+    assert_eq!(Value::Text(String::with_capacity(99)).heap_size_estimate(), 99);
+    assert_eq!(Value::Blob(Vec::with_capacity(99)).heap_size_estimate(), 99);
+
+    let mut list = Vec::with_capacity(9);
+    list.push(Value::Text(String::with_capacity(99)));
+    let list = Value::List(list);
+    assert_eq!(list.heap_size_estimate(), 9 * mem::size_of::<Value>() + 99);
+
+    // Note: clones don't keep capacities
+    let list_size = list.heap_size_estimate();
+    let map = binn_ir::map_from(0, list.clone());
+    assert!(map.heap_size_estimate() > list.clone().heap_size_estimate());
+
+    let mut object = binn_ir::object_from("key", list);
+    assert!(object.heap_size_estimate() > list_size + "key".len());
+    object.object_insert("blob", Value::Blob(Vec::with_capacity(1000)))?;
+    assert!(object.heap_size_estimate() > list_size + 1000);
+
+    Ok(())
+}
+
+#[test]
+fn shrink_to_fit() -> Result<()> {
+    let mut list = Vec::with_capacity(9);
+    list.push(Value::Text(String::with_capacity(99)));
+    list.push(Value::Blob(Vec::with_capacity(99)));
+    let mut object = binn_ir::object_from("list", Value::List(list));
+    object.object_insert("map", binn_ir::map_from(0, Value::Date(String::with_capacity(99))))?;
+
+    let expected = object.clone();
+    object.shrink_to_fit();
+    assert_eq!(object, expected);
+    assert_eq!(object.object_by(&["list"])?.heap_size_estimate(), 2 * mem::size_of::<Value>());
+    assert_eq!(object.object_by(&["map"])?.map_by(&[0])?.heap_size_estimate(), 0);
+
+    // Keys of objects
+    {
+        let mut key = String::with_capacity(99);
+        key.push_str("key");
+        let mut object = Value::Object(binn_ir::Object::from([(key, Value::Null)]));
+        object.shrink_to_fit();
+        assert_eq!(object.as_object()?.keys().map(|k| k.capacity()).collect::<Vec<_>>(), [3]);
+        assert_eq!(object, binn_ir::object_from("key", Value::Null));
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn encode_objects_in_key_order() -> IoResult<()> {
+    let keys = (0..100).map(|i| format!("key-{}", i)).collect::<Vec<_>>();
+
+    let mut first = binn_ir::object();
+    let mut second = binn_ir::object();
+    for (key, reversed_key) in keys.iter().zip(keys.iter().rev()) {
+        first.object_insert(key.as_str(), key.as_str())?;
+        second.object_insert(reversed_key.as_str(), reversed_key.as_str())?;
+    }
+    assert_eq!(first, second);
+
+    let mut first_buf = vec![];
+    first.encode(&mut first_buf)?;
+    let mut second_buf = vec![];
+    second.encode(&mut second_buf)?;
+    assert_eq!(first_buf, second_buf);
+    assert_eq!(format!("{:?}", first), format!("{:?}", second));
+
+    // Keys are sorted
+    let mut sorted_keys = keys.clone();
+    sorted_keys.sort();
+    let mut cursor = Cursor::new(&first_buf);
+    assert_eq!(cursor.decode_object_header()?.map(|(_, item_count)| item_count), Some(100));
+    for key in &sorted_keys {
+        assert_eq!(&cursor.decode_object_key()?, key.as_str());
+        cursor.skip_value()?;
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn hash_objects() -> IoResult<()> {
+    use {
+        core::convert::TryFrom,
+
+        binn_ir::HashObject,
+    };
+
+    let object = (0..100).map(|i| (format!("key-{}", i), Value::from(i))).collect::<HashObject>();
+    let value = Value::from(object.clone());
+    assert_eq!(HashObject::try_from(value.clone())?, object);
+    assert!(HashObject::try_from(Value::Null).is_err());
+
+    // Items are encoded in order of their keys
+    let mut buf = vec![];
+    let size = binn_ir::encode_hash_object(&mut buf, &object)?;
+    assert_eq!(size, value.size()?);
+    let mut expected = vec![];
+    value.encode(&mut expected)?;
+    assert_eq!(buf, expected);
+
+    assert_eq!(binn_ir::decode_hash_object(&mut &buf[..])?, Some(object));
+    assert!(binn_ir::decode_hash_object(&mut &[][..])?.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn container_entries() -> Result<()> {
+    use core::convert::TryFrom;
+
+    let mut object = binn_ir::object();
+    for word in "the sun is hot, the moon is not".split_whitespace() {
+        object.object_entry(word)?.and_modify(|count| *count = Value::U8(u8::try_from(&*count).unwrap() + 1)).or_insert(Value::U8(1));
+    }
+    assert_eq!(u8::try_from(object.object_by(&["the"])?)?, 2);
+    assert_eq!(u8::try_from(object.object_by(&["moon"])?)?, 1);
+    assert!(Value::Null.object_entry("key").is_err());
+
+    let mut map = binn_ir::map();
+    map.map_entry(9)?.or_insert_with(binn_ir::map).map_entry(-9)?.or_insert(Value::Null);
+    assert!(map.map_by(&[9, -9])?.is_null());
+    assert!(binn_ir::list().map_entry(9).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn index_operators() -> Result<()> {
+    let mut doc = Value::Null;
+    doc["user"]["name"] = Value::from("moss");
+    doc["user"]["tags"] = Value::List(vec![Value::True, Value::False]);
+    doc["user"]["tags"][1_usize] = Value::Null;
+    doc["user"]["tags"][0] = Value::U8(0);
+    doc["scores"][9] = Value::U8(9);
+
+    assert_eq!(doc["user"]["name"].as_text()?, "moss");
+    assert_eq!(doc["user"]["tags"], Value::List(vec![Value::U8(0), Value::Null]));
+    assert_eq!(doc["user"]["tags"][0_usize], Value::U8(0));
+    assert_eq!(doc["scores"][9], Value::U8(9));
+    assert!(doc.map_by(&[9]).is_err());
+    for missing in &[&doc["missing"], &doc["user"]["tags"][9_usize], &doc["user"]["tags"][-1], &doc["scores"][0], &doc[0], &doc[0_usize]] {
+        assert!(missing.is_null());
+    }
+
+    Ok(())
+}
+
+#[test]
+#[should_panic]
+fn index_lists_out_of_bounds() {
+    let mut list = binn_ir::list();
+    list[0] = Value::Null;
+}
+
+#[test]
+#[should_panic]
+fn index_wrong_values() {
+    let mut value = Value::True;
+    value["key"] = Value::Null;
+}
+
+#[test]
+fn extend_containers() -> Result<()> {
+    let mut list = binn_ir::list();
+    list.extend(vec![Value::Null, Value::True]);
+    list.extend((0..3_u8).map(Value::from));
+    assert_eq!(list.as_list()?.len(), 5);
+
+    let mut map = Value::Null;
+    map.extend((0..3).map(|i| (i, Value::from(i))));
+    map.extend(vec![(0, Value::Null)]);
+    assert_eq!(map.as_map()?.len(), 3);
+    assert!(map.map_by(&[0])?.is_null());
+
+    let mut object = binn_ir::object_from("id", 0);
+    object.extend(vec![(ObjectKey::from("id"), Value::U8(9)), (ObjectKey::from("name"), Value::from("moss"))]);
+    assert_eq!(object.as_object()?.len(), 2);
+    assert_eq!(object["id"], Value::U8(9));
+
+    Ok(())
+}
+
+#[test]
+#[should_panic]
+fn extend_wrong_containers() {
+    let mut map = binn_ir::map();
+    map.extend(vec![Value::Null]);
+}
+
+#[test]
+fn from_collections() {
+    use std::collections::BTreeMap;
+
+    let list = Value::List(vec![Value::U16(1), Value::U16(2)]);
+    assert_eq!(Value::from([1_u16, 2]), list);
+    assert_eq!(Value::from(&[1_u16, 2][..]), list);
+    assert_eq!(Value::from(vec![1_u16, 2]), list);
+    assert_eq!(Value::from(vec!["a", "b"]), Value::List(vec![Value::from("a"), Value::from("b")]));
+    assert_eq!(Value::from([Value::Null]), Value::List(vec![Value::Null]));
+    assert_eq!(Value::from(&[Value::Null][..]), Value::List(vec![Value::Null]));
+    assert_eq!(Value::from([true; 0]), binn_ir::list());
+
+    // Bytes are blobs
+    assert_eq!(Value::from([1_u8, 2]), Value::Blob(vec![1, 2]));
+    assert_eq!(Value::from(&[1_u8, 2][..]), Value::Blob(vec![1, 2]));
+    assert_eq!(Value::from(vec![1_u8, 2]), Value::Blob(vec![1, 2]));
+
+    let mut map = BTreeMap::new();
+    map.insert(String::from("one"), 1_u8);
+    map.insert(String::from("two"), 2);
+    let mut object = binn_ir::object_from("one", 1_u8);
+    object.object_insert("two", 2_u8).unwrap();
+    assert_eq!(Value::from(map.clone()), object);
+    #[cfg(feature="std")]
+    assert_eq!(Value::from(map.into_iter().collect::<std::collections::HashMap<_, _>>()), object);
+}
+
+#[test]
+fn try_from_collections() -> Result<()> {
+    use {
+        core::convert::TryFrom,
+        std::collections::BTreeMap,
+    };
+
+    assert_eq!(Vec::<u16>::try_from(Value::from(vec![1_u16, 2]))?, [1, 2]);
+    assert_eq!(Vec::<i64>::try_from(Value::from(vec![1_u8.into(), Value::I32(-2)]))?, [1, -2]);
+    assert_eq!(Vec::<String>::try_from(Value::from(vec!["a", "b"]))?, ["a", "b"]);
+    assert!(Vec::<i8>::try_from(Value::from(vec![1_u16, 256])).is_err());
+    assert!(Vec::<bool>::try_from(Value::from(true)).is_err());
+
+    let mut object = binn_ir::object_from("one", 1_u8);
+    object.object_insert("two", 2_u8).unwrap();
+    let map = BTreeMap::<String, u32>::try_from(object.clone())?;
+    assert_eq!(map.len(), 2);
+    assert_eq!(map["one"], 1);
+    assert_eq!(map["two"], 2);
+    assert!(BTreeMap::<String, String>::try_from(object.clone()).is_err());
+    assert!(BTreeMap::<i32, u32>::try_from(object).is_err());
+
+    let mut map = binn_ir::map_from(-1, "minus one");
+    map.map_insert(1, "one")?;
+    let map = BTreeMap::<i32, String>::try_from(map)?;
+    assert_eq!(map.into_iter().collect::<Vec<_>>(), [(-1, String::from("minus one")), (1, String::from("one"))]);
+
+    Ok(())
+}
+
+#[test]
+fn chars() -> Result<()> {
+    use core::convert::TryFrom;
+
+    for c in &['a', 'é', '水', '🦀'] {
+        let value = Value::from(*c);
+        assert_eq!(value.as_text()?, c.to_string());
+        assert_eq!(char::try_from(&value)?, *c);
+        assert_eq!(char::try_from(value)?, *c);
+    }
+    for s in &["", "ab"] {
+        assert!(char::try_from(Value::from(*s)).is_err());
+    }
+    assert!(char::try_from(Value::U32(97)).is_err());
+    assert_eq!(Vec::<char>::try_from(Value::from(['x', 'y']))?, ['x', 'y']);
+
+    Ok(())
+}
+
+#[test]
+fn integers_128_bit() -> Result<()> {
+    use core::convert::TryFrom;
+
+    assert_eq!(Value::from(9_u128), Value::U64(9));
+    assert_eq!(Value::from(-9_i128), Value::I64(-9));
+    assert_eq!(Value::from(u128::MAX), Value::DecimalStr(u128::MAX.to_string()));
+    assert_eq!(Value::from(i128::MIN), Value::DecimalStr(i128::MIN.to_string()));
+
+    for n in &[0, 1, u128::from(u64::MAX), u128::from(u64::MAX) + 1, u128::MAX] {
+        assert_eq!(u128::try_from(Value::from(*n))?, *n);
+    }
+    for n in &[0, -1, i128::from(i64::MIN), i128::from(i64::MIN) - 1, i128::MIN, i128::MAX] {
+        assert_eq!(i128::try_from(Value::from(*n))?, *n);
+    }
+    assert_eq!(i128::try_from(Value::U8(9))?, 9);
+    assert!(u128::try_from(Value::I8(-1)).is_err());
+    assert!(u128::try_from(Value::DecimalStr("1.5".into())).is_err());
+    assert!(i128::try_from(Value::from("9")).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn std_time() -> Result<()> {
+    use {
+        core::convert::TryFrom,
+        std::time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+
+    let duration = Duration::new(99, 123);
+    assert_eq!(Duration::try_from(Value::from(duration))?, duration);
+    assert!(Duration::try_from(Value::U64(99)).is_err());
+
+    for (secs, nanos, s) in &[
+        (0_i64, 0, "1970-01-01T00:00:00Z"),
+        (951_782_400, 500_000_000, "2000-02-29T00:00:00.5Z"),
+        (1_577_836_799, 123_456_789, "2019-12-31T23:59:59.123456789Z"),
+        (-1, 999_000_000, "1969-12-31T23:59:59.999Z"),
+        (-62_167_219_200, 0, "0000-01-01T00:00:00Z"),
+        (253_402_300_799, 0, "9999-12-31T23:59:59Z"),
+    ] {
+        let time = match *secs >= 0 {
+            true => UNIX_EPOCH + Duration::from_secs(*secs as u64),
+            false => UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs()),
+        } + Duration::from_nanos(*nanos);
+        let value = Value::try_from(time)?;
+        assert_eq!(value.as_date_time()?, *s);
+        assert_eq!(SystemTime::try_from(value)?, time);
+    }
+    assert!(Value::try_from(UNIX_EPOCH + Duration::from_secs(253_402_300_800)).is_err());
+
+    let time = SystemTime::try_from(Value::DateTime("2019-12-31T23:59:59.1234567891Z".into()))?;
+    assert_eq!(time, UNIX_EPOCH + Duration::new(1_577_836_799, 123_456_789));
+    for s in &["2020-01-01T08:30:00+08:30", "2019-12-31t23:00:00-01:00", "2019-12-31 23:59:60Z"] {
+        assert_eq!(SystemTime::try_from(Value::DateTime(String::from(*s)))?, UNIX_EPOCH + Duration::from_secs(1_577_836_800));
+    }
+    for s in &["2019-02-29T00:00:00Z", "2019-12-31T24:00:00Z", "2019-12-31T23:59:59", "2019-12-31T23:59:59.Z", "2019-12-31T23:59:59+0100"] {
+        assert!(SystemTime::try_from(Value::DateTime(String::from(*s))).is_err());
+    }
+    assert!(SystemTime::try_from(Value::from("1970-01-01T00:00:00Z")).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn net_addrs() -> Result<()> {
+    use {
+        core::convert::TryFrom,
+        std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    };
+
+    let (ipv4, ipv6) = (Ipv4Addr::new(127, 0, 0, 1), Ipv6Addr::LOCALHOST);
+    assert_eq!(Value::from(ipv4).as_text()?, "127.0.0.1");
+    assert_eq!(Value::from(IpAddr::V6(ipv6)).as_text()?, "::1");
+    assert_eq!(Ipv4Addr::try_from(Value::from(ipv4))?, ipv4);
+    assert_eq!(Ipv6Addr::try_from(Value::from(ipv6))?, ipv6);
+    assert_eq!(IpAddr::try_from(Value::from("::1"))?, IpAddr::V6(ipv6));
+
+    // Blobs
+    assert_eq!(Ipv4Addr::try_from(Value::Blob(vec![127, 0, 0, 1]))?, ipv4);
+    assert_eq!(IpAddr::try_from(Value::Blob(vec![127, 0, 0, 1]))?, IpAddr::V4(ipv4));
+    assert_eq!(IpAddr::try_from(Value::Blob(ipv6.octets().to_vec()))?, IpAddr::V6(ipv6));
+    assert!(IpAddr::try_from(Value::Blob(vec![0; 5])).is_err());
+    assert!(Ipv6Addr::try_from(Value::Blob(vec![0; 4])).is_err());
+
+    let socket_addr = SocketAddr::new(IpAddr::V6(ipv6), 80);
+    assert_eq!(Value::from(socket_addr).as_text()?, "[::1]:80");
+    assert_eq!(SocketAddr::try_from(Value::from(socket_addr))?, socket_addr);
+    assert_eq!(SocketAddrV4::try_from(Value::from("127.0.0.1:80"))?, SocketAddrV4::new(ipv4, 80));
+    assert!(SocketAddrV6::try_from(Value::from("127.0.0.1:80")).is_err());
+    assert!(SocketAddr::try_from(Value::Blob(vec![127, 0, 0, 1])).is_err());
+    assert!(Ipv4Addr::try_from(Value::from("localhost")).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn non_zero_integers() -> Result<()> {
+    use core::{
+        convert::TryFrom,
+        num::{NonZeroI8, NonZeroU16, NonZeroU64},
+    };
+
+    assert_eq!(Value::from(NonZeroI8::new(-9).unwrap()), Value::I8(-9));
+    assert_eq!(Value::from(NonZeroU64::new(9).unwrap()), Value::U64(9));
+    assert_eq!(NonZeroU16::try_from(Value::U8(9))?.get(), 9);
+    assert_eq!(NonZeroI8::try_from(&Value::I64(-9))?.get(), -9);
+    assert!(NonZeroU16::try_from(Value::U8(0)).is_err());
+    assert!(NonZeroU16::try_from(Value::I8(-1)).is_err());
+    assert!(NonZeroU64::try_from(Value::Null).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn validate_temporal() -> IoResult<()> {
+    use binn_ir::DecodeConfig;
+
+    for s in &["2019-12-31T23:59:59Z", "2019-12-31 23:59:60.123456789999+07:00", "2020-02-29t00:00:00-01:30", "2019-12-31T23:59:59"] {
+        Value::DateTime(String::from(*s)).validate_temporal()?;
+    }
+    for s in &["2019-12-31", "2019-12-31T23:59", "2019-12-31T23:59:59+0700", "2019-12-31T23:59:59.Z", "2019-12-31_23:59:59Z", "2019-12-31T"] {
+        assert!(Value::DateTime(String::from(*s)).validate_temporal().is_err(), "{:?}", s);
+    }
+    for s in &["0000-01-01", "2000-02-29", "9999-12-31"] {
+        Value::Date(String::from(*s)).validate_temporal()?;
+    }
+    for s in &["2019-02-29", "1900-02-29", "2019-04-31", "2019-00-01", "2019-1-1", "19-01-01", "2019-01-01Z", "２０１９-01-01"] {
+        assert!(Value::Date(String::from(*s)).validate_temporal().is_err(), "{:?}", s);
+    }
+    for s in &["00:00:00", "23:59:59.5", "12:00:00z", "12:00:00-23:59"] {
+        Value::Time(String::from(*s)).validate_temporal()?;
+    }
+    for s in &["24:00:00", "23:60:00", "23:59:61", "1:00:00", "12:00", "12:00:00+24:00", "12:00:00 "] {
+        assert!(Value::Time(String::from(*s)).validate_temporal().is_err(), "{:?}", s);
+    }
+
+    // Other values are ignored; containers are validated recursively
+    Value::from("2019-02-29").validate_temporal()?;
+    let mut object = binn_ir::object_from("dates", Value::List(vec![Value::Date("2019-12-31".into())]));
+    object.validate_temporal()?;
+    object.object_insert("time", binn_ir::map_from(0, Value::Time("25:00:00".into())))?;
+    let err = object.validate_temporal().unwrap_err();
+    assert!(err.msg().unwrap().contains("25:00:00"));
+
+    // Decoding
+    let mut buf = vec![];
+    object.encode(&mut buf)?;
+    assert_eq!(binn_ir::decode(&mut &buf[..])?, Some(object.clone()));
+    let mut config = DecodeConfig::new();
+    assert_eq!(binn_ir::decode_with_config(&mut &buf[..], &config)?, Some(object));
+    config.validate_temporal = true;
+    assert_eq!(binn_ir::decode_with_config(&mut &buf[..], &config).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert!((&mut &buf[..]).decode_with_config(&config).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn validate_decimal_str() -> IoResult<()> {
+    use binn_ir::DecodeConfig;
+
+    for s in &["0", "-1", "+1", "1.", ".5", "-0.5", "123.456", "1e10", "1.5E-10", "-.5e+3", "00012"] {
+        Value::DecimalStr(String::from(*s)).validate_decimal_str()?;
+        assert_eq!(Value::checked_decimal_str(*s)?, Value::DecimalStr(String::from(*s)));
+    }
+    for s in &["", "-", "+-1", ".", "-.", "1.2.3", "1e", "1e+", "e5", "1,5", " 1", "1 ", "0x10", "NaN", "inf", "１"] {
+        assert!(Value::DecimalStr(String::from(*s)).validate_decimal_str().is_err(), "{:?}", s);
+        assert!(Value::checked_decimal_str(*s).is_err());
+    }
+
+    // Other values are ignored; containers are validated recursively
+    Value::from("one").validate_decimal_str()?;
+    let list = Value::List(vec![Value::DecimalStr("1".into()), binn_ir::object_from("x", Value::DecimalStr("one".into()))]);
+    assert!(list.validate_decimal_str().unwrap_err().msg().unwrap().contains("one"));
+
+    // Decoding
+    let mut buf = vec![];
+    list.encode(&mut buf)?;
+    let mut config = DecodeConfig::new();
+    assert_eq!(binn_ir::decode_with_config(&mut &buf[..], &config)?, Some(list));
+    config.validate_decimal_str = true;
+    assert_eq!(binn_ir::decode_with_config(&mut &buf[..], &config).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn strict_decoding() -> IoResult<()> {
+    use binn_ir::DecodeConfig;
+
+    let values = [
+        Value::Date("2019-02-29".into()),
+        Value::DecimalStr("one".into()),
+        binn_ir::object_from("", Value::Null),
+        binn_ir::object_from("x", Value::List(vec![Value::Time("24:00:00".into())])),
+    ];
+    let config = DecodeConfig::strict();
+    assert!(config.strict);
+    assert!(!config.validate_temporal && !config.validate_decimal_str);
+    for value in &values {
+        let mut buf = vec![];
+        value.encode(&mut buf)?;
+        assert_eq!(binn_ir::decode_with_config(&mut &buf[..], &DecodeConfig::new())?.as_ref(), Some(value));
+        assert_eq!(binn_ir::decode_with_config(&mut &buf[..], &config).unwrap_err().kind(), ErrorKind::InvalidData, "{:?}", value);
+    }
+
+    let value = binn_ir::object_from("x", Value::List(vec![Value::Time("23:59:59".into()), Value::DecimalStr("-1.5".into())]));
+    let mut buf = vec![];
+    value.encode(&mut buf)?;
+    assert_eq!(binn_ir::decode_with_config(&mut &buf[..], &config)?, Some(value));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn decode_error_locations() -> IoResult<()> {
+    use binn_ir::{DecodeError, Path, PathSegment};
+
+    fn inner(err: &io::Error) -> &DecodeError {
+        err.get_ref().and_then(|err| err.downcast_ref::<DecodeError>()).unwrap()
+    }
+
+    let value = binn_ir::object_from("a", Value::List(vec![Value::U8(1), binn_ir::map_from(-1, "ok")]));
+    let mut buf = vec![];
+    value.encode(&mut buf)?;
+    assert_eq!(binn_ir::decode(&mut &buf[..])?, Some(value));
+
+    // Text's null terminator
+    let len = buf.len();
+    buf[len - 1] = b'!';
+    let err = binn_ir::decode(&mut &buf[..]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    let decode_error = inner(&err);
+    assert_eq!(decode_error.offset(), buf.len() as u64);
+    assert_eq!(
+        decode_error.path().segments(),
+        &[PathSegment::ObjectKey("a".into()), PathSegment::Index(1), PathSegment::MapKey(-1)],
+    );
+    assert_eq!(decode_error.path().to_string(), r#"$["a"][1]{-1}"#);
+    assert!(decode_error.to_string().starts_with(&format!("at byte {}, path {}: ", len, decode_error.path())));
+
+    // Truncated data
+    let err = binn_ir::decode(&mut &buf[..len - 3]).unwrap_err();
+    assert_eq!(inner(&err).offset(), len as u64 - 3);
+
+    // Errors of containers themselves belong to the containers
+    let mut buf = vec![];
+    Value::List(vec![Value::List(vec![Value::Null])]).encode(&mut buf)?;
+    buf[5] = 2;
+    let err = binn_ir::decode(&mut &buf[..]).unwrap_err();
+    assert_eq!(inner(&err).path().segments(), &[PathSegment::Index(0)]);
+
+    // Offsets are counted from where decoding started
+    let mut buf = vec![];
+    Value::Null.encode(&mut buf)?;
+    Value::List(vec![Value::U16(0)]).encode(&mut buf)?;
+    buf.pop();
+    let mut cursor = Cursor::new(buf);
+    assert_eq!(cursor.decode()?, Some(Value::Null));
+    let err = cursor.decode().unwrap_err();
+    assert_eq!(inner(&err).offset(), 5);
+    assert_eq!(inner(&err).path().segments(), &[PathSegment::Index(0)]);
+    assert_eq!(Path::new().to_string(), "$");
+
+    Ok(())
+}
+
+#[test]
+fn error_contexts() {
+    use core::convert::TryFrom;
+
+    let err = u8::try_from(Value::U16(300)).unwrap_err();
+    assert_eq!(err.msg(), Some("Integer is out of range"));
+    assert_eq!(err.context(), Some(300));
+    assert!(err.to_string().ends_with("Integer is out of range: 300"));
+
+    let err = i64::try_from(Value::U64(u64::MAX)).unwrap_err();
+    assert_eq!(err.context(), Some(i128::from(u64::MAX)));
+
+    assert_eq!(Value::Null.as_text().unwrap_err().context(), None);
+}
+
+#[test]
+#[cfg(feature="std")]
+fn errors_inside_io_errors() -> IoResult<()> {
+    use binn_ir::Error;
+
+    let err = Value::Null.as_text().unwrap_err();
+    let (line, module_path) = (err.line(), err.module_path().to_string());
+    let err = io::Error::from(err);
+    assert_eq!(err.kind(), ErrorKind::Other);
+    let err = err.get_ref().and_then(|err| err.downcast_ref::<Error>()).unwrap();
+    assert_eq!((err.line(), err.module_path()), (line, module_path.as_str()));
+    assert_eq!(err.msg(), Some("Value is not a Text"));
+
+    // Encoding
+    let value = binn_ir::object_from("x".repeat(300), Value::Null);
+    let err = value.encode(&mut vec![]).unwrap_err();
+    assert_eq!(err.get_ref().and_then(|err| err.downcast_ref::<Error>()).map(|err| err.msg()), Some(value.size().unwrap_err().msg()));
+    assert_eq!(Error::find(&err).map(|err| err.msg()), Some(value.size().unwrap_err().msg()));
+
+    // Decoding: errors are located via DecodeError
+    let mut buf = vec![];
+    Value::List(vec![Value::Time("25:00".into())]).encode(&mut buf)?;
+    let err = binn_ir::decode_with_config(&mut &buf[..], &binn_ir::DecodeConfig::strict()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.get_ref().and_then(|err| err.downcast_ref::<Error>()).is_none());
+    let err = Error::find(&err).unwrap();
+    assert_eq!(err.msg(), Value::Time("25:00".into()).validate_temporal().unwrap_err().msg());
+    assert!(Error::find(&io::Error::from(ErrorKind::InvalidData)).is_none());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn decode_budgets() -> IoResult<()> {
+    use binn_ir::DecodeConfig;
+
+    let value = binn_ir::object_from("list", Value::List(vec![Value::Text("text".into()), Value::Blob(vec![0; 100]), Value::U64(0)]));
+    let mut buf = vec![];
+    value.encode(&mut buf)?;
+    let len = buf.len() as u64;
+
+    // Object, list and 3 items
+    assert_eq!((&buf[..]).with_budget(5, len).decode()?.as_ref(), Some(&value));
+    assert_eq!((&buf[..]).with_budget(4, len).decode().unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!((&buf[..]).with_budget(5, len - 1).decode().unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // Declared sizes are checked before reading data
+    let mut buf = vec![];
+    Value::Blob(vec![0; 1000]).encode(&mut buf)?;
+    let mut config = DecodeConfig::new();
+    config.max_bytes = Some(100);
+    let mut source = &buf[..];
+    assert!(binn_ir::decode_with_config(&mut source, &config).is_err());
+    assert_eq!(source.len(), 1000);
+
+    // Budgets are per call
+    let mut buf = vec![];
+    for _ in 0..10 {
+        buf.encode_u8(0)?;
+    }
+    let mut decoder = Cursor::new(buf).with_budget(1, 2);
+    for _ in 0..10 {
+        assert_eq!(decoder.decode()?, Some(Value::U8(0)));
+    }
+    assert_eq!(decoder.decode()?, None);
+    assert_eq!(decoder.into_inner().position(), 20);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn max_depth() -> IoResult<()> {
+    use binn_ir::{BinnDecoder, DecodeConfig, DepthLimitExceeded, value::DEFAULT_MAX_DEPTH};
+
+    fn nested_lists(depth: usize) -> Value {
+        (1..depth).fold(Value::List(vec![]), |value, _| Value::List(vec![value]))
+    }
+
+    for (depth, ok) in &[(DEFAULT_MAX_DEPTH, true), (DEFAULT_MAX_DEPTH + 1, false)] {
+        let value = nested_lists(*depth);
+        let mut buf = vec![];
+        value.encode(&mut buf)?;
+        match ok {
+            true => {
+                assert_eq!(binn_ir::decode(&mut &buf[..])?.as_ref(), Some(&value));
+                assert_eq!(BinnDecoder::new(&buf[..]).decode()?.as_ref(), Some(&value));
+                assert!(binn_ir::validate(&buf).is_ok());
+            },
+            false => {
+                let err = binn_ir::decode(&mut &buf[..]).unwrap_err();
+                assert_eq!(err.kind(), ErrorKind::InvalidData);
+                assert_eq!(DepthLimitExceeded::find(&err).map(|err| err.max_depth()), Some(DEFAULT_MAX_DEPTH));
+                assert!(DepthLimitExceeded::find(&BinnDecoder::new(&buf[..]).decode().unwrap_err()).is_some());
+                assert!(binn_ir::validate(&buf).is_err());
+            },
+        };
+    }
+
+    let mut buf = vec![];
+    nested_lists(3).encode(&mut buf)?;
+    let mut config = DecodeConfig::new();
+    assert_eq!(config.max_depth, DEFAULT_MAX_DEPTH);
+    config.max_depth = 2;
+    assert!(DepthLimitExceeded::find(&binn_ir::decode_with_config(&mut &buf[..], &config).unwrap_err()).is_some());
+    assert!(config.check_limits(&nested_lists(3)).is_err());
+    config.max_depth = 3;
+    assert_eq!(binn_ir::decode_with_config(&mut &buf[..], &config)?, Some(nested_lists(3)));
+    assert_eq!(nested_lists(3).max_depth(), 3);
+    assert!(config.check_limits(&nested_lists(3)).is_ok());
+    config.max_depth = 0;
+    assert_eq!(binn_ir::decode_with_config(&mut &[value::NULL][..], &config)?, Some(Value::Null));
+
+    assert!(DepthLimitExceeded::find(&io::Error::from(ErrorKind::InvalidData)).is_none());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn compat_levels() -> IoResult<()> {
+    use binn_ir::CompatLevel;
+
+    let values = [
+        (Value::U16(1), vec![value::U8, 1]),
+        (Value::U32(u32::from(u16::MAX)), vec![value::U16, 0xff, 0xff]),
+        (Value::U64(u64::from(u32::MAX) + 1), vec![value::U64, 0, 0, 0, 1, 0, 0, 0, 0]),
+        (Value::I64(0), vec![value::U8, 0]),
+        (Value::I16(i16::from(i8::MIN)), vec![value::I8, 0x80]),
+        (Value::I32(i32::from(i8::MIN) - 1), vec![value::I16, 0xff, 0x7f]),
+        (Value::I64(i64::from(u32::MAX)), vec![value::U32, 0xff, 0xff, 0xff, 0xff]),
+        (Value::I64(i64::from(i32::MIN)), vec![value::I32, 0x80, 0, 0, 0]),
+        (Value::I64(i64::MIN), vec![value::I64, 0x80, 0, 0, 0, 0, 0, 0, 0]),
+        (Value::I8(1), vec![value::I8, 1]),
+        (Value::Double(1.0), vec![value::DOUBLE, 0x3f, 0xf0, 0, 0, 0, 0, 0, 0]),
+    ];
+    for (value, expected) in values.iter() {
+        let mut buf = vec![];
+        assert_eq!(value.encode_with_compat(&mut buf, CompatLevel::CBinn)?, expected.len() as Size);
+        assert_eq!(&buf, expected);
+
+        buf.clear();
+        buf.encode_with_compat(value, CompatLevel::Spec)?;
+        assert_eq!(buf.len() as Size, value.size()?);
+        assert_eq!(binn_ir::decode(&mut &buf[..])?.as_ref(), Some(value));
+    }
+
+    // Containers
+    let mut object = binn_ir::object();
+    object.object_insert("list", vec![Value::I32(1), binn_ir::map_from(-1, Value::U64(2))])?;
+    let mut buf = vec![];
+    object.encode_with_compat(&mut buf, CompatLevel::CBinn)?;
+    assert_eq!(buf, [
+        value::OBJECT, 0x16, 1, 4, b'l', b'i', b's', b't',
+        value::LIST, 0x0e, 2, value::U8, 1, value::MAP, 0x09, 1, 0xff, 0xff, 0xff, 0xff, value::U8, 2,
+    ]);
+    assert_eq!(CompatLevel::default(), CompatLevel::Spec);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn encode_config_shrinks_integers() -> IoResult<()> {
+    use binn_ir::EncodeConfig;
+
+    assert_eq!(EncodeConfig::default(), EncodeConfig::new());
+    assert!(!EncodeConfig::new().shrink_integers);
+
+    let mut config = EncodeConfig::new();
+    config.shrink_integers = true;
+
+    let values = [
+        (Value::U64(300), Value::U16(300)),
+        (Value::U32(1), Value::U8(1)),
+        (Value::I64(70_000), Value::U32(70_000)),
+        (Value::I32(-300), Value::I16(-300)),
+        (Value::I64(i64::MIN), Value::I64(i64::MIN)),
+        (Value::U64(u64::MAX), Value::U64(u64::MAX)),
+        (Value::I8(1), Value::I8(1)),
+        (Value::Float(1.5), Value::Float(1.5)),
+    ];
+    for (value, expected) in values.iter() {
+        let mut buf = vec![];
+        assert_eq!(value.encode_with_config(&mut buf, &config)?, expected.size()?);
+        assert_eq!(binn_ir::decode(&mut &buf[..])?.as_ref(), Some(expected));
+
+        buf.clear();
+        buf.encode_with_config(value, &EncodeConfig::new())?;
+        assert_eq!(binn_ir::decode(&mut &buf[..])?.as_ref(), Some(value));
+    }
+
+    // Containers
+    let mut object = binn_ir::object();
+    object.object_insert("list", vec![Value::U64(300), binn_ir::map_from(-1, Value::I64(-2))])?;
+    let mut buf = vec![];
+    object.encode_with_config(&mut buf, &config)?;
+    let mut expected = binn_ir::object();
+    expected.object_insert("list", vec![Value::U16(300), binn_ir::map_from(-1, Value::I8(-2))])?;
+    assert_eq!(buf.len() as Size, expected.size()?);
+    assert_eq!(binn_ir::decode(&mut &buf[..])?, Some(expected));
+
+    Ok(())
+}
+
+#[test]
+fn getters_with_defaults() -> Result<()> {
+    let mut object = binn_ir::object_from("text", "some");
+    object.object_insert("u8", 9_u8)?;
+    object.object_insert("i32", -9_i32)?;
+    object.object_insert("float", 0.5_f32)?;
+    object.object_insert("false", false)?;
+    object.object_insert("null", Value::Null)?;
+
+    assert_eq!(object.get_str_or("text", "")?, "some");
+    assert_eq!(object.get_u64_or("u8", 0)?, 9);
+    assert_eq!(object.get_i64_or("i32", 0)?, -9);
+    assert_eq!(object.get_f64_or("float", 0.0)?, 0.5);
+    assert!(!object.get_bool_or("false", true)?);
+    assert_eq!(object.get_or::<i8>("u8", 0)?, 9);
+
+    // Defaults
+    for key in &["null", "missing"] {
+        assert_eq!(object.get_str_or(key, "default")?, "default");
+        assert_eq!(object.get_u64_or(key, 1)?, 1);
+        assert_eq!(object.get_i64_or(key, -1)?, -1);
+        assert_eq!(object.get_f64_or(key, 1.5)?, 1.5);
+        assert!(object.get_bool_or(key, true)?);
+    }
+
+    // Wrong types
+    object.get_str_or("u8", "").unwrap_err();
+    object.get_u64_or("i32", 0).unwrap_err();
+    object.get_i64_or("text", 0).unwrap_err();
+    object.get_bool_or("u8", false).unwrap_err();
+    Value::Null.get_u64_or("u8", 0).unwrap_err();
+    binn_ir::list().get_str_or("text", "").unwrap_err();
+
+    Ok(())
+}
+
+#[test]
+fn lossless_integers() -> Result<()> {
+    for value in &[Value::U8(5), Value::I8(5), Value::U16(5), Value::I16(5), Value::U32(5), Value::I32(5), Value::U64(5), Value::I64(5)] {
+        assert_eq!(value.as_i64_lossless()?, 5);
+        assert_eq!(value.as_u64_lossless()?, 5);
+    }
+
+    assert_eq!(Value::I64(i64::MIN).as_i64_lossless()?, i64::MIN);
+    assert_eq!(Value::U64(u64::MAX).as_u64_lossless()?, u64::MAX);
+    Value::U64(u64::MAX).as_i64_lossless().unwrap_err();
+    Value::I8(-1).as_u64_lossless().unwrap_err();
+
+    for value in &[Value::Float(5.0), Value::Double(5.0), Value::DecimalStr("5".into()), Value::Null] {
+        value.as_i64_lossless().unwrap_err();
+        value.as_u64_lossless().unwrap_err();
+    }
+
+    Ok(())
+}
+
+#[test]
+fn lossy_floats() -> Result<()> {
+    for value in &[Value::U8(2), Value::I8(2), Value::U16(2), Value::I16(2), Value::U32(2), Value::I32(2), Value::U64(2), Value::I64(2)] {
+        assert_eq!(value.as_f64_lossy()?, 2.0);
+    }
+    assert_eq!(Value::Float(-0.25).as_f64_lossy()?, -0.25);
+    assert_eq!(Value::Double(f64::MAX).as_f64_lossy()?, f64::MAX);
+    assert_eq!(Value::I64(i64::MIN).as_f64_lossy()?, -9_223_372_036_854_775_808.0);
+
+    // Precision loss
+    assert_eq!(Value::U64((1 << 53) + 1).as_f64_lossy()?, (1_u64 << 53) as f64);
+
+    for value in &[Value::Null, Value::True, Value::DecimalStr("2".into()), Value::from("2")] {
+        value.as_f64_lossy().unwrap_err();
+    }
+
+    Ok(())
+}
+
+#[test]
+fn normalize() -> Result<()> {
+    let values = [
+        (Value::U64(0), Value::U8(0)),
+        (Value::I8(1), Value::U8(1)),
+        (Value::I64(i64::from(u32::MAX)), Value::U32(u32::MAX)),
+        (Value::I64(i64::MIN), Value::I64(i64::MIN)),
+        (Value::I16(-129), Value::I16(-129)),
+        (Value::Double(-1.25), Value::Float(-1.25)),
+        (Value::Double(0.1), Value::Double(0.1)),
+        (Value::DateTime("2019-12-31t00:00:00Z".into()), Value::DateTime("2019-12-31T00:00:00Z".into())),
+        (Value::DateTime("2019-12-31 24:00:00".into()), Value::DateTime("2019-12-31 24:00:00".into())),
+        (Value::DecimalStr("+.5E-1".into()), Value::DecimalStr(".5e-1".into())),
+        (Value::DecimalStr("+E".into()), Value::DecimalStr("+E".into())),
+        (Value::Text("+1E3".into()), Value::Text("+1E3".into())),
+    ];
+    for (value, expected) in values.iter() {
+        let mut value = value.clone();
+        value.normalize();
+        assert_eq!(&value, expected);
+    }
+
+    // Containers
+    let mut a = binn_ir::object_from("list", vec![Value::I32(1), binn_ir::map_from(-1, Value::U64(2))]);
+    let mut b = binn_ir::object_from("list", vec![Value::U16(1), binn_ir::map_from(-1, Value::I8(2))]);
+    assert_ne!(a, b);
+    a.normalize();
+    b.normalize();
+    assert_eq!(a, b);
+    assert_eq!(a.object_by(&["list"])?.as_list()?[1].map_by(&[-1])?, &Value::U8(2));
+
+    Ok(())
+}
+
+#[test]
+fn canonical_floats() -> Result<()> {
+    use std::convert::TryFrom;
+
+    let nan_f32 = f32::from_bits(0xffc0_0001);
+    let nan_f64 = f64::from_bits(0x7ff0_0000_0000_0001);
+
+    let mut value = Value::List(vec![
+        Value::Float(nan_f32), Value::Float(-0.0), Value::Float(-1.5),
+        binn_ir::object_from("double", vec![Value::Double(nan_f64), Value::Double(-0.0), Value::Double(0.1)]),
+    ]);
+    value.canonicalize_floats();
+    let list = value.as_list()?;
+    assert_eq!(f32::try_from(&list[0])?.to_bits(), f32::NAN.to_bits());
+    assert_eq!(f32::try_from(&list[1])?.to_bits(), 0);
+    assert_eq!(list[2], Value::Float(-1.5));
+    let doubles = list[3].object_by(&["double"])?.as_list()?;
+    assert_eq!(f64::try_from(&doubles[0])?.to_bits(), f64::NAN.to_bits());
+    assert_eq!(f64::try_from(&doubles[1])?.to_bits(), 0);
+    assert_eq!(doubles[2], Value::Double(0.1));
+
+    // Normalization
+    let mut value = Value::Double(nan_f64);
+    value.normalize();
+    assert!(matches!(value, Value::Float(f) if f.to_bits() == f32::NAN.to_bits()));
+    let mut value = Value::Double(-0.0);
+    value.normalize();
+    assert!(matches!(value, Value::Float(f) if f.to_bits() == 0));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn encode_config_canonicalizes_floats() -> IoResult<()> {
+    use binn_ir::EncodeConfig;
+
+    let value = Value::List(vec![Value::Float(-0.0), Value::Double(f64::from_bits(0xfff0_0000_0000_0001)), Value::U16(1)]);
+
+    let mut config = EncodeConfig::new();
+    config.canonicalize_floats = true;
+    let mut buf = vec![];
+    value.encode_with_config(&mut buf, &config)?;
+    assert_eq!(buf, [
+        value::LIST, 0x14, 3, value::FLOAT, 0, 0, 0, 0, value::DOUBLE, 0x7f, 0xf8, 0, 0, 0, 0, 0, 0, value::U16, 0, 1,
+    ]);
+
+    let mut buf = vec![];
+    value.encode_with_config(&mut buf, &EncodeConfig::new())?;
+    assert_eq!(&buf[3..8], &[value::FLOAT, 0x80, 0, 0, 0]);
+
+    Ok(())
+}
+
+#[test]
+fn semantic_equality() {
+    let integers = [Value::U8(5), Value::I8(5), Value::U16(5), Value::I16(5), Value::U32(5), Value::I32(5), Value::U64(5), Value::I64(5)];
+    for a in integers.iter() {
+        for b in integers.iter() {
+            assert!(a.semantic_eq(b));
+        }
+        assert!(!a.semantic_eq(&Value::Double(5.0)));
+        assert!(!a.semantic_eq(&Value::Text("5".into())));
+    }
+    assert!(!Value::I64(-1).semantic_eq(&Value::U64(u64::MAX)));
+    assert!(!Value::I8(-1).semantic_eq(&Value::U8(u8::MAX)));
+
+    assert!(Value::Float(-1.5).semantic_eq(&Value::Double(-1.5)));
+    assert!(!Value::Float(0.1).semantic_eq(&Value::Double(0.1)));
+    assert!(!Value::Float(f32::NAN).semantic_eq(&Value::Float(f32::NAN)));
+
+    assert!(Value::Null.semantic_eq(&Value::Null));
+    assert!(!Value::Null.semantic_eq(&Value::False));
+    assert!(!Value::Text("2019-12-31".into()).semantic_eq(&Value::Date("2019-12-31".into())));
+
+    // Containers
+    let a = binn_ir::object_from("list", vec![Value::I32(1), binn_ir::map_from(-1, Value::Double(2.0))]);
+    let b = binn_ir::object_from("list", vec![Value::U8(1), binn_ir::map_from(-1, Value::Float(2.0))]);
+    assert_ne!(a, b);
+    assert!(a.semantic_eq(&b));
+    assert!(!a.semantic_eq(&binn_ir::object_from("list", vec![Value::U8(1)])));
+    assert!(!a.semantic_eq(&binn_ir::object_from("other", vec![Value::U8(1), binn_ir::map_from(-1, Value::Float(2.0))])));
+    assert!(!binn_ir::map_from(0, 1).semantic_eq(&binn_ir::map_from(1, 1)));
+}
+
+#[test]
+fn checked_object_insertion() -> Result<()> {
+    use binn_ir::value::OBJECT_KEY_MAX_LEN;
+
+    assert_eq!(binn_ir::object_key("k".repeat(OBJECT_KEY_MAX_LEN))?.len(), OBJECT_KEY_MAX_LEN);
+    binn_ir::object_key("k".repeat(OBJECT_KEY_MAX_LEN + 1)).unwrap_err();
+    // Length is in bytes, not in characters
+    binn_ir::object_key("\u{e9}".repeat(OBJECT_KEY_MAX_LEN / 2 + 1)).unwrap_err();
+
+    let mut object = binn_ir::object();
+    assert_eq!(object.object_insert_checked("key", 0)?, None);
+    assert_eq!(object.object_insert_checked("key", 1)?, Some(Value::I32(0)));
+    object.object_insert_checked("k".repeat(OBJECT_KEY_MAX_LEN + 1), 2).unwrap_err();
+    assert_eq!(object, binn_ir::object_from("key", 1));
+
+    Value::Null.object_insert_checked("key", 0).unwrap_err();
+
+    let mut object = binn_ir::Object::new();
+    assert_eq!(binn_ir::object_insert_checked(&mut object, "key", 0)?, None);
+    binn_ir::object_insert_checked(&mut object, "k".repeat(OBJECT_KEY_MAX_LEN + 1), 0).unwrap_err();
+    assert_eq!(object.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn encode_config_skips_nulls_and_empty_containers() -> IoResult<()> {
+    use binn_ir::EncodeConfig;
+
+    let mut value = binn_ir::object_from("null", Value::Null);
+    value.object_insert("list", vec![Value::Null, binn_ir::list()])?;
+    value.object_insert("empty-list", binn_ir::list())?;
+    value.object_insert("map", binn_ir::map_from(0, Value::Null))?;
+    value.object_insert("object", binn_ir::object_from("empty", binn_ir::object()))?;
+    value.object_insert("text", "")?;
+
+    let decode = |config: &EncodeConfig| -> IoResult<Value> {
+        let mut buf = vec![];
+        let size = value.encode_with_config(&mut buf, config)?;
+        assert_eq!(size as usize, buf.len());
+        Ok(binn_ir::decode(&mut &buf[..])?.unwrap())
+    };
+
+    let mut config = EncodeConfig::new();
+    assert_eq!(decode(&config)?, value);
+
+    config.skip_nulls = true;
+    let mut expected = binn_ir::object_from("list", vec![Value::Null, binn_ir::list()]);
+    expected.object_insert("empty-list", binn_ir::list())?;
+    expected.object_insert("map", binn_ir::map())?;
+    expected.object_insert("object", binn_ir::object_from("empty", binn_ir::object()))?;
+    expected.object_insert("text", "")?;
+    assert_eq!(decode(&config)?, expected);
+
+    config.skip_empty_containers = true;
+    let mut expected = binn_ir::object_from("list", vec![Value::Null, binn_ir::list()]);
+    expected.object_insert("text", "")?;
+    assert_eq!(decode(&config)?, expected);
+
+    config.skip_nulls = false;
+    let mut expected = binn_ir::object_from("null", Value::Null);
+    expected.object_insert("list", vec![Value::Null, binn_ir::list()])?;
+    expected.object_insert("map", binn_ir::map_from(0, Value::Null))?;
+    expected.object_insert("text", "")?;
+    assert_eq!(decode(&config)?, expected);
+
+    // Root value is always encoded
+    let mut buf = vec![];
+    binn_ir::object().encode_with_config(&mut buf, &config)?;
+    assert_eq!(binn_ir::decode(&mut &buf[..])?, Some(binn_ir::object()));
+
+    Ok(())
+}
+
+#[test]
+fn sort_and_dedup_lists() -> Result<()> {
+    let records = |keys: Vec<Value>| -> Value {
+        keys.into_iter().enumerate().map(|(i, key)| {
+            let mut record = binn_ir::object_from("n", i as u32);
+            if key != Value::False {
+                record.object_insert("key", key).unwrap();
+            }
+            record
+        }).collect()
+    };
+    let order = |value: &Value| -> Vec<u32> {
+        value.as_list().unwrap().iter().map(|record| record.object_by(&["n"]).unwrap().as_u64_lossless().unwrap() as u32).collect()
+    };
+
+    // Numbers
+    let mut value = records(vec![Value::U64(9), Value::I8(-1), Value::Double(0.5), Value::Null, Value::U8(0), Value::I32(9)]);
+    value.sort_list_by_key("key")?;
+    assert_eq!(order(&value), [3, 1, 4, 2, 0, 5]);
+    value.dedup_list_by_key("key")?;
+    assert_eq!(order(&value), [3, 1, 4, 2, 0]);
+
+    // Integers and floats are compared exactly
+    const TWO_POW_53: u64 = 1 << 53;
+    let mut value = records(vec![Value::I64(TWO_POW_53 as i64 + 1), Value::U64(TWO_POW_53), Value::Double(TWO_POW_53 as f64)]);
+    value.sort_list_by_key("key")?;
+    assert_eq!(order(&value), [1, 2, 0]);
+
+    // Strings, and missing keys
+    let mut value = records(vec![Value::from("b"), Value::False, Value::Date("a".into()), Value::from("b")]);
+    value.sort_list_by_key("key")?;
+    assert_eq!(order(&value), [1, 2, 0, 3]);
+    value.dedup_list_by_key("key")?;
+    assert_eq!(order(&value), [1, 2, 0]);
+
+    // Missing keys are never duplicates
+    let mut value = records(vec![Value::False, Value::False]);
+    value.dedup_list_by_key("key")?;
+    assert_eq!(order(&value), [0, 1]);
+
+    // Errors
+    let mut value = records(vec![Value::from("a"), Value::U8(0)]);
+    value.sort_list_by_key("key").unwrap_err();
+    assert_eq!(order(&value), [0, 1]);
+    records(vec![binn_ir::list(), binn_ir::list()]).sort_list_by_key("key").unwrap_err();
+    let mut value = Value::List(vec![binn_ir::object(), Value::Null]);
+    value.sort_list_by_key("key").unwrap_err();
+    value.dedup_list_by_key("key").unwrap_err();
+    Value::Null.sort_list_by_key("key").unwrap_err();
+    Value::Null.dedup_list_by_key("key").unwrap_err();
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature="std")]
+fn encode_into_slices() -> IoResult<()> {
+    let mut object = binn_ir::object_from("blob", Value::Blob(vec![9; 200]));
+    object.object_insert("list", Value::List(vec![Value::Null, Value::I16(-2), Value::Double(0.5), Value::Time("12:00".into())]))?;
+    object.object_insert("map", binn_ir::map_from(-1, Value::U64(u64::MAX)))?;
+    object.object_insert("text", "x".repeat(300))?;
+
+    for value in [Value::True, Value::I8(-1), Value::Float(1.5), binn_ir::list(), object] {
+        let mut expected = vec![];
+        value.encode(&mut expected)?;
+
+        let mut buf = vec![0xff_u8; expected.len() + 1];
+        assert_eq!(value.encode_into_slice(&mut buf)?, expected.len());
+        assert_eq!(&buf[..expected.len()], expected.as_slice());
+        assert_eq!(buf[expected.len()], 0xff);
+
+        // Too small
+        let mut buf = vec![0xff_u8; expected.len() - 1];
+        value.encode_into_slice(&mut buf).unwrap_err();
+        assert!(buf.iter().all(|&b| b == 0xff));
+    }
+
+    // Arrays
+    let mut buf = [0_u8; 2];
+    assert_eq!(Value::U8(7).encode_into_slice(&mut buf)?, 2);
+    assert_eq!(buf, [value::U8, 7]);
+
+    Ok(())
+}
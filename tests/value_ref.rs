@@ -0,0 +1,74 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Value references
+
+#![cfg(feature="heapless")]
+
+extern crate binn_ir;
+
+use {
+    core::convert::TryFrom,
+
+    binn_ir::{Result, Value, ValueRef},
+};
+
+#[test]
+fn value_refs() -> Result<()> {
+    let mut name = heapless::String::<8>::new();
+    name.push_str("x").unwrap();
+    let blob = heapless::Vec::<u8, 200>::from_slice(&[7; 200]).unwrap();
+    let list = [ValueRef::Null, ValueRef::from(true), ValueRef::from(-1_i8), ValueRef::from(0.5), ValueRef::Date("2021-03-14")];
+    let map = [(-1, ValueRef::from(u64::MAX)), (9, ValueRef::DecimalStr("1.5"))];
+    let mut object = heapless::Vec::<(&str, ValueRef), 4>::new();
+    object.push(("blob", ValueRef::from(&blob))).unwrap();
+    object.push(("list", ValueRef::List(&list))).unwrap();
+    object.push(("map", ValueRef::Map(&map))).unwrap();
+    object.push(("name", ValueRef::from(&name))).unwrap();
+
+    for value in [ValueRef::False, ValueRef::from(300_u16), ValueRef::Text(""), ValueRef::List(&[]), ValueRef::from(&object)] {
+        let owned = value.to_value();
+        let size = owned.size()? as usize;
+        assert_eq!(value.size()? as usize, size);
+
+        let mut expected = vec![0; size];
+        owned.encode_into_slice(&mut expected)?;
+        let mut buf = vec![0xff; size + 1];
+        assert_eq!(value.encode_into_slice(&mut buf)?, size);
+        assert_eq!(&buf[..size], expected.as_slice());
+        assert_eq!(buf[size], 0xff);
+        value.encode_into_slice(&mut buf[..size - 1]).unwrap_err();
+
+        // Appending to heapless vectors
+        let mut out = heapless::Vec::<u8, 512>::new();
+        out.push(0xff).unwrap();
+        assert_eq!(value.encode_into_heapless(&mut out)?, size);
+        assert_eq!(&out[1..], expected.as_slice());
+        let mut out = heapless::Vec::<u8, 512>::from_slice(&[0; 512]).unwrap();
+        out.truncate(512 - size + 1);
+        value.encode_into_heapless(&mut out).unwrap_err();
+        assert_eq!(out.len(), 512 - size + 1);
+    }
+
+    drop(object);
+    assert_eq!(Value::from(name), Value::from("x"));
+    assert_eq!(Value::from(blob), Value::Blob(vec![7; 200]));
+
+    Ok(())
+}
+
+#[test]
+fn duplicate_keys() {
+    ValueRef::Map(&[(1, ValueRef::Null), (1, ValueRef::True)]).size().unwrap_err();
+    ValueRef::Object(&[("a", ValueRef::Null), ("b", ValueRef::Null), ("a", ValueRef::True)]).encode_into_slice(&mut [0; 32]).unwrap_err();
+    ValueRef::Object(&[("k", ValueRef::Null)]).size().unwrap();
+}
+
+#[test]
+fn borrowing_values() -> Result<()> {
+    let value = Value::from("text");
+    assert_eq!(ValueRef::try_from(&value)?, ValueRef::Text("text"));
+    assert_eq!(ValueRef::try_from(&Value::U8(1))?, ValueRef::U8(1));
+    ValueRef::try_from(&binn_ir::list()).unwrap_err();
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Values
+
+#![cfg(feature="std")]
+
+extern crate binn_ir;
+
+use {
+    std::io::Cursor,
+
+    binn_ir::{Decoder, IoResult, Value},
+};
+
+#[test]
+fn values() -> IoResult<()> {
+    assert!(binn_ir::values(&[][..]).next().is_none());
+
+    let values = vec![Value::Null, Value::from("text"), binn_ir::object_from("list", vec![Value::True])];
+    let mut buf = vec![];
+    for value in &values {
+        value.encode(&mut buf)?;
+    }
+    assert_eq!(binn_ir::values(&buf[..]).collect::<IoResult<Vec<_>>>()?, values);
+
+    // Source can still be used afterwards
+    let mut source = Cursor::new(buf.clone());
+    assert_eq!((&mut source).iter().take(2).count(), 2);
+    assert_eq!(source.decode()?.as_ref(), values.last());
+
+    // Truncated values stop the iterator
+    let mut values = binn_ir::values(&buf[..buf.len() - 1]);
+    values.next().unwrap()?;
+    values.next().unwrap()?;
+    values.next().unwrap().unwrap_err();
+    assert!(values.next().is_none());
+
+    Ok(())
+}
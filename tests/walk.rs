@@ -0,0 +1,186 @@
+// License: see LICENSE file at root directory of `master` branch
+
+//! # Walk
+
+extern crate binn_ir;
+
+use binn_ir::{Result, Value, Walk};
+
+/// # Makes a sample value
+fn sample() -> Result<Value> {
+    let mut result = binn_ir::object_from("b", vec![Value::U8(0), binn_ir::map_from(-1, Value::Null)]);
+    result.object_insert("a", binn_ir::object_from("c", true))?;
+    result.object_insert("d", "text")?;
+    Ok(result)
+}
+
+#[test]
+fn walk() -> Result<()> {
+    let value = sample()?;
+
+    let mut paths = vec![];
+    assert!(value.walk(|path, _| {
+        paths.push(path.to_string());
+        Walk::Continue
+    }));
+    assert_eq!(paths, [
+        "$", r#"$["a"]"#, r#"$["a"]["c"]"#, r#"$["b"]"#, r#"$["b"][0]"#, r#"$["b"][1]"#, r#"$["b"][1]{-1}"#, r#"$["d"]"#,
+    ]);
+
+    // Skips children
+    let mut paths = vec![];
+    assert!(value.walk(|path, value| {
+        paths.push(path.to_string());
+        match value {
+            Value::List(_) | Value::Object(_) if !path.is_root() => Walk::SkipChildren,
+            _ => Walk::Continue,
+        }
+    }));
+    assert_eq!(paths, ["$", r#"$["a"]"#, r#"$["b"]"#, r#"$["d"]"#]);
+
+    // Stops
+    let mut count = 0;
+    assert!(!value.walk(|_, value| {
+        count += 1;
+        match value {
+            Value::Null => Walk::Stop,
+            _ => Walk::Continue,
+        }
+    }));
+    assert_eq!(count, 7);
+
+    // Scalars
+    let mut count = 0;
+    assert!(Value::Null.walk(|path, _| {
+        assert!(path.is_root());
+        count += 1;
+        Walk::SkipChildren
+    }));
+    assert_eq!(count, 1);
+
+    Ok(())
+}
+
+#[test]
+fn transform() -> Result<()> {
+    let mut value = sample()?;
+
+    let mut paths = vec![];
+    value.transform(|path, value| {
+        paths.push(path.to_string());
+        match value {
+            Value::U8(u) => *u += 1,
+            Value::Map(map) => {
+                binn_ir::map_insert(map, 1, Value::False);
+            },
+            Value::Object(object) if path.is_root() => {
+                if let Some(item) = object.remove("d") {
+                    binn_ir::object_insert(object, "e", item);
+                }
+            },
+            _ => (),
+        };
+    });
+    assert_eq!(paths, [
+        "$", r#"$["a"]"#, r#"$["a"]["c"]"#, r#"$["b"]"#, r#"$["b"][0]"#, r#"$["b"][1]"#, r#"$["b"][1]{-1}"#, r#"$["b"][1]{1}"#, r#"$["e"]"#,
+    ]);
+
+    let mut expected = binn_ir::object_from("a", binn_ir::object_from("c", true));
+    let mut map = binn_ir::map_from(-1, Value::Null);
+    map.map_insert(1, false)?;
+    expected.object_insert("b", vec![Value::U8(1), map])?;
+    expected.object_insert("e", "text")?;
+    assert_eq!(value, expected);
+
+    Ok(())
+}
+
+#[test]
+fn retain() -> Result<()> {
+    let mut value = sample()?;
+
+    let mut paths = vec![];
+    value.retain(|path, value| {
+        paths.push(path.to_string());
+        !matches!(value, Value::Null | Value::True | Value::U8(_))
+    });
+    paths.sort();
+    assert_eq!(paths, [r#"$["a"]"#, r#"$["a"]["c"]"#, r#"$["b"]"#, r#"$["b"][0]"#, r#"$["b"][1]"#, r#"$["b"][1]{-1}"#, r#"$["d"]"#]);
+
+    let mut expected = binn_ir::object_from("a", binn_ir::object());
+    expected.object_insert("b", vec![binn_ir::map()])?;
+    expected.object_insert("d", "text")?;
+    assert_eq!(value, expected);
+
+    // Removed items are not visited
+    let mut value = Value::List(vec![Value::List(vec![Value::Null]), Value::U8(0), Value::List(vec![Value::Null])]);
+    let mut paths = vec![];
+    value.retain(|path, value| {
+        paths.push(path.to_string());
+        path.to_string() != "$[0]" && value != &Value::U8(0)
+    });
+    assert_eq!(paths, ["$[0]", "$[1]", "$[2]", "$[2][0]"]);
+    assert_eq!(value, Value::List(vec![Value::List(vec![Value::Null])]));
+
+    // Root value is kept
+    let mut value = Value::Null;
+    value.retain(|_, _| false);
+    assert_eq!(value, Value::Null);
+
+    Ok(())
+}
+
+#[test]
+fn find() -> Result<()> {
+    let value = sample()?;
+
+    let (path, found) = value.find_first(|value| matches!(value, Value::List(_) | Value::Map(_))).unwrap();
+    assert_eq!(path.to_string(), r#"$["b"]"#);
+    assert_eq!(found.as_list()?.len(), 2);
+    assert!(value.find_first(|value| value == &Value::False).is_none());
+    assert!(value.find_first(|value| value == &value.clone()).unwrap().0.is_root());
+
+    let found = value.find_all(|value| matches!(value, Value::List(_) | Value::Map(_) | Value::Null));
+    assert_eq!(found.iter().map(|(path, _)| path.to_string()).collect::<Vec<_>>(), [r#"$["b"]"#, r#"$["b"][1]"#, r#"$["b"][1]{-1}"#]);
+    assert_eq!(found[2].1, &Value::Null);
+    assert_eq!(value.find_all(|_| true).len(), 8);
+    assert!(value.find_all(|_| false).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn select() -> Result<()> {
+    let mut value = sample()?;
+    value.object_insert("key.with[dots]", 1)?;
+
+    let select = |selector| -> Result<Vec<String>> {
+        Ok(value.select(selector)?.into_iter().map(|(path, _)| path.to_string()).collect())
+    };
+
+    assert_eq!(select("")?, ["$"]);
+    assert_eq!(select("$")?, ["$"]);
+    assert_eq!(select("a.c")?, [r#"$["a"]["c"]"#]);
+    assert_eq!(select("$.a.c")?, [r#"$["a"]["c"]"#]);
+    assert_eq!(select(r#"$["a"]["c"]"#)?, [r#"$["a"]["c"]"#]);
+    assert_eq!(select(r#"["key.with[dots]"]"#)?, [r#"$["key.with[dots]"]"#]);
+    assert_eq!(select("b[1]{-1}")?, [r#"$["b"][1]{-1}"#]);
+    assert_eq!(select("b[*]")?, [r#"$["b"][0]"#, r#"$["b"][1]"#]);
+    assert_eq!(select("b[1]{*}")?, [r#"$["b"][1]{-1}"#]);
+    assert_eq!(select("*")?.len(), 4);
+    assert_eq!(select("$[*].c")?, [r#"$["a"]["c"]"#]);
+    assert_eq!(select("*.*")?, [r#"$["a"]["c"]"#, r#"$["b"][0]"#, r#"$["b"][1]"#]);
+    assert_eq!(value.select("b[1]{-1}")?[0].1, &Value::Null);
+
+    // No matches
+    for selector in &["missing", "a.c.d", "b[2]", "b[1]{0}", "a[0]", "b.c", "d[*]"] {
+        assert!(select(selector)?.is_empty());
+    }
+
+    // Invalid syntax
+    for selector in &["a..c", "a.", "b[", "b[x]", "b[-1]", "b[1]{x}", r#"["a"#, "$a", "[]"] {
+        value.select(selector).unwrap_err();
+    }
+
+    Ok(())
+}